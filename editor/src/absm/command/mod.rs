@@ -880,3 +880,34 @@ impl Command for SetLayerMaskCommand {
         self.swap(context)
     }
 }
+
+#[derive(Debug)]
+pub struct SetLayerAdditiveCommand {
+    pub absm_node_handle: Handle<Node>,
+    pub layer_index: usize,
+    pub additive: bool,
+}
+
+impl SetLayerAdditiveCommand {
+    fn swap(&mut self, context: &mut SceneContext) {
+        let layer =
+            &mut fetch_machine(context, self.absm_node_handle).layers_mut()[self.layer_index];
+        let old = layer.is_additive();
+        layer.set_additive(self.additive);
+        self.additive = old;
+    }
+}
+
+impl Command for SetLayerAdditiveCommand {
+    fn name(&mut self, _context: &SceneContext) -> String {
+        "Set Layer Additive".to_string()
+    }
+
+    fn execute(&mut self, context: &mut SceneContext) {
+        self.swap(context)
+    }
+
+    fn revert(&mut self, context: &mut SceneContext) {
+        self.swap(context)
+    }
+}
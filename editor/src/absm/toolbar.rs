@@ -1,6 +1,9 @@
 use crate::{
     absm::{
-        command::{AddLayerCommand, RemoveLayerCommand, SetLayerMaskCommand, SetLayerNameCommand},
+        command::{
+            AddLayerCommand, RemoveLayerCommand, SetLayerAdditiveCommand, SetLayerMaskCommand,
+            SetLayerNameCommand,
+        },
         fetch_selection,
         selection::AbsmSelection,
     },
@@ -47,6 +50,7 @@ pub struct Toolbar {
     pub add_layer: Handle<UiNode>,
     pub remove_layer: Handle<UiNode>,
     pub edit_mask: Handle<UiNode>,
+    pub additive: Handle<UiNode>,
     pub node_selector: Handle<UiNode>,
 }
 
@@ -64,6 +68,7 @@ impl Toolbar {
         let add_layer;
         let remove_layer;
         let edit_mask;
+        let additive;
         let panel = StackPanelBuilder::new(
             WidgetBuilder::new()
                 .with_child({
@@ -146,6 +151,25 @@ impl Toolbar {
                     )
                     .build(ctx);
                     edit_mask
+                })
+                .with_child({
+                    additive = CheckBoxBuilder::new(
+                        WidgetBuilder::new()
+                            .with_margin(Thickness::uniform(1.0))
+                            .with_tooltip(make_simple_tooltip(
+                                ctx,
+                                "Additive layers add their poses on top of the previous layers.",
+                            )),
+                    )
+                    .with_content(
+                        TextBuilder::new(
+                            WidgetBuilder::new().with_vertical_alignment(VerticalAlignment::Center),
+                        )
+                        .with_text("Additive")
+                        .build(ctx),
+                    )
+                    .build(ctx);
+                    additive
                 }),
         )
         .with_orientation(Orientation::Horizontal)
@@ -159,6 +183,7 @@ impl Toolbar {
             add_layer,
             remove_layer,
             edit_mask,
+            additive,
             node_selector: Handle::NONE,
         }
     }
@@ -182,6 +207,18 @@ impl Toolbar {
                 } else {
                     ToolbarAction::LeavePreviewMode
                 };
+            } else if message.destination() == self.additive
+                && message.direction() == MessageDirection::FromWidget
+            {
+                if let Some(layer_index) = selection.layer {
+                    sender
+                        .send(Message::do_scene_command(SetLayerAdditiveCommand {
+                            absm_node_handle: selection.absm_node_handle,
+                            layer_index,
+                            additive: *value,
+                        }))
+                        .unwrap();
+                }
             }
         } else if let Some(DropdownListMessage::SelectionChanged(Some(index))) = message.data() {
             if message.destination() == self.layers
@@ -393,6 +430,15 @@ impl Toolbar {
                         layer.name().to_string(),
                     ),
                 );
+
+                send_sync_message(
+                    ui,
+                    CheckBoxMessage::checked(
+                        self.additive,
+                        MessageDirection::ToWidget,
+                        Some(layer.is_additive()),
+                    ),
+                );
             }
         }
     }
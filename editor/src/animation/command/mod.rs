@@ -438,6 +438,13 @@ define_animation_swap_command!(SetAnimationTimeSliceCommand<Range<f32>>(self, co
     self.value = old_time_slice;
 });
 
+define_animation_swap_command!(SetAnimationReferenceTimeCommand<f32>(self, context) {
+    let animation = fetch_animation(self.node_handle, self.animation_handle, context);
+    let old_reference_time = animation.reference_time();
+    animation.set_reference_time(self.value);
+    self.value = old_reference_time;
+});
+
 define_animation_swap_command!(SetAnimationNameCommand<String>(self, context) {
     let animation = fetch_animation(self.node_handle, self.animation_handle, context);
     let old_name = animation.name().to_string();
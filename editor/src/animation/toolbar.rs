@@ -3,8 +3,8 @@ use crate::{
         command::{
             AddAnimationCommand, RemoveAnimationCommand, ReplaceAnimationCommand,
             SetAnimationEnabledCommand, SetAnimationLoopingCommand, SetAnimationNameCommand,
-            SetAnimationRootMotionSettingsCommand, SetAnimationSpeedCommand,
-            SetAnimationTimeSliceCommand,
+            SetAnimationReferenceTimeCommand, SetAnimationRootMotionSettingsCommand,
+            SetAnimationSpeedCommand, SetAnimationTimeSliceCommand,
         },
         selection::AnimationSelection,
     },
@@ -66,6 +66,7 @@ pub struct Toolbar {
     pub preview: Handle<UiNode>,
     pub time_slice_start: Handle<UiNode>,
     pub time_slice_end: Handle<UiNode>,
+    pub reference_time: Handle<UiNode>,
     pub import: Handle<UiNode>,
     pub reimport: Handle<UiNode>,
     pub node_selector: Handle<UiNode>,
@@ -391,6 +392,7 @@ impl Toolbar {
         let preview;
         let time_slice_start;
         let time_slice_end;
+        let reference_time;
         let import;
         let reimport;
         let looping;
@@ -688,6 +690,22 @@ impl Toolbar {
                                 .build(ctx);
                                 time_slice_end
                             })
+                            .with_child({
+                                reference_time = NumericUpDownBuilder::<f32>::new(
+                                    WidgetBuilder::new()
+                                        .with_enabled(false)
+                                        .with_width(50.0)
+                                        .with_margin(Thickness::uniform(1.0))
+                                        .with_tooltip(make_simple_tooltip(
+                                            ctx,
+                                            "Reference Pose Time (for Additive Layers)",
+                                        )),
+                                )
+                                .with_min_value(0.0)
+                                .with_value(0.0)
+                                .build(ctx);
+                                reference_time
+                            })
                             .with_child({
                                 root_motion =
                                     ButtonBuilder::new(WidgetBuilder::new().with_tooltip(
@@ -798,10 +816,10 @@ impl Toolbar {
         )
         .with_filter(Filter::new(|p: &Path| {
             if let Some(ext) = p.extension() {
-                // TODO: Here we allow importing only FBX files, but they can contain
+                // TODO: Here we allow importing only FBX and glTF files, but they can contain
                 // multiple animations and it might be good to also add animation selector
                 // that will be used to select a particular animation to import.
-                ext.to_string_lossy().as_ref() == "fbx"
+                matches!(ext.to_string_lossy().as_ref(), "fbx" | "gltf" | "glb")
             } else {
                 p.is_dir()
             }
@@ -823,6 +841,7 @@ impl Toolbar {
             preview,
             time_slice_start,
             time_slice_end,
+            reference_time,
             clone_current_animation,
             import,
             reimport,
@@ -1018,6 +1037,16 @@ impl Toolbar {
                             value: *value,
                         }))
                         .unwrap();
+                } else if message.destination() == self.reference_time {
+                    sender
+                        .send(Message::do_scene_command(
+                            SetAnimationReferenceTimeCommand {
+                                node_handle: animation_player_handle,
+                                animation_handle: selection.animation,
+                                value: *value,
+                            },
+                        ))
+                        .unwrap();
                 }
             }
         }
@@ -1258,6 +1287,15 @@ impl Toolbar {
                 ),
             );
 
+            send_sync_message(
+                ui,
+                NumericUpDownMessage::value(
+                    self.reference_time,
+                    MessageDirection::ToWidget,
+                    animation.reference_time(),
+                ),
+            );
+
             send_sync_message(
                 ui,
                 CheckBoxMessage::checked(
@@ -1284,6 +1322,7 @@ impl Toolbar {
             self.remove_current_animation,
             self.time_slice_start,
             self.time_slice_end,
+            self.reference_time,
             self.clone_current_animation,
             self.looping,
             self.enabled,
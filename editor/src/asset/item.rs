@@ -38,6 +38,7 @@ pub enum AssetKind {
     Texture,
     Sound,
     Shader,
+    Material,
 }
 
 impl Deref for AssetItem {
@@ -151,11 +152,11 @@ impl AssetItemBuilder {
                         kind = AssetKind::Texture;
                         Some(into_gui_texture(resource_manager.request_texture(&path)))
                     }
-                    "fbx" | "rgs" => {
+                    "fbx" | "gltf" | "glb" | "obj" | "rgs" => {
                         kind = AssetKind::Model;
                         load_image(include_bytes!("../../resources/embed/model.png"))
                     }
-                    "ogg" | "wav" => {
+                    "ogg" | "wav" | "mp3" | "flac" => {
                         kind = AssetKind::Sound;
                         load_image(include_bytes!("../../resources/embed/sound.png"))
                     }
@@ -163,6 +164,10 @@ impl AssetItemBuilder {
                         kind = AssetKind::Shader;
                         load_image(include_bytes!("../../resources/embed/shader.png"))
                     }
+                    "material" => {
+                        kind = AssetKind::Material;
+                        load_image(include_bytes!("../../resources/embed/shader.png"))
+                    }
                     _ => None,
                 });
 
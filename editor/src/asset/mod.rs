@@ -38,6 +38,7 @@ use fyrox::{
         BuildContext, HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface,
         VerticalAlignment, BRUSH_DARK,
     },
+    material::{Material, MaterialState, SharedMaterial},
     utils::log::Log,
 };
 use std::{
@@ -60,6 +61,44 @@ struct ContextMenu {
     placement_target: Handle<UiNode>,
 }
 
+struct FolderContextMenu {
+    menu: RcUiNodeHandle,
+    create_material: Handle<UiNode>,
+}
+
+impl FolderContextMenu {
+    fn new(ctx: &mut BuildContext) -> Self {
+        let create_material;
+        let menu = PopupBuilder::new(WidgetBuilder::new())
+            .with_content(
+                StackPanelBuilder::new(WidgetBuilder::new().with_child({
+                    create_material = MenuItemBuilder::new(WidgetBuilder::new())
+                        .with_content(MenuItemContent::text("Create Material"))
+                        .build(ctx);
+                    create_material
+                }))
+                .build(ctx),
+            )
+            .build(ctx);
+        let menu = RcUiNodeHandle::new(menu, ctx.sender());
+
+        Self {
+            menu,
+            create_material,
+        }
+    }
+}
+
+fn make_unique_path(folder: &Path, name: &str, ext: &str) -> PathBuf {
+    let mut path = folder.join(name).with_extension(ext);
+    let mut i = 0;
+    while path.exists() {
+        i += 1;
+        path = folder.join(format!("{} {}", name, i)).with_extension(ext);
+    }
+    path
+}
+
 fn execute_command(command: &mut Command) {
     match command.spawn() {
         Ok(mut process) => Log::verify(process.wait()),
@@ -186,6 +225,7 @@ pub struct AssetBrowser {
     item_to_select: Option<PathBuf>,
     inspector: AssetInspector,
     context_menu: ContextMenu,
+    folder_context_menu: FolderContextMenu,
     selected_path: PathBuf,
 }
 
@@ -193,7 +233,21 @@ fn is_engine_resource(ext: &OsStr) -> bool {
     let ext = ext.to_string_lossy().to_lowercase();
     matches!(
         ext.as_str(),
-        "rgs" | "fbx" | "jpg" | "tga" | "png" | "bmp" | "ogg" | "wav" | "shader"
+        "rgs"
+            | "fbx"
+            | "gltf"
+            | "glb"
+            | "obj"
+            | "jpg"
+            | "tga"
+            | "png"
+            | "bmp"
+            | "ogg"
+            | "wav"
+            | "mp3"
+            | "flac"
+            | "shader"
+            | "material"
     )
 }
 
@@ -208,6 +262,7 @@ impl AssetBrowser {
         let folder_browser;
         let search_bar;
         let scroll_panel;
+        let folder_context_menu = FolderContextMenu::new(ctx);
         let window = WindowBuilder::new(WidgetBuilder::new())
             .can_minimize(false)
             .with_title(WindowTitle::text("Asset Browser"))
@@ -245,7 +300,9 @@ impl AssetBrowser {
                                     })
                                     .with_child({
                                         scroll_panel = ScrollViewerBuilder::new(
-                                            WidgetBuilder::new().on_row(1),
+                                            WidgetBuilder::new().on_row(1).with_context_menu(
+                                                folder_context_menu.menu.clone(),
+                                            ),
                                         )
                                         .with_content({
                                             content_panel = WrapPanelBuilder::new(
@@ -311,6 +368,7 @@ impl AssetBrowser {
             item_to_select: None,
             inspector,
             context_menu,
+            folder_context_menu,
             selected_path: Default::default(),
         }
     }
@@ -457,10 +515,39 @@ impl AssetBrowser {
                     &mut engine.user_interface,
                     sender,
                 ),
+                AssetKind::Material => {
+                    match block_on(engine.resource_manager.request_material(&item.path)) {
+                        Ok(material) => sender
+                            .send(Message::OpenMaterialResourceEditor(material))
+                            .unwrap(),
+                        Err(err) => Log::err(format!(
+                            "Unable to open material {}. Reason: {:?}",
+                            item.path.display(),
+                            err
+                        )),
+                    }
+                }
                 AssetKind::Shader => {
                     Log::warn("Implement me!");
                 }
             }
+        } else if let Some(MenuItemMessage::Click) = message.data::<MenuItemMessage>() {
+            if message.destination() == self.folder_context_menu.create_material {
+                let path = make_unique_path(&self.selected_path, "New Material", "material");
+                let state = MaterialState::new(&path, SharedMaterial::new(Material::standard()));
+                match state.save() {
+                    Ok(_) => {
+                        self.item_to_select = Some(path);
+                        let selected_path = self.selected_path.clone();
+                        self.set_path(&selected_path, ui, &engine.resource_manager);
+                    }
+                    Err(err) => Log::err(format!(
+                        "Unable to create material {}. Reason: {}",
+                        path.display(),
+                        err
+                    )),
+                }
+            }
         } else if let Some(FileBrowserMessage::Path(path)) = message.data::<FileBrowserMessage>() {
             if message.destination() == self.folder_browser
                 && message.direction() == MessageDirection::FromWidget
@@ -0,0 +1,456 @@
+use crate::{
+    gui::make_dropdown_list_option, inspector::EditorEnvironment, send_sync_message,
+    DropdownListBuilder, MSG_SYNC_FLAG,
+};
+use fyrox::{
+    core::{pool::Handle, uuid::Uuid},
+    engine::SerializationContext,
+    gui::{
+        define_constructor,
+        dropdown_list::{DropdownList, DropdownListMessage},
+        inspector::{
+            editors::{
+                PropertyEditorBuildContext, PropertyEditorDefinition,
+                PropertyEditorDefinitionContainer, PropertyEditorInstance,
+                PropertyEditorMessageContext, PropertyEditorTranslationContext,
+            },
+            make_expander_container, FieldKind, Inspector, InspectorBuilder, InspectorContext,
+            InspectorEnvironment, InspectorError, InspectorMessage, PropertyChanged,
+        },
+        message::{MessageDirection, UiMessage},
+        widget::{Widget, WidgetBuilder},
+        BuildContext, Control, UiNode, UserInterface,
+    },
+    scene::sound::custom::CustomEffectWrapper,
+};
+use std::{
+    any::{Any, TypeId},
+    cell::Cell,
+    ops::{Deref, DerefMut},
+    rc::Rc,
+    sync::Arc,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum CustomEffectPropertyEditorMessage {
+    Value(Option<Uuid>),
+    PropertyChanged(PropertyChanged),
+}
+
+impl CustomEffectPropertyEditorMessage {
+    define_constructor!(CustomEffectPropertyEditorMessage:Value => fn value(Option<Uuid>), layout: false);
+    define_constructor!(CustomEffectPropertyEditorMessage:PropertyChanged => fn property_changed(PropertyChanged), layout: false);
+}
+
+#[derive(Clone, Debug)]
+pub struct CustomEffectPropertyEditor {
+    widget: Widget,
+    inspector: Handle<UiNode>,
+    variant_selector: Handle<UiNode>,
+    selected_effect_uuid: Option<Uuid>,
+    need_context_update: Cell<bool>,
+}
+
+impl Deref for CustomEffectPropertyEditor {
+    type Target = Widget;
+
+    fn deref(&self) -> &Self::Target {
+        &self.widget
+    }
+}
+
+impl DerefMut for CustomEffectPropertyEditor {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.widget
+    }
+}
+
+impl Control for CustomEffectPropertyEditor {
+    fn query_component(&self, type_id: TypeId) -> Option<&dyn Any> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage) {
+        self.widget.handle_routed_message(ui, message);
+
+        if let Some(CustomEffectPropertyEditorMessage::Value(id)) = message.data() {
+            if message.destination() == self.handle()
+                && message.direction() == MessageDirection::ToWidget
+                && self.selected_effect_uuid != *id
+            {
+                self.selected_effect_uuid = *id;
+                self.need_context_update.set(true);
+                ui.send_message(message.reverse());
+            }
+        } else if let Some(InspectorMessage::PropertyChanged(property_changed)) =
+            message.data::<InspectorMessage>()
+        {
+            if message.destination() == self.inspector
+                && message.direction() == MessageDirection::FromWidget
+            {
+                ui.send_message(CustomEffectPropertyEditorMessage::property_changed(
+                    self.handle(),
+                    MessageDirection::FromWidget,
+                    property_changed.clone(),
+                ))
+            }
+        }
+    }
+
+    fn preview_message(&self, ui: &UserInterface, message: &mut UiMessage) {
+        if let Some(DropdownListMessage::SelectionChanged(Some(i))) = message.data() {
+            // Selection could be synced with the model, it must not re-create the effect.
+            if message.destination() == self.variant_selector
+                && message.direction() == MessageDirection::FromWidget
+                && message.flags != MSG_SYNC_FLAG
+            {
+                let selected_item = ui
+                    .node(self.variant_selector)
+                    .cast::<DropdownList>()
+                    .expect("Must be DropdownList")
+                    .items()[*i];
+
+                let new_selected_effect_uuid = *ui
+                    .node(selected_item)
+                    .user_data_ref::<Uuid>()
+                    .expect("Must be effect UUID");
+
+                // Nil UUID is used by `<Passthrough>` item.
+                ui.send_message(CustomEffectPropertyEditorMessage::value(
+                    self.handle(),
+                    MessageDirection::ToWidget,
+                    Some(new_selected_effect_uuid).filter(|uuid| !uuid.is_nil()),
+                ));
+            }
+        }
+    }
+}
+
+pub struct CustomEffectPropertyEditorBuilder {
+    widget_builder: WidgetBuilder,
+}
+
+impl CustomEffectPropertyEditorBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self { widget_builder }
+    }
+
+    pub fn build(
+        self,
+        variant_selector: Handle<UiNode>,
+        effect: &CustomEffectWrapper,
+        environment: Option<Rc<dyn InspectorEnvironment>>,
+        sync_flag: u64,
+        layer_index: usize,
+        generate_property_string_values: bool,
+        definition_container: Rc<PropertyEditorDefinitionContainer>,
+        ctx: &mut BuildContext,
+    ) -> Handle<UiNode> {
+        let context = effect.instance().map(|_| {
+            InspectorContext::from_object(
+                effect,
+                ctx,
+                definition_container,
+                environment,
+                sync_flag,
+                layer_index,
+                generate_property_string_values,
+            )
+        });
+
+        let inspector = InspectorBuilder::new(WidgetBuilder::new())
+            .with_opt_context(context)
+            .build(ctx);
+
+        ctx.add_node(UiNode::new(CustomEffectPropertyEditor {
+            widget: self
+                .widget_builder
+                .with_preview_messages(true)
+                .with_child(inspector)
+                .build(),
+            selected_effect_uuid: effect_uuid(effect),
+            variant_selector,
+            inspector,
+            need_context_update: Cell::new(false),
+        }))
+    }
+}
+
+fn effect_uuid(effect: &CustomEffectWrapper) -> Option<Uuid> {
+    effect.instance().map(|e| e.id())
+}
+
+fn create_items(
+    serialization_context: Arc<SerializationContext>,
+    ctx: &mut BuildContext,
+) -> Vec<Handle<UiNode>> {
+    let mut items = vec![{
+        let empty = make_dropdown_list_option(ctx, "<Passthrough>");
+        ctx[empty].user_data = Some(Rc::new(Uuid::default()));
+        empty
+    }];
+
+    items.extend(serialization_context.effect_constructors.map().iter().map(
+        |(type_uuid, constructor)| {
+            let item = make_dropdown_list_option(ctx, &constructor.name);
+            ctx[item].user_data = Some(Rc::new(*type_uuid));
+            item
+        },
+    ));
+
+    items
+}
+
+fn selected_effect(
+    serialization_context: Arc<SerializationContext>,
+    value: &CustomEffectWrapper,
+) -> Option<usize> {
+    effect_uuid(value)
+        .and_then(|id| {
+            serialization_context
+                .effect_constructors
+                .map()
+                .keys()
+                .position(|type_uuid| *type_uuid == id)
+        })
+        .map(|n| {
+            // Because the list has `<Passthrough>` element
+            n + 1
+        })
+}
+
+fn fetch_effect_definitions(
+    instance: Handle<UiNode>,
+    ui: &mut UserInterface,
+) -> Option<Vec<Handle<UiNode>>> {
+    let instance_ref = ui
+        .node(instance)
+        .cast::<CustomEffectPropertyEditor>()
+        .expect("Must be CustomEffectPropertyEditor!");
+
+    let environment = ui
+        .node(instance_ref.inspector)
+        .cast::<Inspector>()
+        .expect("Must be Inspector!")
+        .context()
+        .environment
+        .clone();
+
+    let editor_environment = EditorEnvironment::try_get_from(&environment);
+
+    editor_environment.map(|e| create_items(e.serialization_context.clone(), &mut ui.build_ctx()))
+}
+
+/// Allows to select a type of a custom effect among the effects registered in the serialization
+/// context and to edit the properties of the effect.
+#[derive(Debug)]
+pub struct CustomEffectPropertyEditorDefinition {}
+
+impl PropertyEditorDefinition for CustomEffectPropertyEditorDefinition {
+    fn value_type_id(&self) -> TypeId {
+        TypeId::of::<CustomEffectWrapper>()
+    }
+
+    fn create_instance(
+        &self,
+        ctx: PropertyEditorBuildContext,
+    ) -> Result<PropertyEditorInstance, InspectorError> {
+        let value = ctx.property_info.cast_value::<CustomEffectWrapper>()?;
+
+        let environment = EditorEnvironment::try_get_from(&ctx.environment)
+            .expect("Must have editor environment!");
+
+        let items = create_items(environment.serialization_context.clone(), ctx.build_context);
+
+        let variant_selector = DropdownListBuilder::new(WidgetBuilder::new())
+            .with_selected(
+                selected_effect(environment.serialization_context.clone(), value).unwrap_or(0),
+            )
+            .with_items(items)
+            .build(ctx.build_context);
+
+        let editor;
+        let container = make_expander_container(
+            ctx.layer_index,
+            ctx.property_info.display_name,
+            ctx.property_info.description,
+            variant_selector,
+            {
+                editor = CustomEffectPropertyEditorBuilder::new(WidgetBuilder::new()).build(
+                    variant_selector,
+                    value,
+                    ctx.environment.clone(),
+                    ctx.sync_flag,
+                    ctx.layer_index,
+                    ctx.generate_property_string_values,
+                    ctx.definition_container.clone(),
+                    ctx.build_context,
+                );
+                editor
+            },
+            ctx.build_context,
+        );
+
+        Ok(PropertyEditorInstance::Custom { container, editor })
+    }
+
+    fn create_message(
+        &self,
+        ctx: PropertyEditorMessageContext,
+    ) -> Result<Option<UiMessage>, InspectorError> {
+        let value = ctx.property_info.cast_value::<CustomEffectWrapper>()?;
+
+        let new_effect_definitions_items = fetch_effect_definitions(ctx.instance, ctx.ui);
+
+        let instance_ref = ctx
+            .ui
+            .node(ctx.instance)
+            .cast::<CustomEffectPropertyEditor>()
+            .expect("Must be CustomEffectPropertyEditor!");
+
+        let editor_environment =
+            EditorEnvironment::try_get_from(&ctx.environment).expect("Environment must be set!");
+
+        let variant_selector_ref = ctx
+            .ui
+            .node(instance_ref.variant_selector)
+            .cast::<DropdownList>()
+            .expect("Must be a DropDownList");
+
+        // Effect list might change over time if some plugins were reloaded. The list has
+        // `<Passthrough>` element, so it is one item longer than the list of constructors.
+        if variant_selector_ref.items().len()
+            != editor_environment
+                .serialization_context
+                .effect_constructors
+                .map()
+                .len()
+                + 1
+        {
+            if let Some(items) = new_effect_definitions_items {
+                send_sync_message(
+                    ctx.ui,
+                    DropdownListMessage::items(
+                        instance_ref.variant_selector,
+                        MessageDirection::ToWidget,
+                        items,
+                    ),
+                );
+                send_sync_message(
+                    ctx.ui,
+                    DropdownListMessage::selection(
+                        instance_ref.variant_selector,
+                        MessageDirection::ToWidget,
+                        Some(
+                            selected_effect(
+                                editor_environment.serialization_context.clone(),
+                                value,
+                            )
+                            .unwrap_or(0),
+                        ),
+                    ),
+                );
+            }
+        }
+
+        if instance_ref.selected_effect_uuid != effect_uuid(value)
+            || instance_ref.need_context_update.get()
+        {
+            instance_ref.need_context_update.set(false);
+
+            send_sync_message(
+                ctx.ui,
+                CustomEffectPropertyEditorMessage::value(
+                    ctx.instance,
+                    MessageDirection::ToWidget,
+                    effect_uuid(value),
+                ),
+            );
+
+            let inspector = instance_ref.inspector;
+
+            let context = value
+                .instance()
+                .map(|_| {
+                    InspectorContext::from_object(
+                        value,
+                        &mut ctx.ui.build_ctx(),
+                        ctx.definition_container.clone(),
+                        ctx.environment.clone(),
+                        ctx.sync_flag,
+                        ctx.layer_index + 1,
+                        ctx.generate_property_string_values,
+                    )
+                })
+                .unwrap_or_default();
+
+            let mut msg = InspectorMessage::context(inspector, MessageDirection::ToWidget, context);
+            msg.flags = MSG_SYNC_FLAG;
+            Ok(Some(msg))
+        } else if value.instance().is_some() {
+            let layer_index = ctx.layer_index;
+            let inspector_ctx = ctx
+                .ui
+                .node(instance_ref.inspector)
+                .cast::<Inspector>()
+                .expect("Must be Inspector!")
+                .context()
+                .clone();
+
+            if let Err(e) = inspector_ctx.sync(
+                value,
+                ctx.ui,
+                layer_index + 1,
+                ctx.generate_property_string_values,
+            ) {
+                Err(InspectorError::Group(e))
+            } else {
+                Ok(None)
+            }
+        } else {
+            // Passthrough effect has no properties.
+            Ok(None)
+        }
+    }
+
+    fn translate_message(&self, ctx: PropertyEditorTranslationContext) -> Option<PropertyChanged> {
+        if ctx.message.direction() == MessageDirection::FromWidget {
+            if let Some(message) = ctx.message.data::<CustomEffectPropertyEditorMessage>() {
+                match message {
+                    CustomEffectPropertyEditorMessage::Value(value) => {
+                        if let Some(env) = EditorEnvironment::try_get_from(&ctx.environment) {
+                            let effect = value
+                                .and_then(|uuid| {
+                                    env.serialization_context
+                                        .effect_constructors
+                                        .try_create(&uuid)
+                                })
+                                .map(CustomEffectWrapper::from)
+                                .unwrap_or_default();
+
+                            return Some(PropertyChanged {
+                                owner_type_id: ctx.owner_type_id,
+                                name: ctx.name.to_string(),
+                                value: FieldKind::object(effect),
+                            });
+                        }
+                    }
+                    CustomEffectPropertyEditorMessage::PropertyChanged(property_changed) => {
+                        // Fields of the wrapper are the fields of the effect instance, so there
+                        // is no need to modify the property path.
+                        return Some(PropertyChanged {
+                            name: ctx.name.to_string(),
+                            owner_type_id: ctx.owner_type_id,
+                            value: FieldKind::Inspectable(Box::new(property_changed.clone())),
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+}
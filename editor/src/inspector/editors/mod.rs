@@ -4,8 +4,10 @@ use crate::{
             AnimationContainerPropertyEditorDefinition, AnimationPropertyEditorDefinition,
             MachinePropertyEditorDefinition,
         },
+        effect::CustomEffectPropertyEditorDefinition,
         handle::NodeHandlePropertyEditorDefinition,
         material::MaterialPropertyEditorDefinition,
+        navmesh::NavmeshPropertyEditorDefinition,
         resource::ResourceFieldPropertyEditorDefinition,
         script::ScriptPropertyEditorDefinition,
         spritesheet::SpriteSheetFramesContainerEditorDefinition,
@@ -17,6 +19,7 @@ use crate::{
 use fyrox::animation::machine::node::blendspace::{BlendSpace, BlendSpacePoint};
 use fyrox::{
     animation::{
+        ik::{IkSolver, IkSolverKind, JointLimits},
         machine::{
             node::BasePoseNode,
             transition::{AndNode, LogicNode, NotNode, OrNode, XorNode},
@@ -26,6 +29,7 @@ use fyrox::{
         AnimationContainer,
     },
     core::{
+        algebra::Vector3,
         futures::executor::block_on,
         parking_lot::Mutex,
         pool::{ErasedHandle, Handle},
@@ -37,7 +41,7 @@ use fyrox::{
     },
     material::{
         shader::{Shader, ShaderError, ShaderState},
-        SharedMaterial,
+        MaterialResource, MaterialResourceError, MaterialState, SharedMaterial,
     },
     resource::{
         curve::{CurveResource, CurveResourceError, CurveResourceState},
@@ -69,7 +73,7 @@ use fyrox::{
         },
         mesh::{
             surface::{Surface, SurfaceSharedData},
-            RenderPath,
+            BlendShape, RenderPath,
         },
         node::{Node, NodeHandle},
         particle_system::{
@@ -79,26 +83,36 @@ use fyrox::{
             },
             EmitterWrapper, ParticleSystemRng,
         },
+        ragdoll::Limb,
         rigidbody::RigidBodyType,
         sound::{
             self,
+            chorus::Chorus,
+            compressor::Compressor,
+            delay::Delay,
+            distortion::{Distortion, DistortionKind},
+            equalizer::{Equalizer, EqualizerBand, EqualizerBandKind},
             filter::{
                 AllPassFilterEffect, BandPassFilterEffect, HighPassFilterEffect,
                 HighShelfFilterEffect, LowPassFilterEffect, LowShelfFilterEffect,
             },
+            occlusion::AcousticMaterial,
             reverb::Reverb,
             Attenuate, AudioBus, Biquad, DistanceModel, Effect, EffectWrapper, SoundBufferResource,
-            SoundBufferResourceLoadError, SoundBufferState, Status,
+            SoundBufferResourceLoadError, SoundBufferState, SoundCone, Status,
         },
         terrain::Layer,
         transform::Transform,
     },
+    utils::navmesh::Navmesh,
 };
 use std::{rc::Rc, sync::mpsc::Sender};
 
 pub mod animation;
+pub mod effect;
 pub mod handle;
 pub mod material;
+pub mod navmesh;
 pub mod resource;
 pub mod script;
 pub mod spritesheet;
@@ -190,6 +204,8 @@ pub fn make_property_editors_container(
     container.insert(InheritablePropertyEditorDefinition::<
         Option<SoundBufferResource>,
     >::new());
+    container.insert(EnumPropertyEditorDefinition::<Vector3<f32>>::new_optional());
+    container.insert(InheritablePropertyEditorDefinition::<Option<Vector3<f32>>>::new());
 
     container.insert(ResourceFieldPropertyEditorDefinition::<
         CurveResource,
@@ -209,6 +225,17 @@ pub fn make_property_editors_container(
     })));
     container.insert(InheritablePropertyEditorDefinition::<Option<Shader>>::new());
 
+    container.insert(ResourceFieldPropertyEditorDefinition::<
+        MaterialResource,
+        MaterialState,
+        MaterialResourceError,
+    >::new(Rc::new(|resource_manager, path| {
+        block_on(resource_manager.request_material(path))
+    })));
+    container.insert(InheritablePropertyEditorDefinition::<
+        Option<MaterialResource>,
+    >::new());
+
     container.register_inheritable_inspectable::<ColorGradingLut>();
     container.register_inheritable_inspectable::<InteractionGroups>();
     container.register_inheritable_inspectable::<GeometrySource>();
@@ -223,6 +250,11 @@ pub fn make_property_editors_container(
     container.register_inheritable_inspectable::<RevoluteJoint>();
     container.register_inheritable_inspectable::<PrismaticJoint>();
     container.register_inheritable_inspectable::<dim2::joint::PrismaticJoint>();
+    container.register_inheritable_inspectable::<RopeJoint>();
+    container.register_inheritable_inspectable::<SpringJoint>();
+    container.register_inheritable_inspectable::<JointMotor>();
+    container.register_inheritable_inspectable::<Limb>();
+    container.insert(VecCollectionPropertyEditorDefinition::<Limb>::new());
 
     container.register_inheritable_inspectable::<Base>();
     container.register_inheritable_inspectable::<BaseLight>();
@@ -238,6 +270,16 @@ pub fn make_property_editors_container(
     container.insert(InspectablePropertyEditorDefinition::<LowShelfFilterEffect>::new());
     container.insert(InspectablePropertyEditorDefinition::<HighShelfFilterEffect>::new());
     container.insert(InspectablePropertyEditorDefinition::<Reverb>::new());
+    container.insert(InspectablePropertyEditorDefinition::<Compressor>::new());
+    container.insert(InspectablePropertyEditorDefinition::<Delay>::new());
+    container.insert(InspectablePropertyEditorDefinition::<Chorus>::new());
+    container.insert(InspectablePropertyEditorDefinition::<Distortion>::new());
+    container.insert(EnumPropertyEditorDefinition::<DistortionKind>::new());
+    container.insert(InspectablePropertyEditorDefinition::<Equalizer>::new());
+    container.insert(InspectablePropertyEditorDefinition::<EqualizerBand>::new());
+    container.insert(VecCollectionPropertyEditorDefinition::<EqualizerBand>::new());
+    container.insert(EnumPropertyEditorDefinition::<EqualizerBandKind>::new());
+    container.insert(CustomEffectPropertyEditorDefinition {});
 
     container.register_inheritable_enum::<Emitter, _>();
 
@@ -301,6 +343,8 @@ pub fn make_property_editors_container(
     container.insert(SurfaceDataPropertyEditorDefinition);
     container.insert(InheritablePropertyEditorDefinition::<SurfaceSharedData>::new());
     container.insert(InheritablePropertyEditorDefinition::<Status>::new());
+    container.register_inheritable_inspectable::<SoundCone>();
+    container.register_inheritable_inspectable::<AcousticMaterial>();
 
     container.insert(InspectablePropertyEditorDefinition::<BasePoseNode>::new());
     container.insert(InspectablePropertyEditorDefinition::<IndexedBlendInput>::new());
@@ -326,6 +370,18 @@ pub fn make_property_editors_container(
     container.insert(MachinePropertyEditorDefinition);
     container.insert(InheritablePropertyEditorDefinition::<Machine>::new());
 
+    container.insert(NavmeshPropertyEditorDefinition);
+    container.insert(InheritablePropertyEditorDefinition::<Navmesh>::new());
+
+    container.insert(EnumPropertyEditorDefinition::<IkSolverKind>::new());
+    container.insert(InspectablePropertyEditorDefinition::<JointLimits>::new());
+    container.insert(VecCollectionPropertyEditorDefinition::<JointLimits>::new());
+    container.insert(InspectablePropertyEditorDefinition::<IkSolver>::new());
+    container.register_inheritable_vec_collection::<IkSolver>();
+
+    container.insert(InspectablePropertyEditorDefinition::<BlendShape>::new());
+    container.register_inheritable_vec_collection::<BlendShape>();
+
     container.insert(EnumPropertyEditorDefinition::<LogicNode>::new());
     container.insert(InspectablePropertyEditorDefinition::<AndNode>::new());
     container.insert(InspectablePropertyEditorDefinition::<OrNode>::new());
@@ -0,0 +1,57 @@
+//! Property editor for navmeshes of navigational mesh scene nodes.
+
+use crate::{inspector::EditorEnvironment, Message};
+use fyrox::{
+    gui::{
+        button::{ButtonBuilder, ButtonMessage},
+        inspector::{
+            editors::{
+                PropertyEditorBuildContext, PropertyEditorDefinition, PropertyEditorInstance,
+                PropertyEditorMessageContext, PropertyEditorTranslationContext,
+            },
+            InspectorError, PropertyChanged,
+        },
+        message::{MessageDirection, UiMessage},
+        widget::WidgetBuilder,
+    },
+    utils::navmesh::Navmesh,
+};
+use std::any::TypeId;
+
+#[derive(Debug)]
+pub struct NavmeshPropertyEditorDefinition;
+
+impl PropertyEditorDefinition for NavmeshPropertyEditorDefinition {
+    fn value_type_id(&self) -> TypeId {
+        TypeId::of::<Navmesh>()
+    }
+
+    fn create_instance(
+        &self,
+        ctx: PropertyEditorBuildContext,
+    ) -> Result<PropertyEditorInstance, InspectorError> {
+        Ok(PropertyEditorInstance::Simple {
+            editor: ButtonBuilder::new(WidgetBuilder::new())
+                .with_text("Bake Navmesh...")
+                .build(ctx.build_context),
+        })
+    }
+
+    fn create_message(
+        &self,
+        _ctx: PropertyEditorMessageContext,
+    ) -> Result<Option<UiMessage>, InspectorError> {
+        Ok(None)
+    }
+
+    fn translate_message(&self, ctx: PropertyEditorTranslationContext) -> Option<PropertyChanged> {
+        if ctx.message.direction() == MessageDirection::FromWidget {
+            if let Some(ButtonMessage::Click) = ctx.message.data() {
+                if let Some(environment) = EditorEnvironment::try_get_from(&ctx.environment) {
+                    environment.sender.send(Message::OpenNavmeshBaker).unwrap();
+                }
+            }
+        }
+        None
+    }
+}
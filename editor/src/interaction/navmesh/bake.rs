@@ -0,0 +1,406 @@
+use crate::{
+    inspector::editors::make_property_editors_container,
+    interaction::navmesh::data_model::Navmesh,
+    scene::{
+        commands::navmesh::{AddNavmeshCommand, SetNavigationalMeshCommand},
+        EditorScene, Selection,
+    },
+    GameEngine, Message, MSG_SYNC_FLAG,
+};
+use fyrox::{
+    core::{
+        algebra::{Matrix4, Point3},
+        pool::Handle,
+        scope_profile,
+    },
+    gui::{
+        button::{ButtonBuilder, ButtonMessage},
+        formatted_text::WrapMode,
+        grid::{Column, GridBuilder, Row},
+        inspector::{InspectorBuilder, InspectorContext, InspectorMessage, PropertyAction},
+        message::{MessageDirection, UiMessage},
+        progress_bar::{ProgressBarBuilder, ProgressBarMessage},
+        scroll_viewer::ScrollViewerBuilder,
+        stack_panel::StackPanelBuilder,
+        text::{TextBuilder, TextMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        window::{WindowBuilder, WindowMessage, WindowTitle},
+        BuildContext, HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface,
+    },
+    scene::{navmesh::NavigationalMesh, node::Node},
+    utils::{
+        lightmap::CancellationToken,
+        log::Log,
+        navmesh::{
+            self,
+            bake::{
+                NavmeshBakingError, NavmeshBakingSettings, NavmeshGeometry, ProgressIndicator,
+                ProgressStage,
+            },
+        },
+    },
+};
+use std::{
+    collections::HashSet,
+    rc::Rc,
+    sync::mpsc::{self, Receiver, Sender},
+};
+
+struct BakingTask {
+    cancellation_token: CancellationToken,
+    progress_indicator: ProgressIndicator,
+    result: Receiver<Result<navmesh::Navmesh, NavmeshBakingError>>,
+    // Selected navigational mesh node that will receive the navmesh, the navmesh is added to the
+    // editor navmeshes if there's no such node.
+    target: Handle<Node>,
+    // Inverse global transform of the target node at the moment when baking was started.
+    target_inv_transform: Matrix4<f32>,
+}
+
+pub struct NavmeshBakeWindow {
+    pub window: Handle<UiNode>,
+    inspector: Handle<UiNode>,
+    progress_bar: Handle<UiNode>,
+    progress_text: Handle<UiNode>,
+    bake: Handle<UiNode>,
+    cancel: Handle<UiNode>,
+    sender: Sender<Message>,
+    settings: NavmeshBakingSettings,
+    task: Option<BakingTask>,
+}
+
+impl NavmeshBakeWindow {
+    pub fn new(ctx: &mut BuildContext, sender: Sender<Message>) -> Self {
+        let inspector;
+        let progress_bar;
+        let progress_text;
+        let bake;
+        let cancel;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(350.0).with_height(370.0))
+            .open(false)
+            .can_minimize(false)
+            .with_title(WindowTitle::text("Bake Navmesh"))
+            .with_content(
+                GridBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child(
+                            TextBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(0)
+                                    .with_margin(Thickness::uniform(2.0)),
+                            )
+                            .with_wrap(WrapMode::Word)
+                            .with_text(
+                                "The navmesh will be baked into the selected navigational mesh \
+                                node. If there's no such node, the navmesh will be added to the \
+                                navmeshes of the scene.",
+                            )
+                            .build(ctx),
+                        )
+                        .with_child(
+                            ScrollViewerBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(1)
+                                    .with_margin(Thickness::uniform(2.0)),
+                            )
+                            .with_content({
+                                inspector = InspectorBuilder::new(WidgetBuilder::new()).build(ctx);
+                                inspector
+                            })
+                            .build(ctx),
+                        )
+                        .with_child({
+                            progress_bar = ProgressBarBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(2)
+                                    .with_margin(Thickness::uniform(2.0)),
+                            )
+                            .build(ctx);
+                            progress_bar
+                        })
+                        .with_child({
+                            progress_text = TextBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(3)
+                                    .with_margin(Thickness::uniform(2.0)),
+                            )
+                            .build(ctx);
+                            progress_text
+                        })
+                        .with_child(
+                            StackPanelBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(4)
+                                    .with_horizontal_alignment(HorizontalAlignment::Right)
+                                    .with_child({
+                                        bake = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .with_width(80.0)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Bake")
+                                        .build(ctx);
+                                        bake
+                                    })
+                                    .with_child({
+                                        cancel = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .with_width(80.0)
+                                                .with_enabled(false)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Cancel")
+                                        .build(ctx);
+                                        cancel
+                                    }),
+                            )
+                            .with_orientation(Orientation::Horizontal)
+                            .build(ctx),
+                        ),
+                )
+                .add_row(Row::strict(50.0))
+                .add_row(Row::stretch())
+                .add_row(Row::strict(20.0))
+                .add_row(Row::strict(20.0))
+                .add_row(Row::strict(25.0))
+                .add_column(Column::stretch())
+                .build(ctx),
+            )
+            .build(ctx);
+
+        Self {
+            window,
+            inspector,
+            progress_bar,
+            progress_text,
+            bake,
+            cancel,
+            sender,
+            settings: Default::default(),
+            task: None,
+        }
+    }
+
+    pub fn open(&self, ui: &mut UserInterface) {
+        ui.send_message(WindowMessage::open(
+            self.window,
+            MessageDirection::ToWidget,
+            true,
+        ));
+
+        self.sync_to_model(ui);
+    }
+
+    fn sync_to_model(&self, ui: &mut UserInterface) {
+        let context = InspectorContext::from_object(
+            &self.settings,
+            &mut ui.build_ctx(),
+            Rc::new(make_property_editors_container(self.sender.clone())),
+            None,
+            MSG_SYNC_FLAG,
+            0,
+            true,
+        );
+        ui.send_message(InspectorMessage::context(
+            self.inspector,
+            MessageDirection::ToWidget,
+            context,
+        ));
+    }
+
+    fn set_baking(&self, ui: &UserInterface, baking: bool) {
+        ui.send_message(WidgetMessage::enabled(
+            self.bake,
+            MessageDirection::ToWidget,
+            !baking,
+        ));
+        ui.send_message(WidgetMessage::enabled(
+            self.inspector,
+            MessageDirection::ToWidget,
+            !baking,
+        ));
+        ui.send_message(WidgetMessage::enabled(
+            self.cancel,
+            MessageDirection::ToWidget,
+            baking,
+        ));
+    }
+
+    fn start_baking(&mut self, editor_scene: &EditorScene, engine: &mut GameEngine) {
+        let scene = &mut engine.scenes[editor_scene.scene];
+
+        let editor_objects = scene
+            .graph
+            .traverse_handle_iter(editor_scene.editor_objects_root)
+            .collect::<HashSet<_>>();
+
+        let cancellation_token = CancellationToken::new();
+        let progress_indicator = ProgressIndicator::new();
+
+        // Geometry must be collected on the main thread, because it needs an access to the scene.
+        let geometry = match NavmeshGeometry::from_scene(
+            scene,
+            |handle, _| !editor_objects.contains(&handle),
+            cancellation_token.clone(),
+            progress_indicator.clone(),
+        ) {
+            Ok(geometry) => geometry,
+            Err(e) => {
+                Log::err(format!("Failed to bake navmesh. Reason: {}", e));
+                return;
+            }
+        };
+
+        let (target, target_inv_transform) = match &editor_scene.selection {
+            Selection::Graph(selection) if selection.is_single_selection() => {
+                let node = selection.nodes()[0];
+                match scene.graph.try_get_of_type::<NavigationalMesh>(node) {
+                    Some(navigational_mesh) => (
+                        node,
+                        navigational_mesh
+                            .global_transform()
+                            .try_inverse()
+                            .unwrap_or_else(Matrix4::identity),
+                    ),
+                    None => (Handle::NONE, Matrix4::identity()),
+                }
+            }
+            _ => (Handle::NONE, Matrix4::identity()),
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        let settings = self.settings.clone();
+        let token = cancellation_token.clone();
+        let progress = progress_indicator.clone();
+        std::thread::spawn(move || {
+            // The receiver could be already dropped, nothing to do in this case.
+            let _ = sender.send(geometry.bake(&settings, token, progress));
+        });
+
+        self.task = Some(BakingTask {
+            cancellation_token,
+            progress_indicator,
+            result: receiver,
+            target,
+            target_inv_transform,
+        });
+
+        self.set_baking(&engine.user_interface, true);
+    }
+
+    pub fn update(&mut self, ui: &UserInterface) {
+        let task = match self.task.as_ref() {
+            Some(task) => task,
+            None => return,
+        };
+
+        match task.result.try_recv() {
+            Ok(result) => {
+                match result {
+                    Ok(navmesh) => {
+                        let command = if task.target.is_some() {
+                            // Baked navmesh is in world coordinates, while the node stores it in its
+                            // local coordinates.
+                            let vertices = navmesh
+                                .vertices()
+                                .iter()
+                                .map(|vertex| {
+                                    task.target_inv_transform
+                                        .transform_point(&Point3::from(vertex.position))
+                                        .coords
+                                })
+                                .collect::<Vec<_>>();
+                            Message::do_scene_command(SetNavigationalMeshCommand::new(
+                                task.target,
+                                navmesh::Navmesh::new(navmesh.triangles(), &vertices),
+                            ))
+                        } else {
+                            Message::do_scene_command(AddNavmeshCommand::new(Navmesh::from_native(
+                                &navmesh,
+                            )))
+                        };
+                        self.sender.send(command).unwrap();
+                    }
+                    Err(NavmeshBakingError::Cancelled) => {
+                        Log::info("Navmesh baking was cancelled.")
+                    }
+                    Err(e) => Log::err(format!("Failed to bake navmesh. Reason: {}", e)),
+                }
+
+                self.task = None;
+                self.set_baking(ui, false);
+                ui.send_message(ProgressBarMessage::progress(
+                    self.progress_bar,
+                    MessageDirection::ToWidget,
+                    0.0,
+                ));
+                ui.send_message(TextMessage::text(
+                    self.progress_text,
+                    MessageDirection::ToWidget,
+                    Default::default(),
+                ));
+            }
+            Err(_) => {
+                let progress_indicator = &task.progress_indicator;
+
+                let stage = match progress_indicator.stage() {
+                    ProgressStage::GeometryCaching => "Caching Geometry",
+                    ProgressStage::Voxelization => "Voxelization",
+                    ProgressStage::Filtering => "Filtering",
+                    ProgressStage::MeshBuilding => "Building Mesh",
+                };
+
+                ui.send_message(ProgressBarMessage::progress(
+                    self.progress_bar,
+                    MessageDirection::ToWidget,
+                    progress_indicator.progress_percent() as f32 / 100.0,
+                ));
+                ui.send_message(TextMessage::text(
+                    self.progress_text,
+                    MessageDirection::ToWidget,
+                    format!(
+                        "Stage {} of 4: {}",
+                        progress_indicator.stage() as u32 + 1,
+                        stage
+                    ),
+                ));
+            }
+        }
+    }
+
+    pub fn handle_ui_message(
+        &mut self,
+        message: &UiMessage,
+        editor_scene: &EditorScene,
+        engine: &mut GameEngine,
+    ) {
+        scope_profile!();
+
+        if let Some(ButtonMessage::Click) = message.data::<ButtonMessage>() {
+            if message.destination() == self.bake {
+                if self.task.is_none() {
+                    self.start_baking(editor_scene, engine);
+                }
+            } else if message.destination() == self.cancel {
+                if let Some(task) = self.task.as_ref() {
+                    task.cancellation_token.cancel();
+                }
+            }
+        } else if let Some(InspectorMessage::PropertyChanged(property_changed)) = message.data() {
+            if message.destination() == self.inspector {
+                PropertyAction::from_field_kind(&property_changed.value).apply(
+                    &property_changed.path(),
+                    &mut self.settings,
+                    &mut Log::verify,
+                );
+            }
+        } else if let Some(WindowMessage::Close) = message.data() {
+            if message.destination() == self.window {
+                // Baking is useless without the window.
+                if let Some(task) = self.task.as_ref() {
+                    task.cancellation_token.cancel();
+                }
+            }
+        }
+    }
+}
@@ -91,6 +91,27 @@ impl Navmesh {
         }
     }
 
+    pub fn from_native(navmesh: &fyrox::utils::navmesh::Navmesh) -> Self {
+        Self {
+            vertices: navmesh
+                .vertices()
+                .iter()
+                .map(|vertex| NavmeshVertex {
+                    position: vertex.position,
+                })
+                .collect(),
+            triangles: navmesh
+                .triangles()
+                .iter()
+                .map(|triangle| NavmeshTriangle {
+                    a: Handle::new(triangle[0], 1),
+                    b: Handle::new(triangle[1], 1),
+                    c: Handle::new(triangle[2], 1),
+                })
+                .collect(),
+        }
+    }
+
     pub fn draw(
         &self,
         drawing_context: &mut SceneDrawingContext,
@@ -5,6 +5,7 @@ use crate::{
         calculate_gizmo_distance_scaling,
         gizmo::move_gizmo::MoveGizmo,
         navmesh::{
+            bake::NavmeshBakeWindow,
             data_model::{Navmesh, NavmeshEdge, NavmeshEntity, NavmeshVertex},
             selection::NavmeshSelection,
         },
@@ -51,6 +52,7 @@ use fyrox::{
 };
 use std::{collections::HashMap, rc::Rc, sync::mpsc::Sender};
 
+pub mod bake;
 pub mod data_model;
 pub mod selection;
 
@@ -58,15 +60,18 @@ pub struct NavmeshPanel {
     pub window: Handle<UiNode>,
     navmeshes: Handle<UiNode>,
     add: Handle<UiNode>,
+    bake: Handle<UiNode>,
     connect: Handle<UiNode>,
     remove: Handle<UiNode>,
     sender: Sender<Message>,
     selected: Handle<Navmesh>,
+    bake_window: NavmeshBakeWindow,
 }
 
 impl NavmeshPanel {
     pub fn new(ctx: &mut BuildContext, sender: Sender<Message>) -> Self {
         let add;
+        let bake;
         let remove;
         let navmeshes;
         let connect;
@@ -115,11 +120,22 @@ impl NavmeshPanel {
                                         .with_text("Remove")
                                         .build(ctx);
                                         remove
+                                    })
+                                    .with_child({
+                                        bake = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .with_margin(Thickness::uniform(1.0))
+                                                .on_column(2),
+                                        )
+                                        .with_text("Bake")
+                                        .build(ctx);
+                                        bake
                                     }),
                             )
                             .add_row(Row::stretch())
                             .add_column(Column::stretch())
                             .add_column(Column::stretch())
+                            .add_column(Column::stretch())
                             .build(ctx),
                         ),
                 )
@@ -131,17 +147,29 @@ impl NavmeshPanel {
             )
             .build(ctx);
 
+        let bake_window = NavmeshBakeWindow::new(ctx, sender.clone());
+
         Self {
             window,
             sender,
             add,
+            bake,
             remove,
             navmeshes,
             connect,
             selected: Default::default(),
+            bake_window,
         }
     }
 
+    pub fn update(&mut self, ui: &UserInterface) {
+        self.bake_window.update(ui);
+    }
+
+    pub fn open_bake_window(&self, ui: &mut UserInterface) {
+        self.bake_window.open(ui);
+    }
+
     pub fn sync_to_model(&mut self, editor_scene: &EditorScene, engine: &mut GameEngine) {
         scope_profile!();
 
@@ -222,11 +250,14 @@ impl NavmeshPanel {
         &mut self,
         message: &UiMessage,
         editor_scene: &EditorScene,
-        engine: &GameEngine,
+        engine: &mut GameEngine,
         edit_mode: &mut EditNavmeshMode,
     ) {
         scope_profile!();
 
+        self.bake_window
+            .handle_ui_message(message, editor_scene, engine);
+
         if let Some(ButtonMessage::Click) = message.data::<ButtonMessage>() {
             if message.destination() == self.add {
                 self.sender
@@ -234,6 +265,8 @@ impl NavmeshPanel {
                         Navmesh::new(),
                     )))
                     .unwrap();
+            } else if message.destination() == self.bake {
+                self.open_bake_window(&mut engine.user_interface);
             } else if message.destination() == self.remove {
                 if editor_scene.navmeshes.is_valid_handle(self.selected) {
                     self.sender
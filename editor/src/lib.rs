@@ -62,7 +62,9 @@ use crate::{
     particle::ParticleSystemPreviewControlPanel,
     scene::{
         commands::{
-            graph::AddModelCommand, make_delete_selection_command, mesh::SetMeshTextureCommand,
+            graph::AddModelCommand,
+            make_delete_selection_command,
+            mesh::{SetMeshMaterialResourceCommand, SetMeshTextureCommand},
             ChangeSelectionCommand, CommandGroup, PasteCommand, SceneCommand, SceneContext,
         },
         is_scene_needs_to_be_saved,
@@ -107,7 +109,7 @@ use fyrox::{
         window::{WindowBuilder, WindowMessage, WindowTitle},
         BuildContext, UiNode, UserInterface, VerticalAlignment,
     },
-    material::{shader::Shader, Material, PropertyValue, SharedMaterial},
+    material::{shader::Shader, Material, MaterialResource, PropertyValue, SharedMaterial},
     plugin::PluginConstructor,
     resource::texture::{CompressionOptions, Texture, TextureKind},
     scene::{
@@ -228,7 +230,9 @@ pub enum Message {
     OpenSettings,
     OpenAnimationEditor,
     OpenAbsmEditor,
+    OpenNavmeshBaker,
     OpenMaterialEditor(SharedMaterial),
+    OpenMaterialResourceEditor(MaterialResource),
     ShowInAssetBrowser(PathBuf),
     SetWorldViewerFilter(String),
     LocateObject {
@@ -1705,6 +1709,19 @@ impl Editor {
         ));
     }
 
+    fn open_material_resource_editor(&mut self, resource: MaterialResource) {
+        let engine = &mut self.engine;
+
+        self.material_editor
+            .set_material_resource(Some(resource), engine);
+
+        engine.user_interface.send_message(WindowMessage::open(
+            self.material_editor.window,
+            MessageDirection::ToWidget,
+            true,
+        ));
+    }
+
     fn poll_ui_messages(&mut self) -> usize {
         scope_profile!();
 
@@ -1768,6 +1785,7 @@ impl Editor {
         }
 
         self.log.update(&mut self.engine);
+        self.navmesh_panel.update(&self.engine.user_interface);
         self.material_editor.update(&mut self.engine);
         self.asset_browser.update(&mut self.engine);
 
@@ -1867,6 +1885,9 @@ impl Editor {
                         );
                     }
                     Message::OpenMaterialEditor(material) => self.open_material_editor(material),
+                    Message::OpenMaterialResourceEditor(resource) => {
+                        self.open_material_resource_editor(resource)
+                    }
                     Message::ShowInAssetBrowser(path) => {
                         self.asset_browser
                             .locate_path(&self.engine.user_interface, path);
@@ -1932,6 +1953,9 @@ impl Editor {
                         self.animation_editor.open(&self.engine.user_interface);
                     }
                     Message::OpenAbsmEditor => self.absm_editor.open(&self.engine.user_interface),
+                    Message::OpenNavmeshBaker => self
+                        .navmesh_panel
+                        .open_bake_window(&mut self.engine.user_interface),
                 }
             }
 
@@ -18,6 +18,7 @@ use fyrox::{
     engine::resource_manager::ResourceManager,
     gui::{
         border::BorderBuilder,
+        button::{ButtonBuilder, ButtonMessage},
         check_box::{CheckBoxBuilder, CheckBoxMessage},
         color::{ColorFieldBuilder, ColorFieldMessage},
         dropdown_list::{DropdownListBuilder, DropdownListMessage},
@@ -39,7 +40,7 @@ use fyrox::{
         window::{WindowBuilder, WindowTitle},
         BuildContext, RcUiNodeHandle, Thickness, UiNode, UserInterface, VerticalAlignment,
     },
-    material::{shader::Shader, Material, PropertyValue, SharedMaterial},
+    material::{shader::Shader, Material, MaterialResource, PropertyValue, SharedMaterial},
     resource::texture::TextureState,
     scene::{
         base::BaseBuilder,
@@ -48,7 +49,7 @@ use fyrox::{
             MeshBuilder,
         },
     },
-    utils::into_gui_texture,
+    utils::{into_gui_texture, log::Log},
 };
 use std::sync::mpsc::Sender;
 
@@ -88,7 +89,9 @@ pub struct MaterialEditor {
     properties: BiDirHashMap<ImmutableString, Handle<UiNode>>,
     preview: PreviewPanel,
     material: Option<SharedMaterial>,
+    material_resource: Option<MaterialResource>,
     available_shaders: Handle<UiNode>,
+    save: Handle<UiNode>,
     shaders_list: Vec<Shader>,
     texture_context_menu: TextureContextMenu,
 }
@@ -251,6 +254,7 @@ impl MaterialEditor {
         let panel;
         let properties_panel;
         let available_shaders;
+        let save;
         let window = WindowBuilder::new(WidgetBuilder::new().with_width(300.0))
             .open(false)
             .with_title(WindowTitle::text("Material Editor"))
@@ -276,10 +280,22 @@ impl MaterialEditor {
                                         .with_close_on_selection(true)
                                         .build(ctx);
                                         available_shaders
+                                    })
+                                    .with_child({
+                                        save = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(2)
+                                                .with_enabled(false)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Save")
+                                        .build(ctx);
+                                        save
                                     }),
                             )
                             .add_column(Column::strict(150.0))
                             .add_column(Column::stretch())
+                            .add_column(Column::strict(60.0))
                             .add_row(Row::strict(25.0))
                             .build(ctx),
                         )
@@ -315,7 +331,9 @@ impl MaterialEditor {
             properties_panel,
             properties: Default::default(),
             material: None,
+            material_resource: None,
             available_shaders,
+            save,
             shaders_list: Default::default(),
         };
 
@@ -373,7 +391,34 @@ impl MaterialEditor {
     }
 
     pub fn set_material(&mut self, material: Option<SharedMaterial>, engine: &mut GameEngine) {
+        self.set_material_internal(material, None, engine);
+    }
+
+    /// Opens the material of the given resource for editing. Unlike [`Self::set_material`], the
+    /// changes could be saved back to the resource file.
+    pub fn set_material_resource(
+        &mut self,
+        resource: Option<MaterialResource>,
+        engine: &mut GameEngine,
+    ) {
+        let material = resource.as_ref().map(|r| r.data_ref().material.clone());
+        self.set_material_internal(material, resource, engine);
+    }
+
+    fn set_material_internal(
+        &mut self,
+        material: Option<SharedMaterial>,
+        resource: Option<MaterialResource>,
+        engine: &mut GameEngine,
+    ) {
         self.material = material;
+        self.material_resource = resource;
+
+        engine.user_interface.send_message(WidgetMessage::enabled(
+            self.save,
+            MessageDirection::ToWidget,
+            self.material_resource.is_some(),
+        ));
 
         if let Some(material) = self.material.clone() {
             engine.scenes[self.preview.scene()].graph[self.preview.model()]
@@ -639,6 +684,14 @@ impl MaterialEditor {
     ) {
         self.preview.handle_message(message, engine);
 
+        if let Some(ButtonMessage::Click) = message.data() {
+            if message.destination() == self.save {
+                if let Some(resource) = self.material_resource.as_ref() {
+                    Log::verify(resource.data_ref().save());
+                }
+            }
+        }
+
         if let Some(material) = self.material.clone() {
             if let Some(msg) = message.data::<DropdownListMessage>() {
                 if message.destination() == self.available_shaders
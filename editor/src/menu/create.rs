@@ -25,6 +25,7 @@ use fyrox::{
             surface::{Surface, SurfaceData, SurfaceSharedData},
             MeshBuilder,
         },
+        navmesh::NavigationalMeshBuilder,
         node::Node,
         particle_system::{
             emitter::{base::BaseEmitterBuilder, sphere::SphereEmitterBuilder},
@@ -82,6 +83,7 @@ pub struct CreateEntityMenu {
     create_cylinder: Handle<UiNode>,
     create_quad: Handle<UiNode>,
     create_decal: Handle<UiNode>,
+    create_navmesh: Handle<UiNode>,
     create_point_light: Handle<UiNode>,
     create_spot_light: Handle<UiNode>,
     create_directional_light: Handle<UiNode>,
@@ -110,6 +112,7 @@ impl CreateEntityMenu {
         let create_camera;
         let create_sprite;
         let create_decal;
+        let create_navmesh;
         let create_particle_system;
         let create_terrain;
         let create_pivot;
@@ -208,6 +211,10 @@ impl CreateEntityMenu {
                 create_decal = create_menu_item("Decal", vec![], ctx);
                 create_decal
             },
+            {
+                create_navmesh = create_menu_item("Navigational Mesh", vec![], ctx);
+                create_navmesh
+            },
         ];
 
         (
@@ -228,6 +235,7 @@ impl CreateEntityMenu {
                 create_sound_source,
                 create_listener,
                 create_decal,
+                create_navmesh,
                 physics_menu,
                 physics2d_menu,
                 dim2_menu,
@@ -354,6 +362,13 @@ impl CreateEntityMenu {
                         )
                     } else if message.destination() == self.create_decal {
                         Some(DecalBuilder::new(BaseBuilder::new().with_name("Decal")).build_node())
+                    } else if message.destination() == self.create_navmesh {
+                        Some(
+                            NavigationalMeshBuilder::new(
+                                BaseBuilder::new().with_name("NavigationalMesh"),
+                            )
+                            .build_node(),
+                        )
                     } else if message.destination() == self.create_listener {
                         Some(
                             ListenerBuilder::new(BaseBuilder::new().with_name("Listener"))
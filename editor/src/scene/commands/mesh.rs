@@ -1,7 +1,7 @@
 use crate::{command::Command, scene::commands::SceneContext};
 use fyrox::{
     core::{pool::Handle, sstorage::ImmutableString},
-    material::{shader::SamplerFallback, PropertyValue},
+    material::{shader::SamplerFallback, MaterialResource, PropertyValue, SharedMaterial},
     resource::texture::Texture,
     scene::{mesh::Mesh, node::Node},
 };
@@ -105,3 +105,64 @@ impl Command for SetMeshTextureCommand {
         }
     }
 }
+
+#[derive(Debug)]
+struct SurfaceMaterialState {
+    resource: Option<MaterialResource>,
+    material: SharedMaterial,
+    overridden: bool,
+}
+
+#[derive(Debug)]
+pub struct SetMeshMaterialResourceCommand {
+    node: Handle<Node>,
+    resource: Option<MaterialResource>,
+    old_states: Vec<SurfaceMaterialState>,
+}
+
+impl SetMeshMaterialResourceCommand {
+    pub fn new(node: Handle<Node>, resource: Option<MaterialResource>) -> Self {
+        Self {
+            node,
+            resource,
+            old_states: Default::default(),
+        }
+    }
+}
+
+impl Command for SetMeshMaterialResourceCommand {
+    fn name(&mut self, _context: &SceneContext) -> String {
+        "Set Material Resource".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut SceneContext) {
+        let mesh: &mut Mesh = context.scene.graph[self.node].as_mesh_mut();
+        self.old_states = mesh
+            .surfaces()
+            .iter()
+            .map(|s| SurfaceMaterialState {
+                resource: s.material_resource().cloned(),
+                material: s.material().clone(),
+                overridden: s.is_material_overridden(),
+            })
+            .collect();
+        for surface in mesh.surfaces_mut() {
+            surface.set_material_resource(self.resource.clone());
+        }
+    }
+
+    fn revert(&mut self, context: &mut SceneContext) {
+        let mesh: &mut Mesh = context.scene.graph[self.node].as_mesh_mut();
+        for (surface, old_state) in mesh
+            .surfaces_mut()
+            .iter_mut()
+            .zip(std::mem::take(&mut self.old_states))
+        {
+            let has_resource = old_state.resource.is_some();
+            surface.set_material_resource(old_state.resource);
+            if !has_resource || old_state.overridden {
+                surface.set_material(old_state.material);
+            }
+        }
+    }
+}
@@ -7,6 +7,7 @@ use crate::scene::commands::SceneContext;
 use crate::scene::Selection;
 use fyrox::core::algebra::Vector3;
 use fyrox::core::pool::{Handle, Ticket};
+use fyrox::scene::{navmesh::NavigationalMesh, node::Node};
 
 #[derive(Debug)]
 pub struct AddNavmeshEdgeCommand {
@@ -557,3 +558,40 @@ impl Command for MoveNavmeshVertexCommand {
         self.set_position(&mut context.editor_scene.navmeshes[self.navmesh], position);
     }
 }
+
+#[derive(Debug)]
+pub struct SetNavigationalMeshCommand {
+    node: Handle<Node>,
+    navmesh: fyrox::utils::navmesh::Navmesh,
+}
+
+impl SetNavigationalMeshCommand {
+    pub fn new(node: Handle<Node>, navmesh: fyrox::utils::navmesh::Navmesh) -> Self {
+        Self { node, navmesh }
+    }
+
+    fn swap(&mut self, context: &mut SceneContext) {
+        // The node could be deleted while the navmesh was baking.
+        if let Some(navigational_mesh) = context
+            .scene
+            .graph
+            .try_get_mut_of_type::<NavigationalMesh>(self.node)
+        {
+            self.navmesh = navigational_mesh.set_navmesh(std::mem::take(&mut self.navmesh));
+        }
+    }
+}
+
+impl Command for SetNavigationalMeshCommand {
+    fn name(&mut self, _context: &SceneContext) -> String {
+        "Set Navigational Mesh".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut SceneContext) {
+        self.swap(context);
+    }
+
+    fn revert(&mut self, context: &mut SceneContext) {
+        self.swap(context);
+    }
+}
@@ -4,7 +4,7 @@ use crate::{
     audio::AudioBusSelection,
     camera::CameraController,
     interaction::navmesh::{
-        data_model::{Navmesh, NavmeshContainer, NavmeshTriangle, NavmeshVertex},
+        data_model::{Navmesh, NavmeshContainer},
         selection::NavmeshSelection,
     },
     scene::clipboard::Clipboard,
@@ -31,8 +31,10 @@ use fyrox::{
             buffer::{VertexAttributeUsage, VertexReadTrait},
             Mesh,
         },
+        navmesh::NavigationalMesh,
         node::Node,
         pivot::PivotBuilder,
+        sound::Sound,
         Scene,
     },
 };
@@ -88,24 +90,7 @@ impl EditorScene {
         let mut navmeshes = NavmeshContainer::default();
 
         for navmesh in scene.navmeshes.iter() {
-            let _ = navmeshes.spawn(Navmesh {
-                vertices: navmesh
-                    .vertices()
-                    .iter()
-                    .map(|vertex| NavmeshVertex {
-                        position: vertex.position,
-                    })
-                    .collect(),
-                triangles: navmesh
-                    .triangles()
-                    .iter()
-                    .map(|triangle| NavmeshTriangle {
-                        a: Handle::new(triangle[0], 1),
-                        b: Handle::new(triangle[1], 1),
-                        c: Handle::new(triangle[2], 1),
-                    })
-                    .collect(),
-            });
+            let _ = navmeshes.spawn(Navmesh::from_native(navmesh));
         }
 
         EditorScene {
@@ -347,6 +332,14 @@ impl EditorScene {
                         false,
                     );
                 }
+            } else if let Some(sound) = node.query_component_ref::<Sound>() {
+                if settings.show_sound_bounds {
+                    draw_sound_bounds(sound, ctx);
+                }
+            } else if let Some(navmesh) = node.query_component_ref::<NavigationalMesh>() {
+                if settings.show_navigational_meshes {
+                    draw_navigational_mesh(navmesh, ctx);
+                }
             }
 
             for &child in node.children() {
@@ -354,6 +347,74 @@ impl EditorScene {
             }
         }
 
+        fn draw_navigational_mesh(navmesh: &NavigationalMesh, ctx: &mut SceneDrawingContext) {
+            // Navmesh is stored in local coordinates of the node.
+            let transform = navmesh.global_transform();
+            let vertices = navmesh.navmesh().vertices();
+            let position = |index: u32| {
+                transform
+                    .transform_point(&Point3::from(vertices[index as usize].position))
+                    .coords
+            };
+
+            for (index, triangle) in navmesh.navmesh().triangles().iter().enumerate() {
+                // Carved triangles are shown as well, so it is easy to find the holes.
+                let color = if navmesh.navmesh().is_triangle_enabled(index) {
+                    Color::GREEN
+                } else {
+                    Color::RED
+                };
+                for (a, b) in [(0, 1), (1, 2), (2, 0)] {
+                    ctx.add_line(Line {
+                        begin: position(triangle[a]),
+                        end: position(triangle[b]),
+                        color,
+                    });
+                }
+            }
+        }
+
+        fn draw_sound_bounds(sound: &Sound, ctx: &mut SceneDrawingContext) {
+            ctx.draw_wire_sphere(sound.global_position(), sound.radius(), 30, Color::BLUE);
+
+            let cone = sound.cone();
+            if cone.is_omnidirectional() {
+                return;
+            }
+
+            // Cones of the sound are oriented along look vector, while cones of the drawing
+            // context are oriented along -Y axis.
+            let transform = Matrix4::new_translation(&sound.global_position())
+                * UnitQuaternion::from_matrix_eps(
+                    &sound.global_transform().basis(),
+                    f32::EPSILON,
+                    16,
+                    UnitQuaternion::identity(),
+                )
+                .to_homogeneous()
+                * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), -std::f32::consts::FRAC_PI_2)
+                    .to_homogeneous();
+
+            let length = sound.radius();
+            for (angle, color) in [
+                (cone.inner_angle, Color::BLUE),
+                (cone.outer_angle, Color::opaque(0, 0, 127)),
+            ] {
+                // Cones wider than half-space cannot be drawn.
+                if angle < std::f32::consts::PI {
+                    ctx.draw_cone(
+                        16,
+                        (angle * 0.5).tan() * length,
+                        length,
+                        transform
+                            * Matrix4::new_translation(&Vector3::new(0.0, -length * 0.5, 0.0)),
+                        color,
+                        false,
+                    );
+                }
+            }
+        }
+
         // Draw pivots.
         draw_recursively(
             scene.graph.get_root(),
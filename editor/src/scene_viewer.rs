@@ -4,7 +4,7 @@ use crate::{
     settings::keys::KeyBindings, utils::enable_widget, AddModelCommand, AssetItem, AssetKind,
     BuildProfile, ChangeSelectionCommand, CommandGroup, DropdownListBuilder, EditorScene,
     GameEngine, GraphSelection, InteractionMode, InteractionModeKind, Message, Mode, SceneCommand,
-    Selection, SetMeshTextureCommand, Settings,
+    Selection, SetMeshMaterialResourceCommand, SetMeshTextureCommand, Settings,
 };
 use fyrox::{
     core::{
@@ -1009,6 +1009,36 @@ impl SceneViewer {
                             }
                         }
                     }
+                    AssetKind::Material => {
+                        let cursor_pos = engine.user_interface.cursor_position();
+                        let rel_pos = cursor_pos - screen_bounds.position;
+                        let graph = &engine.scenes[editor_scene.scene].graph;
+                        if let Some(result) = editor_scene.camera_controller.pick(PickingOptions {
+                            cursor_pos: rel_pos,
+                            graph,
+                            editor_objects_root: editor_scene.editor_objects_root,
+                            screen_size: frame_size,
+                            editor_only: false,
+                            filter: |_, _| true,
+                            ignore_back_faces: settings.selection.ignore_back_faces,
+                            use_picking_loop: true,
+                            only_meshes: false,
+                        }) {
+                            if graph[result.node].is_mesh() {
+                                let material =
+                                    engine.resource_manager.request_material(relative_path);
+
+                                self.sender
+                                    .send(Message::do_scene_command(
+                                        SetMeshMaterialResourceCommand::new(
+                                            result.node,
+                                            Some(material),
+                                        ),
+                                    ))
+                                    .unwrap();
+                            }
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -10,6 +10,11 @@ pub struct DebuggingSettings {
     pub show_light_bounds: bool,
     #[serde(default)]
     pub show_camera_bounds: bool,
+    #[serde(default)]
+    pub show_sound_bounds: bool,
+    #[reflect(description = "Show triangles of navigational mesh scene nodes.")]
+    #[serde(default)]
+    pub show_navigational_meshes: bool,
     #[reflect(description = "Size of pictograms in meters. It is used for objects like lights.")]
     #[serde(default)]
     pub pictogram_size: f32,
@@ -23,6 +28,8 @@ impl Default for DebuggingSettings {
             show_tbn: false,
             show_light_bounds: true,
             show_camera_bounds: true,
+            show_sound_bounds: true,
+            show_navigational_meshes: true,
             pictogram_size: 0.33,
         }
     }
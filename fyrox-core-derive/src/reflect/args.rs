@@ -54,7 +54,9 @@ impl TypeArgs {
             clause.predicates.extend(bounds.iter().cloned());
         }
 
-        if self.hide_all {
+        // Bounds on fields are needed for generic types only, they also must not be added for other
+        // types to allow recursive types (for example, a tree node with `Vec<Self>` children).
+        if self.hide_all || self.generics.type_params().next().is_none() {
             return generics;
         }
 
@@ -37,6 +37,12 @@ fn create_impl_generics(
 ) -> Generics {
     let mut generics = generics.clone();
 
+    // Bounds on fields are needed for generic types only, they also must not be added for other types
+    // to allow recursive types (for example, a tree node with `Vec<Self>` children).
+    if generics.type_params().next().is_none() {
+        return generics;
+    }
+
     // Add where clause for every visited field
     generics.make_where_clause().predicates.extend(
         field_args
@@ -135,6 +135,74 @@ impl Octree {
         &self.nodes
     }
 
+    /// Adds a triangle index to every leaf that intersects given bounds of the triangle. Leaves
+    /// are not split, so the octree becomes less efficient if too many triangles were added.
+    pub fn insert(&mut self, index: u32, bounds: &AxisAlignedBoundingBox) {
+        self.insert_recursive(self.root, index, bounds);
+    }
+
+    fn insert_recursive(
+        &mut self,
+        node: Handle<OctreeNode>,
+        index: u32,
+        bounds: &AxisAlignedBoundingBox,
+    ) {
+        match self.nodes.borrow_mut(node) {
+            OctreeNode::Leaf {
+                indices,
+                bounds: leaf_bounds,
+            } => {
+                if leaf_bounds.intersect_aabb(bounds) && !indices.contains(&index) {
+                    indices.push(index);
+                }
+            }
+            OctreeNode::Branch {
+                bounds: branch_bounds,
+                leaves,
+            } => {
+                if branch_bounds.intersect_aabb(bounds) {
+                    for leaf in *leaves {
+                        self.insert_recursive(leaf, index, bounds)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Removes a triangle index from every leaf that intersects given bounds of the triangle. The
+    /// bounds must be the same as the ones that were used to insert the triangle.
+    pub fn remove(&mut self, index: u32, bounds: &AxisAlignedBoundingBox) {
+        self.remove_recursive(self.root, index, bounds);
+    }
+
+    fn remove_recursive(
+        &mut self,
+        node: Handle<OctreeNode>,
+        index: u32,
+        bounds: &AxisAlignedBoundingBox,
+    ) {
+        match self.nodes.borrow_mut(node) {
+            OctreeNode::Leaf {
+                indices,
+                bounds: leaf_bounds,
+            } => {
+                if leaf_bounds.intersect_aabb(bounds) {
+                    indices.retain(|i| *i != index);
+                }
+            }
+            OctreeNode::Branch {
+                bounds: branch_bounds,
+                leaves,
+            } => {
+                if branch_bounds.intersect_aabb(bounds) {
+                    for leaf in *leaves {
+                        self.remove_recursive(leaf, index, bounds)
+                    }
+                }
+            }
+        }
+    }
+
     pub fn ray_query_static<const CAP: usize>(
         &self,
         ray: &Ray,
@@ -156,6 +156,12 @@ impl AudioBus {
         self.ping_pong_buffer.input_mut()
     }
 
+    // Valid only after rendering, contains processed samples of the bus mixed with the signal of its
+    // child buses.
+    pub(crate) fn output_buffer(&self) -> &[(f32, f32)] {
+        self.ping_pong_buffer.input_ref()
+    }
+
     pub(crate) fn begin_render(&mut self, buffer_size: usize) {
         if self.ping_pong_buffer.capacity() < buffer_size {
             self.ping_pong_buffer.resize(buffer_size);
@@ -164,11 +170,27 @@ impl AudioBus {
         }
     }
 
-    fn apply_effects(&mut self) {
+    fn has_side_chain(&self) -> bool {
+        self.effects.iter().any(|effect| match &effect.0 {
+            Effect::Compressor(compressor) => compressor.side_chain_bus().is_some(),
+            _ => false,
+        })
+    }
+
+    fn apply_effects(&mut self, buses: Option<&Pool<AudioBus>>) {
         // Pass through the chain of effects.
         for effect in self.effects.iter_mut() {
             let (input, output) = self.ping_pong_buffer.input_output_buffers();
-            effect.render(input, output);
+            match &mut effect.0 {
+                Effect::Compressor(compressor) => {
+                    let side_chain = compressor
+                        .side_chain_bus()
+                        .and_then(|name| buses?.iter().find(|bus| bus.name == name))
+                        .map(|bus| bus.ping_pong_buffer.input_ref());
+                    compressor.render_with_side_chain(input, side_chain, output);
+                }
+                effect => effect.render(input, output),
+            }
             self.ping_pong_buffer.swap();
         }
     }
@@ -461,14 +483,27 @@ impl AudioBusGraph {
 
     pub(crate) fn end_render(&mut self, output_device_buffer: &mut [(f32, f32)]) {
         let mut leafs = Vec::new();
+        let mut side_chained = Vec::new();
         for (handle, bus) in self.buses.pair_iter_mut() {
-            bus.apply_effects();
+            if bus.has_side_chain() {
+                side_chained.push(handle);
+            } else {
+                bus.apply_effects(None);
+            }
 
             if bus.child_buses.is_empty() {
                 leafs.push(handle);
             }
         }
 
+        // Buses with side-chained effects are processed last, when the signal of the other buses
+        // is ready.
+        for handle in side_chained {
+            let (ticket, mut bus) = self.buses.take_reserve(handle);
+            bus.apply_effects(Some(&self.buses));
+            self.buses.put_back(ticket, bus);
+        }
+
         for mut leaf in leafs {
             while leaf.is_some() {
                 let mut ctx = self.buses.begin_multi_borrow::<2>();
@@ -504,7 +539,7 @@ impl AudioBusGraph {
 mod test {
     use crate::{
         bus::{AudioBus, AudioBusGraph},
-        effects::{Attenuate, Effect},
+        effects::{compressor::Compressor, Attenuate, Effect},
     };
 
     #[test]
@@ -553,6 +588,40 @@ mod test {
         assert_eq!(output_buffer[0], (1.0, 1.0));
     }
 
+    #[test]
+    fn test_side_chain_compression() {
+        let mut output_buffer = [(0.0f32, 0.0f32)];
+
+        let mut graph = AudioBusGraph::new();
+
+        let mut compressor = Compressor::new(-20.0, 1000.0);
+        compressor.set_attack_time(0.0);
+        compressor.set_side_chain_bus("Dialogue");
+        let mut music = AudioBus::new("Music".to_string());
+        music.add_effect(Effect::Compressor(compressor));
+        let music = graph.add_bus(music, graph.root);
+
+        let dialogue = graph.add_bus(AudioBus::new("Dialogue".to_string()), graph.root);
+
+        graph.begin_render(output_buffer.len());
+
+        for (left, right) in graph.buses[music].input_buffer() {
+            *left = 0.05;
+            *right = 0.05;
+        }
+
+        for (left, right) in graph.buses[dialogue].input_buffer() {
+            *left = 1.0;
+            *right = 1.0;
+        }
+
+        graph.end_render(&mut output_buffer);
+
+        // Music must be ducked by ~20 dB under the loud dialogue.
+        let music_level = graph.buses[music].output_buffer()[0].0;
+        assert!(music_level > 0.0 && music_level < 0.0051);
+    }
+
     #[test]
     fn test_multi_bus_data_flow_with_effects() {
         let mut output_buffer = [(0.0f32, 0.0f32)];
@@ -18,6 +18,7 @@ use crate::{
     source::{SoundSource, Status},
 };
 use fyrox_core::{
+    math::lerpf,
     pool::{Handle, Pool},
     reflect::prelude::*,
     visitor::prelude::*,
@@ -98,7 +99,7 @@ impl PartialEq for SoundContext {
 }
 
 /// Internal state of context.
-#[derive(Default, Debug, Clone, Reflect)]
+#[derive(Debug, Clone, Reflect)]
 pub struct State {
     sources: Pool<SoundSource>,
     listener: Listener,
@@ -107,9 +108,32 @@ pub struct State {
     bus_graph: AudioBusGraph,
     distance_model: DistanceModel,
     paused: bool,
+    #[reflect(min_value = 0.0, step = 1.0)]
+    speed_of_sound: f32,
+    #[reflect(min_value = 0.0, step = 0.05)]
+    doppler_factor: f32,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            sources: Pool::new(),
+            listener: Listener::new(),
+            render_duration: Default::default(),
+            renderer: Renderer::Default,
+            bus_graph: AudioBusGraph::new(),
+            distance_model: DistanceModel::InverseDistance,
+            paused: false,
+            speed_of_sound: State::DEFAULT_SPEED_OF_SOUND,
+            doppler_factor: 1.0,
+        }
+    }
 }
 
 impl State {
+    /// Speed of sound in the air in meters per second.
+    pub const DEFAULT_SPEED_OF_SOUND: f32 = 343.3;
+
     /// Extracts a source from the context and reserves its handle. It is used to temporarily take
     /// ownership over source, and then put node back using given ticket.
     pub fn take_reserve(
@@ -153,6 +177,30 @@ impl State {
         self.distance_model
     }
 
+    /// Sets new speed of sound (in units per second) which is used to calculate Doppler effect.
+    /// Default value is [`Self::DEFAULT_SPEED_OF_SOUND`] which is the speed of sound in the air
+    /// in meters per second. Change it if your game uses different units of length.
+    pub fn set_speed_of_sound(&mut self, speed_of_sound: f32) {
+        self.speed_of_sound = speed_of_sound.max(0.0);
+    }
+
+    /// Returns current speed of sound.
+    pub fn speed_of_sound(&self) -> f32 {
+        self.speed_of_sound
+    }
+
+    /// Sets global Doppler factor, it is multiplied with Doppler factor of each sound source. 0.0 -
+    /// disables Doppler effect for every source, 1.0 - physically correct effect (default), values
+    /// larger than 1.0 exaggerate the effect.
+    pub fn set_doppler_factor(&mut self, doppler_factor: f32) {
+        self.doppler_factor = doppler_factor.max(0.0);
+    }
+
+    /// Returns global Doppler factor.
+    pub fn doppler_factor(&self) -> f32 {
+        self.doppler_factor
+    }
+
     /// Normalizes given frequency using context's sampling rate. Normalized frequency then can be used
     /// to create filters.
     pub fn normalize_frequency(&self, f: f32) -> f32 {
@@ -258,7 +306,21 @@ impl State {
             {
                 if let Some(bus_input_buffer) = self.bus_graph.try_get_bus_input_buffer(&source.bus)
                 {
-                    source.render(output_device_buffer.len());
+                    // Doppler shift is a spatial effect, so it should be scaled by spatial blend
+                    // factor the same way as distance attenuation.
+                    let doppler_pitch = lerpf(
+                        1.0,
+                        source.calculate_doppler_pitch(
+                            &self.listener,
+                            self.speed_of_sound,
+                            self.doppler_factor,
+                        ) as f32,
+                        source.spatial_blend(),
+                    );
+
+                    source.render(output_device_buffer.len(), doppler_pitch as f64);
+                    source.apply_cone_low_pass(&self.listener);
+                    source.apply_occlusion_low_pass();
 
                     match self.renderer {
                         Renderer::Default => {
@@ -304,15 +366,7 @@ impl SoundContext {
     /// because separate thread also uses context.
     pub fn new() -> Self {
         Self {
-            state: Some(Arc::new(Mutex::new(State {
-                sources: Pool::new(),
-                listener: Listener::new(),
-                render_duration: Default::default(),
-                renderer: Renderer::Default,
-                bus_graph: AudioBusGraph::new(),
-                distance_model: DistanceModel::InverseDistance,
-                paused: false,
-            }))),
+            state: Some(Arc::new(Mutex::new(State::default()))),
         }
     }
 
@@ -365,6 +419,8 @@ impl Visit for State {
         self.renderer.visit("Renderer", &mut region)?;
         self.paused.visit("Paused", &mut region)?;
         self.distance_model.visit("DistanceModel", &mut region)?;
+        let _ = self.speed_of_sound.visit("SpeedOfSound", &mut region); // Backward compatibility.
+        let _ = self.doppler_factor.visit("DopplerFactor", &mut region); // Backward compatibility.
 
         Ok(())
     }
@@ -0,0 +1,182 @@
+use crate::{buffer::DataSource, error::SoundError};
+use claxon::FlacReader;
+use std::{
+    fmt::{Debug, Formatter},
+    io::{Read, Seek, SeekFrom},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+// Claxon does not support seeking and does not give access to the source of its reader, so the
+// source is shared between the decoder and its reader. It allows to rewind the source without
+// destroying the reader.
+#[derive(Clone)]
+struct SharedSource(Arc<Mutex<DataSource>>);
+
+impl Read for SharedSource {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0
+            .lock()
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::Other, "poisoned mutex"))?
+            .read(buf)
+    }
+}
+
+/// FLAC decoder
+pub(crate) struct FlacDecoder {
+    source: SharedSource,
+    // Option here is because the reader can be lost if the stream cannot be read again after
+    // rewinding.
+    reader: Option<FlacReader<SharedSource>>,
+    // Interleaved samples of current block.
+    samples: Vec<f32>,
+    // Position of the next sample in the interleaved samples of current block.
+    position: usize,
+    // Reusable buffer for block decoding, it helps to avoid allocations on each block.
+    block_buffer: Vec<i32>,
+    scale: f32,
+    channel_count: usize,
+    sample_rate: usize,
+    total_samples: Option<u64>,
+}
+
+impl Debug for FlacDecoder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FlacDecoder")
+    }
+}
+
+fn is_flac(source: &mut DataSource) -> bool {
+    let pos = source.stream_position().unwrap();
+
+    let is_flac = FlacReader::new(source.by_ref()).is_ok();
+
+    source.seek(SeekFrom::Start(pos)).unwrap();
+
+    is_flac
+}
+
+impl FlacDecoder {
+    pub fn new(mut source: DataSource) -> Result<Self, DataSource> {
+        if is_flac(&mut source) {
+            let source = SharedSource(Arc::new(Mutex::new(source)));
+            let reader = FlacReader::new(source.clone()).unwrap();
+            let info = reader.streaminfo();
+
+            Ok(Self {
+                samples: Default::default(),
+                position: 0,
+                block_buffer: Default::default(),
+                // Samples are stored as signed integers with variable bit depth, we need to
+                // normalize them to [-1; 1] range.
+                scale: 1.0 / (1u64 << (info.bits_per_sample - 1)) as f32,
+                channel_count: info.channels as usize,
+                sample_rate: info.sample_rate as usize,
+                total_samples: info.samples,
+                source,
+                reader: Some(reader),
+            })
+        } else {
+            Err(source)
+        }
+    }
+
+    fn read_next_block(&mut self) -> bool {
+        let reader = match self.reader.as_mut() {
+            Some(reader) => reader,
+            None => return false,
+        };
+
+        let buffer = std::mem::take(&mut self.block_buffer);
+        match reader.blocks().read_next_or_eof(buffer) {
+            Ok(Some(block)) => {
+                self.samples.clear();
+                // Blocks store samples per channel, interleave them.
+                for i in 0..block.duration() {
+                    for channel in 0..block.channels() {
+                        self.samples
+                            .push(block.sample(channel, i) as f32 * self.scale);
+                    }
+                }
+                self.position = 0;
+                self.block_buffer = block.into_buffer();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn rewind(&mut self) -> Result<(), SoundError> {
+        if self.reader.is_none() {
+            // Reader was lost by previous failed rewind.
+            return Err(SoundError::UnsupportedFormat);
+        }
+
+        // Reader is kept untouched on failure, source is still at the same position, so decoding
+        // can be continued.
+        self.source.0.lock()?.rewind()?;
+
+        self.samples.clear();
+        self.position = 0;
+
+        match FlacReader::new(self.source.clone()) {
+            Ok(reader) => {
+                self.reader = Some(reader);
+                Ok(())
+            }
+            Err(_) => {
+                // Drop reader here, the decoder can't produce any samples anymore, but it stays
+                // usable. This is unrecoverable error, but *should* never happen in reality.
+                self.reader = None;
+                Err(SoundError::UnsupportedFormat)
+            }
+        }
+    }
+
+    pub fn time_seek(&mut self, location: Duration) {
+        // There is no way to seek using claxon, so we have to rewind and skip samples until the
+        // desired location is reached. This is slow, but correct.
+        if self.rewind().is_err() {
+            return;
+        }
+
+        let mut samples_to_skip =
+            (location.as_secs_f64() * self.sample_rate as f64) as usize * self.channel_count;
+        while samples_to_skip > 0 && self.read_next_block() {
+            if samples_to_skip < self.samples.len() {
+                self.position = samples_to_skip;
+                break;
+            }
+            samples_to_skip -= self.samples.len();
+            self.position = self.samples.len();
+        }
+    }
+
+    pub fn duration(&self) -> Option<Duration> {
+        self.total_samples
+            .map(|samples| Duration::from_secs_f64(samples as f64 / self.sample_rate as f64))
+    }
+
+    pub fn channel_count(&self) -> usize {
+        self.channel_count
+    }
+
+    pub fn sample_rate(&self) -> usize {
+        self.sample_rate
+    }
+}
+
+impl Iterator for FlacDecoder {
+    type Item = f32;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position >= self.samples.len() && !self.read_next_block() {
+            return None;
+        }
+
+        let sample = self.samples.get(self.position).cloned();
+        self.position += 1;
+        sample
+    }
+}
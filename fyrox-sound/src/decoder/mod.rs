@@ -1,10 +1,12 @@
 use crate::{
     buffer::DataSource,
-    decoder::{vorbis::OggDecoder, wav::WavDecoder},
+    decoder::{flac::FlacDecoder, mp3::Mp3Decoder, vorbis::OggDecoder, wav::WavDecoder},
     error::SoundError,
 };
 use std::time::Duration;
 
+mod flac;
+mod mp3;
 mod vorbis;
 mod wav;
 
@@ -12,6 +14,8 @@ mod wav;
 pub(crate) enum Decoder {
     Wav(WavDecoder),
     Ogg(OggDecoder),
+    Flac(FlacDecoder),
+    Mp3(Mp3Decoder),
 }
 
 impl Iterator for Decoder {
@@ -21,6 +25,8 @@ impl Iterator for Decoder {
         match self {
             Decoder::Wav(wav) => wav.next(),
             Decoder::Ogg(ogg) => ogg.next(),
+            Decoder::Flac(flac) => flac.next(),
+            Decoder::Mp3(mp3) => mp3.next(),
         }
     }
 }
@@ -37,6 +43,17 @@ impl Decoder {
             Ok(ogg_decoder) => return Ok(Decoder::Ogg(ogg_decoder)),
             Err(source) => source,
         };
+        // Try Flac
+        let source = match FlacDecoder::new(source) {
+            Ok(flac_decoder) => return Ok(Decoder::Flac(flac_decoder)),
+            Err(source) => source,
+        };
+        // Try Mp3. It must be the last one, because mp3 decoder skips any junk data until it
+        // finds a valid frame and could be too tolerant to data of other formats.
+        let source = match Mp3Decoder::new(source) {
+            Ok(mp3_decoder) => return Ok(Decoder::Mp3(mp3_decoder)),
+            Err(source) => source,
+        };
         Err(source)
     }
 
@@ -44,6 +61,8 @@ impl Decoder {
         match self {
             Decoder::Wav(wav) => wav.rewind(),
             Decoder::Ogg(ogg) => ogg.rewind(),
+            Decoder::Flac(flac) => flac.rewind(),
+            Decoder::Mp3(mp3) => mp3.rewind(),
         }
     }
 
@@ -51,6 +70,8 @@ impl Decoder {
         match self {
             Decoder::Wav(wav) => wav.time_seek(location),
             Decoder::Ogg(ogg) => ogg.time_seek(location),
+            Decoder::Flac(flac) => flac.time_seek(location),
+            Decoder::Mp3(mp3) => mp3.time_seek(location),
         }
     }
 
@@ -58,6 +79,8 @@ impl Decoder {
         match self {
             Decoder::Wav(wav) => wav.channel_count(),
             Decoder::Ogg(ogg) => ogg.channel_count,
+            Decoder::Flac(flac) => flac.channel_count(),
+            Decoder::Mp3(mp3) => mp3.channel_count(),
         }
     }
 
@@ -65,6 +88,8 @@ impl Decoder {
         match self {
             Decoder::Wav(wav) => wav.sample_rate(),
             Decoder::Ogg(ogg) => ogg.sample_rate,
+            Decoder::Flac(flac) => flac.sample_rate(),
+            Decoder::Mp3(mp3) => mp3.sample_rate(),
         }
     }
 
@@ -76,6 +101,111 @@ impl Decoder {
         match self {
             Decoder::Wav(wav) => wav.duration(),
             Decoder::Ogg(ogg) => ogg.duration(),
+            Decoder::Flac(flac) => flac.duration(),
+            Decoder::Mp3(mp3) => mp3.duration(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{buffer::DataSource, decoder::Decoder};
+    use std::time::Duration;
+
+    fn make_decoder(data: &[u8]) -> Decoder {
+        Decoder::new(DataSource::from_memory(data.to_vec())).unwrap()
+    }
+
+    fn is_silent(samples: &[f32]) -> bool {
+        samples.iter().all(|s| s.abs() < 1.0e-3)
+    }
+
+    fn check_rewind_and_time_seek(mut decoder: Decoder) {
+        let samples = decoder.by_ref().collect::<Vec<_>>();
+        assert!(!samples.is_empty());
+
+        decoder.rewind().unwrap();
+        assert_eq!(decoder.by_ref().collect::<Vec<_>>(), samples);
+
+        let location = Duration::from_millis(100);
+        let offset = (location.as_secs_f64() * decoder.get_sample_rate() as f64) as usize
+            * decoder.get_channel_count();
+        decoder.time_seek(location);
+        assert_eq!(decoder.collect::<Vec<_>>(), &samples[offset..]);
+    }
+
+    // Checks that seeking lands exactly at the requested offset, the data must not be silent,
+    // otherwise samples at any offset would be equal.
+    fn check_seek_accuracy(mut decoder: Decoder) {
+        let samples = decoder.by_ref().collect::<Vec<_>>();
+        assert!(!is_silent(&samples));
+
+        let channel_count = decoder.get_channel_count();
+        let sample_rate = decoder.get_sample_rate() as f64;
+        // Seek backwards and forwards, to the beginning and in the middle of blocks.
+        for millis in [150, 10, 0, 123, 200, 37] {
+            let location = Duration::from_millis(millis);
+            let offset = (location.as_secs_f64() * sample_rate) as usize * channel_count;
+            decoder.time_seek(location);
+            let expected = &samples[offset..offset + 256];
+            assert!(!is_silent(expected));
+            let actual = decoder.by_ref().take(expected.len()).collect::<Vec<_>>();
+            assert_eq!(actual, expected, "seek to {} ms", millis);
+        }
+
+        // Rewinding after partial read must start from the first sample.
+        decoder.rewind().unwrap();
+        let actual = decoder.by_ref().take(1024).collect::<Vec<_>>();
+        assert_eq!(actual, &samples[..1024]);
+    }
+
+    #[test]
+    fn test_flac_decoder() {
+        let decoder = make_decoder(include_bytes!("../../examples/data/sine_440hz.flac"));
+        assert!(matches!(decoder, Decoder::Flac(_)));
+        assert_eq!(decoder.get_channel_count(), 2);
+        assert_eq!(decoder.get_sample_rate(), 44100);
+        assert_eq!(decoder.duration(), Some(Duration::from_millis(250)));
+
+        let samples =
+            make_decoder(include_bytes!("../../examples/data/sine_440hz.flac")).into_samples();
+        // 0.25 seconds of stereo sound, the channels are inverted.
+        assert_eq!(samples.len(), 11025 * 2);
+        for frame in samples.chunks(2) {
+            assert_eq!(frame[0], -frame[1]);
+            assert!(frame[0].abs() <= 0.5);
         }
+
+        check_rewind_and_time_seek(decoder);
+        check_seek_accuracy(make_decoder(include_bytes!(
+            "../../examples/data/sine_440hz.flac"
+        )));
+    }
+
+    #[test]
+    fn test_mp3_decoder() {
+        let decoder = make_decoder(include_bytes!("../../examples/data/silence.mp3"));
+        assert!(matches!(decoder, Decoder::Mp3(_)));
+        assert_eq!(decoder.get_channel_count(), 2);
+        assert_eq!(decoder.get_sample_rate(), 44100);
+        // 40 MPEG-1 Layer III frames, 1152 samples each.
+        let duration = decoder.duration().unwrap().as_secs_f64();
+        assert!((duration - 40.0 * 1152.0 / 44100.0).abs() < 1.0e-6);
+
+        check_rewind_and_time_seek(decoder);
+    }
+
+    #[test]
+    fn test_mp3_decoder_seek() {
+        let decoder = make_decoder(include_bytes!("../../examples/data/tone.mp3"));
+        assert!(matches!(decoder, Decoder::Mp3(_)));
+        assert_eq!(decoder.get_channel_count(), 2);
+        assert_eq!(decoder.get_sample_rate(), 44100);
+        // 40 MPEG-1 Layer I frames, 384 samples each.
+        let duration = decoder.duration().unwrap().as_secs_f64();
+        assert!((duration - 40.0 * 384.0 / 44100.0).abs() < 1.0e-6);
+
+        check_rewind_and_time_seek(make_decoder(include_bytes!("../../examples/data/tone.mp3")));
+        check_seek_accuracy(decoder);
     }
 }
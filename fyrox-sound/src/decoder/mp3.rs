@@ -0,0 +1,303 @@
+use crate::{buffer::DataSource, error::SoundError};
+use minimp3::{Decoder, Frame};
+use std::{
+    fmt::{Debug, Formatter},
+    io::{ErrorKind, Read, Seek, SeekFrom},
+    time::Duration,
+};
+
+/// MP3 decoder
+pub(crate) struct Mp3Decoder {
+    // Option here is because we need to extract the data source on rewind and create new decoder
+    // from it, minimp3 does not support seeking.
+    decoder: Option<Decoder<DataSource>>,
+    frame: Frame,
+    // Position of the next sample in the current frame.
+    position: usize,
+    channel_count: usize,
+    sample_rate: usize,
+    duration: Option<Duration>,
+}
+
+impl Debug for Mp3Decoder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Mp3Decoder")
+    }
+}
+
+fn is_mp3(source: &mut DataSource) -> bool {
+    let pos = source.stream_position().unwrap();
+
+    let is_mp3 = Decoder::new(source.by_ref()).next_frame().is_ok();
+
+    source.seek(SeekFrom::Start(pos)).unwrap();
+
+    is_mp3
+}
+
+// Bit rates in kbps for MPEG-1 and MPEG-2/2.5 for each layer, zero index means "free" bit rate.
+const BIT_RATES: [[[u32; 15]; 3]; 2] = [
+    [
+        [
+            0, 32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448,
+        ],
+        [
+            0, 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384,
+        ],
+        [
+            0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+        ],
+    ],
+    [
+        [
+            0, 32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256,
+        ],
+        [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+        [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+    ],
+];
+
+struct FrameHeader {
+    // Total length of the frame in bytes, including the header.
+    length: usize,
+    // Amount of samples per channel in the frame.
+    samples: u32,
+    sample_rate: u32,
+}
+
+impl FrameHeader {
+    fn parse(header: [u8; 4]) -> Option<Self> {
+        if header[0] != 0xFF || header[1] & 0xE0 != 0xE0 {
+            return None;
+        }
+
+        // 0 - MPEG-2.5, 1 - reserved, 2 - MPEG-2, 3 - MPEG-1
+        let version = (header[1] >> 3) & 0b11;
+        // 0 - reserved, 1 - Layer III, 2 - Layer II, 3 - Layer I
+        let layer = (header[1] >> 1) & 0b11;
+        let bit_rate_index = (header[2] >> 4) as usize;
+        let sample_rate_index = ((header[2] >> 2) & 0b11) as usize;
+        let padding = ((header[2] >> 1) & 1) as usize;
+
+        // "Free" bit rate is not supported, frame length cannot be calculated from the header.
+        if version == 1
+            || layer == 0
+            || bit_rate_index == 0
+            || bit_rate_index == 15
+            || sample_rate_index == 3
+        {
+            return None;
+        }
+
+        let is_mpeg1 = version == 3;
+        let sample_rate = [44100, 48000, 32000][sample_rate_index]
+            >> match version {
+                3 => 0,
+                2 => 1,
+                _ => 2,
+            };
+        let bit_rate = BIT_RATES[if is_mpeg1 { 0 } else { 1 }][3 - layer as usize][bit_rate_index]
+            as usize
+            * 1000;
+
+        let (samples, length) = match layer {
+            // Layer I
+            3 => (384, (12 * bit_rate / sample_rate as usize + padding) * 4),
+            // Layer II
+            2 => (1152, 144 * bit_rate / sample_rate as usize + padding),
+            // Layer III
+            _ => {
+                if is_mpeg1 {
+                    (1152, 144 * bit_rate / sample_rate as usize + padding)
+                } else {
+                    (576, 72 * bit_rate / sample_rate as usize + padding)
+                }
+            }
+        };
+
+        Some(Self {
+            length,
+            samples,
+            sample_rate,
+        })
+    }
+}
+
+fn skip_id3v2_tag(source: &mut DataSource) -> std::io::Result<()> {
+    let mut header = [0u8; 10];
+    source.read_exact(&mut header)?;
+    if &header[0..3] == b"ID3" {
+        // Size is stored as "synchsafe" integer - 7 bits per byte.
+        let size = header[6..10]
+            .iter()
+            .fold(0i64, |size, byte| (size << 7) | (*byte & 0x7F) as i64);
+        // Footer presence flag.
+        let footer = if header[5] & 0x10 != 0 { 10 } else { 0 };
+        source.seek(SeekFrom::Current(size + footer))?;
+    } else {
+        source.seek(SeekFrom::Current(-10))?;
+    }
+    Ok(())
+}
+
+fn scan_duration(source: &mut DataSource) -> std::io::Result<Option<Duration>> {
+    skip_id3v2_tag(source)?;
+
+    let mut seconds = 0.0;
+    let mut frame_count = 0;
+    let mut header = [0u8; 4];
+    loop {
+        match source.read_exact(&mut header) {
+            Ok(_) => (),
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err),
+        }
+
+        match FrameHeader::parse(header) {
+            Some(frame) => {
+                seconds += frame.samples as f64 / frame.sample_rate as f64;
+                frame_count += 1;
+                source.seek(SeekFrom::Current(frame.length as i64 - 4))?;
+            }
+            None => {
+                // Junk data or a tag, try to find next frame starting from the next byte.
+                source.seek(SeekFrom::Current(-3))?;
+            }
+        }
+    }
+
+    Ok(if frame_count > 0 {
+        Some(Duration::from_secs_f64(seconds))
+    } else {
+        None
+    })
+}
+
+// Calculates duration of the stream by walking over the headers of its frames, it is much faster
+// than decoding the frames. Position of the source is preserved.
+fn calculate_duration(source: &mut DataSource) -> Option<Duration> {
+    let pos = source.stream_position().ok()?;
+
+    let duration = scan_duration(source).ok().flatten();
+
+    source.seek(SeekFrom::Start(pos)).ok()?;
+
+    duration
+}
+
+impl Mp3Decoder {
+    pub fn new(mut source: DataSource) -> Result<Self, DataSource> {
+        if is_mp3(&mut source) {
+            let duration = calculate_duration(&mut source);
+            Ok(Self::from_source(source, duration))
+        } else {
+            Err(source)
+        }
+    }
+
+    // The source must be checked by `is_mp3` first.
+    fn from_source(source: DataSource, duration: Option<Duration>) -> Self {
+        let mut decoder = Decoder::new(source);
+        // Format of the stream is stored in the frames, so we have to decode the first one
+        // to get it.
+        let frame = decoder.next_frame().unwrap();
+
+        Self {
+            channel_count: frame.channels,
+            sample_rate: frame.sample_rate as usize,
+            frame,
+            position: 0,
+            decoder: Some(decoder),
+            duration,
+        }
+    }
+
+    fn read_next_frame(&mut self) -> bool {
+        if let Some(decoder) = self.decoder.as_mut() {
+            if let Ok(frame) = decoder.next_frame() {
+                self.frame = frame;
+                self.position = 0;
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn rewind(&mut self) -> Result<(), SoundError> {
+        let mut source = match self.decoder.take() {
+            Some(decoder) => decoder.into_inner(),
+            // Source was lost by previous failed rewind.
+            None => return Err(SoundError::UnsupportedFormat),
+        };
+
+        if let Err(err) = source.rewind() {
+            // Source is still at the same position, so decoding can be continued.
+            self.decoder = Some(Decoder::new(source));
+            return Err(err.into());
+        }
+
+        if is_mp3(&mut source) {
+            *self = Self::from_source(source, self.duration);
+            Ok(())
+        } else {
+            // Drop source here, the decoder can't produce any samples anymore, but it stays
+            // usable. This is unrecoverable error, but *should* never happen in reality.
+            self.frame.data.clear();
+            self.position = 0;
+            Err(SoundError::UnsupportedFormat)
+        }
+    }
+
+    pub fn time_seek(&mut self, location: Duration) {
+        // There is no way to seek using minimp3, so we have to rewind and skip frames until the
+        // desired location is reached. This is slow, but correct.
+        if self.rewind().is_err() {
+            return;
+        }
+
+        let mut samples_to_skip =
+            (location.as_secs_f64() * self.sample_rate as f64) as usize * self.channel_count;
+        loop {
+            if samples_to_skip < self.frame.data.len() {
+                self.position = samples_to_skip;
+                break;
+            }
+            samples_to_skip -= self.frame.data.len();
+            self.position = self.frame.data.len();
+            if !self.read_next_frame() {
+                break;
+            }
+        }
+    }
+
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration
+    }
+
+    pub fn channel_count(&self) -> usize {
+        self.channel_count
+    }
+
+    pub fn sample_rate(&self) -> usize {
+        self.sample_rate
+    }
+}
+
+impl Iterator for Mp3Decoder {
+    type Item = f32;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position >= self.frame.data.len() && !self.read_next_frame() {
+            return None;
+        }
+
+        let sample = self
+            .frame
+            .data
+            .get(self.position)
+            .map(|s| *s as f32 / i16::MAX as f32);
+        self.position += 1;
+        sample
+    }
+}
@@ -192,6 +192,10 @@ pub enum BiquadKind {
     /// Reduces amplitude of frequencies in a shape like this _/̅  where location of center of /
     /// defined by F_center.
     HighShelf,
+
+    /// Boosts or reduces amplitude of frequencies in some band around F_center giving _/\_ (or
+    /// ̅ \/̅ ) shape.
+    Peaking,
 }
 
 /// Generic second order digital filter.
@@ -310,6 +314,15 @@ impl Biquad {
                 let a2 = (gain + 1.0) - (gain - 1.0) * w0_cos - sq;
                 (b0, b1, b2, a0, a1, a2)
             }
+            BiquadKind::Peaking => {
+                let b0 = 1.0 + alpha * gain;
+                let b1 = -2.0 * w0_cos;
+                let b2 = 1.0 - alpha * gain;
+                let a0 = 1.0 + alpha / gain;
+                let a1 = -2.0 * w0_cos;
+                let a2 = 1.0 - alpha / gain;
+                (b0, b1, b2, a0, a1, a2)
+            }
         };
 
         self.b0 = b0 / a0;
@@ -0,0 +1,313 @@
+//! Chorus and flanger module.
+//!
+//! # Overview
+//!
+//! Both effects mix the input signal with its copy, delayed by a periodically changing amount of time.
+//! Chorus uses relatively long delay (15-30 ms) and no feedback, this makes a single voice sound like
+//! multiple voices. Flanger uses short delay (1-5 ms) and feedback, this gives a "jet plane" sound.
+//! See [`Chorus::chorus`] and [`Chorus::flanger`] for the presets.
+//!
+//! # Usage
+//!
+//! ```
+//! use fyrox_sound::{
+//!     context::SoundContext,
+//!     effects::{chorus::Chorus, Effect},
+//! };
+//!
+//! fn add_flanger(context: &mut SoundContext) {
+//!     context
+//!         .state()
+//!         .bus_graph_mut()
+//!         .primary_bus_mut()
+//!         .add_effect(Effect::Chorus(Chorus::flanger()));
+//! }
+//! ```
+
+use crate::{context::SAMPLE_RATE, effects::EffectRenderTrait};
+use fyrox_core::{reflect::prelude::*, visitor::prelude::*};
+
+/// Maximum delay (including modulation depth) of the effect in seconds.
+pub const MAX_CHORUS_DELAY: f32 = 0.1;
+
+/// See module docs.
+#[derive(Debug, Clone, PartialEq, Visit, Reflect)]
+pub struct Chorus {
+    #[reflect(
+        description = "Base delay (in seconds) of the delayed copy of the signal.",
+        min_value = 0.0,
+        max_value = 0.1
+    )]
+    delay: f32,
+
+    #[reflect(
+        description = "Amplitude (in seconds) of the delay modulation.",
+        min_value = 0.0,
+        max_value = 0.1
+    )]
+    depth: f32,
+
+    #[reflect(
+        description = "Frequency (in Hz) of the delay modulation.",
+        min_value = 0.0
+    )]
+    rate: f32,
+
+    #[reflect(
+        description = "Amount of the delayed signal that is fed back to the delay line.",
+        min_value = -0.95,
+        max_value = 0.95,
+        step = 0.05
+    )]
+    feedback: f32,
+
+    #[reflect(
+        description = "Amount of the input signal that is passed to the output without any processing.",
+        min_value = 0.0,
+        max_value = 1.0,
+        step = 0.05
+    )]
+    dry: f32,
+
+    #[reflect(
+        description = "Amount of the delayed signal in the output.",
+        min_value = 0.0,
+        max_value = 1.0,
+        step = 0.05
+    )]
+    wet: f32,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    buffer: Vec<(f32, f32)>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    position: usize,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    phase: f32,
+}
+
+impl Default for Chorus {
+    fn default() -> Self {
+        Self::chorus()
+    }
+}
+
+impl Chorus {
+    /// Creates new effect with the given base delay (in seconds), modulation depth (in seconds),
+    /// modulation rate (in Hz) and feedback.
+    pub fn new(delay: f32, depth: f32, rate: f32, feedback: f32) -> Self {
+        Self {
+            delay: delay.clamp(0.0, MAX_CHORUS_DELAY),
+            depth: depth.clamp(0.0, MAX_CHORUS_DELAY),
+            rate: rate.max(0.0),
+            feedback: feedback.clamp(-0.95, 0.95),
+            dry: 1.0,
+            wet: 0.5,
+            buffer: Default::default(),
+            position: 0,
+            phase: 0.0,
+        }
+    }
+
+    /// Creates new chorus effect with 20 ms delay, 5 ms depth, 0.8 Hz rate and no feedback.
+    pub fn chorus() -> Self {
+        Self::new(0.02, 0.005, 0.8, 0.0)
+    }
+
+    /// Creates new flanger effect with 3 ms delay, 2 ms depth, 0.25 Hz rate and 0.7 feedback.
+    pub fn flanger() -> Self {
+        Self::new(0.003, 0.002, 0.25, 0.7)
+    }
+
+    /// Sets base delay (in seconds) of the delayed copy of the signal.
+    pub fn set_delay(&mut self, delay: f32) {
+        self.delay = delay.clamp(0.0, MAX_CHORUS_DELAY);
+    }
+
+    /// Returns current base delay.
+    pub fn delay(&self) -> f32 {
+        self.delay
+    }
+
+    /// Sets amplitude (in seconds) of the delay modulation.
+    pub fn set_depth(&mut self, depth: f32) {
+        self.depth = depth.clamp(0.0, MAX_CHORUS_DELAY);
+    }
+
+    /// Returns current modulation depth.
+    pub fn depth(&self) -> f32 {
+        self.depth
+    }
+
+    /// Sets frequency (in Hz) of the delay modulation.
+    pub fn set_rate(&mut self, rate: f32) {
+        self.rate = rate.max(0.0);
+    }
+
+    /// Returns current modulation rate.
+    pub fn rate(&self) -> f32 {
+        self.rate
+    }
+
+    /// Sets amount of the delayed signal that is fed back to the delay line. The value is clamped
+    /// to `[-0.95; 0.95]` range to keep the effect stable.
+    pub fn set_feedback(&mut self, feedback: f32) {
+        self.feedback = feedback.clamp(-0.95, 0.95);
+    }
+
+    /// Returns current feedback.
+    pub fn feedback(&self) -> f32 {
+        self.feedback
+    }
+
+    /// Sets amount of the input signal that is passed to the output without any processing.
+    pub fn set_dry(&mut self, dry: f32) {
+        self.dry = dry.clamp(0.0, 1.0);
+    }
+
+    /// Returns dry part.
+    pub fn dry(&self) -> f32 {
+        self.dry
+    }
+
+    /// Sets amount of the delayed signal in the output.
+    pub fn set_wet(&mut self, wet: f32) {
+        self.wet = wet.clamp(0.0, 1.0);
+    }
+
+    /// Returns wet part.
+    pub fn wet(&self) -> f32 {
+        self.wet
+    }
+
+    // Reads a sample from the delay line with linear interpolation.
+    fn read(&self, delay_in_samples: f32) -> (f32, f32) {
+        let len = self.buffer.len();
+        let int_delay = delay_in_samples as usize;
+        let frac = delay_in_samples - int_delay as f32;
+        let a = self.buffer[(self.position + len - int_delay % len) % len];
+        let b = self.buffer[(self.position + len - (int_delay + 1) % len) % len];
+        (a.0 + (b.0 - a.0) * frac, a.1 + (b.1 - a.1) * frac)
+    }
+}
+
+impl EffectRenderTrait for Chorus {
+    fn render(&mut self, input: &[(f32, f32)], output: &mut [(f32, f32)]) {
+        // Two extra samples are needed for interpolation.
+        let len = (2.0 * MAX_CHORUS_DELAY * SAMPLE_RATE as f32) as usize + 2;
+        if self.buffer.len() != len {
+            self.buffer = vec![(0.0, 0.0); len];
+            self.position = 0;
+        }
+
+        let sample_rate = SAMPLE_RATE as f32;
+        let phase_step = self.rate * std::f32::consts::TAU / sample_rate;
+        let base_delay = self.delay.clamp(0.0, MAX_CHORUS_DELAY) * sample_rate;
+        let depth = self.depth.clamp(0.0, MAX_CHORUS_DELAY) * sample_rate;
+        let feedback = self.feedback.clamp(-0.95, 0.95);
+
+        for ((input_left, input_right), (output_left, output_right)) in
+            input.iter().zip(output.iter_mut())
+        {
+            // Shift phase of the right channel a bit to make the effect wider.
+            let delay_left = (base_delay + depth * self.phase.sin()).max(1.0);
+            let delay_right =
+                (base_delay + depth * (self.phase + std::f32::consts::FRAC_PI_2).sin()).max(1.0);
+
+            let (delayed_left, _) = self.read(delay_left);
+            let (_, delayed_right) = self.read(delay_right);
+
+            self.buffer[self.position] = (
+                *input_left + delayed_left * feedback,
+                *input_right + delayed_right * feedback,
+            );
+            self.position = (self.position + 1) % len;
+
+            self.phase += phase_step;
+            if self.phase > std::f32::consts::TAU {
+                self.phase -= std::f32::consts::TAU;
+            }
+
+            *output_left = *input_left * self.dry + delayed_left * self.wet;
+            *output_right = *input_right * self.dry + delayed_right * self.wet;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::effects::{chorus::Chorus, EffectRenderTrait};
+
+    // Chorus without modulation is just a fractional delay line.
+    fn make_unmodulated(feedback: f32) -> Chorus {
+        let mut chorus = Chorus::new(0.001, 0.0, 0.0, feedback);
+        chorus.set_dry(0.0);
+        chorus.set_wet(1.0);
+        chorus
+    }
+
+    #[test]
+    fn test_chorus_impulse_response() {
+        let mut chorus = make_unmodulated(0.0);
+
+        let mut input = vec![(0.0, 0.0); 200];
+        input[0] = (1.0, 1.0);
+        let mut output = vec![(0.0, 0.0); input.len()];
+        chorus.render(&input, &mut output);
+
+        // 1 ms at 44100 Hz is 44.1 samples, so the impulse is spread between two samples.
+        for (i, (left, right)) in output.iter().enumerate() {
+            let expected = match i {
+                44 => 0.9,
+                45 => 0.1,
+                _ => 0.0,
+            };
+            assert!((left - expected).abs() < 1.0e-4, "sample {}", i);
+            assert!((right - expected).abs() < 1.0e-4, "sample {}", i);
+        }
+    }
+
+    #[test]
+    fn test_chorus_step_response() {
+        for (feedback, expected) in [(0.0, 1.0), (0.5, 2.0), (-0.5, 2.0 / 3.0)] {
+            let mut chorus = make_unmodulated(feedback);
+
+            let input = vec![(1.0, 1.0); 4410];
+            let mut output = vec![(0.0, 0.0); input.len()];
+            chorus.render(&input, &mut output);
+
+            let (left, right) = *output.last().unwrap();
+            assert!((left - expected).abs() < 1.0e-3);
+            assert!((right - expected).abs() < 1.0e-3);
+        }
+    }
+
+    #[test]
+    fn test_chorus_modulation() {
+        let mut chorus = Chorus::new(0.01, 0.005, 5.0, 0.0);
+        chorus.set_dry(0.0);
+        chorus.set_wet(1.0);
+
+        let mut input = vec![(0.0, 0.0); 1000];
+        input[0] = (1.0, 1.0);
+        let mut output = vec![(0.0, 0.0); input.len()];
+        chorus.render(&input, &mut output);
+
+        // The delay is modulated in [5; 15] ms range, the impulse can't come out of the range.
+        for (i, (left, right)) in output.iter().enumerate() {
+            if !(220..=663).contains(&i) {
+                assert_eq!((*left, *right), (0.0, 0.0), "sample {}", i);
+            }
+        }
+        assert!(output.iter().any(|(left, _)| *left > 0.0));
+        // Channels are modulated with different phases.
+        assert_ne!(
+            output.iter().position(|(left, _)| *left > 0.0),
+            output.iter().position(|(_, right)| *right > 0.0)
+        );
+    }
+}
@@ -0,0 +1,341 @@
+//! Compressor module.
+//!
+//! # Overview
+//!
+//! Compressor reduces dynamic range of a signal - it attenuates the signal when its level exceeds
+//! some threshold. Compressor with very high ratio and short attack time is called limiter, it is
+//! used to prevent the signal from exceeding the threshold at all.
+//!
+//! # Side-chain
+//!
+//! Compressor could use a signal of some other audio bus to detect the level (so called side-chain
+//! compression). This is useful for "ducking": for example a compressor on music bus with side-chain
+//! from dialogue bus will make the music quieter while someone speaks.
+//!
+//! # Usage
+//!
+//! ```
+//! use fyrox_sound::{
+//!     bus::{AudioBus, AudioBusGraph},
+//!     effects::{compressor::Compressor, Effect},
+//! };
+//!
+//! fn add_ducking(graph: &mut AudioBusGraph) {
+//!     let mut music = AudioBus::new("Music".to_string());
+//!     let mut compressor = Compressor::new(-30.0, 4.0);
+//!     compressor.set_side_chain_bus("Dialogue");
+//!     music.add_effect(Effect::Compressor(compressor));
+//!     let primary = graph.primary_bus_handle();
+//!     graph.add_bus(music, primary);
+//!     graph.add_bus(AudioBus::new("Dialogue".to_string()), primary);
+//! }
+//! ```
+
+use crate::{context::SAMPLE_RATE, effects::EffectRenderTrait};
+use fyrox_core::{reflect::prelude::*, visitor::prelude::*};
+
+/// Converts a value in decibels to linear gain.
+pub(crate) fn db_to_gain(db: f32) -> f32 {
+    10.0f32.powf(db / 20.0)
+}
+
+/// Converts linear gain to a value in decibels.
+pub(crate) fn gain_to_db(gain: f32) -> f32 {
+    20.0 * gain.max(1.0e-9).log10()
+}
+
+// Calculates a coefficient of one-pole smoothing filter that reaches ~63% of the target value in
+// the given time.
+fn time_coefficient(time: f32) -> f32 {
+    if time <= 0.0 {
+        0.0
+    } else {
+        (-1.0 / (time * SAMPLE_RATE as f32)).exp()
+    }
+}
+
+/// See module docs.
+#[derive(Debug, Clone, PartialEq, Visit, Reflect)]
+pub struct Compressor {
+    #[reflect(description = "Level (in decibels) above which the signal will be compressed.")]
+    threshold: f32,
+
+    #[reflect(
+        description = "Compression ratio, for example 4.0 means that the signal that is 4 dB \
+        above the threshold will be 1 dB above the threshold after compression.",
+        min_value = 1.0
+    )]
+    ratio: f32,
+
+    #[reflect(
+        description = "Time (in seconds) that is needed for the compressor to react on rising level.",
+        min_value = 0.0
+    )]
+    attack_time: f32,
+
+    #[reflect(
+        description = "Time (in seconds) that is needed for the compressor to react on falling level.",
+        min_value = 0.0
+    )]
+    release_time: f32,
+
+    #[reflect(description = "Gain (in decibels) that is applied after compression.")]
+    makeup_gain: f32,
+
+    #[reflect(
+        description = "A name of an audio bus, that is used to detect the level of the signal. \
+        If empty, the input signal of the compressor is used."
+    )]
+    side_chain_bus: String,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    envelope: f32,
+}
+
+impl Default for Compressor {
+    fn default() -> Self {
+        Self::new(-20.0, 4.0)
+    }
+}
+
+impl Compressor {
+    /// Creates new compressor with the given threshold (in decibels) and ratio, 10 ms attack time and
+    /// 100 ms release time.
+    pub fn new(threshold: f32, ratio: f32) -> Self {
+        Self {
+            threshold,
+            ratio: ratio.max(1.0),
+            attack_time: 0.01,
+            release_time: 0.1,
+            makeup_gain: 0.0,
+            side_chain_bus: Default::default(),
+            envelope: 0.0,
+        }
+    }
+
+    /// Creates new limiter with the given threshold (in decibels). Limiter is a compressor with
+    /// very high ratio and very short attack time.
+    pub fn limiter(threshold: f32) -> Self {
+        Self {
+            ratio: 1000.0,
+            attack_time: 0.0005,
+            release_time: 0.05,
+            ..Self::new(threshold, 1.0)
+        }
+    }
+
+    /// Sets level (in decibels) above which the signal will be compressed.
+    pub fn set_threshold(&mut self, threshold: f32) {
+        self.threshold = threshold;
+    }
+
+    /// Returns current threshold.
+    pub fn threshold(&self) -> f32 {
+        self.threshold
+    }
+
+    /// Sets compression ratio. For example 4.0 means that the signal that is 4 dB above the threshold
+    /// will be 1 dB above the threshold after compression. The value is clamped to `[1.0; inf)` range.
+    pub fn set_ratio(&mut self, ratio: f32) {
+        self.ratio = ratio.max(1.0);
+    }
+
+    /// Returns current compression ratio.
+    pub fn ratio(&self) -> f32 {
+        self.ratio
+    }
+
+    /// Sets time (in seconds) that is needed for the compressor to react on rising level.
+    pub fn set_attack_time(&mut self, attack_time: f32) {
+        self.attack_time = attack_time.max(0.0);
+    }
+
+    /// Returns current attack time.
+    pub fn attack_time(&self) -> f32 {
+        self.attack_time
+    }
+
+    /// Sets time (in seconds) that is needed for the compressor to react on falling level.
+    pub fn set_release_time(&mut self, release_time: f32) {
+        self.release_time = release_time.max(0.0);
+    }
+
+    /// Returns current release time.
+    pub fn release_time(&self) -> f32 {
+        self.release_time
+    }
+
+    /// Sets gain (in decibels) that is applied after compression. It is used to compensate the loss
+    /// of loudness after compression.
+    pub fn set_makeup_gain(&mut self, makeup_gain: f32) {
+        self.makeup_gain = makeup_gain;
+    }
+
+    /// Returns current makeup gain.
+    pub fn makeup_gain(&self) -> f32 {
+        self.makeup_gain
+    }
+
+    /// Sets a name of an audio bus, that will be used to detect the level of the signal. Empty name
+    /// means that the input signal of the compressor is used. The audio bus must not be compressed
+    /// by a side-chained compressor itself, otherwise its signal could be one frame late.
+    pub fn set_side_chain_bus<S: AsRef<str>>(&mut self, name: S) {
+        self.side_chain_bus = name.as_ref().to_owned();
+    }
+
+    /// Returns a name of the side-chain audio bus.
+    pub fn side_chain_bus(&self) -> Option<&str> {
+        if self.side_chain_bus.is_empty() {
+            None
+        } else {
+            Some(&self.side_chain_bus)
+        }
+    }
+
+    pub(crate) fn render_with_side_chain(
+        &mut self,
+        input: &[(f32, f32)],
+        side_chain: Option<&[(f32, f32)]>,
+        output: &mut [(f32, f32)],
+    ) {
+        let attack = time_coefficient(self.attack_time);
+        let release = time_coefficient(self.release_time);
+        let slope = 1.0 - 1.0 / self.ratio;
+        let makeup_gain = self.makeup_gain;
+
+        for (i, ((input_left, input_right), (output_left, output_right))) in
+            input.iter().zip(output.iter_mut()).enumerate()
+        {
+            let (key_left, key_right) = match side_chain {
+                // Treat missing samples of the side-chain as silence.
+                Some(side_chain) => side_chain.get(i).cloned().unwrap_or_default(),
+                None => (*input_left, *input_right),
+            };
+
+            let level = key_left.abs().max(key_right.abs());
+            let k = if level > self.envelope {
+                attack
+            } else {
+                release
+            };
+            self.envelope = level + k * (self.envelope - level);
+
+            let overshoot = gain_to_db(self.envelope) - self.threshold;
+            let reduction = if overshoot > 0.0 {
+                -overshoot * slope
+            } else {
+                0.0
+            };
+            let gain = db_to_gain(reduction + makeup_gain);
+
+            *output_left = *input_left * gain;
+            *output_right = *input_right * gain;
+        }
+    }
+}
+
+impl EffectRenderTrait for Compressor {
+    fn render(&mut self, input: &[(f32, f32)], output: &mut [(f32, f32)]) {
+        self.render_with_side_chain(input, None, output)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        context::SAMPLE_RATE,
+        effects::{
+            compressor::{db_to_gain, gain_to_db, Compressor},
+            EffectRenderTrait,
+        },
+    };
+
+    // Renders the given amount of samples of a constant signal with the given level, returns the
+    // gain of the last sample.
+    fn render_constant(compressor: &mut Compressor, level: f32, count: usize) -> f32 {
+        let input = vec![(level, -level); count];
+        let mut output = vec![(0.0, 0.0); count];
+        compressor.render(&input, &mut output);
+        let (left, right) = output[count - 1];
+        assert_eq!(left, -right);
+        left / level
+    }
+
+    // Restores the level of the envelope of the compressor from the gain that was applied to a
+    // sample.
+    fn envelope_from_gain(compressor: &Compressor, gain: f32) -> f32 {
+        let slope = 1.0 - 1.0 / compressor.ratio();
+        db_to_gain(compressor.threshold() - gain_to_db(gain) / slope)
+    }
+
+    fn samples(time: f32) -> usize {
+        (time * SAMPLE_RATE as f32) as usize
+    }
+
+    #[test]
+    fn test_compressor_ratio() {
+        for ratio in [2.0, 4.0, 10.0] {
+            let mut compressor = Compressor::new(-20.0, ratio);
+            // 0 dB signal is 20 dB above the threshold, it must be 20 / ratio dB above the
+            // threshold after compression.
+            let gain = render_constant(&mut compressor, 1.0, samples(0.5));
+            let expected = -20.0 * (1.0 - 1.0 / ratio);
+            assert!((gain_to_db(gain) - expected).abs() < 0.01, "{}", gain);
+        }
+    }
+
+    #[test]
+    fn test_compressor_attack() {
+        let mut compressor = Compressor::new(-20.0, 4.0);
+        let attack_samples = samples(compressor.attack_time());
+
+        // The envelope must reach ~63% of the level in the attack time.
+        let gain = render_constant(&mut compressor, 1.0, attack_samples);
+        let envelope = envelope_from_gain(&compressor, gain);
+        assert!(
+            (envelope - (1.0 - (-1.0f32).exp())).abs() < 0.01,
+            "{}",
+            envelope
+        );
+
+        // The gain reduction rises during the attack.
+        let next_gain = render_constant(&mut compressor, 1.0, attack_samples);
+        assert!(next_gain < gain);
+    }
+
+    #[test]
+    fn test_compressor_release() {
+        let mut compressor = Compressor::new(-20.0, 4.0);
+        render_constant(&mut compressor, 1.0, samples(0.5));
+
+        // -6 dB signal is still above the threshold, the envelope must fall from 1.0 to 0.5 by ~63%
+        // in the release time.
+        let release_samples = samples(compressor.release_time());
+        let gain = render_constant(&mut compressor, 0.5, release_samples);
+        let envelope = envelope_from_gain(&compressor, gain);
+        let expected = 0.5 + 0.5 * (-1.0f32).exp();
+        assert!((envelope - expected).abs() < 0.01, "{}", envelope);
+
+        // The gain reduction of the steady -6 dB signal.
+        let gain = render_constant(&mut compressor, 0.5, samples(1.0));
+        let expected = (gain_to_db(0.5) + 20.0) * -0.75;
+        assert!((gain_to_db(gain) - expected).abs() < 0.01, "{}", gain);
+    }
+
+    #[test]
+    fn test_compressor_below_threshold() {
+        let mut compressor = Compressor::new(-20.0, 4.0);
+
+        // -26 dB signal must pass through unchanged.
+        let input = (0..samples(0.1))
+            .map(|i| {
+                let sample = 0.05 * (i as f32 * 0.05).sin();
+                (sample, -sample)
+            })
+            .collect::<Vec<_>>();
+        let mut output = vec![(0.0, 0.0); input.len()];
+        compressor.render(&input, &mut output);
+        assert_eq!(input, output);
+    }
+}
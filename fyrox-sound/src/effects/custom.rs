@@ -0,0 +1,556 @@
+//! Custom effects module.
+//!
+//! # Overview
+//!
+//! Built-in effects could be not enough for some specific tasks, custom effects allows you to write
+//! your own digital signal processing units and place them on any audio bus. Custom effect is a type
+//! that implements [`CustomEffect`] trait, it must also be registered in a
+//! [`CustomEffectConstructorContainer`] so it could be restored from a data source by its type UUID.
+//!
+//! # Usage
+//!
+//! ```
+//! use fyrox_core::{reflect::prelude::*, uuid::uuid, uuid::Uuid, visitor::prelude::*};
+//! use fyrox_sound::{
+//!     context::SoundContext,
+//!     effects::{
+//!         custom::{CustomEffect, CustomEffectConstructorContainer, CustomEffectWrapper},
+//!         Effect,
+//!     },
+//! };
+//!
+//! #[derive(Default, Debug, Clone, Visit, Reflect)]
+//! struct BitCrusher {
+//!     levels: f32,
+//! }
+//!
+//! impl CustomEffect for BitCrusher {
+//!     fn id(&self) -> Uuid {
+//!         uuid!("c8a1f1a6-4c4b-4e52-9f0a-5a8f2cf5a0d3")
+//!     }
+//!
+//!     fn render(&mut self, input: &[(f32, f32)], output: &mut [(f32, f32)]) {
+//!         let levels = self.levels.max(1.0);
+//!         for ((in_left, in_right), (out_left, out_right)) in input.iter().zip(output) {
+//!             *out_left = (*in_left * levels).round() / levels;
+//!             *out_right = (*in_right * levels).round() / levels;
+//!         }
+//!     }
+//! }
+//!
+//! fn add_bit_crusher(constructors: &CustomEffectConstructorContainer, context: &mut SoundContext) {
+//!     // Registration is needed only once, it allows to deserialize the effect.
+//!     constructors.add::<BitCrusher>("Bit Crusher");
+//!
+//!     let effect = CustomEffectWrapper::new(BitCrusher { levels: 8.0 });
+//!     context
+//!         .state()
+//!         .bus_graph_mut()
+//!         .primary_bus_mut()
+//!         .add_effect(Effect::Custom(effect));
+//! }
+//! ```
+//!
+//! # Serialization
+//!
+//! Custom effects are restored using their type UUID, so the visitor that is used to load a sound
+//! context must have a [`CustomEffectConstructorContainer`] in its environment. The engine does this
+//! automatically for scenes.
+
+use crate::effects::EffectRenderTrait;
+use fyrox_core::{
+    parking_lot::{Mutex, MutexGuard},
+    reflect::{prelude::*, ReflectArray, ReflectList},
+    uuid::Uuid,
+    visitor::prelude::*,
+};
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    fmt::Debug,
+    ops::{Deref, DerefMut},
+};
+
+/// Base custom effect trait is used to automatically implement some traits to reduce amount of
+/// boilerplate code.
+pub trait BaseCustomEffect: Visit + Reflect + Send + Debug + 'static {
+    /// Creates exact copy of the effect.
+    fn clone_box(&self) -> Box<dyn CustomEffect>;
+
+    /// Casts self as `Any`
+    fn as_any_ref(&self) -> &dyn Any;
+
+    /// Casts self as `Any`
+    fn as_any_ref_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T> BaseCustomEffect for T
+where
+    T: Clone + CustomEffect,
+{
+    fn clone_box(&self) -> Box<dyn CustomEffect> {
+        Box::new(self.clone())
+    }
+
+    fn as_any_ref(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_ref_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// User-defined digital signal processing unit. See module docs for more info.
+pub trait CustomEffect: BaseCustomEffect {
+    /// Effect type UUID. The value will be used for serialization, to write type identifier to a
+    /// data source so the engine can restore the effect from data source. The UUID must be unique
+    /// and must not change between calls.
+    fn id(&self) -> Uuid;
+
+    /// Processes the input samples and writes the result to the output buffer. Both buffers have
+    /// the same length, each sample is a pair of left and right channel values.
+    fn render(&mut self, input: &[(f32, f32)], output: &mut [(f32, f32)]);
+}
+
+/// A wrapper for actual custom effect instance. Default value has no instance and simply passes
+/// the signal through.
+#[derive(Debug, Default)]
+pub struct CustomEffectWrapper {
+    effect: Option<Box<dyn CustomEffect>>,
+    // Type UUID of an effect, that could not be restored on deserialization, because there was no
+    // constructor for it. Such effect passes the signal through, the UUID is kept to not lose the
+    // effect type when the data is saved back.
+    unknown_type: Uuid,
+}
+
+impl From<Box<dyn CustomEffect>> for CustomEffectWrapper {
+    fn from(effect: Box<dyn CustomEffect>) -> Self {
+        Self {
+            effect: Some(effect),
+            unknown_type: Uuid::nil(),
+        }
+    }
+}
+
+impl CustomEffectWrapper {
+    /// Creates new wrapper using the given effect instance.
+    pub fn new<T: CustomEffect>(effect: T) -> Self {
+        Self::from(Box::new(effect) as Box<dyn CustomEffect>)
+    }
+
+    /// Returns a reference to the effect instance, if any.
+    pub fn instance(&self) -> Option<&dyn CustomEffect> {
+        self.effect.as_deref()
+    }
+
+    /// Returns a reference to the effect instance, if any.
+    pub fn instance_mut(&mut self) -> Option<&mut (dyn CustomEffect + 'static)> {
+        self.effect.as_deref_mut()
+    }
+
+    /// Returns type UUID of the effect, that could not be restored on deserialization, because
+    /// there was no constructor for it. Such effect simply passes the signal through.
+    pub fn unknown_type(&self) -> Option<Uuid> {
+        if self.unknown_type.is_nil() {
+            None
+        } else {
+            Some(self.unknown_type)
+        }
+    }
+
+    /// Tries to cast the effect instance to a particular type.
+    pub fn cast<T: CustomEffect>(&self) -> Option<&T> {
+        self.effect
+            .as_ref()
+            .and_then(|effect| effect.as_any_ref().downcast_ref::<T>())
+    }
+
+    /// Tries to cast the effect instance to a particular type.
+    pub fn cast_mut<T: CustomEffect>(&mut self) -> Option<&mut T> {
+        self.effect
+            .as_mut()
+            .and_then(|effect| effect.as_any_ref_mut().downcast_mut::<T>())
+    }
+}
+
+impl Clone for CustomEffectWrapper {
+    fn clone(&self) -> Self {
+        Self {
+            effect: self.effect.as_ref().map(|effect| effect.clone_box()),
+            unknown_type: self.unknown_type,
+        }
+    }
+}
+
+impl PartialEq for CustomEffectWrapper {
+    // There is no way to compare arbitrary effects, so the wrappers are equal only if they share
+    // the same instance (or both are empty).
+    fn eq(&self, other: &Self) -> bool {
+        match (self.effect.as_ref(), other.effect.as_ref()) {
+            (Some(a), Some(b)) => std::ptr::eq(
+                a.deref() as *const dyn CustomEffect as *const u8,
+                b.deref() as *const dyn CustomEffect as *const u8,
+            ),
+            (None, None) => self.unknown_type == other.unknown_type,
+            _ => false,
+        }
+    }
+}
+
+impl Visit for CustomEffectWrapper {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut region = visitor.enter_region(name)?;
+
+        let mut type_uuid = self
+            .effect
+            .as_ref()
+            .map(|e| e.id())
+            .unwrap_or(self.unknown_type);
+        type_uuid.visit("TypeUuid", &mut region)?;
+
+        if region.is_reading() {
+            self.effect = if type_uuid.is_nil() {
+                None
+            } else {
+                region
+                    .environment
+                    .as_ref()
+                    .and_then(|e| e.downcast_ref::<CustomEffectConstructorContainer>())
+                    .and_then(|constructors| constructors.try_create(&type_uuid))
+            };
+
+            // Unknown effect must not break loading of the whole bus graph, it is replaced with
+            // passthrough effect instead. See `unknown_type` method.
+            self.unknown_type = if self.effect.is_none() {
+                type_uuid
+            } else {
+                Uuid::nil()
+            };
+        }
+
+        if let Some(effect) = self.effect.as_mut() {
+            if region.is_reading() {
+                // Data of the effect is missing if the effect was saved while its type was unknown,
+                // keep default values of the effect in this case.
+                let _ = effect.visit("EffectData", &mut region);
+            } else {
+                effect.visit("EffectData", &mut region)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+macro_rules! delegate_or {
+    ($self:ident, $instance:ident => $expr:expr, $default:expr) => {
+        match $self.effect {
+            Some(ref $instance) => $expr,
+            None => $default,
+        }
+    };
+}
+
+macro_rules! delegate_mut_or {
+    ($self:ident, $instance:ident => $expr:expr, $default:expr) => {
+        match $self.effect {
+            Some(ref mut $instance) => $expr,
+            None => $default,
+        }
+    };
+}
+
+// Type related methods are not delegated to the effect instance, so the wrapper could be replaced
+// as a whole (for example, when the type of the effect is changed in the editor). Fields are
+// delegated to the instance.
+impl Reflect for CustomEffectWrapper {
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    fn fields_info(&self, func: &mut dyn FnMut(Vec<FieldInfo>)) {
+        delegate_or!(self, i => i.deref().fields_info(func), func(vec![]))
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+
+    fn as_any(&self, func: &mut dyn FnMut(&dyn Any)) {
+        func(self)
+    }
+
+    fn as_any_mut(&mut self, func: &mut dyn FnMut(&mut dyn Any)) {
+        func(self)
+    }
+
+    fn as_reflect(&self, func: &mut dyn FnMut(&dyn Reflect)) {
+        func(self)
+    }
+
+    fn as_reflect_mut(&mut self, func: &mut dyn FnMut(&mut dyn Reflect)) {
+        func(self)
+    }
+
+    fn set(&mut self, value: Box<dyn Reflect>) -> Result<Box<dyn Reflect>, Box<dyn Reflect>> {
+        let this = std::mem::replace(self, value.take()?);
+        Ok(Box::new(this))
+    }
+
+    fn fields(&self, func: &mut dyn FnMut(Vec<&dyn Reflect>)) {
+        delegate_or!(self, i => i.deref().fields(func), func(vec![]))
+    }
+
+    fn fields_mut(&mut self, func: &mut dyn FnMut(Vec<&mut dyn Reflect>)) {
+        delegate_mut_or!(self, i => i.deref_mut().fields_mut(func), func(vec![]))
+    }
+
+    fn field(&self, name: &str, func: &mut dyn FnMut(Option<&dyn Reflect>)) {
+        delegate_or!(self, i => i.deref().field(name, func), func(None))
+    }
+
+    fn field_mut(&mut self, name: &str, func: &mut dyn FnMut(Option<&mut dyn Reflect>)) {
+        delegate_mut_or!(self, i => i.deref_mut().field_mut(name, func), func(None))
+    }
+
+    fn as_array(&self, func: &mut dyn FnMut(Option<&dyn ReflectArray>)) {
+        delegate_or!(self, i => i.deref().as_array(func), func(None))
+    }
+
+    fn as_array_mut(&mut self, func: &mut dyn FnMut(Option<&mut dyn ReflectArray>)) {
+        delegate_mut_or!(self, i => i.deref_mut().as_array_mut(func), func(None))
+    }
+
+    fn as_list(&self, func: &mut dyn FnMut(Option<&dyn ReflectList>)) {
+        delegate_or!(self, i => i.deref().as_list(func), func(None))
+    }
+
+    fn as_list_mut(&mut self, func: &mut dyn FnMut(Option<&mut dyn ReflectList>)) {
+        delegate_mut_or!(self, i => i.deref_mut().as_list_mut(func), func(None))
+    }
+}
+
+impl EffectRenderTrait for CustomEffectWrapper {
+    fn render(&mut self, input: &[(f32, f32)], output: &mut [(f32, f32)]) {
+        match self.effect.as_mut() {
+            Some(effect) => effect.render(input, output),
+            None => {
+                for (input, output) in input.iter().zip(output.iter_mut()) {
+                    *output = *input;
+                }
+            }
+        }
+    }
+}
+
+/// Custom effect constructor contains all required data and methods to create effect instances
+/// by their UUIDs. It is primarily used for serialization needs.
+pub struct CustomEffectConstructor {
+    /// A simple type alias for boxed effect constructor.
+    pub constructor: Box<dyn FnMut() -> Box<dyn CustomEffect> + Send>,
+
+    /// Effect name.
+    pub name: String,
+}
+
+/// A special container that is able to create custom effects by their type UUID.
+#[derive(Default)]
+pub struct CustomEffectConstructorContainer {
+    // BTreeMap allows to have sorted list of constructors.
+    map: Mutex<BTreeMap<Uuid, CustomEffectConstructor>>,
+}
+
+impl CustomEffectConstructorContainer {
+    /// Creates new empty custom effect constructor container.
+    pub fn new() -> Self {
+        CustomEffectConstructorContainer::default()
+    }
+
+    /// Adds new type constructor for a given type. Type UUID is taken from a default instance of
+    /// the type.
+    ///
+    /// # Panic
+    ///
+    /// The method will panic if there is already a constructor for given type uuid.
+    pub fn add<T>(&self, name: &str) -> &Self
+    where
+        T: CustomEffect + Default,
+    {
+        let old = self.map.lock().insert(
+            T::default().id(),
+            CustomEffectConstructor {
+                constructor: Box::new(|| Box::new(T::default())),
+                name: name.to_owned(),
+            },
+        );
+
+        assert!(old.is_none());
+
+        self
+    }
+
+    /// Adds custom type constructor.
+    ///
+    /// # Panic
+    ///
+    /// The method will panic if there is already a constructor for given type uuid.
+    pub fn add_custom(&self, type_uuid: Uuid, constructor: CustomEffectConstructor) {
+        let old = self.map.lock().insert(type_uuid, constructor);
+
+        assert!(old.is_none());
+    }
+
+    /// Unregisters type constructor.
+    pub fn remove(&self, type_uuid: Uuid) {
+        self.map.lock().remove(&type_uuid);
+    }
+
+    /// Makes an attempt to create an effect using provided type UUID. It may fail if there is no
+    /// effect constructor for specified type UUID.
+    pub fn try_create(&self, type_uuid: &Uuid) -> Option<Box<dyn CustomEffect>> {
+        self.map
+            .lock()
+            .get_mut(type_uuid)
+            .map(|c| (c.constructor)())
+    }
+
+    /// Returns inner map of effect constructors.
+    pub fn map(&self) -> MutexGuard<BTreeMap<Uuid, CustomEffectConstructor>> {
+        self.map.lock()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        bus::AudioBus,
+        effects::{
+            custom::{CustomEffect, CustomEffectConstructorContainer, CustomEffectWrapper},
+            Attenuate, Effect, EffectRenderTrait,
+        },
+    };
+    use fyrox_core::{reflect::prelude::*, uuid::uuid, uuid::Uuid, visitor::prelude::*};
+    use std::sync::Arc;
+
+    #[derive(Default, Debug, Clone, Visit, Reflect)]
+    struct Invert {
+        gain: f32,
+    }
+
+    impl CustomEffect for Invert {
+        fn id(&self) -> Uuid {
+            uuid!("7a3b6a52-2cf4-4b0e-9d5c-0c7e3f5d2b11")
+        }
+
+        fn render(&mut self, input: &[(f32, f32)], output: &mut [(f32, f32)]) {
+            for ((in_left, in_right), (out_left, out_right)) in input.iter().zip(output) {
+                *out_left = -*in_left * self.gain;
+                *out_right = -*in_right * self.gain;
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_effect_render() {
+        let mut effect = CustomEffectWrapper::new(Invert { gain: 2.0 });
+        let mut output = [(0.0, 0.0)];
+        effect.render(&[(1.0, 0.5)], &mut output);
+        assert_eq!(output[0], (-2.0, -1.0));
+        assert_eq!(effect.cast::<Invert>().unwrap().gain, 2.0);
+    }
+
+    #[test]
+    fn test_custom_effect_serialization() {
+        let mut bus = AudioBus::new("Bus".to_string());
+        bus.add_effect(Effect::Custom(CustomEffectWrapper::new(Invert {
+            gain: 3.0,
+        })));
+
+        let mut visitor = Visitor::new();
+        bus.visit("Bus", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        let constructors = CustomEffectConstructorContainer::new();
+        constructors.add::<Invert>("Invert");
+
+        let mut visitor = Visitor::load_from_memory(data).unwrap();
+        visitor.environment = Some(Arc::new(constructors));
+        let mut loaded = AudioBus::default();
+        loaded.visit("Bus", &mut visitor).unwrap();
+
+        match loaded.effect(0) {
+            Some(Effect::Custom(effect)) => {
+                assert_eq!(effect.cast::<Invert>().unwrap().gain, 3.0)
+            }
+            _ => panic!("Custom effect must be loaded!"),
+        }
+    }
+
+    #[test]
+    fn test_unknown_custom_effect_is_passthrough() {
+        let mut bus = AudioBus::new("Bus".to_string());
+        bus.add_effect(Effect::Custom(CustomEffectWrapper::new(Invert {
+            gain: 3.0,
+        })));
+        bus.add_effect(Effect::Attenuate(Attenuate::new(0.5)));
+
+        let mut visitor = Visitor::new();
+        bus.visit("Bus", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        // No constructors, the effect cannot be restored, but the rest of the bus must be loaded.
+        let mut visitor = Visitor::load_from_memory(data).unwrap();
+        visitor.environment = Some(Arc::new(CustomEffectConstructorContainer::new()));
+        let mut loaded = AudioBus::default();
+        loaded.visit("Bus", &mut visitor).unwrap();
+
+        assert!(matches!(loaded.effect(1), Some(Effect::Attenuate(_))));
+        match loaded.effect_mut(0) {
+            Some(Effect::Custom(effect)) => {
+                assert!(effect.instance().is_none());
+                assert_eq!(
+                    effect.unknown_type(),
+                    Some(uuid!("7a3b6a52-2cf4-4b0e-9d5c-0c7e3f5d2b11"))
+                );
+
+                let mut output = [(0.0, 0.0)];
+                effect.render(&[(1.0, 0.5)], &mut output);
+                assert_eq!(output[0], (1.0, 0.5));
+            }
+            _ => panic!("Custom effect must be loaded!"),
+        }
+
+        // Type of the effect must be kept when the data is saved back.
+        let mut visitor = Visitor::new();
+        loaded.visit("Bus", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        let constructors = CustomEffectConstructorContainer::new();
+        constructors.add::<Invert>("Invert");
+        let mut visitor = Visitor::load_from_memory(data).unwrap();
+        visitor.environment = Some(Arc::new(constructors));
+        let mut loaded = AudioBus::default();
+        loaded.visit("Bus", &mut visitor).unwrap();
+
+        match loaded.effect(0) {
+            Some(Effect::Custom(effect)) => assert!(effect.cast::<Invert>().is_some()),
+            _ => panic!("Custom effect must be loaded!"),
+        }
+    }
+
+    #[test]
+    fn test_custom_effect_replacement() {
+        let mut effect = CustomEffectWrapper::default();
+        effect
+            .set(Box::new(CustomEffectWrapper::new(Invert { gain: 2.0 })))
+            .unwrap();
+        assert_eq!(effect.cast::<Invert>().unwrap().gain, 2.0);
+
+        // Fields are delegated to the instance.
+        effect.field_mut("gain", &mut |field| {
+            field.unwrap().set(Box::new(4.0f32)).unwrap();
+        });
+        assert_eq!(effect.cast::<Invert>().unwrap().gain, 4.0);
+    }
+}
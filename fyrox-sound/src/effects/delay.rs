@@ -0,0 +1,231 @@
+//! Delay (echo) module.
+//!
+//! # Overview
+//!
+//! Delay effect repeats the input signal after some time, each repetition is attenuated by the
+//! feedback coefficient. It could be used to simulate echo in large open spaces, such as mountains
+//! or canyons.
+//!
+//! # Usage
+//!
+//! ```
+//! use fyrox_sound::{
+//!     context::SoundContext,
+//!     effects::{delay::Delay, Effect},
+//! };
+//!
+//! fn add_echo(context: &mut SoundContext) {
+//!     let delay = Delay::new(0.35, 0.4);
+//!     context.state().bus_graph_mut().primary_bus_mut().add_effect(Effect::Delay(delay));
+//! }
+//! ```
+
+use crate::{context::SAMPLE_RATE, effects::EffectRenderTrait};
+use fyrox_core::{reflect::prelude::*, visitor::prelude::*};
+
+/// Maximum delay time of the effect in seconds.
+pub const MAX_DELAY_TIME: f32 = 10.0;
+
+/// See module docs.
+#[derive(Debug, Clone, PartialEq, Visit, Reflect)]
+pub struct Delay {
+    #[reflect(
+        description = "Time (in seconds) between repetitions.",
+        min_value = 0.0,
+        max_value = 10.0
+    )]
+    delay_time: f32,
+
+    #[reflect(
+        description = "Attenuation of each repetition.",
+        min_value = 0.0,
+        max_value = 1.0,
+        step = 0.05
+    )]
+    feedback: f32,
+
+    #[reflect(
+        description = "Amount of the input signal that is passed to the output without any processing.",
+        min_value = 0.0,
+        max_value = 1.0,
+        step = 0.05
+    )]
+    dry: f32,
+
+    #[reflect(
+        description = "Amount of the delayed signal in the output.",
+        min_value = 0.0,
+        max_value = 1.0,
+        step = 0.05
+    )]
+    wet: f32,
+
+    // Delay buffers are allocated lazily, when the effect is rendered.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    buffer: Vec<(f32, f32)>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    position: usize,
+}
+
+impl Default for Delay {
+    fn default() -> Self {
+        Self::new(0.25, 0.5)
+    }
+}
+
+impl Delay {
+    /// Creates new delay effect with the given delay time (in seconds) and feedback coefficient.
+    pub fn new(delay_time: f32, feedback: f32) -> Self {
+        Self {
+            delay_time: delay_time.clamp(0.0, MAX_DELAY_TIME),
+            feedback: feedback.clamp(0.0, 1.0),
+            dry: 1.0,
+            wet: 0.5,
+            buffer: Default::default(),
+            position: 0,
+        }
+    }
+
+    /// Sets time (in seconds) between repetitions. The value is clamped to `[0.0; MAX_DELAY_TIME]` range.
+    pub fn set_delay_time(&mut self, delay_time: f32) {
+        self.delay_time = delay_time.clamp(0.0, MAX_DELAY_TIME);
+    }
+
+    /// Returns current delay time.
+    pub fn delay_time(&self) -> f32 {
+        self.delay_time
+    }
+
+    /// Sets attenuation of each repetition. The value is clamped to `[0.0; 1.0]` range, 1.0 means
+    /// that repetitions won't decay at all.
+    pub fn set_feedback(&mut self, feedback: f32) {
+        self.feedback = feedback.clamp(0.0, 1.0);
+    }
+
+    /// Returns current feedback coefficient.
+    pub fn feedback(&self) -> f32 {
+        self.feedback
+    }
+
+    /// Sets amount of the input signal that is passed to the output without any processing.
+    pub fn set_dry(&mut self, dry: f32) {
+        self.dry = dry.clamp(0.0, 1.0);
+    }
+
+    /// Returns dry part.
+    pub fn dry(&self) -> f32 {
+        self.dry
+    }
+
+    /// Sets amount of the delayed signal in the output.
+    pub fn set_wet(&mut self, wet: f32) {
+        self.wet = wet.clamp(0.0, 1.0);
+    }
+
+    /// Returns wet part.
+    pub fn wet(&self) -> f32 {
+        self.wet
+    }
+}
+
+impl EffectRenderTrait for Delay {
+    fn render(&mut self, input: &[(f32, f32)], output: &mut [(f32, f32)]) {
+        let len =
+            ((self.delay_time.clamp(0.0, MAX_DELAY_TIME) * SAMPLE_RATE as f32) as usize).max(1);
+        if self.buffer.len() != len {
+            // Delay time has changed (or the effect was just created), the old data is discarded.
+            self.buffer = vec![(0.0, 0.0); len];
+            self.position = 0;
+        }
+
+        let feedback = self.feedback.clamp(0.0, 1.0);
+        for ((input_left, input_right), (output_left, output_right)) in
+            input.iter().zip(output.iter_mut())
+        {
+            let (delayed_left, delayed_right) = self.buffer[self.position];
+
+            self.buffer[self.position] = (
+                *input_left + delayed_left * feedback,
+                *input_right + delayed_right * feedback,
+            );
+            self.position = (self.position + 1) % len;
+
+            *output_left = *input_left * self.dry + delayed_left * self.wet;
+            *output_right = *input_right * self.dry + delayed_right * self.wet;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::effects::{delay::Delay, EffectRenderTrait};
+
+    fn impulse(len: usize) -> Vec<(f32, f32)> {
+        let mut input = vec![(0.0, 0.0); len];
+        input[0] = (1.0, 1.0);
+        input
+    }
+
+    #[test]
+    fn test_delay_impulse_response() {
+        // 1 ms at 44100 Hz is 44 samples.
+        let mut delay = Delay::new(0.001, 0.5);
+        delay.set_wet(1.0);
+
+        let input = impulse(200);
+        let mut output = vec![(0.0, 0.0); input.len()];
+        delay.render(&input, &mut output);
+
+        for (i, (left, right)) in output.iter().enumerate() {
+            let expected = match i {
+                0 => 1.0,
+                44 => 1.0,
+                88 => 0.5,
+                132 => 0.25,
+                176 => 0.125,
+                _ => 0.0,
+            };
+            assert_eq!(*left, expected, "sample {}", i);
+            assert_eq!(*right, expected, "sample {}", i);
+        }
+    }
+
+    #[test]
+    fn test_delay_keeps_state_between_blocks() {
+        let input = impulse(200);
+
+        let mut delay = Delay::new(0.001, 0.5);
+        let mut expected = vec![(0.0, 0.0); input.len()];
+        delay.render(&input, &mut expected);
+
+        let mut delay = Delay::new(0.001, 0.5);
+        let mut output = vec![(0.0, 0.0); input.len()];
+        for (input, output) in input.chunks(30).zip(output.chunks_mut(30)) {
+            delay.render(input, output);
+        }
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_delay_step_response() {
+        // Each repetition is added to the signal, so constant input converges to 1 / (1 - feedback).
+        let mut delay = Delay::new(0.001, 0.5);
+        delay.set_dry(0.0);
+        delay.set_wet(1.0);
+
+        let input = vec![(1.0, 1.0); 4410];
+        let mut output = vec![(0.0, 0.0); input.len()];
+        delay.render(&input, &mut output);
+
+        assert_eq!(output[0], (0.0, 0.0));
+        assert_eq!(output[44], (1.0, 1.0));
+        assert_eq!(output[88], (1.5, 1.5));
+        let (left, right) = *output.last().unwrap();
+        assert!((left - 2.0).abs() < 1.0e-3);
+        assert!((right - 2.0).abs() < 1.0e-3);
+    }
+}
@@ -0,0 +1,218 @@
+//! Distortion module.
+//!
+//! # Overview
+//!
+//! Distortion amplifies the input signal and then clips it, which adds a lot of harmonics to the
+//! signal. It could be used for radio transmissions, megaphones, damaged speakers, etc.
+
+use crate::effects::EffectRenderTrait;
+use fyrox_core::{reflect::prelude::*, visitor::prelude::*};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+/// Defines how the amplified signal will be clipped.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, Visit, Reflect, AsRefStr, EnumString, EnumVariantNames,
+)]
+pub enum DistortionKind {
+    /// Smooth clipping using hyperbolic tangent, gives "warm" tube-like sound.
+    SoftClip,
+    /// Hard clipping of the signal at `[-1.0; 1.0]` range, gives harsh sound.
+    HardClip,
+    /// The signal is folded back when it exceeds `[-1.0; 1.0]` range, gives metallic sound.
+    Foldback,
+}
+
+impl Default for DistortionKind {
+    fn default() -> Self {
+        Self::SoftClip
+    }
+}
+
+/// See module docs.
+#[derive(Debug, Clone, PartialEq, Visit, Reflect)]
+pub struct Distortion {
+    #[reflect(description = "Clipping function of the distortion.")]
+    kind: DistortionKind,
+
+    #[reflect(
+        description = "Amplification of the input signal before clipping.",
+        min_value = 1.0
+    )]
+    drive: f32,
+
+    #[reflect(
+        description = "Gain of the output signal.",
+        min_value = 0.0,
+        step = 0.05
+    )]
+    output_gain: f32,
+
+    #[reflect(
+        description = "Amount of the distorted signal in the output.",
+        min_value = 0.0,
+        max_value = 1.0,
+        step = 0.05
+    )]
+    wet: f32,
+}
+
+impl Default for Distortion {
+    fn default() -> Self {
+        Self::new(DistortionKind::SoftClip, 4.0)
+    }
+}
+
+impl Distortion {
+    /// Creates new distortion effect of the given kind and drive.
+    pub fn new(kind: DistortionKind, drive: f32) -> Self {
+        Self {
+            kind,
+            drive: drive.max(1.0),
+            output_gain: 0.5,
+            wet: 1.0,
+        }
+    }
+
+    /// Sets clipping function of the distortion.
+    pub fn set_kind(&mut self, kind: DistortionKind) {
+        self.kind = kind;
+    }
+
+    /// Returns clipping function of the distortion.
+    pub fn kind(&self) -> DistortionKind {
+        self.kind
+    }
+
+    /// Sets amplification of the input signal before clipping, the higher the value, the more
+    /// distorted the signal will be.
+    pub fn set_drive(&mut self, drive: f32) {
+        self.drive = drive.max(1.0);
+    }
+
+    /// Returns current drive.
+    pub fn drive(&self) -> f32 {
+        self.drive
+    }
+
+    /// Sets gain of the output signal.
+    pub fn set_output_gain(&mut self, output_gain: f32) {
+        self.output_gain = output_gain.max(0.0);
+    }
+
+    /// Returns current output gain.
+    pub fn output_gain(&self) -> f32 {
+        self.output_gain
+    }
+
+    /// Sets amount of the distorted signal in the output, the rest is the input signal.
+    pub fn set_wet(&mut self, wet: f32) {
+        self.wet = wet.clamp(0.0, 1.0);
+    }
+
+    /// Returns wet part.
+    pub fn wet(&self) -> f32 {
+        self.wet
+    }
+
+    fn distort(&self, sample: f32) -> f32 {
+        let sample = sample * self.drive;
+        match self.kind {
+            DistortionKind::SoftClip => sample.tanh(),
+            DistortionKind::HardClip => sample.clamp(-1.0, 1.0),
+            DistortionKind::Foldback => {
+                if sample.abs() > 1.0 {
+                    // Reflect the signal from the [-1; 1] bounds.
+                    ((sample - 1.0).rem_euclid(4.0) - 2.0).abs() - 1.0
+                } else {
+                    sample
+                }
+            }
+        }
+    }
+}
+
+impl EffectRenderTrait for Distortion {
+    fn render(&mut self, input: &[(f32, f32)], output: &mut [(f32, f32)]) {
+        let dry = 1.0 - self.wet;
+        for ((input_left, input_right), (output_left, output_right)) in
+            input.iter().zip(output.iter_mut())
+        {
+            *output_left =
+                (self.distort(*input_left) * self.wet + *input_left * dry) * self.output_gain;
+            *output_right =
+                (self.distort(*input_right) * self.wet + *input_right * dry) * self.output_gain;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::effects::{
+        distortion::{Distortion, DistortionKind},
+        EffectRenderTrait,
+    };
+
+    fn render_sample(distortion: &mut Distortion, sample: f32) -> f32 {
+        let mut output = [(0.0, 0.0)];
+        distortion.render(&[(sample, -sample)], &mut output);
+        // All clipping functions are odd.
+        assert!((output[0].0 + output[0].1).abs() < 1.0e-5);
+        output[0].0
+    }
+
+    fn make_distortion(kind: DistortionKind) -> Distortion {
+        let mut distortion = Distortion::new(kind, 4.0);
+        distortion.set_output_gain(1.0);
+        distortion
+    }
+
+    #[test]
+    fn test_hard_clip() {
+        let mut distortion = make_distortion(DistortionKind::HardClip);
+        assert!((render_sample(&mut distortion, 0.1) - 0.4).abs() < 1.0e-6);
+        assert_eq!(render_sample(&mut distortion, 0.5), 1.0);
+        assert_eq!(render_sample(&mut distortion, -0.5), -1.0);
+    }
+
+    #[test]
+    fn test_soft_clip() {
+        let mut distortion = make_distortion(DistortionKind::SoftClip);
+        assert_eq!(render_sample(&mut distortion, 0.0), 0.0);
+        assert!((render_sample(&mut distortion, 0.5) - 2.0f32.tanh()).abs() < 1.0e-6);
+        assert!(render_sample(&mut distortion, 10.0) <= 1.0);
+    }
+
+    #[test]
+    fn test_foldback() {
+        let mut distortion = make_distortion(DistortionKind::Foldback);
+        assert!((render_sample(&mut distortion, 0.2) - 0.8).abs() < 1.0e-6);
+        // 1.5 is folded back to 0.5, 2.0 - to 0.0.
+        assert!((render_sample(&mut distortion, 0.375) - 0.5).abs() < 1.0e-6);
+        assert!((render_sample(&mut distortion, -0.375) + 0.5).abs() < 1.0e-6);
+        assert!(render_sample(&mut distortion, 0.5).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn test_distortion_output_is_bounded() {
+        for kind in [
+            DistortionKind::SoftClip,
+            DistortionKind::HardClip,
+            DistortionKind::Foldback,
+        ] {
+            let mut distortion = make_distortion(kind);
+            distortion.set_output_gain(0.5);
+            for i in -100..=100 {
+                let sample = i as f32 * 0.1;
+                assert!(render_sample(&mut distortion, sample).abs() <= 0.5 + 1.0e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_distortion_dry_signal() {
+        let mut distortion = make_distortion(DistortionKind::HardClip);
+        distortion.set_wet(0.0);
+        distortion.set_output_gain(0.5);
+        assert_eq!(render_sample(&mut distortion, 0.5), 0.25);
+    }
+}
@@ -0,0 +1,267 @@
+//! Equalizer module.
+//!
+//! # Overview
+//!
+//! Multi-band parametric equalizer allows you to boost or cut specific frequency ranges of a signal.
+//! Each band is a separate filter, bands are applied one after another.
+//!
+//! # Usage
+//!
+//! ```
+//! use fyrox_sound::{
+//!     context::SoundContext,
+//!     effects::{
+//!         equalizer::{Equalizer, EqualizerBand, EqualizerBandKind},
+//!         Effect,
+//!     },
+//! };
+//!
+//! fn add_equalizer(context: &mut SoundContext) {
+//!     let equalizer = Equalizer::new(vec![
+//!         EqualizerBand::new(EqualizerBandKind::LowShelf, 120.0, 3.0, 0.7),
+//!         EqualizerBand::new(EqualizerBandKind::Peaking, 2500.0, -4.0, 1.5),
+//!         EqualizerBand::new(EqualizerBandKind::HighShelf, 9000.0, 2.0, 0.7),
+//!     ]);
+//!     context
+//!         .state()
+//!         .bus_graph_mut()
+//!         .primary_bus_mut()
+//!         .add_effect(Effect::Equalizer(equalizer));
+//! }
+//! ```
+
+use crate::{
+    context::SAMPLE_RATE,
+    dsp::filters::{Biquad, BiquadKind},
+    effects::EffectRenderTrait,
+};
+use fyrox_core::{reflect::prelude::*, visitor::prelude::*};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+/// Shape of an equalizer band.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, Visit, Reflect, AsRefStr, EnumString, EnumVariantNames,
+)]
+pub enum EqualizerBandKind {
+    /// Boosts or cuts frequencies below the band frequency.
+    LowShelf,
+    /// Boosts or cuts frequencies around the band frequency.
+    Peaking,
+    /// Boosts or cuts frequencies above the band frequency.
+    HighShelf,
+}
+
+impl Default for EqualizerBandKind {
+    fn default() -> Self {
+        Self::Peaking
+    }
+}
+
+fn biquad_kind(kind: EqualizerBandKind) -> BiquadKind {
+    match kind {
+        EqualizerBandKind::LowShelf => BiquadKind::LowShelf,
+        EqualizerBandKind::Peaking => BiquadKind::Peaking,
+        EqualizerBandKind::HighShelf => BiquadKind::HighShelf,
+    }
+}
+
+/// A single band of the equalizer.
+#[derive(Debug, Clone, PartialEq, Visit, Reflect)]
+pub struct EqualizerBand {
+    /// Shape of the band.
+    pub kind: EqualizerBandKind,
+
+    /// Center (or corner for shelves) frequency of the band in Hertz.
+    #[reflect(min_value = 0.0)]
+    pub frequency: f32,
+
+    /// Gain of the band in decibels, positive values boost frequencies, negative - cut.
+    pub gain: f32,
+
+    /// Quality of the band, the higher the value the narrower the band.
+    #[reflect(min_value = 0.01, step = 0.05)]
+    pub quality: f32,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    left: Biquad,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    right: Biquad,
+
+    // Parameters with which the filters were tuned last time. Parameters could be changed directly,
+    // so filters are re-tuned lazily.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    tuned: Option<(EqualizerBandKind, f32, f32, f32)>,
+}
+
+impl Default for EqualizerBand {
+    fn default() -> Self {
+        Self::new(EqualizerBandKind::Peaking, 1000.0, 0.0, 1.0)
+    }
+}
+
+impl EqualizerBand {
+    /// Creates new band of the given kind with the given frequency (in Hertz), gain (in decibels)
+    /// and quality.
+    pub fn new(kind: EqualizerBandKind, frequency: f32, gain: f32, quality: f32) -> Self {
+        Self {
+            kind,
+            frequency,
+            gain,
+            quality,
+            left: Default::default(),
+            right: Default::default(),
+            tuned: None,
+        }
+    }
+
+    fn tune(&mut self) {
+        let params = (self.kind, self.frequency, self.gain, self.quality);
+        if self.tuned == Some(params) {
+            return;
+        }
+
+        // Keep the frequency below Nyquist frequency, otherwise the filter will be unstable.
+        let fc = (self.frequency / SAMPLE_RATE as f32).clamp(0.0, 0.49);
+        // Biquad expects the gain as a square root of linear amplitude.
+        let gain = 10.0f32.powf(self.gain / 40.0);
+        let quality = self.quality.max(0.01);
+
+        self.left.tune(biquad_kind(self.kind), fc, gain, quality);
+        self.right.tune(biquad_kind(self.kind), fc, gain, quality);
+        self.tuned = Some(params);
+    }
+}
+
+/// See module docs.
+#[derive(Debug, Clone, PartialEq, Visit, Reflect)]
+pub struct Equalizer {
+    bands: Vec<EqualizerBand>,
+}
+
+impl Default for Equalizer {
+    fn default() -> Self {
+        Self::new(vec![
+            EqualizerBand::new(EqualizerBandKind::LowShelf, 200.0, 0.0, 0.7),
+            EqualizerBand::new(EqualizerBandKind::Peaking, 1000.0, 0.0, 1.0),
+            EqualizerBand::new(EqualizerBandKind::HighShelf, 5000.0, 0.0, 0.7),
+        ])
+    }
+}
+
+impl Equalizer {
+    /// Creates new equalizer with the given set of bands.
+    pub fn new(bands: Vec<EqualizerBand>) -> Self {
+        Self { bands }
+    }
+
+    /// Sets new set of bands.
+    pub fn set_bands(&mut self, bands: Vec<EqualizerBand>) {
+        self.bands = bands;
+    }
+
+    /// Returns a reference to the bands of the equalizer.
+    pub fn bands(&self) -> &[EqualizerBand] {
+        &self.bands
+    }
+
+    /// Returns a reference to the bands of the equalizer.
+    pub fn bands_mut(&mut self) -> &mut Vec<EqualizerBand> {
+        &mut self.bands
+    }
+}
+
+impl EffectRenderTrait for Equalizer {
+    fn render(&mut self, input: &[(f32, f32)], output: &mut [(f32, f32)]) {
+        for band in self.bands.iter_mut() {
+            band.tune();
+        }
+
+        for ((input_left, input_right), (output_left, output_right)) in
+            input.iter().zip(output.iter_mut())
+        {
+            let mut left = *input_left;
+            let mut right = *input_right;
+            for band in self.bands.iter_mut() {
+                left = band.left.feed(left);
+                right = band.right.feed(right);
+            }
+            *output_left = left;
+            *output_right = right;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        context::SAMPLE_RATE,
+        effects::{
+            equalizer::{Equalizer, EqualizerBand, EqualizerBandKind},
+            EffectRenderTrait,
+        },
+    };
+
+    // Returns amplitude of the output of the equalizer for a sine wave of the given frequency.
+    fn measure_gain(equalizer: &mut Equalizer, frequency: f32) -> f32 {
+        let input = (0..SAMPLE_RATE)
+            .map(|i| {
+                let sample =
+                    (std::f32::consts::TAU * frequency * i as f32 / SAMPLE_RATE as f32).sin();
+                (sample, sample)
+            })
+            .collect::<Vec<_>>();
+        let mut output = vec![(0.0, 0.0); input.len()];
+        equalizer.render(&input, &mut output);
+
+        // Skip the first half to let the filters settle.
+        output[output.len() / 2..]
+            .iter()
+            .fold(0.0f32, |amplitude, (left, _)| amplitude.max(left.abs()))
+    }
+
+    fn make_peaking(gain: f32) -> Equalizer {
+        Equalizer::new(vec![EqualizerBand::new(
+            EqualizerBandKind::Peaking,
+            1000.0,
+            gain,
+            1.0,
+        )])
+    }
+
+    #[test]
+    fn test_peaking_band_boost() {
+        let mut equalizer = make_peaking(6.0);
+        // +6 dB is ~2 times louder.
+        assert!((measure_gain(&mut equalizer, 1000.0) - 1.995).abs() < 0.01);
+        // Frequencies far from the band are not affected.
+        assert!((measure_gain(&mut equalizer, 30.0) - 1.0).abs() < 0.02);
+        assert!((measure_gain(&mut equalizer, 15000.0) - 1.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_peaking_band_cut() {
+        let mut equalizer = make_peaking(-6.0);
+        assert!((measure_gain(&mut equalizer, 1000.0) - 0.501).abs() < 0.01);
+        assert!((measure_gain(&mut equalizer, 15000.0) - 1.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_flat_equalizer_impulse_response() {
+        let mut equalizer = Equalizer::default();
+
+        let mut input = vec![(0.0, 0.0); 64];
+        input[0] = (1.0, 1.0);
+        let mut output = vec![(0.0, 0.0); input.len()];
+        equalizer.render(&input, &mut output);
+
+        for (i, (left, right)) in output.iter().enumerate() {
+            let expected = if i == 0 { 1.0 } else { 0.0 };
+            assert!((left - expected).abs() < 1.0e-5, "sample {}", i);
+            assert!((right - expected).abs() < 1.0e-5, "sample {}", i);
+        }
+    }
+}
@@ -1,6 +1,12 @@
 //! Contins everything related to audio effects that can be applied to an audio bus.
 
 use crate::{
+    effects::chorus::Chorus,
+    effects::compressor::Compressor,
+    effects::custom::CustomEffectWrapper,
+    effects::delay::Delay,
+    effects::distortion::Distortion,
+    effects::equalizer::Equalizer,
     effects::filter::{
         AllPassFilterEffect, BandPassFilterEffect, HighPassFilterEffect, HighShelfFilterEffect,
         LowPassFilterEffect, LowShelfFilterEffect,
@@ -11,6 +17,12 @@ use fyrox_core::{reflect::prelude::*, visitor::prelude::*};
 use std::ops::{Deref, DerefMut};
 use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
 
+pub mod chorus;
+pub mod compressor;
+pub mod custom;
+pub mod delay;
+pub mod distortion;
+pub mod equalizer;
 pub mod filter;
 pub mod reverb;
 
@@ -91,6 +103,18 @@ pub enum Effect {
     LowShelfFilter(LowShelfFilterEffect),
     /// See [`HighShelfFilterEffect`] docs for more info.
     HighShelfFilter(HighShelfFilterEffect),
+    /// See [`Compressor`] docs for more info.
+    Compressor(Compressor),
+    /// See [`Delay`] docs for more info.
+    Delay(Delay),
+    /// See [`Chorus`] docs for more info.
+    Chorus(Chorus),
+    /// See [`Distortion`] docs for more info.
+    Distortion(Distortion),
+    /// See [`Equalizer`] docs for more info.
+    Equalizer(Equalizer),
+    /// See [`custom`] module docs for more info.
+    Custom(CustomEffectWrapper),
 }
 
 impl Default for Effect {
@@ -114,6 +138,12 @@ macro_rules! static_dispatch {
             Effect::AllPassFilter(v) => v.$func($($args),*),
             Effect::LowShelfFilter(v) => v.$func($($args),*),
             Effect::HighShelfFilter(v) => v.$func($($args),*),
+            Effect::Compressor(v) => v.$func($($args),*),
+            Effect::Delay(v) => v.$func($($args),*),
+            Effect::Chorus(v) => v.$func($($args),*),
+            Effect::Distortion(v) => v.$func($($args),*),
+            Effect::Equalizer(v) => v.$func($($args),*),
+            Effect::Custom(v) => v.$func($($args),*),
         }
     };
 }
@@ -38,7 +38,8 @@ impl SoundEngine {
 
     /// Creates new instance of a sound engine without OS audio output device (so called headless mode).
     /// The user should periodically run [`State::render`] if they want to implement their own sample sending
-    /// method to an output device (or a file, etc.).
+    /// method to an output device (or a file, etc.). See also [`crate::offline::OfflineRenderer`], that
+    /// renders a single context to a file faster than real time.
     pub fn without_device() -> Self {
         Self(Arc::new(Mutex::new(State {
             contexts: Default::default(),
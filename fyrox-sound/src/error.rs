@@ -53,6 +53,9 @@ pub enum SoundError {
 
     /// A buffer is not loaded yet, consider to `await` it before use.
     BufferIsNotLoaded,
+
+    /// An error occurred while writing WAV data, exact reason stored in inner value.
+    WavWriter(hound::Error),
 }
 
 impl From<std::io::Error> for SoundError {
@@ -93,6 +96,7 @@ impl Display for SoundError {
             SoundError::DecoderError(de) => write!(f, "internal decoder error: {:?}", de),
             SoundError::BufferFailedToLoad => write!(f, "a buffer failed to load"),
             SoundError::BufferIsNotLoaded => write!(f, "a buffer is not loaded yet"),
+            SoundError::WavWriter(e) => write!(f, "failed to write wav data. reason: {}", e),
         }
     }
 }
@@ -70,6 +70,7 @@ pub mod effects;
 pub mod engine;
 pub mod error;
 pub mod listener;
+pub mod offline;
 pub mod renderer;
 pub mod source;
 
@@ -0,0 +1,231 @@
+//! Offline (non-realtime) rendering module.
+//!
+//! # Overview
+//!
+//! Offline renderer advances a sound context as fast as possible (without any audio output device)
+//! and captures the output of the primary audio bus or any other audio bus. It could be used to
+//! render audio tracks of cinematics to a file, or to check output of the mixer and effects in
+//! tests.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use fyrox_sound::{
+//!     context::SoundContext, error::SoundError, offline::OfflineRenderer,
+//! };
+//! use std::time::Duration;
+//!
+//! fn render_track(context: SoundContext) -> Result<(), SoundError> {
+//!     let mut renderer = OfflineRenderer::new(context);
+//!     renderer.render_to_wav(Duration::from_secs(10), "track.wav")
+//! }
+//! ```
+//!
+//! # Important notes
+//!
+//! The context must not be added to a sound engine with an output device, otherwise it will be
+//! advanced by the output device too. The context is rendered in blocks of fixed size, samples
+//! that were rendered, but not requested yet, are kept and returned on next call.
+
+use crate::{
+    context::{SoundContext, SAMPLE_RATE},
+    error::SoundError,
+};
+use std::{
+    fs::File,
+    io::{BufWriter, Seek, Write},
+    path::Path,
+    time::Duration,
+};
+
+/// See module docs.
+pub struct OfflineRenderer {
+    context: SoundContext,
+    bus: Option<String>,
+    pending: Vec<(f32, f32)>,
+}
+
+impl OfflineRenderer {
+    /// Creates new offline renderer for the given context, that captures output of the primary
+    /// audio bus.
+    pub fn new(context: SoundContext) -> Self {
+        Self {
+            context,
+            bus: None,
+            pending: Default::default(),
+        }
+    }
+
+    /// Sets a name of an audio bus, that output will be captured. Output of the bus includes the
+    /// signal of its child buses and it is scaled by the gain of the bus. `None` means the primary
+    /// audio bus.
+    pub fn set_bus<S: AsRef<str>>(&mut self, name: Option<S>) {
+        self.bus = name.map(|name| name.as_ref().to_owned());
+    }
+
+    /// Sets a name of an audio bus, that output will be captured. See [`Self::set_bus`] for more info.
+    pub fn with_bus<S: AsRef<str>>(mut self, name: S) -> Self {
+        self.set_bus(Some(name));
+        self
+    }
+
+    /// Returns a name of the captured audio bus. `None` means the primary audio bus.
+    pub fn bus(&self) -> Option<&str> {
+        self.bus.as_deref()
+    }
+
+    /// Returns a reference to the rendered context.
+    pub fn context(&self) -> &SoundContext {
+        &self.context
+    }
+
+    /// Advances the context for the given duration and returns rendered samples.
+    pub fn render(&mut self, duration: Duration) -> Vec<(f32, f32)> {
+        self.render_samples((duration.as_secs_f64() * SAMPLE_RATE as f64).round() as usize)
+    }
+
+    /// Advances the context for the given amount of samples (per channel) and returns them.
+    pub fn render_samples(&mut self, sample_count: usize) -> Vec<(f32, f32)> {
+        let mut block = vec![(0.0, 0.0); SoundContext::SAMPLES_PER_CHANNEL];
+
+        while self.pending.len() < sample_count {
+            for sample in block.iter_mut() {
+                *sample = (0.0, 0.0);
+            }
+
+            let mut state = self.context.state();
+
+            state.render(&mut block);
+
+            match self.bus.as_deref() {
+                None => self.pending.extend_from_slice(&block),
+                Some(name) => {
+                    match state
+                        .bus_graph_ref()
+                        .buses_iter()
+                        .find(|bus| bus.name() == name)
+                    {
+                        Some(bus) => {
+                            let gain = bus.gain();
+                            self.pending.extend(
+                                bus.output_buffer()
+                                    .iter()
+                                    .take(block.len())
+                                    .map(|(left, right)| (*left * gain, *right * gain)),
+                            );
+                        }
+                        // Missing bus produces silence, the same way as sources of a missing bus.
+                        None => self
+                            .pending
+                            .resize(self.pending.len() + block.len(), (0.0, 0.0)),
+                    }
+                }
+            }
+        }
+
+        let rest = self.pending.split_off(sample_count);
+        std::mem::replace(&mut self.pending, rest)
+    }
+
+    /// Advances the context for the given duration and writes rendered samples to a WAV file
+    /// (stereo, 32-bit float, 44100 Hz).
+    pub fn render_to_wav<P: AsRef<Path>>(
+        &mut self,
+        duration: Duration,
+        path: P,
+    ) -> Result<(), SoundError> {
+        let samples = self.render(duration);
+        write_wav(&samples, BufWriter::new(File::create(path)?))
+    }
+}
+
+/// Writes the given samples to a WAV data (stereo, 32-bit float, 44100 Hz).
+pub fn write_wav<W: Write + Seek>(samples: &[(f32, f32)], writer: W) -> Result<(), SoundError> {
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate: SAMPLE_RATE,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+
+    let mut writer = hound::WavWriter::new(writer, spec).map_err(SoundError::WavWriter)?;
+    for (left, right) in samples {
+        writer.write_sample(*left).map_err(SoundError::WavWriter)?;
+        writer.write_sample(*right).map_err(SoundError::WavWriter)?;
+    }
+    writer.finalize().map_err(SoundError::WavWriter)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        buffer::{DataSource, SoundBufferResource},
+        bus::AudioBus,
+        context::SoundContext,
+        effects::{Attenuate, Effect},
+        offline::{write_wav, OfflineRenderer},
+        source::{SoundSourceBuilder, Status},
+    };
+    use std::io::Cursor;
+
+    fn make_context() -> SoundContext {
+        let context = SoundContext::new();
+
+        let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: 44100,
+            channel_count: 1,
+            samples: vec![0.5; 44100],
+        })
+        .unwrap();
+
+        {
+            let mut state = context.state();
+            let graph = state.bus_graph_mut();
+            let primary = graph.primary_bus_handle();
+            let mut sfx = AudioBus::new("SFX".to_string());
+            sfx.add_effect(Effect::Attenuate(Attenuate::new(0.5)));
+            graph.add_bus(sfx, primary);
+
+            state.add_source(
+                SoundSourceBuilder::new()
+                    .with_buffer(buffer)
+                    .with_bus("SFX")
+                    .with_status(Status::Playing)
+                    .build()
+                    .unwrap(),
+            );
+        }
+
+        context
+    }
+
+    #[test]
+    fn test_offline_rendering() {
+        let mut renderer = OfflineRenderer::new(make_context());
+
+        // Request an amount that is not a multiple of the block size.
+        let samples = renderer.render_samples(1000);
+        assert_eq!(samples.len(), 1000);
+        assert!(samples.iter().all(|(l, r)| *l > 0.0 && *r > 0.0));
+
+        let samples = renderer.render_samples(10000);
+        assert_eq!(samples.len(), 10000);
+    }
+
+    #[test]
+    fn test_offline_bus_capture() {
+        let context = make_context();
+
+        let primary = OfflineRenderer::new(context.deep_clone()).render_samples(100);
+        let sfx = OfflineRenderer::new(context)
+            .with_bus("SFX")
+            .render_samples(100);
+
+        assert_eq!(primary, sfx);
+
+        let mut data = Cursor::new(Vec::new());
+        write_wav(&sfx, &mut data).unwrap();
+        let reader = hound::WavReader::new(Cursor::new(data.into_inner())).unwrap();
+        assert_eq!(reader.len(), 200);
+    }
+}
@@ -89,7 +89,13 @@ pub(crate) fn render_source_default(
         source.calculate_panning(listener),
         source.spatial_blend(),
     );
-    let gain = distance_gain * source.gain();
+    let cone_gain = lerpf(
+        1.0,
+        source.calculate_cone_gain(listener),
+        source.spatial_blend(),
+    );
+    let occlusion_gain = lerpf(1.0, source.occlusion_gain(), source.spatial_blend());
+    let gain = distance_gain * cone_gain * occlusion_gain * source.gain();
     let left_gain = gain * (1.0 + panning);
     let right_gain = gain * (1.0 - panning);
     render_with_params(source, left_gain, right_gain, mix_buffer);
@@ -31,12 +31,14 @@
 use crate::bus::AudioBusGraph;
 use crate::{
     buffer::{streaming::StreamingBuffer, SoundBufferResource, SoundBufferState},
-    context::DistanceModel,
+    context::{DistanceModel, SAMPLE_RATE},
+    dsp::filters::OnePole,
     error::SoundError,
     listener::Listener,
 };
 use fyrox_core::{
     algebra::Vector3,
+    math::lerpf,
     reflect::prelude::*,
     visitor::{Visit, VisitResult, Visitor},
 };
@@ -59,6 +61,83 @@ pub enum Status {
     Paused = 2,
 }
 
+/// Sound cone defines directivity of a sound source. A listener inside the inner cone hears the
+/// source at full gain, outside of the outer cone - with [`SoundCone::outer_gain`] and optional
+/// low-pass filtering, between the cones the parameters are interpolated. Cones are oriented along
+/// the direction of a source (see [`SoundSource::set_direction`]).
+///
+/// Default cone has both angles equal to 360 degrees, which makes a source omnidirectional.
+#[derive(Copy, Clone, Debug, PartialEq, Reflect, Visit)]
+pub struct SoundCone {
+    /// Full angle (in radians) of the inner cone.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub inner_angle: f32,
+
+    /// Full angle (in radians) of the outer cone. Must be greater or equal than the inner angle.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub outer_angle: f32,
+
+    /// Gain of the source outside of the outer cone.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub outer_gain: f32,
+
+    /// Optional cutoff frequency (in Hz) of a low-pass filter that is applied to the sound outside
+    /// of the outer cone. It is used to simulate muffled sound "behind" a source.
+    pub outer_low_pass: Option<f32>,
+}
+
+impl Default for SoundCone {
+    fn default() -> Self {
+        Self {
+            inner_angle: std::f32::consts::TAU,
+            outer_angle: std::f32::consts::TAU,
+            outer_gain: 1.0,
+            outer_low_pass: None,
+        }
+    }
+}
+
+impl SoundCone {
+    /// Creates new sound cone with given angles (in radians) and gain outside of the outer cone.
+    pub fn new(inner_angle: f32, outer_angle: f32, outer_gain: f32) -> Self {
+        Self {
+            inner_angle,
+            outer_angle,
+            outer_gain,
+            outer_low_pass: None,
+        }
+    }
+
+    /// Sets cutoff frequency (in Hz) of the low-pass filter outside of the outer cone.
+    pub fn with_outer_low_pass(mut self, cutoff_frequency: Option<f32>) -> Self {
+        self.outer_low_pass = cutoff_frequency;
+        self
+    }
+
+    /// Returns true if the cone does not affect the sound in any direction.
+    pub fn is_omnidirectional(&self) -> bool {
+        self.inner_angle >= std::f32::consts::TAU
+            || (self.outer_gain == 1.0 && self.outer_low_pass.is_none())
+    }
+
+    /// Returns interpolation factor for a given angle (in radians) between the direction of the
+    /// cone and a direction to a listener. 0.0 means that the listener is inside the inner cone,
+    /// 1.0 - outside of the outer cone.
+    pub fn factor(&self, angle: f32) -> f32 {
+        // Angles of the cones are full angles, while given angle is measured from the axis.
+        let angle = angle * 2.0;
+        let inner_angle = self.inner_angle.max(0.0);
+        let outer_angle = self.outer_angle.max(inner_angle);
+        if angle <= inner_angle {
+            0.0
+        } else if angle >= outer_angle {
+            1.0
+        } else {
+            (angle - inner_angle) / (outer_angle - inner_angle)
+        }
+    }
+}
+
 /// See module info.
 #[derive(Debug, Clone, Reflect, Visit)]
 pub struct SoundSource {
@@ -119,6 +198,15 @@ pub struct SoundSource {
     #[reflect(min_value = 0.0, step = 0.05)]
     radius: f32,
     position: Vector3<f32>,
+    #[visit(optional)]
+    velocity: Vector3<f32>,
+    #[visit(optional)]
+    direction: Vector3<f32>,
+    #[visit(optional)]
+    cone: SoundCone,
+    #[visit(optional)]
+    #[reflect(min_value = 0.0, step = 0.05)]
+    doppler_factor: f32,
     #[reflect(min_value = 0.0, step = 0.05)]
     max_distance: f32,
     #[reflect(min_value = 0.0, step = 0.05)]
@@ -136,6 +224,20 @@ pub struct SoundSource {
     #[reflect(hidden)]
     #[visit(skip)]
     pub(crate) prev_distance_gain: Option<f32>,
+    // Low-pass filters (one per channel) that is used to muffle the sound outside of the cone.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    cone_filters: (OnePole, OnePole),
+    // Occlusion is calculated at runtime by the user (or the engine), so it is not serialized.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    occlusion_gain: f32,
+    #[reflect(hidden)]
+    #[visit(skip)]
+    occlusion_low_pass: Option<f32>,
+    #[reflect(hidden)]
+    #[visit(skip)]
+    occlusion_filters: (OnePole, OnePole),
 }
 
 impl Default for SoundSource {
@@ -160,12 +262,20 @@ impl Default for SoundSource {
             prev_buffer_sample: (0.0, 0.0),
             radius: 1.0,
             position: Vector3::new(0.0, 0.0, 0.0),
+            velocity: Vector3::new(0.0, 0.0, 0.0),
+            direction: Vector3::new(0.0, 0.0, 1.0),
+            cone: Default::default(),
+            doppler_factor: 1.0,
             max_distance: f32::MAX,
             rolloff_factor: 1.0,
             prev_left_samples: Default::default(),
             prev_right_samples: Default::default(),
             prev_sampling_vector: Vector3::new(0.0, 0.0, 1.0),
             prev_distance_gain: None,
+            cone_filters: Default::default(),
+            occlusion_gain: 1.0,
+            occlusion_low_pass: None,
+            occlusion_filters: Default::default(),
         }
     }
 }
@@ -356,6 +466,76 @@ impl SoundSource {
         self.position
     }
 
+    /// Sets velocity of source in world space. Velocity is used only to calculate Doppler effect,
+    /// it does not change position of the source.
+    pub fn set_velocity(&mut self, velocity: Vector3<f32>) -> &mut Self {
+        self.velocity = velocity;
+        self
+    }
+
+    /// Returns velocity of source.
+    pub fn velocity(&self) -> Vector3<f32> {
+        self.velocity
+    }
+
+    /// Sets direction of source in world space. Direction is used only by sound cone, see
+    /// [`SoundCone`] docs for more info.
+    pub fn set_direction(&mut self, direction: Vector3<f32>) -> &mut Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Returns direction of source.
+    pub fn direction(&self) -> Vector3<f32> {
+        self.direction
+    }
+
+    /// Sets new sound cone of source. Sound cone defines directivity of the source, see [`SoundCone`]
+    /// docs for more info.
+    pub fn set_cone(&mut self, cone: SoundCone) -> &mut Self {
+        self.cone = cone;
+        self
+    }
+
+    /// Returns sound cone of source.
+    pub fn cone(&self) -> &SoundCone {
+        &self.cone
+    }
+
+    /// Sets occlusion of the source by some geometry between the source and the listener. `gain`
+    /// defines how much of the signal will pass through the geometry (in `[0.0; 1.0]` range), and
+    /// `low_pass_cutoff` is an optional cutoff frequency (in Hz) of a low-pass filter that will be
+    /// applied to the signal. Occlusion is a spatial effect, so it is scaled by spatial blend
+    /// factor. Occlusion is not serialized, it should be updated every frame.
+    pub fn set_occlusion(&mut self, gain: f32, low_pass_cutoff: Option<f32>) {
+        self.occlusion_gain = gain.clamp(0.0, 1.0);
+        self.occlusion_low_pass = low_pass_cutoff.map(|cutoff| cutoff.max(0.0));
+    }
+
+    /// Returns current occlusion gain of the source.
+    pub fn occlusion_gain(&self) -> f32 {
+        self.occlusion_gain
+    }
+
+    /// Returns current cutoff frequency of the occlusion low-pass filter.
+    pub fn occlusion_low_pass(&self) -> Option<f32> {
+        self.occlusion_low_pass
+    }
+
+    /// Sets Doppler factor of the source. It defines how strong the Doppler effect will be for the
+    /// source, 0.0 - disables the effect, 1.0 - physically correct effect (default), values larger
+    /// than 1.0 exaggerate the effect. The factor is multiplied with the Doppler factor of the
+    /// context.
+    pub fn set_doppler_factor(&mut self, doppler_factor: f32) -> &mut Self {
+        self.doppler_factor = doppler_factor.max(0.0);
+        self
+    }
+
+    /// Returns Doppler factor of the source.
+    pub fn doppler_factor(&self) -> f32 {
+        self.doppler_factor
+    }
+
     /// Sets radius of imaginable sphere around source in which no distance attenuation is applied.
     pub fn set_radius(&mut self, radius: f32) -> &mut Self {
         self.radius = radius;
@@ -438,6 +618,103 @@ impl SoundSource {
             .dot(&listener.ear_axis())
     }
 
+    // Returns 0.0 if listener is inside the inner cone, 1.0 - if it is outside of the outer cone.
+    pub(crate) fn calculate_cone_factor(&self, listener: &Listener) -> f32 {
+        if self.cone.is_omnidirectional() {
+            return 0.0;
+        }
+
+        match (
+            (listener.position() - self.position).try_normalize(f32::EPSILON),
+            self.direction.try_normalize(f32::EPSILON),
+        ) {
+            (Some(to_listener), Some(direction)) => self
+                .cone
+                .factor(to_listener.dot(&direction).clamp(-1.0, 1.0).acos()),
+            // Listener is at the same position as the source, or the source has no direction.
+            _ => 0.0,
+        }
+    }
+
+    pub(crate) fn calculate_cone_gain(&self, listener: &Listener) -> f32 {
+        lerpf(
+            1.0,
+            self.cone.outer_gain,
+            self.calculate_cone_factor(listener),
+        )
+    }
+
+    // Applies low-pass filter of the cone to the rendered samples.
+    pub(crate) fn apply_cone_low_pass(&mut self, listener: &Listener) {
+        if let Some(cutoff_frequency) = self.cone.outer_low_pass {
+            let k = self.spatial_blend * self.calculate_cone_factor(listener);
+            if k <= 0.0 {
+                return;
+            }
+
+            let fc = cutoff_frequency / SAMPLE_RATE as f32;
+            self.cone_filters.0.set_fc(fc);
+            self.cone_filters.1.set_fc(fc);
+
+            for (left, right) in self.frame_samples.iter_mut() {
+                *left = lerpf(*left, self.cone_filters.0.feed(*left), k);
+                *right = lerpf(*right, self.cone_filters.1.feed(*right), k);
+            }
+        }
+    }
+
+    // Applies low-pass filter of the occlusion to the rendered samples.
+    pub(crate) fn apply_occlusion_low_pass(&mut self) {
+        if let Some(cutoff_frequency) = self.occlusion_low_pass {
+            let k = self.spatial_blend;
+            if k <= 0.0 {
+                return;
+            }
+
+            let fc = cutoff_frequency / SAMPLE_RATE as f32;
+            self.occlusion_filters.0.set_fc(fc);
+            self.occlusion_filters.1.set_fc(fc);
+
+            for (left, right) in self.frame_samples.iter_mut() {
+                *left = lerpf(*left, self.occlusion_filters.0.feed(*left), k);
+                *right = lerpf(*right, self.occlusion_filters.1.feed(*right), k);
+            }
+        }
+    }
+
+    // Doppler shift formula was taken from OpenAL Specification as well.
+    pub(crate) fn calculate_doppler_pitch(
+        &self,
+        listener: &Listener,
+        speed_of_sound: f32,
+        doppler_factor: f32,
+    ) -> f64 {
+        let doppler_factor = doppler_factor * self.doppler_factor;
+        if doppler_factor <= 0.0 || speed_of_sound <= 0.0 {
+            return 1.0;
+        }
+
+        let source_to_listener = listener.position() - self.position;
+        let distance = source_to_listener.norm();
+        if distance <= f32::EPSILON {
+            return 1.0;
+        }
+
+        // Project velocities on the line between source and listener. Clamp them, so the source
+        // and the listener can't move faster than sound, otherwise pitch would be negative.
+        let max_speed = speed_of_sound / doppler_factor;
+        let listener_speed =
+            (source_to_listener.dot(&listener.velocity()) / distance).min(max_speed);
+        let source_speed = (source_to_listener.dot(&self.velocity) / distance).min(max_speed);
+
+        let denominator = speed_of_sound - doppler_factor * source_speed;
+        if denominator <= f32::EPSILON {
+            return 1.0;
+        }
+
+        ((speed_of_sound - doppler_factor * listener_speed) / denominator) as f64
+    }
+
     pub(crate) fn calculate_sampling_vector(&self, listener: &Listener) -> Vector3<f32> {
         let to_self = listener.position() - self.position;
 
@@ -485,7 +762,9 @@ impl SoundSource {
         }
     }
 
-    pub(crate) fn render(&mut self, amount: usize) {
+    // Pitch multiplier is used to apply pitch modifications that are calculated by the context
+    // each frame (such as Doppler shift).
+    pub(crate) fn render(&mut self, amount: usize, pitch_multiplier: f64) {
         if self.frame_samples.capacity() < amount {
             self.frame_samples = Vec::with_capacity(amount);
         }
@@ -496,7 +775,7 @@ impl SoundSource {
             let mut state = buffer.state();
             if let ResourceState::Ok(ref mut buffer) = *state {
                 if self.status == Status::Playing && !buffer.is_empty() {
-                    self.render_playing(buffer, amount);
+                    self.render_playing(buffer, amount, pitch_multiplier);
                 }
             }
         }
@@ -504,10 +783,16 @@ impl SoundSource {
         self.frame_samples.resize(amount, (0.0, 0.0));
     }
 
-    fn render_playing(&mut self, buffer: &mut SoundBufferState, amount: usize) {
+    fn render_playing(
+        &mut self,
+        buffer: &mut SoundBufferState,
+        amount: usize,
+        pitch_multiplier: f64,
+    ) {
+        let step = self.pitch * pitch_multiplier * self.resampling_multiplier;
         let mut count = 0;
         loop {
-            count += self.render_until_block_end(buffer, amount - count);
+            count += self.render_until_block_end(buffer, amount - count, step);
             if count == amount {
                 break;
             }
@@ -544,8 +829,8 @@ impl SoundSource {
         &mut self,
         buffer: &mut SoundBufferState,
         mut amount: usize,
+        step: f64,
     ) -> usize {
-        let step = self.pitch * self.resampling_multiplier;
         if step == 1.0 {
             if self.buf_read_pos < 0.0 {
                 // This can theoretically happen if we change pitch on the fly.
@@ -713,6 +998,10 @@ pub struct SoundSourceBuilder {
     playback_time: Duration,
     radius: f32,
     position: Vector3<f32>,
+    velocity: Vector3<f32>,
+    direction: Vector3<f32>,
+    cone: SoundCone,
+    doppler_factor: f32,
     max_distance: f32,
     rolloff_factor: f32,
     spatial_blend: f32,
@@ -740,6 +1029,10 @@ impl SoundSourceBuilder {
             playback_time: Default::default(),
             radius: 1.0,
             position: Vector3::new(0.0, 0.0, 0.0),
+            velocity: Vector3::new(0.0, 0.0, 0.0),
+            direction: Vector3::new(0.0, 0.0, 1.0),
+            cone: Default::default(),
+            doppler_factor: 1.0,
             max_distance: f32::MAX,
             rolloff_factor: 1.0,
             spatial_blend: 1.0,
@@ -819,6 +1112,30 @@ impl SoundSourceBuilder {
         self
     }
 
+    /// See [`SoundSource::set_velocity`]
+    pub fn with_velocity(mut self, velocity: Vector3<f32>) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    /// See [`SoundSource::set_direction`]
+    pub fn with_direction(mut self, direction: Vector3<f32>) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// See [`SoundSource::set_cone`]
+    pub fn with_cone(mut self, cone: SoundCone) -> Self {
+        self.cone = cone;
+        self
+    }
+
+    /// See [`SoundSource::set_doppler_factor`]
+    pub fn with_doppler_factor(mut self, doppler_factor: f32) -> Self {
+        self.doppler_factor = doppler_factor.max(0.0);
+        self
+    }
+
     /// See `set_radius` of SpatialSource.
     pub fn with_radius(mut self, radius: f32) -> Self {
         self.radius = radius;
@@ -857,12 +1174,18 @@ impl SoundSourceBuilder {
             frame_samples: Default::default(),
             radius: self.radius,
             position: self.position,
+            velocity: self.velocity,
+            direction: self.direction,
+            cone: self.cone,
+            doppler_factor: self.doppler_factor,
             max_distance: self.max_distance,
             rolloff_factor: self.rolloff_factor,
             spatial_blend: self.spatial_blend,
             prev_left_samples: Default::default(),
             prev_right_samples: Default::default(),
             bus: self.bus,
+            cone_filters: Default::default(),
+            occlusion_filters: Default::default(),
             ..Default::default()
         };
 
@@ -872,3 +1195,175 @@ impl SoundSourceBuilder {
         Ok(source)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        listener::Listener,
+        source::{SoundCone, SoundSource, SoundSourceBuilder},
+    };
+    use fyrox_core::algebra::Vector3;
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    const SPEED_OF_SOUND: f32 = 343.0;
+
+    fn make_source(velocity: Vector3<f32>) -> SoundSource {
+        SoundSourceBuilder::new()
+            .with_position(Vector3::new(10.0, 0.0, 0.0))
+            .with_velocity(velocity)
+            .build()
+            .unwrap()
+    }
+
+    fn make_listener(velocity: Vector3<f32>) -> Listener {
+        let mut listener = Listener::new();
+        listener.set_velocity(velocity);
+        listener
+    }
+
+    fn doppler_pitch(source: &SoundSource, listener: &Listener) -> f64 {
+        source.calculate_doppler_pitch(listener, SPEED_OF_SOUND, 1.0)
+    }
+
+    #[test]
+    fn test_doppler_pitch_of_static_source() {
+        let listener = make_listener(Default::default());
+        assert_eq!(
+            doppler_pitch(&make_source(Default::default()), &listener),
+            1.0
+        );
+        // Velocity perpendicular to the line between the source and the listener does not
+        // change the pitch.
+        assert_eq!(
+            doppler_pitch(&make_source(Vector3::new(0.0, 50.0, 0.0)), &listener),
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_doppler_pitch_of_approaching_source() {
+        let listener = make_listener(Default::default());
+        let pitch = doppler_pitch(&make_source(Vector3::new(-34.3, 0.0, 0.0)), &listener);
+        assert!((pitch - 343.0 / 308.7).abs() < 1.0e-4);
+
+        // Listener moves towards the source.
+        let listener = make_listener(Vector3::new(34.3, 0.0, 0.0));
+        let pitch = doppler_pitch(&make_source(Default::default()), &listener);
+        assert!((pitch - 1.1).abs() < 1.0e-4);
+    }
+
+    #[test]
+    fn test_doppler_pitch_of_receding_source() {
+        let listener = make_listener(Default::default());
+        let pitch = doppler_pitch(&make_source(Vector3::new(34.3, 0.0, 0.0)), &listener);
+        assert!((pitch - 343.0 / 377.3).abs() < 1.0e-4);
+
+        // Listener moves away from the source.
+        let listener = make_listener(Vector3::new(-34.3, 0.0, 0.0));
+        let pitch = doppler_pitch(&make_source(Default::default()), &listener);
+        assert!((pitch - 0.9).abs() < 1.0e-4);
+    }
+
+    #[test]
+    fn test_doppler_pitch_clamping() {
+        // Supersonic listener that moves away from the source can't hear it.
+        let listener = make_listener(Vector3::new(-1000.0, 0.0, 0.0));
+        assert_eq!(
+            doppler_pitch(&make_source(Default::default()), &listener),
+            0.0
+        );
+
+        // Supersonic source that moves towards the listener must not produce infinite or negative
+        // pitch.
+        let listener = make_listener(Default::default());
+        let pitch = doppler_pitch(&make_source(Vector3::new(-1000.0, 0.0, 0.0)), &listener);
+        assert!(pitch.is_finite() && pitch > 0.0);
+
+        // Only approaching speed is clamped, supersonic receding source just has very low pitch.
+        let pitch = doppler_pitch(&make_source(Vector3::new(1000.0, 0.0, 0.0)), &listener);
+        assert!((pitch - 343.0 / 1343.0).abs() < 1.0e-4);
+    }
+
+    #[test]
+    fn test_doppler_pitch_disabled() {
+        let listener = make_listener(Default::default());
+        let mut source = make_source(Vector3::new(-34.3, 0.0, 0.0));
+        assert_eq!(
+            source.calculate_doppler_pitch(&listener, SPEED_OF_SOUND, 0.0),
+            1.0
+        );
+        assert_eq!(source.calculate_doppler_pitch(&listener, 0.0, 1.0), 1.0);
+
+        source.set_doppler_factor(0.0);
+        assert_eq!(doppler_pitch(&source, &listener), 1.0);
+    }
+
+    // Inner cone is 90 degrees wide, outer - 180 degrees.
+    fn make_cone() -> SoundCone {
+        SoundCone::new(FRAC_PI_2, PI, 0.25)
+    }
+
+    #[test]
+    fn test_sound_cone_factor() {
+        let cone = make_cone();
+        assert!(!cone.is_omnidirectional());
+        // Inside of the inner cone.
+        assert_eq!(cone.factor(0.0), 0.0);
+        assert_eq!(cone.factor(FRAC_PI_4), 0.0);
+        // Transition between the cones.
+        assert!((cone.factor(FRAC_PI_4 * 1.5) - 0.5).abs() < 1.0e-5);
+        // Outside of the outer cone.
+        assert_eq!(cone.factor(FRAC_PI_2), 1.0);
+        assert_eq!(cone.factor(PI), 1.0);
+
+        // Outer angle can't be less than the inner angle.
+        let cone = SoundCone::new(FRAC_PI_2, 0.0, 0.25);
+        assert_eq!(cone.factor(FRAC_PI_4), 0.0);
+        assert_eq!(cone.factor(FRAC_PI_4 * 1.01), 1.0);
+
+        let cone = SoundCone::default();
+        assert!(cone.is_omnidirectional());
+        assert_eq!(cone.factor(PI), 0.0);
+    }
+
+    fn make_directional_source() -> SoundSource {
+        SoundSourceBuilder::new()
+            .with_direction(Vector3::new(0.0, 0.0, 1.0))
+            .with_cone(make_cone())
+            .build()
+            .unwrap()
+    }
+
+    // Creates a listener at the given angle from the direction of the source.
+    fn make_listener_at(angle: f32) -> Listener {
+        let mut listener = Listener::new();
+        listener.set_position(Vector3::new(angle.sin(), 0.0, angle.cos()).scale(10.0));
+        listener
+    }
+
+    #[test]
+    fn test_cone_factor_and_gain() {
+        let source = make_directional_source();
+
+        for (angle, factor, gain) in [
+            (0.0, 0.0, 1.0),
+            (FRAC_PI_4 * 0.5, 0.0, 1.0),
+            (FRAC_PI_4 * 1.5, 0.5, 0.625),
+            (FRAC_PI_2, 1.0, 0.25),
+            (PI, 1.0, 0.25),
+        ] {
+            let listener = make_listener_at(angle);
+            assert!((source.calculate_cone_factor(&listener) - factor).abs() < 1.0e-4);
+            assert!((source.calculate_cone_gain(&listener) - gain).abs() < 1.0e-4);
+        }
+
+        // Listener at the position of the source.
+        let listener = Listener::new();
+        assert_eq!(source.calculate_cone_factor(&listener), 0.0);
+
+        // Source without direction is omnidirectional.
+        let mut source = make_directional_source();
+        source.set_direction(Default::default());
+        assert_eq!(source.calculate_cone_factor(&make_listener_at(PI)), 0.0);
+    }
+}
@@ -69,6 +69,18 @@ impl Control for Button {
                         ui.capture_mouse(message.destination());
                         message.set_handled(true);
                     }
+                    WidgetMessage::Tap { .. } => {
+                        ui.send_message(ButtonMessage::click(
+                            self.handle(),
+                            MessageDirection::FromWidget,
+                        ));
+                        message.set_handled(true);
+                    }
+                    WidgetMessage::TouchStarted { id, .. } => {
+                        // The capture is released automatically when the finger is lifted.
+                        ui.capture_touch(*id, message.destination());
+                        message.set_handled(true);
+                    }
                     _ => (),
                 }
             }
@@ -37,6 +37,26 @@ pub struct CheckBox {
 
 crate::define_widget_deref!(CheckBox);
 
+impl CheckBox {
+    fn toggle(&self, ui: &UserInterface) {
+        if let Some(value) = self.checked {
+            // Invert state if it is defined.
+            ui.send_message(CheckBoxMessage::checked(
+                self.handle(),
+                MessageDirection::ToWidget,
+                Some(!value),
+            ));
+        } else {
+            // Switch from undefined state to checked.
+            ui.send_message(CheckBoxMessage::checked(
+                self.handle(),
+                MessageDirection::ToWidget,
+                Some(true),
+            ));
+        }
+    }
+}
+
 impl Control for CheckBox {
     fn query_component(&self, type_id: TypeId) -> Option<&dyn Any> {
         if type_id == TypeId::of::<Self>() {
@@ -72,21 +92,14 @@ impl Control for CheckBox {
                     {
                         ui.release_mouse_capture();
 
-                        if let Some(value) = self.checked {
-                            // Invert state if it is defined.
-                            ui.send_message(CheckBoxMessage::checked(
-                                self.handle(),
-                                MessageDirection::ToWidget,
-                                Some(!value),
-                            ));
-                        } else {
-                            // Switch from undefined state to checked.
-                            ui.send_message(CheckBoxMessage::checked(
-                                self.handle(),
-                                MessageDirection::ToWidget,
-                                Some(true),
-                            ));
-                        }
+                        self.toggle(ui);
+                    }
+                }
+                WidgetMessage::Tap { .. } => {
+                    if message.destination() == self.handle()
+                        || self.widget.has_descendant(message.destination(), ui)
+                    {
+                        self.toggle(ui);
                     }
                 }
                 _ => (),
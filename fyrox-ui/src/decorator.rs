@@ -183,14 +183,18 @@ impl Control for Decorator {
                             self.hover_brush.clone(),
                         ));
                     }
-                    WidgetMessage::MouseDown { .. } if self.is_pressable => {
+                    WidgetMessage::MouseDown { .. } | WidgetMessage::TouchStarted { .. }
+                        if self.is_pressable =>
+                    {
                         ui.send_message(WidgetMessage::background(
                             self.handle(),
                             MessageDirection::ToWidget,
                             self.pressed_brush.clone(),
                         ));
                     }
-                    WidgetMessage::MouseUp { .. } => {
+                    WidgetMessage::MouseUp { .. }
+                    | WidgetMessage::TouchEnded { .. }
+                    | WidgetMessage::TouchCancelled { .. } => {
                         if self.is_selected {
                             ui.send_message(WidgetMessage::background(
                                 self.handle(),
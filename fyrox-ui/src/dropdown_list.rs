@@ -82,7 +82,9 @@ impl Control for DropdownList {
     fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage) {
         self.widget.handle_routed_message(ui, message);
 
-        if let Some(WidgetMessage::MouseDown { .. }) = message.data::<WidgetMessage>() {
+        if let Some(WidgetMessage::MouseDown { .. } | WidgetMessage::Tap { .. }) =
+            message.data::<WidgetMessage>()
+        {
             if message.destination() == self.handle()
                 || self.widget.has_descendant(message.destination(), ui)
             {
@@ -71,7 +71,7 @@ use crate::{
     draw::{CommandTexture, Draw, DrawingContext},
     message::{
         ButtonState, CursorIcon, KeyboardModifiers, MessageDirection, MouseButton, OsEvent,
-        UiMessage,
+        TouchPhase, UiMessage,
     },
     popup::{Placement, PopupMessage},
     ttf::{Font, FontBuilder, SharedFont},
@@ -709,6 +709,18 @@ struct DoubleClickEntry {
     click_count: u32,
 }
 
+#[derive(Clone, Debug)]
+struct TouchEntry {
+    // A node on which the touch has started, it receives gesture messages.
+    node: Handle<UiNode>,
+    start_position: Vector2<f32>,
+    position: Vector2<f32>,
+    elapsed: f32,
+    // Whether the finger was moved farther than the tap distance threshold.
+    moved: bool,
+    long_press_sent: bool,
+}
+
 pub struct UserInterface {
     screen_size: Vector2<f32>,
     nodes: Pool<UiNode>,
@@ -738,6 +750,14 @@ pub struct UserInterface {
     pub default_font: SharedFont,
     double_click_entries: FxHashMap<MouseButton, DoubleClickEntry>,
     pub double_click_time_slice: f32,
+    touches: FxHashMap<u64, TouchEntry>,
+    touch_captures: FxHashMap<u64, Handle<UiNode>>,
+    /// Amount of time (in seconds) a finger must be held still on a widget to emit
+    /// [`WidgetMessage::LongPress`]. Shorter touches are treated as taps.
+    pub long_press_time: f32,
+    /// Maximum distance (in pixels) a finger could move and still produce a tap or a long press.
+    /// Farther movement is treated as a pan or a pinch gesture.
+    pub tap_distance_threshold: f32,
 }
 
 fn is_on_screen(node: &UiNode, nodes: &Pool<UiNode>) -> bool {
@@ -858,6 +878,10 @@ impl UserInterface {
             default_font,
             double_click_entries: Default::default(),
             double_click_time_slice: 0.5, // 500 ms is standard in most operating systems.
+            touches: Default::default(),
+            touch_captures: Default::default(),
+            long_press_time: 0.5,
+            tap_distance_threshold: 10.0,
         };
         ui.root_canvas = ui.add_node(UiNode::new(Canvas::new(WidgetBuilder::new().build())));
         ui.keyboard_focus_node = ui.root_canvas;
@@ -887,6 +911,40 @@ impl UserInterface {
         self.captured_node = Handle::NONE;
     }
 
+    /// Captures a finger with the given id, so every touch message of the finger will be sent to
+    /// the given node. Returns `false` if the finger is already captured by some node. The capture
+    /// is released automatically when the finger is lifted from the screen.
+    #[inline]
+    pub fn capture_touch(&mut self, id: u64, node: Handle<UiNode>) -> bool {
+        match self.touch_captures.entry(id) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(node);
+                true
+            }
+        }
+    }
+
+    /// Releases a capture of a finger with the given id, touch messages of the finger will be sent
+    /// to picked nodes again. It is not required to release the capture when the finger is lifted
+    /// from the screen, it is done automatically.
+    #[inline]
+    pub fn release_touch_capture(&mut self, id: u64) {
+        self.touch_captures.remove(&id);
+    }
+
+    /// Returns a handle of a node that has captured a finger with the given id.
+    #[inline]
+    pub fn touch_captured_node(&self, id: u64) -> Handle<UiNode> {
+        self.touch_captures.get(&id).cloned().unwrap_or_default()
+    }
+
+    /// Returns amount of fingers that are currently on the screen.
+    #[inline]
+    pub fn touch_count(&self) -> usize {
+        self.touches.len()
+    }
+
     #[inline]
     pub fn get_drawing_context(&self) -> &DrawingContext {
         &self.drawing_context
@@ -1016,6 +1074,25 @@ impl UserInterface {
             entry.timer -= dt;
         }
 
+        for (&id, entry) in self.touches.iter_mut() {
+            entry.elapsed += dt;
+            if !entry.moved
+                && !entry.long_press_sent
+                && entry.elapsed >= self.long_press_time
+                && entry.node.is_some()
+            {
+                entry.long_press_sent = true;
+                self.sender
+                    .send(WidgetMessage::long_press(
+                        entry.node,
+                        MessageDirection::FromWidget,
+                        entry.position,
+                        id,
+                    ))
+                    .unwrap();
+            }
+        }
+
         self.handle_layout_events();
 
         self.measure_node(self.root_canvas, screen_size);
@@ -1374,7 +1451,14 @@ impl UserInterface {
 
         if self.nodes.is_valid_handle(self.captured_node) {
             self.captured_node
-        } else if self.picking_stack.is_empty() {
+        } else {
+            self.hit_test_restricted(pt)
+        }
+    }
+
+    // Performs hit test with respect to picking restrictions, but ignores mouse capture.
+    fn hit_test_restricted(&self, pt: Vector2<f32>) -> Handle<UiNode> {
+        if self.picking_stack.is_empty() {
             self.hit_test_unrestricted(pt)
         } else {
             // We have some picking restriction chain.
@@ -1897,6 +1981,30 @@ impl UserInterface {
         }
     }
 
+    fn touch_hit_test(&self, id: u64, pt: Vector2<f32>) -> Handle<UiNode> {
+        match self.touch_captures.get(&id) {
+            Some(&captured) if self.nodes.is_valid_handle(captured) => captured,
+            _ => self.hit_test_restricted(pt),
+        }
+    }
+
+    // Returns the center of all active touches and average distance from the center to them.
+    fn touches_center_and_spread(&self) -> (Vector2<f32>, f32) {
+        let count = self.touches.len().max(1) as f32;
+        let center = self
+            .touches
+            .values()
+            .fold(Vector2::default(), |sum, entry| sum + entry.position)
+            .scale(1.0 / count);
+        let spread = self
+            .touches
+            .values()
+            .map(|entry| (entry.position - center).norm())
+            .sum::<f32>()
+            / count;
+        (center, spread)
+    }
+
     fn reset_double_click_entries(&mut self) {
         for entry in self.double_click_entries.values_mut() {
             entry.timer = self.double_click_time_slice;
@@ -2181,6 +2289,131 @@ impl UserInterface {
                 // TODO: Is message needed for focused node?
                 self.keyboard_modifiers = modifiers;
             }
+            &OsEvent::Touch {
+                phase,
+                position,
+                id,
+            } => {
+                let node = self.touch_hit_test(id, position);
+
+                match phase {
+                    TouchPhase::Started => {
+                        self.touches.insert(
+                            id,
+                            TouchEntry {
+                                node,
+                                start_position: position,
+                                position,
+                                elapsed: 0.0,
+                                moved: false,
+                                long_press_sent: false,
+                            },
+                        );
+
+                        if node.is_some() {
+                            self.request_focus(node);
+                            self.send_message(WidgetMessage::touch_started(
+                                node,
+                                MessageDirection::FromWidget,
+                                position,
+                                id,
+                            ));
+                            event_processed = true;
+                        }
+                    }
+                    TouchPhase::Moved => {
+                        let (prev_center, prev_spread) = self.touches_center_and_spread();
+
+                        let mut gesture_node = Handle::NONE;
+                        if let Some(entry) = self.touches.get_mut(&id) {
+                            entry.position = position;
+                            if (position - entry.start_position).norm()
+                                > self.tap_distance_threshold
+                            {
+                                entry.moved = true;
+                            }
+                            if entry.moved {
+                                gesture_node = entry.node;
+                            }
+                        }
+
+                        if node.is_some() {
+                            self.send_message(WidgetMessage::touch_moved(
+                                node,
+                                MessageDirection::FromWidget,
+                                position,
+                                id,
+                            ));
+                            event_processed = true;
+                        }
+
+                        // Gestures are sent to the node on which the touch has started, so
+                        // they could bubble up to a widget that can consume them.
+                        if gesture_node.is_some() {
+                            let (center, spread) = self.touches_center_and_spread();
+
+                            let delta = center - prev_center;
+                            if delta != Vector2::default() {
+                                self.send_message(WidgetMessage::pan(
+                                    gesture_node,
+                                    MessageDirection::FromWidget,
+                                    delta,
+                                ));
+                            }
+
+                            if self.touches.len() > 1
+                                && prev_spread > f32::EPSILON
+                                && (spread - prev_spread).abs() > f32::EPSILON
+                            {
+                                self.send_message(WidgetMessage::pinch(
+                                    gesture_node,
+                                    MessageDirection::FromWidget,
+                                    center,
+                                    spread / prev_spread,
+                                ));
+                            }
+                        }
+                    }
+                    TouchPhase::Ended | TouchPhase::Cancelled => {
+                        let entry = self.touches.remove(&id);
+                        self.touch_captures.remove(&id);
+
+                        if node.is_some() {
+                            self.send_message(if phase == TouchPhase::Ended {
+                                WidgetMessage::touch_ended(
+                                    node,
+                                    MessageDirection::FromWidget,
+                                    position,
+                                    id,
+                                )
+                            } else {
+                                WidgetMessage::touch_cancelled(
+                                    node,
+                                    MessageDirection::FromWidget,
+                                    position,
+                                    id,
+                                )
+                            });
+                            event_processed = true;
+                        }
+
+                        if let Some(entry) = entry {
+                            if phase == TouchPhase::Ended
+                                && entry.node.is_some()
+                                && !entry.moved
+                                && !entry.long_press_sent
+                            {
+                                self.send_message(WidgetMessage::tap(
+                                    entry.node,
+                                    MessageDirection::FromWidget,
+                                    position,
+                                    id,
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
         }
 
         self.prev_picked_node = self.picked_node;
@@ -2271,6 +2504,13 @@ impl UserInterface {
             if self.captured_node == handle {
                 self.captured_node = Handle::NONE;
             }
+            self.touch_captures
+                .retain(|_, captured| *captured != handle);
+            for entry in self.touches.values_mut() {
+                if entry.node == handle {
+                    entry.node = Handle::NONE;
+                }
+            }
             if self.keyboard_focus_node == handle {
                 self.keyboard_focus_node = Handle::NONE;
             }
@@ -2616,8 +2856,11 @@ fn transform_size(transform_space_bounds: Vector2<f32>, matrix: &Matrix3<f32>) -
 mod test {
     use crate::{
         border::BorderBuilder,
+        button::{ButtonBuilder, ButtonMessage},
+        check_box::{CheckBoxBuilder, CheckBoxMessage},
         core::algebra::{Rotation2, UnitComplex, Vector2},
-        message::MessageDirection,
+        message::{MessageDirection, TouchPhase, UiMessage},
+        popup::{PopupBuilder, PopupMessage},
         text::TextMessage,
         text_box::TextBoxBuilder,
         transform_size,
@@ -2709,4 +2952,151 @@ mod test {
 
         assert!(ui.poll_message().is_none());
     }
+
+    fn touch(ui: &mut UserInterface, phase: TouchPhase, x: f32, y: f32, id: u64) -> Vec<UiMessage> {
+        ui.process_os_event(&OsEvent::Touch {
+            phase,
+            position: Vector2::new(x, y),
+            id,
+        });
+        let mut messages = Vec::new();
+        while let Some(message) = ui.poll_message() {
+            messages.push(message);
+        }
+        messages
+    }
+
+    #[test]
+    fn test_touch_gestures() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let widget = BorderBuilder::new(WidgetBuilder::new().with_width(100.0).with_height(100.0))
+            .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+        // Hit testing uses drawing commands.
+        ui.draw();
+        while ui.poll_message().is_some() {}
+
+        // Tap.
+        let messages = touch(&mut ui, TouchPhase::Started, 50.0, 50.0, 0);
+        assert!(messages.contains(&WidgetMessage::touch_started(
+            widget,
+            MessageDirection::FromWidget,
+            Vector2::new(50.0, 50.0),
+            0
+        )));
+        let messages = touch(&mut ui, TouchPhase::Ended, 52.0, 50.0, 0);
+        assert!(messages.contains(&WidgetMessage::tap(
+            widget,
+            MessageDirection::FromWidget,
+            Vector2::new(52.0, 50.0),
+            0
+        )));
+
+        // Long press, no tap after it.
+        touch(&mut ui, TouchPhase::Started, 50.0, 50.0, 1);
+        ui.update(screen_size, ui.long_press_time);
+        let mut messages = Vec::new();
+        while let Some(message) = ui.poll_message() {
+            messages.push(message);
+        }
+        assert!(messages.contains(&WidgetMessage::long_press(
+            widget,
+            MessageDirection::FromWidget,
+            Vector2::new(50.0, 50.0),
+            1
+        )));
+        let messages = touch(&mut ui, TouchPhase::Ended, 50.0, 50.0, 1);
+        assert!(!messages
+            .iter()
+            .any(|m| matches!(m.data::<WidgetMessage>(), Some(WidgetMessage::Tap { .. }))));
+
+        // Captured finger outside of the widget produces pan.
+        assert!(ui.capture_touch(2, widget));
+        assert!(!ui.capture_touch(2, ui.root()));
+        touch(&mut ui, TouchPhase::Started, 500.0, 500.0, 2);
+        let messages = touch(&mut ui, TouchPhase::Moved, 600.0, 500.0, 2);
+        assert!(messages.contains(&WidgetMessage::touch_moved(
+            widget,
+            MessageDirection::FromWidget,
+            Vector2::new(600.0, 500.0),
+            2
+        )));
+        assert!(messages.contains(&WidgetMessage::pan(
+            widget,
+            MessageDirection::FromWidget,
+            Vector2::new(100.0, 0.0)
+        )));
+        touch(&mut ui, TouchPhase::Cancelled, 600.0, 500.0, 2);
+        assert!(ui.touch_captured_node(2).is_none());
+        assert_eq!(ui.touch_count(), 0);
+
+        // Pinch.
+        touch(&mut ui, TouchPhase::Started, 40.0, 50.0, 3);
+        touch(&mut ui, TouchPhase::Started, 60.0, 50.0, 4);
+        let messages = touch(&mut ui, TouchPhase::Moved, 80.0, 50.0, 4);
+        assert!(messages.contains(&WidgetMessage::pinch(
+            widget,
+            MessageDirection::FromWidget,
+            Vector2::new(60.0, 50.0),
+            2.0
+        )));
+    }
+
+    #[test]
+    fn test_tap_on_standard_widgets() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let button = ButtonBuilder::new(WidgetBuilder::new().with_width(100.0).with_height(100.0))
+            .with_text("Button")
+            .build(&mut ui.build_ctx());
+        let check_box = CheckBoxBuilder::new(
+            WidgetBuilder::new()
+                .with_desired_position(Vector2::new(200.0, 0.0))
+                .with_width(20.0)
+                .with_height(20.0),
+        )
+        .checked(Some(false))
+        .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+        // Hit testing uses drawing commands.
+        ui.draw();
+        while ui.poll_message().is_some() {}
+
+        touch(&mut ui, TouchPhase::Started, 50.0, 50.0, 0);
+        assert!(ui.touch_captured_node(0).is_some());
+        let messages = touch(&mut ui, TouchPhase::Ended, 50.0, 50.0, 0);
+        assert!(messages.contains(&ButtonMessage::click(button, MessageDirection::FromWidget)));
+        assert!(ui.touch_captured_node(0).is_none());
+
+        touch(&mut ui, TouchPhase::Started, 210.0, 10.0, 1);
+        let messages = touch(&mut ui, TouchPhase::Ended, 210.0, 10.0, 1);
+        assert!(messages.contains(&CheckBoxMessage::checked(
+            check_box,
+            MessageDirection::ToWidget,
+            Some(true)
+        )));
+    }
+
+    #[test]
+    fn test_popup_closes_on_touch_outside() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let popup = PopupBuilder::new(WidgetBuilder::new().with_width(100.0).with_height(100.0))
+            .build(&mut ui.build_ctx());
+        ui.send_message(PopupMessage::open(popup, MessageDirection::ToWidget));
+        while ui.poll_message().is_some() {}
+        ui.update(screen_size, 0.0);
+        // Hit testing uses drawing commands.
+        ui.draw();
+        while ui.poll_message().is_some() {}
+
+        // Touch inside keeps the popup open.
+        let messages = touch(&mut ui, TouchPhase::Started, 50.0, 50.0, 0);
+        assert!(!messages.contains(&PopupMessage::close(popup, MessageDirection::ToWidget)));
+        touch(&mut ui, TouchPhase::Ended, 50.0, 50.0, 0);
+
+        let messages = touch(&mut ui, TouchPhase::Started, 500.0, 500.0, 1);
+        assert!(messages.contains(&PopupMessage::close(popup, MessageDirection::ToWidget)));
+    }
 }
@@ -5,7 +5,7 @@ use crate::{
     decorator::DecoratorBuilder,
     define_constructor,
     grid::{Column, GridBuilder, Row},
-    message::{ButtonState, MessageDirection, OsEvent, UiMessage},
+    message::{MessageDirection, OsEvent, UiMessage},
     popup::{Placement, Popup, PopupBuilder, PopupMessage},
     stack_panel::StackPanelBuilder,
     text::TextBuilder,
@@ -121,10 +121,9 @@ impl Control for Menu {
         // raw event here because we need to know the fact that mouse was clicked
         // and we do not care which element was clicked so we'll get here in any
         // case.
-        if let OsEvent::MouseInput { state, .. } = event {
-            if *state == ButtonState::Pressed && self.active {
+        if let Some(pos) = event.press_position(ui.cursor_position()) {
+            if self.active {
                 // TODO: Make picking more accurate - right now it works only with rects.
-                let pos = ui.cursor_position();
                 if !self.widget.screen_bounds().contains(pos) {
                     // Also check if we clicked inside some descendant menu item - in this
                     // case we don't need to close menu.
@@ -179,6 +178,41 @@ pub struct MenuItem {
 
 crate::define_widget_deref!(MenuItem);
 
+impl MenuItem {
+    fn on_pressed(&self, ui: &UserInterface) {
+        let menu = find_menu(self.parent(), ui);
+        if menu.is_some() {
+            // Activate menu so it user will be able to open submenus by
+            // mouse hover.
+            ui.send_message(MenuMessage::activate(menu, MessageDirection::ToWidget));
+
+            ui.send_message(MenuItemMessage::open(
+                self.handle(),
+                MessageDirection::ToWidget,
+            ));
+        }
+    }
+
+    fn on_released(&self, ui: &UserInterface) {
+        if self.items.is_empty() || self.clickable_when_not_empty {
+            ui.send_message(MenuItemMessage::click(
+                self.handle(),
+                MessageDirection::ToWidget,
+            ));
+        }
+        if self.items.is_empty() {
+            let menu = find_menu(self.parent(), ui);
+            if menu.is_some() {
+                // Deactivate menu if we have one.
+                ui.send_message(MenuMessage::deactivate(menu, MessageDirection::ToWidget));
+            } else {
+                // Or close menu chain if menu item is in "orphaned" state.
+                close_menu_chain(self.parent(), ui);
+            }
+        }
+    }
+}
+
 // MenuItem uses popup to show its content, popup can be top-most only if it is
 // direct child of root canvas of UI. This fact adds some complications to search
 // of parent menu - we can't just traverse the tree because popup is not a child
@@ -266,39 +300,19 @@ impl Control for MenuItem {
         if let Some(msg) = message.data::<WidgetMessage>() {
             match msg {
                 WidgetMessage::MouseDown { .. } => {
-                    let menu = find_menu(self.parent(), ui);
-                    if menu.is_some() {
-                        // Activate menu so it user will be able to open submenus by
-                        // mouse hover.
-                        ui.send_message(MenuMessage::activate(menu, MessageDirection::ToWidget));
-
-                        ui.send_message(MenuItemMessage::open(
-                            self.handle(),
-                            MessageDirection::ToWidget,
-                        ));
-                    }
+                    self.on_pressed(ui);
                 }
                 WidgetMessage::MouseUp { .. } => {
                     if !message.handled() {
-                        if self.items.is_empty() || self.clickable_when_not_empty {
-                            ui.send_message(MenuItemMessage::click(
-                                self.handle(),
-                                MessageDirection::ToWidget,
-                            ));
-                        }
-                        if self.items.is_empty() {
-                            let menu = find_menu(self.parent(), ui);
-                            if menu.is_some() {
-                                // Deactivate menu if we have one.
-                                ui.send_message(MenuMessage::deactivate(
-                                    menu,
-                                    MessageDirection::ToWidget,
-                                ));
-                            } else {
-                                // Or close menu chain if menu item is in "orphaned" state.
-                                close_menu_chain(self.parent(), ui);
-                            }
-                        }
+                        self.on_released(ui);
+                        message.set_handled(true);
+                    }
+                }
+                WidgetMessage::Tap { .. } => {
+                    // Tap is a press and a release at once.
+                    if !message.handled() {
+                        self.on_pressed(ui);
+                        self.on_released(ui);
                         message.set_handled(true);
                     }
                 }
@@ -438,23 +452,21 @@ impl Control for MenuItem {
         ui: &mut UserInterface,
         event: &OsEvent,
     ) {
-        // Allow closing "orphaned" menus by clicking outside of them.
-        if let OsEvent::MouseInput { state, .. } = event {
-            if *state == ButtonState::Pressed {
-                if let Some(popup) = ui.node(self.popup).query_component::<Popup>() {
-                    if popup.is_open {
-                        // Ensure that cursor is outside of any menus.
-                        if !is_any_menu_item_contains_point(ui, ui.cursor_position())
-                            && find_menu(self.parent(), ui).is_none()
-                        {
-                            ui.send_message(PopupMessage::close(
-                                self.popup,
-                                MessageDirection::ToWidget,
-                            ));
+        // Allow closing "orphaned" menus by clicking (or tapping) outside of them.
+        if let Some(pos) = event.press_position(ui.cursor_position()) {
+            if let Some(popup) = ui.node(self.popup).query_component::<Popup>() {
+                if popup.is_open {
+                    // Ensure that cursor is outside of any menus.
+                    if !is_any_menu_item_contains_point(ui, pos)
+                        && find_menu(self.parent(), ui).is_none()
+                    {
+                        ui.send_message(PopupMessage::close(
+                            self.popup,
+                            MessageDirection::ToWidget,
+                        ));
 
-                            // Close all other popups.
-                            close_menu_chain(self.parent(), ui);
-                        }
+                        // Close all other popups.
+                        close_menu_chain(self.parent(), ui);
                     }
                 }
             }
@@ -271,6 +271,19 @@ pub enum MouseButton {
     Other(u16),
 }
 
+/// Describes a phase of a touch (a finger on a touch screen).
+#[derive(Debug, Hash, Ord, PartialOrd, PartialEq, Eq, Clone, Copy)]
+pub enum TouchPhase {
+    /// A finger has touched the screen.
+    Started,
+    /// A finger has moved on the screen.
+    Moved,
+    /// A finger was lifted from the screen.
+    Ended,
+    /// The system has cancelled tracking of the touch (for example when the window lost focus).
+    Cancelled,
+}
+
 pub enum OsEvent {
     MouseInput {
         button: MouseButton,
@@ -286,6 +299,34 @@ pub enum OsEvent {
     Character(char),
     KeyboardModifiers(KeyboardModifiers),
     MouseWheel(f32, f32),
+    Touch {
+        phase: TouchPhase,
+        /// Position of the finger in screen coordinates.
+        position: Vector2<f32>,
+        /// Unique identifier of the finger. It stays the same while the finger is on the screen,
+        /// but it could be reused for next touches.
+        id: u64,
+    },
+}
+
+impl OsEvent {
+    /// Returns screen position of a press, if the event is a press of a mouse button (the given
+    /// cursor position is returned in this case) or a start of a touch. It is used by widgets that
+    /// should be closed by a click or a tap outside of them (popups, menus, etc.).
+    pub fn press_position(&self, cursor_position: Vector2<f32>) -> Option<Vector2<f32>> {
+        match *self {
+            OsEvent::MouseInput {
+                state: ButtonState::Pressed,
+                ..
+            } => Some(cursor_position),
+            OsEvent::Touch {
+                phase: TouchPhase::Started,
+                position,
+                ..
+            } => Some(position),
+            _ => None,
+        }
+    }
 }
 
 #[derive(
@@ -2,7 +2,7 @@ use crate::{
     border::BorderBuilder,
     core::{algebra::Vector2, math::Rect, pool::Handle},
     define_constructor,
-    message::{ButtonState, MessageDirection, OsEvent, UiMessage},
+    message::{MessageDirection, OsEvent, UiMessage},
     widget::{Widget, WidgetBuilder, WidgetMessage},
     BuildContext, Control, NodeHandleMapping, RestrictionEntry, Thickness, UiNode, UserInterface,
     BRUSH_DARKEST, BRUSH_PRIMARY,
@@ -249,19 +249,17 @@ impl Control for Popup {
         ui: &mut UserInterface,
         event: &OsEvent,
     ) {
-        if let OsEvent::MouseInput { state, .. } = event {
+        if let Some(pos) = event.press_position(ui.cursor_position()) {
             if let Some(top_restriction) = ui.top_picking_restriction() {
-                if *state == ButtonState::Pressed
-                    && top_restriction.handle == self_handle
+                if top_restriction.handle == self_handle
                     && self.is_open
+                    && !self.widget.screen_bounds().contains(pos)
+                    && !self.stays_open
                 {
-                    let pos = ui.cursor_position();
-                    if !self.widget.screen_bounds().contains(pos) && !self.stays_open {
-                        ui.send_message(PopupMessage::close(
-                            self.handle(),
-                            MessageDirection::ToWidget,
-                        ));
-                    }
+                    ui.send_message(PopupMessage::close(
+                        self.handle(),
+                        MessageDirection::ToWidget,
+                    ));
                 }
             }
         }
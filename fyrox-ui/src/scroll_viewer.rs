@@ -126,6 +126,30 @@ impl Control for ScrollViewer {
                     ));
                 }
             }
+        } else if let Some(WidgetMessage::Pan { delta }) = message.data::<WidgetMessage>() {
+            if !message.handled() {
+                // Content follows the fingers, so scroll in opposite direction.
+                for (scroll_bar, amount) in
+                    [(self.h_scroll_bar, delta.x), (self.v_scroll_bar, delta.y)]
+                {
+                    if let Some(scroll_bar_ref) = ui
+                        .try_get_node(scroll_bar)
+                        .and_then(|n| n.cast::<ScrollBar>())
+                    {
+                        let old_value = scroll_bar_ref.value;
+                        let new_value =
+                            (old_value - amount).clamp(scroll_bar_ref.min, scroll_bar_ref.max);
+                        if (old_value - new_value).abs() > f32::EPSILON {
+                            message.set_handled(true);
+                            ui.send_message(ScrollBarMessage::value(
+                                scroll_bar,
+                                MessageDirection::ToWidget,
+                                new_value,
+                            ));
+                        }
+                    }
+                }
+            }
         } else if let Some(msg) = message.data::<ScrollPanelMessage>() {
             if message.destination() == self.scroll_panel {
                 let msg = match *msg {
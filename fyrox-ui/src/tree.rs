@@ -143,7 +143,7 @@ impl Control for Tree {
         } else if let Some(msg) = message.data::<WidgetMessage>() {
             if !message.handled() {
                 match msg {
-                    WidgetMessage::MouseDown { .. } => {
+                    WidgetMessage::MouseDown { .. } | WidgetMessage::Tap { .. } => {
                         let keyboard_modifiers = ui.keyboard_modifiers();
                         // Prevent selection changes by Alt+Click to be able to drag'n'drop tree items.
                         if !keyboard_modifiers.alt {
@@ -278,6 +278,91 @@ pub enum WidgetMessage {
     /// A double click of a mouse button has occurred on a widget.
     DoubleClick { button: MouseButton },
 
+    /// Initiated when user touches a widget's geometry with a finger.
+    ///
+    /// Direction: **From UI**.
+    TouchStarted {
+        /// Position of the finger.
+        pos: Vector2<f32>,
+        /// Unique identifier of the finger.
+        id: u64,
+    },
+
+    /// Initiated when user moves a finger over widget's geometry (or over any geometry, if the
+    /// finger was captured by the widget).
+    ///
+    /// Direction: **From UI**.
+    TouchMoved {
+        /// New position of the finger.
+        pos: Vector2<f32>,
+        /// Unique identifier of the finger.
+        id: u64,
+    },
+
+    /// Initiated when user lifts a finger from widget's geometry.
+    ///
+    /// Direction: **From UI**.
+    TouchEnded {
+        /// Position of the finger.
+        pos: Vector2<f32>,
+        /// Unique identifier of the finger.
+        id: u64,
+    },
+
+    /// Initiated when the system has cancelled a touch. A widget should discard any action that
+    /// was initiated by the touch.
+    ///
+    /// Direction: **From UI**.
+    TouchCancelled {
+        /// Last known position of the finger.
+        pos: Vector2<f32>,
+        /// Unique identifier of the finger.
+        id: u64,
+    },
+
+    /// Initiated when user has touched a widget and lifted the finger quickly without moving it.
+    /// See [`UserInterface::long_press_time`] and [`UserInterface::tap_distance_threshold`].
+    ///
+    /// Direction: **From UI**.
+    Tap {
+        /// Position of the finger.
+        pos: Vector2<f32>,
+        /// Unique identifier of the finger.
+        id: u64,
+    },
+
+    /// Initiated when user holds a finger on a widget without moving it for some time. See
+    /// [`UserInterface::long_press_time`].
+    ///
+    /// Direction: **From UI**.
+    LongPress {
+        /// Position of the finger.
+        pos: Vector2<f32>,
+        /// Unique identifier of the finger.
+        id: u64,
+    },
+
+    /// Initiated when user moves one or more fingers that were put on a widget. The message is
+    /// sent to the widget on which the touch has started, any widget up on the hierarchy could
+    /// consume it (for example [`crate::scroll_viewer::ScrollViewer`] scrolls its content).
+    ///
+    /// Direction: **From UI**.
+    Pan {
+        /// Movement of the center of all active touches in screen coordinates.
+        delta: Vector2<f32>,
+    },
+
+    /// Initiated when user moves two or more fingers closer or farther from each other.
+    ///
+    /// Direction: **From UI**.
+    Pinch {
+        /// Center of all active touches in screen coordinates.
+        center: Vector2<f32>,
+        /// Relative change of the distance between the fingers since last pinch message. Values
+        /// greater than 1.0 mean that fingers move apart from each other.
+        scale: f32,
+    },
+
     /// A request to set new context menu for a widget. Old context menu will be removed.
     ContextMenu(Option<RcUiNodeHandle>),
 }
@@ -329,6 +414,14 @@ impl WidgetMessage {
     define_constructor!(WidgetMessage:DragOver => fn drag_over(Handle<UiNode>), layout: false);
     define_constructor!(WidgetMessage:Drop => fn drop(Handle<UiNode>), layout: false);
     define_constructor!(WidgetMessage:DoubleClick => fn double_click(button: MouseButton), layout: false);
+    define_constructor!(WidgetMessage:TouchStarted => fn touch_started(pos: Vector2<f32>, id: u64), layout: false);
+    define_constructor!(WidgetMessage:TouchMoved => fn touch_moved(pos: Vector2<f32>, id: u64), layout: false);
+    define_constructor!(WidgetMessage:TouchEnded => fn touch_ended(pos: Vector2<f32>, id: u64), layout: false);
+    define_constructor!(WidgetMessage:TouchCancelled => fn touch_cancelled(pos: Vector2<f32>, id: u64), layout: false);
+    define_constructor!(WidgetMessage:Tap => fn tap(pos: Vector2<f32>, id: u64), layout: false);
+    define_constructor!(WidgetMessage:LongPress => fn long_press(pos: Vector2<f32>, id: u64), layout: false);
+    define_constructor!(WidgetMessage:Pan => fn pan(delta: Vector2<f32>), layout: false);
+    define_constructor!(WidgetMessage:Pinch => fn pinch(center: Vector2<f32>, scale: f32), layout: false);
 }
 
 #[derive(Debug, Clone)]
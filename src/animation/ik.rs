@@ -0,0 +1,818 @@
+//! Inverse kinematics (IK) solvers. IK solvers are used to modify an animation pose so that the end of a chain
+//! of bones reaches a target. Typical use cases are foot placement on uneven terrain, or hands that are grabbing
+//! props. See [`IkSolver`] docs for more info.
+
+use crate::{
+    animation::{
+        machine::{Parameter, ParameterContainer, PoseWeight},
+        value::{BoundValue, TrackValue, ValueBinding},
+        AnimationPose, NodePose,
+    },
+    core::{
+        algebra::{Matrix3, Matrix4, Rotation3, UnitQuaternion, Vector3},
+        math::Matrix4Ext,
+        pool::Handle,
+        reflect::prelude::*,
+        visitor::prelude::*,
+    },
+    scene::{graph::NodePool, node::Node, transform::Transform},
+};
+use std::collections::hash_map::Entry;
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+/// An algorithm that is used by an IK solver.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, Hash, Visit, Reflect, AsRefStr, EnumString, EnumVariantNames,
+)]
+pub enum IkSolverKind {
+    /// Analytic solver for a chain of exactly three nodes (for example: thigh, calf, foot or upper arm, forearm,
+    /// hand). It is the fastest and the most stable solver, it should be preferred for limbs.
+    TwoBone,
+
+    /// Forward And Backward Reaching Inverse Kinematics. Iterative solver for chains of any length, it produces
+    /// natural looking results for long chains (tails, tentacles, spines).
+    Fabrik,
+
+    /// Cyclic Coordinate Descent. Iterative solver for chains of any length, it tends to curl the end of a chain
+    /// first.
+    Ccd,
+}
+
+impl Default for IkSolverKind {
+    fn default() -> Self {
+        Self::TwoBone
+    }
+}
+
+/// Angle limits of a joint of an IK chain. The limits restrict the bend angle of a joint - an angle between the
+/// direction of the incoming bone (from the parent joint to the joint) and the direction of the outgoing bone
+/// (from the joint to the child joint). Zero angle means that the bones are collinear (the chain is straight
+/// at the joint).
+#[derive(Copy, Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct JointLimits {
+    /// Minimal bend angle in radians.
+    #[reflect(min_value = 0.0, max_value = 3.1415)]
+    pub min_angle: f32,
+
+    /// Maximal bend angle in radians.
+    #[reflect(min_value = 0.0, max_value = 3.1415)]
+    pub max_angle: f32,
+}
+
+impl Default for JointLimits {
+    fn default() -> Self {
+        Self {
+            min_angle: 0.0,
+            max_angle: std::f32::consts::PI,
+        }
+    }
+}
+
+impl JointLimits {
+    /// Creates new joint limits from the given angles (in radians).
+    pub fn new(min_angle: f32, max_angle: f32) -> Self {
+        Self {
+            min_angle,
+            max_angle,
+        }
+    }
+
+    fn clamp(&self, angle: f32) -> f32 {
+        let min = self.min_angle.max(0.0);
+        let max = self.max_angle.min(std::f32::consts::PI).max(min);
+        angle.clamp(min, max)
+    }
+}
+
+/// IK solver modifies an animation pose so the end effector of a chain of nodes reaches (if possible) a target
+/// node. The chain is defined by two nodes - a root and an end effector, the root must be an ancestor of the end
+/// effector. The solver works as a post-process of an animation pose, it takes the pose produced by an
+/// animation player or a state machine and modifies rotations of the chain nodes.
+///
+/// # Pole
+///
+/// Pole is an optional node that defines a direction in which the chain should bend. For example, a pole for a
+/// leg chain is usually placed in front of the knee.
+///
+/// # Weight
+///
+/// Weight defines how much the solved pose affects the animation pose. It could be either a constant or a
+/// `Weight` parameter of a state machine, so the IK could be enabled/disabled smoothly. For example, a character
+/// could enable IK for hands only when it is near a prop it is going to grab.
+///
+/// # Joint limits
+///
+/// Every joint of a chain could have its own limits (see [`JointLimits`] docs for more info). Limits are
+/// specified per joint, starting from the root of a chain. Limits of the root and the end effector are ignored,
+/// since their bend angles are not defined by the chain. Missing limits are treated as no limits.
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::{
+///     animation::{
+///         ik::{IkSolver, IkSolverKind, JointLimits},
+///         machine::PoseWeight,
+///     },
+///     core::pool::Handle,
+/// };
+///
+/// // Assume that these are correct handles.
+/// let thigh = Handle::default();
+/// let foot = Handle::default();
+/// let foot_target = Handle::default();
+/// let knee_pole = Handle::default();
+///
+/// let mut solver = IkSolver::new(IkSolverKind::TwoBone, thigh, foot, foot_target);
+/// solver.pole = knee_pole;
+/// solver.weight = PoseWeight::Parameter("LeftFootIk".to_string());
+/// // Do not allow the knee to bend more than 150 degrees.
+/// solver.limits = vec![
+///     JointLimits::default(),
+///     JointLimits::new(0.0, 150.0f32.to_radians()),
+/// ];
+/// ```
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct IkSolver {
+    /// Name of the solver.
+    pub name: String,
+
+    /// Disabled solvers does not modify animation poses.
+    pub enabled: bool,
+
+    /// An algorithm that will be used to solve the chain.
+    pub kind: IkSolverKind,
+
+    /// First node of the chain.
+    pub root: Handle<Node>,
+
+    /// Last node of the chain, the solver will try to move it to the target.
+    pub end_effector: Handle<Node>,
+
+    /// A node, which position will be used as the target for the end effector.
+    pub target: Handle<Node>,
+
+    /// An optional node, which position defines a direction in which the chain will bend.
+    pub pole: Handle<Node>,
+
+    /// Weight of the solved pose. See [`IkSolver`] docs for more info.
+    pub weight: PoseWeight,
+
+    /// Maximum amount of iterations for iterative solvers.
+    pub iterations: u32,
+
+    /// Maximum distance between the end effector and the target at which iterative solvers stop.
+    #[reflect(min_value = 0.0)]
+    pub tolerance: f32,
+
+    /// Per-joint limits, starting from the root of the chain.
+    pub limits: Vec<JointLimits>,
+}
+
+impl Default for IkSolver {
+    fn default() -> Self {
+        Self {
+            name: "IkSolver".to_string(),
+            enabled: true,
+            kind: Default::default(),
+            root: Default::default(),
+            end_effector: Default::default(),
+            target: Default::default(),
+            pole: Default::default(),
+            weight: PoseWeight::Constant(1.0),
+            iterations: 10,
+            tolerance: 0.001,
+            limits: Default::default(),
+        }
+    }
+}
+
+const EPSILON: f32 = 1.0e-6;
+
+fn posed_local_transform(
+    nodes: &NodePool,
+    pose: &AnimationPose,
+    handle: Handle<Node>,
+) -> Option<Transform> {
+    let node = nodes.try_borrow(handle)?;
+    let mut transform = node.local_transform().clone();
+    if let Some(node_pose) = pose.poses().get(&handle) {
+        for bound_value in node_pose.values.values.iter() {
+            match (&bound_value.binding, &bound_value.value) {
+                (ValueBinding::Position, TrackValue::Vector3(position)) => {
+                    transform.set_position(*position);
+                }
+                (ValueBinding::Rotation, TrackValue::UnitQuaternion(rotation)) => {
+                    transform.set_rotation(*rotation);
+                }
+                (ValueBinding::Scale, TrackValue::Vector3(scale)) => {
+                    transform.set_scale(*scale);
+                }
+                _ => (),
+            }
+        }
+    }
+    Some(transform)
+}
+
+/// Calculates global transform of a node using its local transform and local transforms of its ancestors, taking
+/// the values from the pose into account. Global transforms of scene nodes cannot be used here, because they're
+/// calculated before the pose is applied.
+fn posed_global_transform(
+    nodes: &NodePool,
+    pose: &AnimationPose,
+    handle: Handle<Node>,
+) -> Matrix4<f32> {
+    let mut global = Matrix4::identity();
+    let mut current = handle;
+    while let Some(local) = posed_local_transform(nodes, pose, current) {
+        global = local.matrix() * global;
+        current = nodes[current].parent();
+    }
+    global
+}
+
+fn rotation_of(matrix: &Matrix4<f32>) -> UnitQuaternion<f32> {
+    let basis = Matrix3::from_columns(&[
+        matrix
+            .side()
+            .try_normalize(EPSILON)
+            .unwrap_or_else(Vector3::x),
+        matrix
+            .up()
+            .try_normalize(EPSILON)
+            .unwrap_or_else(Vector3::y),
+        matrix
+            .look()
+            .try_normalize(EPSILON)
+            .unwrap_or_else(Vector3::z),
+    ]);
+    UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix_unchecked(basis))
+}
+
+fn any_perpendicular(v: &Vector3<f32>) -> Vector3<f32> {
+    let other = if v.x.abs() < 0.9 {
+        Vector3::x()
+    } else {
+        Vector3::y()
+    };
+    v.cross(&other)
+        .try_normalize(EPSILON)
+        .unwrap_or_else(Vector3::z)
+}
+
+fn rotation_between(from: &Vector3<f32>, to: &Vector3<f32>) -> UnitQuaternion<f32> {
+    UnitQuaternion::rotation_between(from, to).unwrap_or_else(|| {
+        if from.dot(to) < 0.0 {
+            // Opposite vectors.
+            UnitQuaternion::from_scaled_axis(any_perpendicular(from) * std::f32::consts::PI)
+        } else {
+            // Degenerated case (zero-length vectors).
+            UnitQuaternion::identity()
+        }
+    })
+}
+
+/// Restricts the bend angle between two directions, returns a new outgoing direction.
+fn limit_bend(
+    incoming: &Vector3<f32>,
+    outgoing: &Vector3<f32>,
+    limits: &JointLimits,
+) -> Vector3<f32> {
+    let (incoming, outgoing) = match (
+        incoming.try_normalize(EPSILON),
+        outgoing.try_normalize(EPSILON),
+    ) {
+        (Some(incoming), Some(outgoing)) => (incoming, outgoing),
+        _ => return *outgoing,
+    };
+
+    let angle = incoming.dot(&outgoing).clamp(-1.0, 1.0).acos();
+    let clamped = limits.clamp(angle);
+    if (clamped - angle).abs() <= EPSILON {
+        return outgoing;
+    }
+
+    let axis = incoming
+        .cross(&outgoing)
+        .try_normalize(EPSILON)
+        .unwrap_or_else(|| any_perpendicular(&incoming));
+    UnitQuaternion::from_scaled_axis(axis * clamped) * incoming
+}
+
+fn rotate_sub_chain(
+    positions: &mut [Vector3<f32>],
+    pivot_index: usize,
+    rotation: &UnitQuaternion<f32>,
+) {
+    let pivot = positions[pivot_index];
+    for position in positions[(pivot_index + 1)..].iter_mut() {
+        *position = pivot + rotation.transform_vector(&(*position - pivot));
+    }
+}
+
+/// Rotates every inner joint of a chain around the line between its neighbours so that the joint points
+/// towards the pole as much as possible. Such rotation preserves lengths of the bones.
+fn apply_pole(positions: &mut [Vector3<f32>], pole: &Vector3<f32>) {
+    for i in 1..positions.len().saturating_sub(1) {
+        let start = positions[i - 1];
+        let axis = match (positions[i + 1] - start).try_normalize(EPSILON) {
+            Some(axis) => axis,
+            None => continue,
+        };
+
+        let project = |v: Vector3<f32>| {
+            let v = v - start;
+            v - axis.scale(v.dot(&axis))
+        };
+
+        let joint = project(positions[i]);
+        let pole = project(*pole);
+        if joint.norm() <= EPSILON || pole.norm() <= EPSILON {
+            continue;
+        }
+
+        let angle = joint.angle(&pole);
+        let sign = axis.dot(&joint.cross(&pole)).signum();
+        let rotation = UnitQuaternion::from_scaled_axis(axis * angle * sign);
+        positions[i] = start + rotation.transform_vector(&(positions[i] - start));
+    }
+}
+
+fn solve_two_bone(
+    positions: &mut [Vector3<f32>],
+    target: &Vector3<f32>,
+    pole: Option<&Vector3<f32>>,
+    limits: &JointLimits,
+) {
+    let a = (positions[1] - positions[0]).norm();
+    let b = (positions[2] - positions[1]).norm();
+    let to_target = target - positions[0];
+    let distance = to_target.norm();
+    if a <= EPSILON || b <= EPSILON || distance <= EPSILON {
+        return;
+    }
+
+    // Distance between the root and the end effector as a function of the bend angle of the middle joint.
+    let distance_at = |bend: f32| (a * a + b * b + 2.0 * a * b * bend.cos()).max(0.0).sqrt();
+    let min_distance = distance_at(limits.clamp(std::f32::consts::PI));
+    let max_distance = distance_at(limits.clamp(0.0));
+    let distance = distance.clamp(min_distance, max_distance).max(EPSILON);
+
+    let direction = to_target.scale(1.0 / to_target.norm());
+    let bend_hint = pole
+        .map(|pole| pole - positions[0])
+        .unwrap_or(positions[1] - positions[0]);
+    let bend_direction = (bend_hint - direction.scale(bend_hint.dot(&direction)))
+        .try_normalize(EPSILON)
+        .unwrap_or_else(|| any_perpendicular(&direction));
+
+    let cos_root = ((a * a + distance * distance - b * b) / (2.0 * a * distance)).clamp(-1.0, 1.0);
+    let sin_root = (1.0 - cos_root * cos_root).max(0.0).sqrt();
+
+    positions[1] = positions[0] + (direction.scale(cos_root) + bend_direction.scale(sin_root)) * a;
+    positions[2] = positions[0] + direction.scale(distance);
+}
+
+fn solve_fabrik(
+    positions: &mut [Vector3<f32>],
+    target: &Vector3<f32>,
+    limits: &[JointLimits],
+    iterations: u32,
+    tolerance: f32,
+) {
+    let count = positions.len();
+    let lengths = positions
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).norm())
+        .collect::<Vec<_>>();
+    let root = positions[0];
+
+    for _ in 0..iterations {
+        // Forward reaching - from the end effector to the root.
+        positions[count - 1] = *target;
+        for i in (0..count - 1).rev() {
+            let direction = (positions[i] - positions[i + 1])
+                .try_normalize(EPSILON)
+                .unwrap_or_else(Vector3::y);
+            positions[i] = positions[i + 1] + direction.scale(lengths[i]);
+        }
+
+        // Backward reaching - from the root to the end effector.
+        positions[0] = root;
+        for i in 0..count - 1 {
+            let mut direction = (positions[i + 1] - positions[i])
+                .try_normalize(EPSILON)
+                .unwrap_or_else(Vector3::y);
+            if i > 0 {
+                if let Some(limits) = limits.get(i) {
+                    direction = limit_bend(&(positions[i] - positions[i - 1]), &direction, limits);
+                }
+            }
+            positions[i + 1] = positions[i] + direction.scale(lengths[i]);
+        }
+
+        if (positions[count - 1] - target).norm() <= tolerance {
+            break;
+        }
+    }
+}
+
+fn solve_ccd(
+    positions: &mut [Vector3<f32>],
+    target: &Vector3<f32>,
+    limits: &[JointLimits],
+    iterations: u32,
+    tolerance: f32,
+) {
+    let count = positions.len();
+
+    for _ in 0..iterations {
+        for i in (0..count - 1).rev() {
+            let rotation = rotation_between(
+                &(positions[count - 1] - positions[i]),
+                &(target - positions[i]),
+            );
+            rotate_sub_chain(positions, i, &rotation);
+
+            if i > 0 {
+                if let Some(limits) = limits.get(i) {
+                    let outgoing = positions[i + 1] - positions[i];
+                    let limited = limit_bend(&(positions[i] - positions[i - 1]), &outgoing, limits);
+                    let correction = rotation_between(&outgoing, &limited);
+                    rotate_sub_chain(positions, i, &correction);
+                }
+            }
+        }
+
+        if (positions[count - 1] - target).norm() <= tolerance {
+            break;
+        }
+    }
+}
+
+fn set_rotation(pose: &mut AnimationPose, node: Handle<Node>, rotation: UnitQuaternion<f32>) {
+    let bound_value = BoundValue {
+        binding: ValueBinding::Rotation,
+        value: TrackValue::UnitQuaternion(rotation),
+    };
+
+    match pose.poses_mut().entry(node) {
+        Entry::Occupied(entry) => {
+            let values = &mut entry.into_mut().values.values;
+            if let Some(existing) = values
+                .iter_mut()
+                .find(|v| v.binding == ValueBinding::Rotation)
+            {
+                *existing = bound_value;
+            } else {
+                values.push(bound_value);
+            }
+        }
+        Entry::Vacant(entry) => {
+            let mut node_pose = NodePose {
+                node,
+                values: Default::default(),
+            };
+            node_pose.values.values.push(bound_value);
+            entry.insert(node_pose);
+        }
+    }
+}
+
+impl IkSolver {
+    /// Creates a new solver of the given kind for the chain, defined by the root and the end effector.
+    pub fn new(
+        kind: IkSolverKind,
+        root: Handle<Node>,
+        end_effector: Handle<Node>,
+        target: Handle<Node>,
+    ) -> Self {
+        Self {
+            kind,
+            root,
+            end_effector,
+            target,
+            ..Default::default()
+        }
+    }
+
+    /// Calculates actual weight of the solver using the given set of parameters.
+    pub fn weight_value(&self, parameters: &ParameterContainer) -> f32 {
+        match self.weight {
+            PoseWeight::Constant(value) => value,
+            PoseWeight::Parameter(ref param_id) => {
+                if let Some(Parameter::Weight(weight)) = parameters.get(param_id) {
+                    *weight
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
+    /// Collects the chain of nodes from the root to the end effector (both inclusive). Returns `None` if the root
+    /// is not an ancestor of the end effector.
+    pub fn collect_chain(&self, nodes: &NodePool) -> Option<Vec<Handle<Node>>> {
+        let mut chain = Vec::new();
+        let mut current = self.end_effector;
+        while let Some(node) = nodes.try_borrow(current) {
+            chain.push(current);
+            if current == self.root {
+                chain.reverse();
+                return Some(chain);
+            }
+            current = node.parent();
+        }
+        None
+    }
+
+    /// Solves the chain and writes new rotations of the chain nodes to the given pose. Nodes that are not
+    /// in the pose will be added to it.
+    pub fn solve(
+        &self,
+        pose: &mut AnimationPose,
+        nodes: &NodePool,
+        parameters: &ParameterContainer,
+    ) {
+        if !self.enabled {
+            return;
+        }
+
+        let weight = self.weight_value(parameters).clamp(0.0, 1.0);
+        if weight <= 0.0 {
+            return;
+        }
+
+        let chain = match self.collect_chain(nodes) {
+            Some(chain) if chain.len() >= 2 => chain,
+            _ => return,
+        };
+
+        if self.kind == IkSolverKind::TwoBone && chain.len() != 3 {
+            return;
+        }
+
+        if nodes.try_borrow(self.target).is_none() {
+            return;
+        }
+
+        let target = posed_global_transform(nodes, pose, self.target).position();
+        let pole = if nodes.try_borrow(self.pole).is_some() {
+            Some(posed_global_transform(nodes, pose, self.pole).position())
+        } else {
+            None
+        };
+
+        let mut locals = chain
+            .iter()
+            .filter_map(|handle| posed_local_transform(nodes, pose, *handle))
+            .collect::<Vec<_>>();
+        let root_parent_global = posed_global_transform(nodes, pose, nodes[self.root].parent());
+
+        // Gather current positions of joints.
+        let mut positions = Vec::with_capacity(locals.len());
+        let mut global = root_parent_global;
+        for local in locals.iter() {
+            global *= local.matrix();
+            positions.push(global.position());
+        }
+
+        match self.kind {
+            IkSolverKind::TwoBone => solve_two_bone(
+                &mut positions,
+                &target,
+                pole.as_ref(),
+                &self.limits.get(1).cloned().unwrap_or_default(),
+            ),
+            IkSolverKind::Fabrik => solve_fabrik(
+                &mut positions,
+                &target,
+                &self.limits,
+                self.iterations,
+                self.tolerance,
+            ),
+            IkSolverKind::Ccd => solve_ccd(
+                &mut positions,
+                &target,
+                &self.limits,
+                self.iterations,
+                self.tolerance,
+            ),
+        }
+
+        if self.kind != IkSolverKind::TwoBone {
+            if let Some(pole) = pole.as_ref() {
+                apply_pole(&mut positions, pole);
+            }
+        }
+
+        // Convert new positions of the joints to local rotations.
+        let mut parent_global = root_parent_global;
+        for i in 0..locals.len() - 1 {
+            let current_global = parent_global * locals[i].matrix();
+            let joint_position = current_global.position();
+            let child_position = (current_global * locals[i + 1].matrix()).position();
+
+            let delta = rotation_between(
+                &(child_position - joint_position),
+                &(positions[i + 1] - joint_position),
+            );
+
+            let parent_rotation = rotation_of(&parent_global) * **locals[i].pre_rotation();
+            let rotation = **locals[i].rotation();
+            let solved = parent_rotation.inverse() * delta * parent_rotation * rotation;
+            let blended = rotation
+                .try_slerp(&solved, weight, EPSILON)
+                .unwrap_or(solved);
+
+            locals[i].set_rotation(blended);
+            set_rotation(pose, chain[i], blended);
+
+            parent_global *= locals[i].matrix();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::ik::{
+            solve_ccd, solve_fabrik, solve_two_bone, IkSolver, IkSolverKind, JointLimits,
+        },
+        core::{
+            algebra::{UnitQuaternion, Vector2, Vector3},
+            pool::Handle,
+        },
+        scene::{
+            animation::{absm::AnimationBlendingStateMachineBuilder, AnimationPlayerBuilder},
+            base::BaseBuilder,
+            graph::Graph,
+            node::Node,
+            pivot::PivotBuilder,
+            transform::TransformBuilder,
+        },
+    };
+
+    fn lengths(positions: &[Vector3<f32>]) -> Vec<f32> {
+        positions
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).norm())
+            .collect()
+    }
+
+    fn assert_lengths_eq(a: &[f32], b: &[f32]) {
+        for (a, b) in a.iter().zip(b) {
+            assert!((a - b).abs() < 1.0e-3, "{} != {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_two_bone_reaches_target() {
+        let mut positions = [
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.1),
+            Vector3::new(0.0, -2.0, 0.0),
+        ];
+        let initial_lengths = lengths(&positions);
+        let target = Vector3::new(0.5, -1.5, 0.0);
+        let pole = Vector3::new(0.0, -1.0, 1.0);
+
+        solve_two_bone(&mut positions, &target, Some(&pole), &Default::default());
+
+        assert!((positions[2] - target).norm() < 1.0e-3);
+        assert_lengths_eq(&initial_lengths, &lengths(&positions));
+        // The middle joint must bend towards the pole.
+        assert!(positions[1].z > 0.0);
+    }
+
+    #[test]
+    fn test_two_bone_limits() {
+        let mut positions = [
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            Vector3::new(0.0, -2.0, 0.0),
+        ];
+        // The target is very close to the root, the knee would have to bend almost completely.
+        let target = Vector3::new(0.0, -0.1, 0.0);
+        let limits = JointLimits::new(0.0, std::f32::consts::FRAC_PI_2);
+
+        solve_two_bone(&mut positions, &target, None, &limits);
+
+        let incoming = positions[1] - positions[0];
+        let outgoing = positions[2] - positions[1];
+        assert!(incoming.angle(&outgoing) <= std::f32::consts::FRAC_PI_2 + 1.0e-3);
+    }
+
+    #[test]
+    fn test_iterative_solvers_reach_target() {
+        let initial = [
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 2.0, 0.0),
+            Vector3::new(0.0, 3.0, 0.0),
+        ];
+        let initial_lengths = lengths(&initial);
+        let target = Vector3::new(1.5, 1.5, 0.0);
+
+        let mut positions = initial;
+        solve_fabrik(&mut positions, &target, &[], 32, 1.0e-4);
+        assert!((positions[3] - target).norm() < 1.0e-2);
+        assert_lengths_eq(&initial_lengths, &lengths(&positions));
+
+        let mut positions = initial;
+        solve_ccd(&mut positions, &target, &[], 32, 1.0e-4);
+        assert!((positions[3] - target).norm() < 1.0e-2);
+        assert_lengths_eq(&initial_lengths, &lengths(&positions));
+    }
+
+    fn make_pivot(
+        graph: &mut Graph,
+        position: Vector3<f32>,
+        rotation: UnitQuaternion<f32>,
+        children: &[Handle<Node>],
+    ) -> Handle<Node> {
+        PivotBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .with_local_rotation(rotation)
+                        .build(),
+                )
+                .with_children(children),
+        )
+        .build(graph)
+    }
+
+    #[test]
+    fn test_solve_chain_with_rotated_parents() {
+        for kind in [
+            IkSolverKind::TwoBone,
+            IkSolverKind::Fabrik,
+            IkSolverKind::Ccd,
+        ] {
+            let mut graph = Graph::new();
+
+            // Every node of the chain and its parent have non-identity rotations, so the solver must
+            // correctly convert global positions of the joints to local rotations.
+            let end_effector = make_pivot(
+                &mut graph,
+                Vector3::new(0.0, -1.0, 0.0),
+                UnitQuaternion::from_euler_angles(0.3, 0.0, 0.2),
+                &[],
+            );
+            let middle = make_pivot(
+                &mut graph,
+                Vector3::new(0.0, -1.0, 0.0),
+                UnitQuaternion::from_euler_angles(0.25, 0.1, 0.0),
+                &[end_effector],
+            );
+            let root = make_pivot(
+                &mut graph,
+                Vector3::new(0.5, 0.0, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.35, -0.4),
+                &[middle],
+            );
+            make_pivot(
+                &mut graph,
+                Vector3::new(1.0, 2.0, 3.0),
+                UnitQuaternion::from_euler_angles(0.7, -0.5, 0.5),
+                &[root],
+            );
+            graph.update_hierarchical_data();
+
+            let target_position = graph[root].global_position() + Vector3::new(0.6, -1.0, 0.5);
+            let target = make_pivot(&mut graph, target_position, UnitQuaternion::identity(), &[]);
+
+            let mut solver = IkSolver::new(kind, root, end_effector, target);
+            solver.iterations = 64;
+            solver.tolerance = 1.0e-5;
+
+            let animation_player =
+                AnimationPlayerBuilder::new(BaseBuilder::new()).build(&mut graph);
+            AnimationBlendingStateMachineBuilder::new(BaseBuilder::new())
+                .with_animation_player(animation_player)
+                .with_ik_solvers(vec![solver])
+                .build(&mut graph);
+
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+            graph.update_hierarchical_data();
+
+            let position = graph[end_effector].global_position();
+            assert!(
+                (position - target_position).norm() < 1.0e-3,
+                "{:?}: {:?} != {:?}",
+                kind,
+                position,
+                target_position
+            );
+            // Lengths of the bones must be preserved.
+            assert!(
+                ((graph[middle].global_position() - graph[root].global_position()).norm() - 1.0)
+                    .abs()
+                    < 1.0e-3
+            );
+        }
+    }
+}
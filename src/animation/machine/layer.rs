@@ -73,6 +73,9 @@ pub struct MachineLayer {
 
     mask: LayerMask,
 
+    #[visit(optional)]
+    additive: bool,
+
     #[reflect(hidden)]
     nodes: Pool<PoseNode>,
 
@@ -95,6 +98,10 @@ pub struct MachineLayer {
     #[reflect(hidden)]
     final_pose: AnimationPose,
 
+    #[visit(skip)]
+    #[reflect(hidden)]
+    reference_pose: AnimationPose,
+
     #[visit(skip)]
     #[reflect(hidden)]
     events: FixedEventQueue,
@@ -120,6 +127,7 @@ impl MachineLayer {
             states: Default::default(),
             transitions: Default::default(),
             final_pose: Default::default(),
+            reference_pose: Default::default(),
             active_state: Default::default(),
             entry_state: Default::default(),
             active_transition: Default::default(),
@@ -127,6 +135,7 @@ impl MachineLayer {
             events: FixedEventQueue::new(2048),
             debug: false,
             mask: Default::default(),
+            additive: false,
         }
     }
 
@@ -387,6 +396,29 @@ impl MachineLayer {
         &self.mask
     }
 
+    /// Makes the layer additive or not. Additive layer does not replace the poses of the previous layers, instead
+    /// it calculates a difference between its pose and a reference pose and adds the difference (delta) on top
+    /// of the poses of the previous layers. Reference pose of the layer is taken from the animations used by the
+    /// layer (see [`crate::animation::Animation::set_reference_time`] for more info). Additive layers are useful
+    /// for such things as breathing, recoil, leaning, etc. that should be played on top of locomotion animations.
+    ///
+    /// # Important notes
+    ///
+    /// Additive layer can only modify values that are animated by the previous layers, since there is nothing to
+    /// add the difference to otherwise. If a scene node is animated by multiple animations of the layer, the
+    /// reference pose will be taken from the first one, so every animation of an additive layer should have the
+    /// same reference pose for such nodes.
+    #[inline]
+    pub fn set_additive(&mut self, additive: bool) {
+        self.additive = additive;
+    }
+
+    /// Returns `true` if the layer is additive, `false` - otherwise. See [`Self::set_additive`] for more info.
+    #[inline]
+    pub fn is_additive(&self) -> bool {
+        self.additive
+    }
+
     /// Returns final pose of the layer.
     #[inline]
     pub fn pose(&self) -> &AnimationPose {
@@ -500,6 +532,176 @@ impl MachineLayer {
             .poses_mut()
             .retain(|h, _| self.mask.should_animate(*h));
 
+        if self.additive {
+            self.reference_pose.reset();
+            for node in self.nodes.iter() {
+                if let PoseNode::PlayAnimation(play_animation) = node {
+                    if let Some(animation) = animations.try_get(play_animation.animation) {
+                        animation.reference_pose_into(&mut self.reference_pose);
+                    }
+                }
+            }
+
+            self.final_pose.make_difference(&self.reference_pose);
+        }
+
         &self.final_pose
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::{
+            container::{TrackDataContainer, TrackValueKind},
+            machine::{Machine, MachineLayer, PlayAnimation, PoseNode, State},
+            track::Track,
+            value::{TrackValue, ValueBinding},
+            Animation, AnimationContainer,
+        },
+        core::{
+            algebra::{UnitQuaternion, Vector3},
+            curve::{Curve, CurveKey, CurveKeyKind},
+            pool::Handle,
+        },
+        scene::node::Node,
+    };
+
+    fn make_track(
+        node: Handle<Node>,
+        binding: ValueBinding,
+        kind: TrackValueKind,
+        curves: Vec<Vec<(f32, f32)>>,
+    ) -> Track {
+        let mut container = TrackDataContainer::new(kind);
+        for (curve, keys) in container.curves_mut().iter_mut().zip(curves) {
+            *curve = Curve::from(
+                keys.into_iter()
+                    .map(|(time, value)| CurveKey::new(time, value, CurveKeyKind::Linear))
+                    .collect::<Vec<_>>(),
+            );
+        }
+        let mut track = Track::new(container, binding);
+        track.set_target(node);
+        track
+    }
+
+    fn make_animation(tracks: Vec<Track>) -> Animation {
+        let mut animation = Animation::default();
+        for track in tracks {
+            animation.add_track(track);
+        }
+        animation.set_time_slice(0.0..2.0);
+        animation.set_time_position(1.0);
+        // Calculate the pose at the current time position.
+        animation.tick(0.0);
+        animation
+    }
+
+    fn make_layer(animation: Handle<Animation>) -> MachineLayer {
+        let mut layer = MachineLayer::new();
+        let play = layer.add_node(PoseNode::PlayAnimation(PlayAnimation::new(animation)));
+        let state = layer.add_state(State::new("State", play));
+        layer.set_entry_state(state);
+        layer
+    }
+
+    #[test]
+    fn test_additive_layer() {
+        let node = Handle::new(1, 1);
+        let additive_only_node = Handle::new(2, 1);
+
+        let mut animations = AnimationContainer::new();
+        let base_animation = animations.add(make_animation(vec![
+            make_track(
+                node,
+                ValueBinding::Position,
+                TrackValueKind::Vector3,
+                vec![vec![(0.0, 1.0)], vec![(0.0, 2.0)], vec![(0.0, 3.0)]],
+            ),
+            make_track(
+                node,
+                ValueBinding::Rotation,
+                TrackValueKind::UnitQuaternion,
+                vec![vec![(0.0, 0.0)], vec![(0.0, 0.0)], vec![(0.0, 1.0)]],
+            ),
+        ]));
+        // Reference pose of the additive animation is at 0.0, the layer will add the difference
+        // between the poses at 1.0 and 0.0 - (1.0, 0.0, 0.0) offset and 0.4 rad rotation around
+        // X axis.
+        let additive_animation = animations.add(make_animation(vec![
+            make_track(
+                node,
+                ValueBinding::Position,
+                TrackValueKind::Vector3,
+                vec![
+                    vec![(0.0, 0.0), (2.0, 2.0)],
+                    vec![(0.0, 5.0)],
+                    vec![(0.0, 0.0)],
+                ],
+            ),
+            make_track(
+                node,
+                ValueBinding::Rotation,
+                TrackValueKind::UnitQuaternion,
+                vec![
+                    vec![(0.0, 0.0), (2.0, 0.8)],
+                    vec![(0.0, 0.0)],
+                    vec![(0.0, 0.0)],
+                ],
+            ),
+            make_track(
+                additive_only_node,
+                ValueBinding::Position,
+                TrackValueKind::Vector3,
+                vec![
+                    vec![(0.0, 0.0), (2.0, 2.0)],
+                    vec![(0.0, 0.0)],
+                    vec![(0.0, 0.0)],
+                ],
+            ),
+        ]));
+
+        let mut machine = Machine::new();
+        machine.layers_mut()[0] = make_layer(base_animation);
+        let mut additive_layer = make_layer(additive_animation);
+        additive_layer.set_additive(true);
+        machine.add_layer(additive_layer);
+
+        for weight in [1.0, 0.5, 0.0] {
+            machine.layers_mut()[1].set_weight(weight);
+
+            let pose = machine.evaluate_pose(&animations, 0.0);
+
+            // Nothing to add the difference to.
+            assert!(pose.poses().get(&additive_only_node).is_none());
+
+            let values = &pose.poses()[&node].values.values;
+            assert_eq!(values.len(), 2);
+            for value in values {
+                match (&value.binding, &value.value) {
+                    (ValueBinding::Position, TrackValue::Vector3(position)) => {
+                        let expected = Vector3::new(1.0 + weight, 2.0, 3.0);
+                        assert!(
+                            (position - expected).norm() < 1.0e-5,
+                            "{} {:?}",
+                            weight,
+                            position
+                        );
+                    }
+                    (ValueBinding::Rotation, TrackValue::UnitQuaternion(rotation)) => {
+                        let expected = UnitQuaternion::from_axis_angle(&Vector3::z_axis(), 1.0)
+                            * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), 0.4 * weight);
+                        assert!(
+                            rotation.angle_to(&expected) < 1.0e-3,
+                            "{} {:?}",
+                            weight,
+                            rotation
+                        );
+                    }
+                    _ => unreachable!(),
+                }
+            }
+        }
+    }
+}
@@ -6,11 +6,12 @@
 #![warn(missing_docs)]
 
 use crate::{
-    animation::{AnimationContainer, AnimationPose},
+    animation::{ik::IkSolver, AnimationContainer, AnimationPose},
     core::{
         reflect::prelude::*,
         visitor::{Visit, VisitResult, Visitor},
     },
+    scene::graph::NodePool,
     utils,
 };
 
@@ -291,11 +292,26 @@ impl Machine {
 
         for layer in self.layers.iter_mut() {
             let weight = layer.weight();
+            let additive = layer.is_additive();
             let pose = layer.evaluate_pose(animations, &self.parameters, dt);
 
-            self.final_pose.blend_with(pose, weight);
+            if additive {
+                self.final_pose.blend_additive(pose, weight);
+            } else {
+                self.final_pose.blend_with(pose, weight);
+            }
         }
 
         &self.final_pose
     }
+
+    /// Applies the given set of IK solvers to the final pose of the machine. This method should be called after
+    /// [`Self::evaluate_pose`], weights of the solvers are calculated using parameters of the machine. See
+    /// [`IkSolver`] docs for more info.
+    #[inline]
+    pub fn apply_ik_solvers(&mut self, solvers: &[IkSolver], nodes: &NodePool) {
+        for solver in solvers {
+            solver.solve(&mut self.final_pose, nodes, &self.parameters);
+        }
+    }
 }
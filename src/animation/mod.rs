@@ -30,6 +30,7 @@ pub use pose::{AnimationPose, NodePose};
 pub use signal::{AnimationEvent, AnimationSignal};
 
 pub mod container;
+pub mod ik;
 pub mod machine;
 pub mod pose;
 pub mod signal;
@@ -191,6 +192,9 @@ pub struct Animation {
     #[visit(optional)]
     root_motion_settings: Option<RootMotionSettings>,
 
+    #[visit(optional)]
+    reference_time: f32,
+
     #[reflect(hidden)]
     #[visit(skip)]
     root_motion: Option<RootMotion>,
@@ -266,6 +270,7 @@ impl Clone for Animation {
             events: Default::default(),
             time_slice: self.time_slice.clone(),
             root_motion: self.root_motion.clone(),
+            reference_time: self.reference_time,
         }
     }
 }
@@ -779,6 +784,38 @@ impl Animation {
     pub fn pose(&self) -> &AnimationPose {
         &self.pose
     }
+
+    /// Sets new time position of the reference pose of the animation. Reference pose is used to calculate a difference
+    /// between the current pose of the animation and the reference pose, when the animation is used in additive
+    /// layers (see [`machine::MachineLayer::set_additive`] for more info). By default it is `0.0` (first frame).
+    pub fn set_reference_time(&mut self, time: f32) -> &mut Self {
+        self.reference_time = time;
+        self
+    }
+
+    /// Returns time position of the reference pose of the animation.
+    pub fn reference_time(&self) -> f32 {
+        self.reference_time
+    }
+
+    /// Samples every enabled track of the animation at the reference time and writes the values to the given pose.
+    /// See [`Self::set_reference_time`] for more info.
+    pub fn reference_pose_into(&self, dest: &mut AnimationPose) {
+        for track in self.tracks.iter() {
+            if track.is_enabled() {
+                if let Some(bound_value) = track.fetch(self.reference_time) {
+                    dest.add_to_node_pose(track.target(), bound_value);
+                }
+            }
+        }
+    }
+
+    /// Returns reference pose of the animation. See [`Self::set_reference_time`] for more info.
+    pub fn reference_pose(&self) -> AnimationPose {
+        let mut pose = AnimationPose::default();
+        self.reference_pose_into(&mut pose);
+        pose
+    }
 }
 
 impl Default for Animation {
@@ -796,6 +833,7 @@ impl Default for Animation {
             events: Default::default(),
             time_slice: Default::default(),
             root_motion: None,
+            reference_time: 0.0,
         }
     }
 }
@@ -43,6 +43,18 @@ impl NodePose {
     pub fn blend_with(&mut self, other: &NodePose, weight: f32) {
         self.values.blend_with(&other.values, weight)
     }
+
+    /// Turns the current pose into a difference between the pose and the reference pose. See
+    /// [`super::value::TrackValue::difference`] docs for more info.
+    pub fn make_difference(&mut self, reference: &NodePose) {
+        self.values.make_difference(&reference.values)
+    }
+
+    /// Adds a difference pose to the current pose using the given weight. See
+    /// [`super::value::TrackValue::blend_additive`] docs for more info.
+    pub fn blend_additive(&mut self, delta: &NodePose, weight: f32) {
+        self.values.blend_additive(&delta.values, weight)
+    }
 }
 
 /// Animations pose is a set of node poses. See [`NodePose`] docs for more info.
@@ -91,6 +103,33 @@ impl AnimationPose {
             .blend_with(&other.root_motion.clone().unwrap_or_default(), weight);
     }
 
+    /// Turns the current animation pose into a difference (additive pose) between the pose and the reference pose. Node
+    /// poses that does not have respective node pose in the reference pose will be removed. Additive poses could then be
+    /// added on top of other poses using [`Self::blend_additive`].
+    pub fn make_difference(&mut self, reference: &AnimationPose) {
+        self.poses.retain(|handle, pose| {
+            if let Some(reference_pose) = reference.poses.get(handle) {
+                pose.make_difference(reference_pose);
+                !pose.values.values.is_empty()
+            } else {
+                false
+            }
+        });
+        // Additive poses does not contain any root motion, it is always taken from base poses.
+        self.root_motion = None;
+    }
+
+    /// Adds an additive pose (see [`Self::make_difference`]) on top of the current pose using a weight coefficient.
+    /// Values from the additive pose that does not have respective values in the current pose will be ignored, since
+    /// there is nothing to add them to.
+    pub fn blend_additive(&mut self, additive: &AnimationPose, weight: f32) {
+        for (handle, additive_pose) in additive.poses.iter() {
+            if let Some(current_pose) = self.poses.get_mut(handle) {
+                current_pose.blend_additive(additive_pose, weight);
+            }
+        }
+    }
+
     fn add_node_pose(&mut self, local_pose: NodePose) {
         self.poses.insert(local_pose.node, local_pose);
     }
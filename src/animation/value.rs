@@ -167,6 +167,48 @@ impl TrackValue {
         }
     }
 
+    /// Calculates a difference (delta) between the current value and the reference value. The difference could then be
+    /// added to some other value using [`Self::blend_additive`]. For numbers and vectors the difference is a simple
+    /// subtraction, for rotations it is a rotation that transforms the reference rotation to the current rotation (in
+    /// local space of the reference rotation), so `reference * delta == current`. Returns `None` if the types of the
+    /// values don't match.
+    pub fn difference(&self, reference: &Self) -> Option<Self> {
+        match (self, reference) {
+            (Self::Real(a), Self::Real(b)) => Some(Self::Real(*a - *b)),
+            (Self::Vector2(a), Self::Vector2(b)) => Some(Self::Vector2(a - b)),
+            (Self::Vector3(a), Self::Vector3(b)) => Some(Self::Vector3(a - b)),
+            (Self::Vector4(a), Self::Vector4(b)) => Some(Self::Vector4(a - b)),
+            (Self::UnitQuaternion(a), Self::UnitQuaternion(b)) => {
+                Some(Self::UnitQuaternion(b.inverse() * a))
+            }
+            _ => None,
+        }
+    }
+
+    /// Adds a delta (see [`Self::difference`]) to the current value using the given weight. For numbers and vectors
+    /// the delta is scaled by the weight and added to the current value, rotations are composed as `current * delta`,
+    /// where delta is interpolated between identity and the actual delta using the weight. Adding is possible only if
+    /// the types are the same.
+    pub fn blend_additive(&mut self, delta: &Self, weight: f32) {
+        match (self, delta) {
+            (Self::Real(a), Self::Real(b)) => *a += *b * weight,
+            (Self::Vector2(a), Self::Vector2(b)) => *a += b.scale(weight),
+            (Self::Vector3(a), Self::Vector3(b)) => *a += b.scale(weight),
+            (Self::Vector4(a), Self::Vector4(b)) => *a += b.scale(weight),
+            (Self::UnitQuaternion(a), Self::UnitQuaternion(b)) => {
+                // Make sure that the delta takes the shortest path, otherwise weighted delta will
+                // rotate in opposite direction.
+                let delta = if b.w < 0.0 {
+                    UnitQuaternion::new_unchecked(-b.into_inner())
+                } else {
+                    *b
+                };
+                *a *= UnitQuaternion::identity().nlerp(&delta, weight);
+            }
+            _ => (),
+        }
+    }
+
     /// Tries to calculate intermediate value between the current and an other using interpolation coefficient. Interpolation
     /// will fail if the types of current and the other values don't match.
     pub fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
@@ -343,6 +385,27 @@ impl BoundValue {
         self.value.blend_with(&other.value, weight);
     }
 
+    /// Calculates a difference between the current value and the reference value. See [`TrackValue::difference`] for
+    /// more info. Returns `None` if the values are bound to different properties.
+    pub fn difference(&self, reference: &Self) -> Option<Self> {
+        if self.binding != reference.binding {
+            return None;
+        }
+        self.value.difference(&reference.value).map(|value| Self {
+            binding: self.binding.clone(),
+            value,
+        })
+    }
+
+    /// Adds a delta to the current value using the given weight. See [`TrackValue::blend_additive`] for more info.
+    /// The current value stays unchanged if the delta is bound to a different property.
+    pub fn blend_additive(&mut self, delta: &Self, weight: f32) {
+        if self.binding != delta.binding {
+            return;
+        }
+        self.value.blend_additive(&delta.value, weight);
+    }
+
     /// Tries to interpolate the current value with some other using the given interpolation coefficient. See
     /// [`TrackValue::interpolate`] for more info.
     pub fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
@@ -383,6 +446,33 @@ impl BoundValueCollection {
         }
     }
 
+    /// Replaces each value of the current collection with a difference between the value and a respective (by binding)
+    /// value in the reference collection. Values without a respective reference value are removed from the collection.
+    /// See [`TrackValue::difference`] docs for more info.
+    pub fn make_difference(&mut self, reference: &Self) {
+        self.values = self
+            .values
+            .iter()
+            .filter_map(|value| {
+                reference
+                    .values
+                    .iter()
+                    .find(|v| v.binding == value.binding)
+                    .and_then(|reference_value| value.difference(reference_value))
+            })
+            .collect();
+    }
+
+    /// Tries to add each value of the other (delta) collection to a respective (by binding) value in the current
+    /// collection. See [`TrackValue::blend_additive`] docs for more info.
+    pub fn blend_additive(&mut self, delta: &Self, weight: f32) {
+        for value in self.values.iter_mut() {
+            if let Some(delta_value) = delta.values.iter().find(|v| v.binding == value.binding) {
+                value.blend_additive(delta_value, weight);
+            }
+        }
+    }
+
     /// Tries to interpolate each value of the current collection with a respective (by binding) value in the other
     /// collection and returns the new collection of interpolated values. See [`TrackValue::interpolate`] docs for more
     /// info.
@@ -460,3 +550,68 @@ impl BoundValueCollection {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::value::{BoundValue, TrackValue, ValueBinding},
+        core::algebra::{UnitQuaternion, Vector3},
+    };
+
+    #[test]
+    fn test_additive_rotation() {
+        let reference = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 0.5);
+        let current = UnitQuaternion::from_axis_angle(&Vector3::x_axis(), 0.3) * reference;
+        let base = UnitQuaternion::from_axis_angle(&Vector3::z_axis(), 1.0);
+
+        let delta = TrackValue::UnitQuaternion(current)
+            .difference(&TrackValue::UnitQuaternion(reference))
+            .unwrap();
+
+        // Full weight must produce exact composition.
+        let mut value = TrackValue::UnitQuaternion(base);
+        value.blend_additive(&delta, 1.0);
+        let expected = base * (reference.inverse() * current);
+        match value {
+            TrackValue::UnitQuaternion(result) => assert!(result.angle_to(&expected) < 1.0e-5),
+            _ => unreachable!(),
+        }
+
+        // Zero weight must leave the base value as is.
+        let mut value = TrackValue::UnitQuaternion(base);
+        value.blend_additive(&delta, 0.0);
+        match value {
+            TrackValue::UnitQuaternion(result) => assert!(result.angle_to(&base) < 1.0e-5),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_additive_vector() {
+        let delta = TrackValue::Vector3(Vector3::new(2.0, 3.0, 4.0))
+            .difference(&TrackValue::Vector3(Vector3::new(1.0, 1.0, 1.0)))
+            .unwrap();
+
+        let mut value = TrackValue::Vector3(Vector3::new(10.0, 10.0, 10.0));
+        value.blend_additive(&delta, 0.5);
+        assert_eq!(value, TrackValue::Vector3(Vector3::new(10.5, 11.0, 11.5)));
+    }
+
+    #[test]
+    fn test_additive_different_bindings() {
+        let position = BoundValue {
+            binding: ValueBinding::Position,
+            value: TrackValue::Vector3(Vector3::new(1.0, 2.0, 3.0)),
+        };
+        let scale = BoundValue {
+            binding: ValueBinding::Scale,
+            value: TrackValue::Vector3(Vector3::new(1.0, 1.0, 1.0)),
+        };
+
+        assert!(position.difference(&scale).is_none());
+
+        let mut value = position.clone();
+        value.blend_additive(&scale, 1.0);
+        assert_eq!(value, position);
+    }
+}
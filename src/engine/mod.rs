@@ -24,7 +24,7 @@ use crate::{
         base::NodeScriptMessage,
         graph::GraphUpdateSwitches,
         node::{constructor::NodeConstructorContainer, Node},
-        sound::SoundEngine,
+        sound::{custom::CustomEffectConstructorContainer, SoundEngine},
         Scene, SceneContainer,
     },
     script::{
@@ -76,6 +76,9 @@ pub struct SerializationContext {
     pub node_constructors: NodeConstructorContainer,
     /// A script constructor container.
     pub script_constructors: ScriptConstructorContainer,
+    /// A custom sound effect constructor container. It is wrapped in `Arc`, because it is passed
+    /// to the sound engine when a sound context is deserialized.
+    pub effect_constructors: Arc<CustomEffectConstructorContainer>,
 }
 
 impl Default for SerializationContext {
@@ -90,6 +93,7 @@ impl SerializationContext {
         Self {
             node_constructors: NodeConstructorContainer::new(),
             script_constructors: ScriptConstructorContainer::new(),
+            effect_constructors: Arc::new(CustomEffectConstructorContainer::new()),
         }
     }
 }
@@ -406,6 +410,42 @@ impl ScriptProcessor {
             .push(resource_manager.state().containers_mut().get_wait_context());
     }
 
+    // Sends physics collision events of every enabled scripted scene to the scripts of the colliders
+    // that participated in the collision. Each collider receives an event in which `collider1` is
+    // the collider itself.
+    fn queue_collision_events(&self, scenes: &SceneContainer) {
+        for scripted_scene in self.scripted_scenes.iter() {
+            if let Some(scene) = scenes.try_get(scripted_scene.handle) {
+                if !scene.enabled {
+                    continue;
+                }
+
+                let has_script = |handle: Handle<Node>| {
+                    scene
+                        .graph
+                        .try_get(handle)
+                        .map_or(false, |node| node.script.is_some())
+                };
+
+                for event in scene.graph.collision_events() {
+                    let (collider1, collider2) = event.colliders();
+
+                    if has_script(collider1) {
+                        scripted_scene
+                            .message_sender
+                            .send_to_target(collider1, *event);
+                    }
+
+                    if has_script(collider2) {
+                        scripted_scene
+                            .message_sender
+                            .send_to_target(collider2, event.swapped());
+                    }
+                }
+            }
+        }
+    }
+
     fn handle_scripts(
         &mut self,
         scenes: &mut SceneContainer,
@@ -1153,6 +1193,10 @@ impl Engine {
                 );
             }
 
+            // Collision events must be queued before plugins update, because plugins could drain
+            // them from graphs.
+            self.script_processor.queue_collision_events(&self.scenes);
+
             self.update_plugins(dt, control_flow, lag);
             self.handle_scripts(dt);
         }
@@ -1490,10 +1534,25 @@ impl Drop for Engine {
 mod test {
     use crate::script::{ScriptMessageContext, ScriptMessagePayload};
     use crate::{
-        core::{pool::Handle, reflect::prelude::*, uuid::Uuid, visitor::prelude::*},
+        core::{
+            algebra::{Vector2, Vector3},
+            pool::Handle,
+            reflect::prelude::*,
+            uuid::Uuid,
+            visitor::prelude::*,
+        },
         engine::{resource_manager::ResourceManager, ScriptProcessor},
         impl_component_provider,
-        scene::{base::BaseBuilder, node::Node, pivot::PivotBuilder, Scene, SceneContainer},
+        scene::{
+            base::BaseBuilder,
+            collider::{ColliderBuilder, ColliderShape},
+            graph::physics::CollisionEvent,
+            node::Node,
+            pivot::PivotBuilder,
+            rigidbody::{RigidBodyBuilder, RigidBodyType},
+            transform::TransformBuilder,
+            Scene, SceneContainer,
+        },
         script::{Script, ScriptContext, ScriptDeinitContext, ScriptTrait},
     };
     use std::sync::mpsc::{self, Sender, TryRecvError};
@@ -1816,4 +1875,132 @@ mod test {
             }
         }
     }
+
+    #[derive(Debug, Clone, Reflect, Visit)]
+    struct CollisionListener {
+        #[reflect(hidden)]
+        #[visit(skip)]
+        sender: Sender<(Handle<Node>, CollisionEvent)>,
+    }
+
+    impl_component_provider!(CollisionListener);
+
+    impl ScriptTrait for CollisionListener {
+        fn on_start(&mut self, ctx: &mut ScriptContext) {
+            ctx.message_dispatcher
+                .subscribe_to::<CollisionEvent>(ctx.handle);
+        }
+
+        fn on_message(
+            &mut self,
+            message: &mut dyn ScriptMessagePayload,
+            ctx: &mut ScriptMessageContext,
+        ) {
+            if let Some(event) = message.downcast_ref::<CollisionEvent>() {
+                self.sender.send((ctx.handle, *event)).unwrap();
+            }
+        }
+
+        fn id(&self) -> Uuid {
+            Uuid::new_v4()
+        }
+    }
+
+    #[test]
+    fn test_collision_events() {
+        let resource_manager = ResourceManager::new(Default::default());
+        let mut scene = Scene::new();
+        scene.graph.physics.gravity = Vector3::default();
+
+        let (tx, rx) = mpsc::channel();
+
+        let mut make_cube = |position: Vector3<f32>, body_type: RigidBodyType, sensor: bool| {
+            let collider = ColliderBuilder::new(
+                BaseBuilder::new()
+                    .with_script(Script::new(CollisionListener { sender: tx.clone() })),
+            )
+            .with_shape(ColliderShape::cuboid(0.5, 0.5, 0.5))
+            .with_sensor(sensor)
+            .build(&mut scene.graph);
+            RigidBodyBuilder::new(
+                BaseBuilder::new()
+                    .with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(position)
+                            .build(),
+                    )
+                    .with_children(&[collider]),
+            )
+            .with_body_type(body_type)
+            .build(&mut scene.graph);
+            collider
+        };
+
+        let sensor = make_cube(Vector3::default(), RigidBodyType::Static, true);
+        let other = make_cube(Vector3::new(0.5, 0.0, 0.0), RigidBodyType::Dynamic, false);
+
+        let mut scene_container = SceneContainer::new(Default::default());
+
+        let scene_handle = scene_container.add(scene);
+
+        let mut script_processor = ScriptProcessor::default();
+
+        script_processor.register_scripted_scene(
+            scene_handle,
+            &mut scene_container,
+            &resource_manager,
+        );
+
+        // Start the scripts first, so they will subscribe to the events.
+        script_processor.handle_scripts(
+            &mut scene_container,
+            &mut Default::default(),
+            &resource_manager,
+            0.0,
+            0.0,
+        );
+
+        let mut received = Vec::new();
+        for _ in 0..2 {
+            scene_container[scene_handle].graph.update(
+                Vector2::new(800.0, 600.0),
+                1.0,
+                Default::default(),
+            );
+
+            script_processor.queue_collision_events(&scene_container);
+
+            script_processor.handle_scripts(
+                &mut scene_container,
+                &mut Default::default(),
+                &resource_manager,
+                0.0,
+                0.0,
+            );
+
+            received.extend(rx.try_iter());
+        }
+
+        // Each collider receives the event, where `collider1` is the collider itself.
+        received.sort_by_key(|(handle, _)| *handle == other);
+        assert_eq!(
+            received,
+            vec![
+                (
+                    sensor,
+                    CollisionEvent::IntersectionStarted {
+                        collider1: sensor,
+                        collider2: other
+                    }
+                ),
+                (
+                    other,
+                    CollisionEvent::IntersectionStarted {
+                        collider1: other,
+                        collider2: sensor
+                    }
+                )
+            ]
+        );
+    }
 }
@@ -3,27 +3,65 @@
 
 use crate::{
     asset::{Resource, ResourceData, ResourceLoadError, ResourceState},
-    core::{futures::future::JoinAll, variable::InheritableVariable, VecExtensions},
+    core::{
+        futures::future::JoinAll, reflect::prelude::*, variable::InheritableVariable, VecExtensions,
+    },
     engine::resource_manager::{
         container::{
             entry::{TimedEntry, DEFAULT_RESOURCE_LIFETIME},
             event::{ResourceEvent, ResourceEventBroadcaster},
         },
-        loader::ResourceLoader,
+        loader::{BoxedLoaderFuture, ResourceLoader},
         options::ImportOptions,
         task::TaskPool,
     },
     utils::log::Log,
 };
-use std::{future::Future, ops::Deref, path::Path, sync::Arc};
+use std::{any::Any, future::Future, ops::Deref, path::Path, sync::Arc};
 
 pub mod entry;
 pub mod event;
 
-pub(crate) trait Container {
+/// Type-erased interface of a resource container. It allows the resource manager to work with
+/// containers of any resource type in a uniform way.
+pub(crate) trait Container: Any {
     fn try_reload_resource_from_path(&mut self, path: &Path) -> bool;
+
+    fn update(&mut self, dt: f32);
+
+    fn destroy_unused(&mut self);
+
+    fn len(&self) -> usize;
+
+    fn count_pending_resources(&self) -> usize;
+
+    fn count_loaded_resources(&self) -> usize;
+
+    /// Requests a resource at the given path, the result is always a boxed resource of the type
+    /// handled by the container.
+    fn request_any(&mut self, path: &Path) -> Box<dyn Any>;
+
+    /// Returns a set of checkers that tells whether a resource is loaded (or failed to load).
+    fn loaded_checkers(&self) -> Vec<Box<dyn Fn() -> bool + Send>>;
+
+    /// Tries to restore a "shallow" resource after deserialization, if the entity is a resource
+    /// handled by the container. Returns a future that can be used to wait until the restored
+    /// resource is loaded.
+    fn try_restore_reflect(&mut self, entity: &mut dyn Reflect) -> Option<BoxedLoaderFuture>;
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
 }
 
+/// Boxed type-erased resource container.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) type BoxedContainer = Box<dyn Container + Send>;
+
+/// Boxed type-erased resource container.
+#[cfg(target_arch = "wasm32")]
+pub(crate) type BoxedContainer = Box<dyn Container>;
+
 /// Generic container for any resource in the engine. Main purpose of the container is to
 /// track resources life time and remove unused timed-out resources. It also provides useful
 /// methods to search resources, count loaded or pending, wait until all resources are loading,
@@ -59,6 +97,10 @@ where
         }
     }
 
+    pub(crate) fn task_pool(&self) -> Arc<TaskPool> {
+        self.task_pool.clone()
+    }
+
     /// Sets the loader to load resources with.
     pub fn set_loader<L>(&mut self, loader: L)
     where
@@ -308,10 +350,16 @@ where
 
 impl<T, R, E, O> Container for ResourceContainer<T, O>
 where
-    T: Deref<Target = Resource<R, E>> + Clone + Send + Future + From<Resource<R, E>>,
+    T: Deref<Target = Resource<R, E>>
+        + Clone
+        + Send
+        + Future
+        + From<Resource<R, E>>
+        + Reflect
+        + 'static,
     R: ResourceData,
     E: ResourceLoadError,
-    O: ImportOptions,
+    O: ImportOptions + 'static,
 {
     fn try_reload_resource_from_path(&mut self, path: &Path) -> bool {
         if let Some(resource) = self.find(path).cloned() {
@@ -321,4 +369,61 @@ where
             false
         }
     }
+
+    fn update(&mut self, dt: f32) {
+        ResourceContainer::update(self, dt)
+    }
+
+    fn destroy_unused(&mut self) {
+        ResourceContainer::destroy_unused(self)
+    }
+
+    fn len(&self) -> usize {
+        ResourceContainer::len(self)
+    }
+
+    fn count_pending_resources(&self) -> usize {
+        ResourceContainer::count_pending_resources(self)
+    }
+
+    fn count_loaded_resources(&self) -> usize {
+        ResourceContainer::count_loaded_resources(self)
+    }
+
+    fn request_any(&mut self, path: &Path) -> Box<dyn Any> {
+        Box::new(self.request(path))
+    }
+
+    fn loaded_checkers(&self) -> Vec<Box<dyn Fn() -> bool + Send>> {
+        self.resources
+            .iter()
+            .map(|entry| {
+                let resource = entry.value.clone();
+                Box::new(move || !resource.is_loading()) as Box<dyn Fn() -> bool + Send>
+            })
+            .collect()
+    }
+
+    fn try_restore_reflect(&mut self, entity: &mut dyn Reflect) -> Option<BoxedLoaderFuture> {
+        let mut future = None;
+        entity.downcast_mut::<T>(&mut |result| {
+            if let Some(resource) = result {
+                self.try_restore_resource(resource);
+
+                let resource = resource.clone();
+                future = Some(Box::pin(async move {
+                    let _ = resource.await;
+                }) as BoxedLoaderFuture);
+            }
+        });
+        future
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
@@ -0,0 +1,51 @@
+//! Material loader.
+
+use crate::{
+    engine::resource_manager::{
+        container::event::ResourceEventBroadcaster,
+        loader::{BoxedLoaderFuture, ResourceLoader},
+        ResourceManager,
+    },
+    material::{MaterialImportOptions, MaterialResource, MaterialState},
+    utils::log::Log,
+};
+
+/// Default implementation for material loading.
+pub struct MaterialLoader {
+    /// Resource manager that will be used to request shaders and textures of loaded materials.
+    pub resource_manager: ResourceManager,
+}
+
+impl ResourceLoader<MaterialResource, MaterialImportOptions> for MaterialLoader {
+    fn load(
+        &self,
+        material: MaterialResource,
+        _default_import_options: MaterialImportOptions,
+        event_broadcaster: ResourceEventBroadcaster<MaterialResource>,
+        reload: bool,
+    ) -> BoxedLoaderFuture {
+        let resource_manager = self.resource_manager.clone();
+
+        Box::pin(async move {
+            let path = material.state().path().to_path_buf();
+
+            match MaterialState::from_file(&path, &resource_manager).await {
+                Ok(material_state) => {
+                    Log::info(format!("Material {:?} is loaded!", path));
+
+                    material.state().commit_ok(material_state);
+
+                    event_broadcaster.broadcast_loaded_or_reloaded(material, reload);
+                }
+                Err(error) => {
+                    Log::err(format!(
+                        "Unable to load material from {:?}! Reason {:?}",
+                        path, error
+                    ));
+
+                    material.state().commit_error(path, error);
+                }
+            }
+        })
+    }
+}
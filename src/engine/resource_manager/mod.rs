@@ -6,23 +6,29 @@ use crate::{
         futures::future::join_all,
         make_relative_path,
         parking_lot::{Mutex, MutexGuard},
+        reflect::prelude::*,
     },
     engine::{
         resource_manager::{
-            container::{Container, ResourceContainer},
+            container::{BoxedContainer, Container, ResourceContainer},
             loader::{
                 curve::CurveLoader,
+                material::MaterialLoader,
                 model::ModelLoader,
                 shader::ShaderLoader,
                 sound::{SoundBufferImportOptions, SoundBufferLoader},
                 texture::TextureLoader,
                 ResourceLoader,
             },
+            options::ImportOptions,
             task::TaskPool,
         },
         SerializationContext,
     },
-    material::shader::{Shader, ShaderImportOptions},
+    material::{
+        shader::{Shader, ShaderImportOptions},
+        MaterialImportOptions, MaterialResource,
+    },
     resource::{
         curve::{CurveImportOptions, CurveResource},
         model::{Model, ModelImportOptions},
@@ -30,8 +36,10 @@ use crate::{
     },
     utils::{log::Log, watcher::FileSystemWatcher},
 };
+use fxhash::FxHashMap;
 use fyrox_sound::buffer::SoundBufferResource;
 use std::{
+    any::TypeId,
     fmt::{Debug, Display, Formatter},
     future::Future,
     ops::Deref,
@@ -60,6 +68,12 @@ pub struct ContainersStorage {
 
     /// Container for curve resources.
     pub curves: ResourceContainer<CurveResource, CurveImportOptions>,
+
+    /// Container for material resources.
+    pub materials: ResourceContainer<MaterialResource, MaterialImportOptions>,
+
+    /// Containers for user-defined resource types.
+    custom: FxHashMap<TypeId, BoxedContainer>,
 }
 
 impl ContainersStorage {
@@ -103,6 +117,74 @@ impl ContainersStorage {
         self.curves.set_loader(loader);
     }
 
+    /// Sets a custom material loader.
+    pub fn set_material_loader<L>(&mut self, loader: L)
+    where
+        L: 'static + ResourceLoader<MaterialResource, MaterialImportOptions>,
+    {
+        self.materials.set_loader(loader);
+    }
+
+    /// Registers a new user-defined resource type with the given loader. Resources of the type
+    /// will be loaded asynchronously, hot-reloaded, destroyed when unused and restored after
+    /// deserialization the same way as built-in resources. Registering the same type twice
+    /// replaces its container (and all its resources) with a new one.
+    pub fn register<T, R, E, O, L>(&mut self, loader: L)
+    where
+        T: Deref<Target = Resource<R, E>>
+            + Clone
+            + Send
+            + Future
+            + From<Resource<R, E>>
+            + Reflect
+            + 'static,
+        R: ResourceData,
+        E: ResourceLoadError,
+        O: ImportOptions + Send + 'static,
+        L: 'static + ResourceLoader<T, O>,
+    {
+        let task_pool = self.textures.task_pool();
+        self.custom.insert(
+            TypeId::of::<T>(),
+            Box::new(ResourceContainer::<T, O>::new(task_pool, Box::new(loader))),
+        );
+    }
+
+    /// Returns `true` if a container for the given user-defined resource type was registered.
+    pub fn is_registered<T: 'static>(&self) -> bool {
+        self.custom.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Returns a reference to a container of a user-defined resource type, `None` if there's no
+    /// such container or it uses other import options.
+    pub fn container<T, O>(&self) -> Option<&ResourceContainer<T, O>>
+    where
+        T: Clone + 'static,
+        O: ImportOptions + 'static,
+    {
+        self.custom
+            .get(&TypeId::of::<T>())
+            .and_then(|container| container.as_any().downcast_ref())
+    }
+
+    /// Returns a reference to a container of a user-defined resource type, `None` if there's no
+    /// such container or it uses other import options.
+    pub fn container_mut<T, O>(&mut self) -> Option<&mut ResourceContainer<T, O>>
+    where
+        T: Clone + 'static,
+        O: ImportOptions + 'static,
+    {
+        self.custom
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|container| container.as_any_mut().downcast_mut())
+    }
+
+    pub(crate) fn custom_containers_mut(
+        &mut self,
+    ) -> impl Iterator<Item = &mut BoxedContainer> + '_ {
+        self.custom.values_mut()
+    }
+
     /// Wait until all resources are loaded (or failed to load).
     pub fn get_wait_context(&self) -> ResourceWaitContext {
         ResourceWaitContext {
@@ -111,6 +193,12 @@ impl ContainersStorage {
             shaders: self.shaders.resources(),
             textures: self.textures.resources(),
             sound_buffers: self.sound_buffers.resources(),
+            materials: self.materials.resources(),
+            custom: self
+                .custom
+                .values()
+                .flat_map(|container| container.loaded_checkers())
+                .collect(),
         }
     }
 }
@@ -124,6 +212,8 @@ pub struct ResourceWaitContext {
     shaders: Vec<Shader>,
     textures: Vec<Texture>,
     sound_buffers: Vec<SoundBufferResource>,
+    materials: Vec<MaterialResource>,
+    custom: Vec<Box<dyn Fn() -> bool + Send>>,
 }
 
 impl ResourceWaitContext {
@@ -155,6 +245,8 @@ impl ResourceWaitContext {
             && check_container(&self.shaders)
             && check_container(&self.textures)
             && check_container(&self.sound_buffers)
+            && check_container(&self.materials)
+            && self.custom.iter().all(|is_loaded| is_loaded())
     }
 }
 
@@ -201,6 +293,24 @@ impl From<TextureError> for TextureRegistrationError {
     }
 }
 
+/// An error that may occur when requesting a resource of a user-defined type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceRequestError {
+    /// The resource type wasn't registered using [`ResourceManager::register_resource_type`]. Holds
+    /// the name of the type.
+    UnregisteredType(&'static str),
+}
+
+impl Display for ResourceRequestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResourceRequestError::UnregisteredType(type_name) => {
+                write!(f, "Resource type {} is not registered!", type_name)
+            }
+        }
+    }
+}
+
 impl ResourceManager {
     /// Creates a resource manager with default settings and loaders.
     pub fn new(serialization_context: Arc<SerializationContext>) -> Self {
@@ -221,7 +331,14 @@ impl ResourceManager {
             ),
             sound_buffers: ResourceContainer::new(task_pool.clone(), Box::new(SoundBufferLoader)),
             shaders: ResourceContainer::new(task_pool.clone(), Box::new(ShaderLoader)),
-            curves: ResourceContainer::new(task_pool, Box::new(CurveLoader)),
+            curves: ResourceContainer::new(task_pool.clone(), Box::new(CurveLoader)),
+            materials: ResourceContainer::new(
+                task_pool,
+                Box::new(MaterialLoader {
+                    resource_manager: resource_manager.clone(),
+                }),
+            ),
+            custom: Default::default(),
         });
 
         resource_manager
@@ -351,6 +468,133 @@ impl ResourceManager {
         self.state().containers_mut().curves.request(path)
     }
 
+    /// Tries to load a new material resource from given path or get instance of existing, if any.
+    /// This method is asynchronous, it immediately returns a material resource which can be shared
+    /// across multiple places, the loading may fail, but it is internal state of the resource.
+    /// Shader and textures of the material are requested automatically.
+    ///
+    /// # Async/.await
+    ///
+    /// Each material resource implements Future trait and can be used in async contexts.
+    pub fn request_material<P: AsRef<Path>>(&self, path: P) -> MaterialResource {
+        self.state().containers_mut().materials.request(path)
+    }
+
+    /// Registers a new user-defined resource type with the given loader. See
+    /// [`ContainersStorage::register`] for more info.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use fyrox::{
+    /// #     asset::{define_new_resource, Resource, ResourceData},
+    /// #     core::{reflect::prelude::*, visitor::prelude::*},
+    /// #     engine::resource_manager::{
+    /// #         container::event::ResourceEventBroadcaster,
+    /// #         loader::{BoxedLoaderFuture, ResourceLoader},
+    /// #         options::ImportOptions,
+    /// #         ResourceManager,
+    /// #     },
+    /// # };
+    /// # use serde::{Deserialize, Serialize};
+    /// # use std::{borrow::Cow, path::{Path, PathBuf}};
+    /// #[derive(Default, Debug, Visit)]
+    /// struct DialogData {
+    ///     path: PathBuf,
+    ///     text: String,
+    /// }
+    ///
+    /// impl ResourceData for DialogData {
+    ///     fn path(&self) -> Cow<Path> {
+    ///         Cow::Borrowed(&self.path)
+    ///     }
+    ///
+    ///     fn set_path(&mut self, path: PathBuf) {
+    ///         self.path = path;
+    ///     }
+    /// }
+    ///
+    /// define_new_resource!(
+    ///     /// A dialog resource.
+    ///     #[derive(Reflect)]
+    ///     #[reflect(hide_all)]
+    ///     Dialog<DialogData, ()>
+    /// );
+    ///
+    /// #[derive(Clone, Default, Serialize, Deserialize)]
+    /// struct DialogImportOptions;
+    ///
+    /// impl ImportOptions for DialogImportOptions {}
+    ///
+    /// struct DialogLoader;
+    ///
+    /// impl ResourceLoader<Dialog, DialogImportOptions> for DialogLoader {
+    ///     fn load(
+    ///         &self,
+    ///         dialog: Dialog,
+    ///         _default_import_options: DialogImportOptions,
+    ///         _event_broadcaster: ResourceEventBroadcaster<Dialog>,
+    ///         _reload: bool,
+    ///     ) -> BoxedLoaderFuture {
+    ///         Box::pin(async move {
+    ///             let path = dialog.state().path().to_path_buf();
+    ///             let text = std::fs::read_to_string(&path).unwrap_or_default();
+    ///             dialog.state().commit_ok(DialogData { path, text });
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// fn load_dialog(resource_manager: &ResourceManager) -> Dialog {
+    ///     resource_manager.register_resource_type::<Dialog, _, _, DialogImportOptions, _>(DialogLoader);
+    ///     resource_manager
+    ///         .request::<Dialog, _>("data/intro.dialog")
+    ///         .expect("Dialog resource type must be registered!")
+    /// }
+    /// ```
+    pub fn register_resource_type<T, R, E, O, L>(&self, loader: L)
+    where
+        T: Deref<Target = Resource<R, E>>
+            + Clone
+            + Send
+            + Future
+            + From<Resource<R, E>>
+            + Reflect
+            + 'static,
+        R: ResourceData,
+        E: ResourceLoadError,
+        O: ImportOptions + Send + 'static,
+        L: 'static + ResourceLoader<T, O>,
+    {
+        self.state()
+            .containers_mut()
+            .register::<T, R, E, O, L>(loader)
+    }
+
+    /// Tries to load a resource of a user-defined type from given path or get instance of existing,
+    /// if any. This method is asynchronous, it immediately returns a resource which can be shared
+    /// across multiple places, the loading may fail, but it is internal state of the resource.
+    ///
+    /// # Errors
+    ///
+    /// The method returns [`ResourceRequestError::UnregisteredType`] if the resource type wasn't
+    /// registered using [`Self::register_resource_type`].
+    pub fn request<T, P>(&self, path: P) -> Result<T, ResourceRequestError>
+    where
+        T: 'static,
+        P: AsRef<Path>,
+    {
+        let mut state = self.state();
+        let container = state
+            .containers_mut()
+            .custom
+            .get_mut(&TypeId::of::<T>())
+            .ok_or_else(|| ResourceRequestError::UnregisteredType(std::any::type_name::<T>()))?;
+        Ok(*container
+            .request_any(path.as_ref())
+            .downcast::<T>()
+            .expect("Container must produce resources of its type!"))
+    }
+
     /// Reloads every loaded texture. This method is asynchronous, internally it uses thread pool
     /// to run reload on separate thread per texture.
     pub async fn reload_textures(&self) {
@@ -379,6 +623,13 @@ impl ResourceManager {
         join_all(resources).await;
     }
 
+    /// Reloads every loaded material resource. This method is asynchronous, internally it uses thread pool
+    /// to run reload on separate thread per resource.
+    pub async fn reload_materials(&self) {
+        let resources = self.state().containers_mut().materials.reload_resources();
+        join_all(resources).await;
+    }
+
     /// Reloads every loaded sound buffer. This method is asynchronous, internally it uses thread pool
     /// to run reload on separate thread per sound buffer.
     pub async fn reload_sound_buffers(&self) {
@@ -400,6 +651,7 @@ impl ResourceManager {
             self.reload_sound_buffers(),
             self.reload_shaders(),
             self.reload_curve_resources(),
+            self.reload_materials(),
         );
     }
 }
@@ -442,6 +694,12 @@ impl ResourceManagerState {
             + containers.models.count_pending_resources()
             + containers.shaders.count_pending_resources()
             + containers.curves.count_pending_resources()
+            + containers.materials.count_pending_resources()
+            + containers
+                .custom
+                .values()
+                .map(|c| c.count_pending_resources())
+                .sum::<usize>()
     }
 
     /// Returns total amount of loaded resources.
@@ -452,6 +710,12 @@ impl ResourceManagerState {
             + containers.models.count_loaded_resources()
             + containers.shaders.count_loaded_resources()
             + containers.curves.count_loaded_resources()
+            + containers.materials.count_loaded_resources()
+            + containers
+                .custom
+                .values()
+                .map(|c| c.count_loaded_resources())
+                .sum::<usize>()
     }
 
     /// Returns total amount of registered resources.
@@ -462,6 +726,8 @@ impl ResourceManagerState {
             + containers.models.len()
             + containers.shaders.len()
             + containers.curves.len()
+            + containers.materials.len()
+            + containers.custom.values().map(|c| c.len()).sum::<usize>()
     }
 
     /// Returns percentage of loading progress. This method is useful to show progress on
@@ -485,6 +751,10 @@ impl ResourceManagerState {
         containers.textures.destroy_unused();
         containers.shaders.destroy_unused();
         containers.curves.destroy_unused();
+        containers.materials.destroy_unused();
+        for container in containers.custom.values_mut() {
+            container.destroy_unused();
+        }
     }
 
     /// Update resource containers and do hot-reloading.
@@ -501,6 +771,10 @@ impl ResourceManagerState {
         containers.sound_buffers.update(dt);
         containers.shaders.update(dt);
         containers.curves.update(dt);
+        containers.materials.update(dt);
+        for container in containers.custom.values_mut() {
+            container.update(dt);
+        }
 
         if let Some(watcher) = self.watcher.as_ref() {
             if let Some(evt) = watcher.try_get_event() {
@@ -514,7 +788,15 @@ impl ResourceManagerState {
                                 &mut containers.sound_buffers as &mut dyn Container,
                                 &mut containers.shaders as &mut dyn Container,
                                 &mut containers.curves as &mut dyn Container,
-                            ] {
+                                &mut containers.materials as &mut dyn Container,
+                            ]
+                            .into_iter()
+                            .chain(
+                                containers
+                                    .custom
+                                    .values_mut()
+                                    .map(|c| &mut **c as &mut dyn Container),
+                            ) {
                                 if container.try_reload_resource_from_path(&relative_path) {
                                     Log::info(format!(
                                         "File {} was changed, trying to reload a respective resource...",
@@ -531,3 +813,116 @@ impl ResourceManagerState {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::{define_new_resource, Resource, ResourceData, ResourceState},
+        core::{futures::executor::block_on, reflect::prelude::*, visitor::prelude::*},
+        engine::resource_manager::{
+            container::event::ResourceEventBroadcaster,
+            loader::{BoxedLoaderFuture, ResourceLoader},
+            options::ImportOptions,
+            ResourceManager, ResourceRequestError,
+        },
+    };
+    use serde::{Deserialize, Serialize};
+    use std::{
+        borrow::Cow,
+        env,
+        path::{Path, PathBuf},
+    };
+
+    #[derive(Default, Debug, Visit)]
+    pub struct DialogData {
+        path: PathBuf,
+        text: String,
+    }
+
+    impl ResourceData for DialogData {
+        fn path(&self) -> Cow<Path> {
+            Cow::Borrowed(&self.path)
+        }
+
+        fn set_path(&mut self, path: PathBuf) {
+            self.path = path;
+        }
+    }
+
+    define_new_resource!(
+        #[derive(Reflect)]
+        #[reflect(hide_all)]
+        Dialog<DialogData, std::io::Error>
+    );
+
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct DialogImportOptions;
+
+    impl ImportOptions for DialogImportOptions {}
+
+    struct DialogLoader;
+
+    impl ResourceLoader<Dialog, DialogImportOptions> for DialogLoader {
+        fn load(
+            &self,
+            dialog: Dialog,
+            _default_import_options: DialogImportOptions,
+            _event_broadcaster: ResourceEventBroadcaster<Dialog>,
+            _reload: bool,
+        ) -> BoxedLoaderFuture {
+            Box::pin(async move {
+                let path = dialog.state().path().to_path_buf();
+                match std::fs::read_to_string(&path) {
+                    Ok(text) => dialog.state().commit_ok(DialogData { path, text }),
+                    Err(e) => dialog.state().commit_error(path, e),
+                }
+            })
+        }
+    }
+
+    #[test]
+    fn test_custom_resource_type() {
+        let resource_manager = ResourceManager::new(Default::default());
+
+        assert_eq!(
+            resource_manager.request::<Dialog, _>("intro.dialog"),
+            Err(ResourceRequestError::UnregisteredType(
+                std::any::type_name::<Dialog>()
+            ))
+        );
+
+        resource_manager
+            .register_resource_type::<Dialog, _, _, DialogImportOptions, _>(DialogLoader);
+        assert!(resource_manager
+            .state()
+            .containers()
+            .is_registered::<Dialog>());
+
+        let path = {
+            let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+            let root = PathBuf::from(manifest_dir).join("test_output");
+            if !root.exists() {
+                std::fs::create_dir(&root).unwrap();
+            }
+            root.join("custom_resource_type.dialog")
+        };
+        std::fs::write(&path, "Hello!").unwrap();
+
+        let dialog = resource_manager.request::<Dialog, _>(&path).unwrap();
+        let dialog = block_on(dialog).unwrap();
+        match *dialog.state() {
+            ResourceState::Ok(ref data) => assert_eq!(data.text, "Hello!"),
+            _ => panic!("Dialog must be loaded!"),
+        }
+
+        // Requesting the same path must give the same resource.
+        let same = resource_manager.request::<Dialog, _>(&path).unwrap();
+        assert_eq!(same, dialog);
+
+        // Missing file must produce a failed resource instead of a panic.
+        let missing = resource_manager
+            .request::<Dialog, _>("this/file/does/not/exist.dialog")
+            .unwrap();
+        assert!(block_on(missing).is_err());
+    }
+}
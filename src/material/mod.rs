@@ -5,22 +5,30 @@
 #![warn(missing_docs)]
 
 use crate::{
+    asset::{define_new_resource, Resource, ResourceData, ResourceState},
     core::{
         algebra::{Matrix2, Matrix3, Matrix4, Vector2, Vector3, Vector4},
         color::Color,
+        io::{self, FileLoadError},
         parking_lot::{Mutex, MutexGuard},
         reflect::prelude::*,
         sstorage::ImmutableString,
         visitor::prelude::*,
     },
-    engine::resource_manager::ResourceManager,
+    engine::resource_manager::{options::ImportOptions, ResourceManager},
     material::shader::{PropertyKind, SamplerFallback, Shader},
     resource::texture::Texture,
+    utils::log::Log,
 };
 use fxhash::FxHashMap;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
 use std::{
+    borrow::Cow,
+    collections::BTreeMap,
     fmt::{Display, Formatter},
     ops::Deref,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 
@@ -652,6 +660,47 @@ impl Material {
     pub fn properties(&self) -> &FxHashMap<ImmutableString, PropertyValue> {
         &self.properties
     }
+
+    // Converts the material to its text representation. Shader and textures are stored as paths,
+    // procedural textures can't be referenced by path, so they're replaced with fallback values.
+    fn to_definition(&self) -> MaterialDefinition {
+        MaterialDefinition {
+            shader: self.shader.state().path().to_path_buf(),
+            properties: self
+                .properties
+                .iter()
+                .map(|(name, value)| {
+                    (
+                        name.deref().to_owned(),
+                        PropertyValueDefinition::from_property(name, value),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    // Creates a material from its text representation. Shader and textures are requested from the
+    // given resource manager, built-in shaders are used as is.
+    fn from_definition(definition: MaterialDefinition, resource_manager: &ResourceManager) -> Self {
+        let shader = Shader::standard_shaders()
+            .into_iter()
+            .find(|shader| shader.state().path() == definition.shader)
+            .unwrap_or_else(|| resource_manager.request_shader(&definition.shader));
+
+        Self {
+            shader,
+            properties: definition
+                .properties
+                .into_iter()
+                .map(|(name, value)| {
+                    (
+                        ImmutableString::new(name),
+                        value.into_property(resource_manager),
+                    )
+                })
+                .collect(),
+        }
+    }
 }
 
 /// Shared material is a material instance that can be used across multiple objects. It is useful
@@ -709,3 +758,401 @@ impl SharedMaterial {
         Self::new(self.0.lock().clone())
     }
 }
+
+// Text representation of a property value in a material file. It is the same as [`PropertyValue`],
+// except that textures are stored as paths.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+enum PropertyValueDefinition {
+    Float(f32),
+    FloatArray(Vec<f32>),
+    Int(i32),
+    IntArray(Vec<i32>),
+    UInt(u32),
+    UIntArray(Vec<u32>),
+    Vector2(Vector2<f32>),
+    Vector2Array(Vec<Vector2<f32>>),
+    Vector3(Vector3<f32>),
+    Vector3Array(Vec<Vector3<f32>>),
+    Vector4(Vector4<f32>),
+    Vector4Array(Vec<Vector4<f32>>),
+    Matrix2(Matrix2<f32>),
+    Matrix2Array(Vec<Matrix2<f32>>),
+    Matrix3(Matrix3<f32>),
+    Matrix3Array(Vec<Matrix3<f32>>),
+    Matrix4(Matrix4<f32>),
+    Matrix4Array(Vec<Matrix4<f32>>),
+    Bool(bool),
+    Color {
+        r: u8,
+        g: u8,
+        b: u8,
+        a: u8,
+    },
+    Sampler {
+        value: Option<PathBuf>,
+        fallback: SamplerFallback,
+    },
+}
+
+impl PropertyValueDefinition {
+    fn from_property(name: &ImmutableString, value: &PropertyValue) -> Self {
+        match value {
+            PropertyValue::Float(v) => Self::Float(*v),
+            PropertyValue::FloatArray(v) => Self::FloatArray(v.clone()),
+            PropertyValue::Int(v) => Self::Int(*v),
+            PropertyValue::IntArray(v) => Self::IntArray(v.clone()),
+            PropertyValue::UInt(v) => Self::UInt(*v),
+            PropertyValue::UIntArray(v) => Self::UIntArray(v.clone()),
+            PropertyValue::Vector2(v) => Self::Vector2(*v),
+            PropertyValue::Vector2Array(v) => Self::Vector2Array(v.clone()),
+            PropertyValue::Vector3(v) => Self::Vector3(*v),
+            PropertyValue::Vector3Array(v) => Self::Vector3Array(v.clone()),
+            PropertyValue::Vector4(v) => Self::Vector4(*v),
+            PropertyValue::Vector4Array(v) => Self::Vector4Array(v.clone()),
+            PropertyValue::Matrix2(v) => Self::Matrix2(*v),
+            PropertyValue::Matrix2Array(v) => Self::Matrix2Array(v.clone()),
+            PropertyValue::Matrix3(v) => Self::Matrix3(*v),
+            PropertyValue::Matrix3Array(v) => Self::Matrix3Array(v.clone()),
+            PropertyValue::Matrix4(v) => Self::Matrix4(*v),
+            PropertyValue::Matrix4Array(v) => Self::Matrix4Array(v.clone()),
+            PropertyValue::Bool(v) => Self::Bool(*v),
+            PropertyValue::Color(v) => Self::Color {
+                r: v.r,
+                g: v.g,
+                b: v.b,
+                a: v.a,
+            },
+            PropertyValue::Sampler { value, fallback } => Self::Sampler {
+                value: value.as_ref().and_then(|texture| match &*texture.state() {
+                    ResourceState::Ok(state) if state.is_procedural() => {
+                        Log::warn(format!(
+                            "Procedural texture of {} property cannot be saved to a material \
+                            file, fallback value will be used instead.",
+                            name.deref()
+                        ));
+                        None
+                    }
+                    state => Some(state.path().to_path_buf()),
+                }),
+                fallback: *fallback,
+            },
+        }
+    }
+
+    fn into_property(self, resource_manager: &ResourceManager) -> PropertyValue {
+        match self {
+            Self::Float(v) => PropertyValue::Float(v),
+            Self::FloatArray(v) => PropertyValue::FloatArray(v),
+            Self::Int(v) => PropertyValue::Int(v),
+            Self::IntArray(v) => PropertyValue::IntArray(v),
+            Self::UInt(v) => PropertyValue::UInt(v),
+            Self::UIntArray(v) => PropertyValue::UIntArray(v),
+            Self::Vector2(v) => PropertyValue::Vector2(v),
+            Self::Vector2Array(v) => PropertyValue::Vector2Array(v),
+            Self::Vector3(v) => PropertyValue::Vector3(v),
+            Self::Vector3Array(v) => PropertyValue::Vector3Array(v),
+            Self::Vector4(v) => PropertyValue::Vector4(v),
+            Self::Vector4Array(v) => PropertyValue::Vector4Array(v),
+            Self::Matrix2(v) => PropertyValue::Matrix2(v),
+            Self::Matrix2Array(v) => PropertyValue::Matrix2Array(v),
+            Self::Matrix3(v) => PropertyValue::Matrix3(v),
+            Self::Matrix3Array(v) => PropertyValue::Matrix3Array(v),
+            Self::Matrix4(v) => PropertyValue::Matrix4(v),
+            Self::Matrix4Array(v) => PropertyValue::Matrix4Array(v),
+            Self::Bool(v) => PropertyValue::Bool(v),
+            Self::Color { r, g, b, a } => PropertyValue::Color(Color::from_rgba(r, g, b, a)),
+            Self::Sampler { value, fallback } => PropertyValue::Sampler {
+                value: value.map(|path| resource_manager.request_texture(path)),
+                fallback,
+            },
+        }
+    }
+}
+
+// Text representation of a material, it is stored in material files in RON format. Properties are
+// sorted by their names, so the files could be easily compared.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
+struct MaterialDefinition {
+    shader: PathBuf,
+    properties: BTreeMap<String, PropertyValueDefinition>,
+}
+
+/// An error that may occur during material resource loading or saving.
+#[derive(Debug)]
+pub enum MaterialResourceError {
+    /// An i/o error has occurred while reading a material file.
+    Io(FileLoadError),
+
+    /// A parsing error has occurred.
+    ParseError(ron::error::SpannedError),
+
+    /// A material cannot be written to a file.
+    SaveError(ron::Error),
+}
+
+impl Display for MaterialResourceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MaterialResourceError::Io(v) => {
+                write!(f, "A file load error has occurred {v:?}")
+            }
+            MaterialResourceError::ParseError(v) => {
+                write!(f, "A parsing error has occurred {v:?}")
+            }
+            MaterialResourceError::SaveError(v) => {
+                write!(f, "Unable to save material. Reason: {v:?}")
+            }
+        }
+    }
+}
+
+impl From<FileLoadError> for MaterialResourceError {
+    fn from(e: FileLoadError) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<ron::error::SpannedError> for MaterialResourceError {
+    fn from(e: ron::error::SpannedError) -> Self {
+        Self::ParseError(e)
+    }
+}
+
+impl From<ron::Error> for MaterialResourceError {
+    fn from(e: ron::Error) -> Self {
+        Self::SaveError(e)
+    }
+}
+
+impl From<std::io::Error> for MaterialResourceError {
+    fn from(e: std::io::Error) -> Self {
+        Self::SaveError(e.into())
+    }
+}
+
+/// State of the [`MaterialResource`].
+#[derive(Debug, Default)]
+pub struct MaterialState {
+    pub(crate) path: PathBuf,
+
+    /// Actual material. The instance is shared across every surface that uses the resource.
+    pub material: SharedMaterial,
+}
+
+impl Visit for MaterialState {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut region = visitor.enter_region(name)?;
+
+        // Only path is stored, the material itself will be loaded from the file when the resource
+        // is restored after deserialization.
+        self.path.visit("Path", &mut region)
+    }
+}
+
+impl ResourceData for MaterialState {
+    fn path(&self) -> Cow<Path> {
+        Cow::Borrowed(&self.path)
+    }
+
+    fn set_path(&mut self, path: PathBuf) {
+        self.path = path;
+    }
+}
+
+impl MaterialState {
+    /// Creates new material resource state, that could be saved to the given path later on.
+    pub fn new<P: AsRef<Path>>(path: P, material: SharedMaterial) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            material,
+        }
+    }
+
+    /// Loads a material from the specific file path. Shader and textures of the material will be
+    /// requested from the given resource manager.
+    pub async fn from_file(
+        path: &Path,
+        resource_manager: &ResourceManager,
+    ) -> Result<Self, MaterialResourceError> {
+        let content = io::load_file(path).await?;
+        Self::from_str(&String::from_utf8_lossy(&content), path, resource_manager)
+    }
+
+    /// Creates a material from the given string in the format of material files (see [`Self::save`]).
+    /// Shader and textures of the material will be requested from the given resource manager.
+    pub fn from_str<P: AsRef<Path>>(
+        str: &str,
+        path: P,
+        resource_manager: &ResourceManager,
+    ) -> Result<Self, MaterialResourceError> {
+        let definition: MaterialDefinition = ron::de::from_str(str)?;
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            material: SharedMaterial::new(Material::from_definition(definition, resource_manager)),
+        })
+    }
+
+    /// Converts the material to a string in the format of material files (see [`Self::save`]).
+    pub fn to_string(&self) -> Result<String, MaterialResourceError> {
+        let definition = self.material.lock().to_definition();
+        Ok(ron::ser::to_string_pretty(
+            &definition,
+            PrettyConfig::default(),
+        )?)
+    }
+
+    /// Saves the material to its path in a text (RON) format, so material files could be compared
+    /// and merged by version control systems. Shader and textures are saved as references (paths)
+    /// to respective resources, procedural textures cannot be saved and their fallback values are
+    /// used instead.
+    pub fn save(&self) -> Result<(), MaterialResourceError> {
+        std::fs::write(&self.path, self.to_string()?)?;
+        Ok(())
+    }
+}
+
+define_new_resource!(
+    /// Material resource allows you to store a material in a separate file (usually with
+    /// `.material` extension) and share it across multiple surfaces. Every surface that uses
+    /// the resource will use the same [`SharedMaterial`] instance, the material is automatically
+    /// re-applied to surfaces when the file is changed and the resource is reloaded. See
+    /// [`crate::scene::mesh::surface::Surface::set_material_resource`] for more info.
+    #[derive(Reflect)]
+    #[reflect(hide_all)]
+    MaterialResource<MaterialState, MaterialResourceError>
+);
+
+/// Import options for material resource.
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct MaterialImportOptions {}
+
+impl ImportOptions for MaterialImportOptions {}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::{Resource, ResourceState},
+        core::{
+            algebra::Matrix4, color::Color, futures::executor::block_on, sstorage::ImmutableString,
+            visitor::Visitor,
+        },
+        engine::resource_manager::ResourceManager,
+        material::{
+            shader::SamplerFallback, Material, MaterialResource, MaterialState, PropertyValue,
+            SharedMaterial,
+        },
+        scene::{
+            base::BaseBuilder,
+            mesh::{
+                surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+                MeshBuilder,
+            },
+            Scene,
+        },
+    };
+    use std::{env, path::PathBuf};
+
+    fn test_material(resource_manager: &ResourceManager) -> Material {
+        let mut material = Material::standard();
+        material
+            .set_property(
+                &ImmutableString::new("diffuseColor"),
+                PropertyValue::Color(Color::from_rgba(10, 20, 30, 40)),
+            )
+            .unwrap();
+        material
+            .set_property(
+                &ImmutableString::new("diffuseTexture"),
+                PropertyValue::Sampler {
+                    value: Some(resource_manager.request_texture("data/brick.png")),
+                    fallback: SamplerFallback::White,
+                },
+            )
+            .unwrap();
+        material
+    }
+
+    #[test]
+    fn test_material_resource_save_load() {
+        let path = {
+            let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+            let root = PathBuf::from(manifest_dir).join("test_output");
+            if !root.exists() {
+                std::fs::create_dir(&root).unwrap();
+            }
+            root.join("save_load.material")
+        };
+
+        let resource_manager = ResourceManager::new(Default::default());
+
+        let saved =
+            MaterialState::new(&path, SharedMaterial::new(test_material(&resource_manager)));
+        saved.save().unwrap();
+
+        // Material files must be human-readable.
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.contains("diffuseColor"));
+        assert!(text.contains("data/brick.png"));
+
+        let loaded = block_on(MaterialState::from_file(&path, &resource_manager)).unwrap();
+        assert_eq!(
+            saved.material.lock().to_definition(),
+            loaded.material.lock().to_definition()
+        );
+
+        let loaded_material = loaded.material.lock();
+        // Standard shader is shared, its state must not be locked twice at the same time.
+        let standard_shader_path = Material::standard().shader().state().path().to_path_buf();
+        assert_eq!(
+            loaded_material.shader().state().path(),
+            standard_shader_path
+        );
+        assert!(matches!(
+            loaded_material.property_ref(&ImmutableString::new("diffuseColor")),
+            Some(PropertyValue::Color(color)) if *color == Color::from_rgba(10, 20, 30, 40)
+        ));
+        match loaded_material.property_ref(&ImmutableString::new("diffuseTexture")) {
+            Some(PropertyValue::Sampler {
+                value: Some(texture),
+                fallback: SamplerFallback::White,
+            }) => assert_eq!(texture.state().path(), PathBuf::from("data/brick.png")),
+            _ => panic!("Texture must be restored!"),
+        }
+    }
+
+    #[test]
+    fn test_scene_does_not_embed_material_resource() {
+        let resource_manager = ResourceManager::new(Default::default());
+
+        let resource = MaterialResource(Resource::new(ResourceState::Ok(MaterialState::new(
+            "shared.material",
+            SharedMaterial::new(test_material(&resource_manager)),
+        ))));
+
+        let mut scene = Scene::new();
+        let mesh = MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+                SurfaceData::make_cube(Matrix4::identity()),
+            ))
+            .with_material_resource(resource.clone())
+            .build()])
+            .build(&mut scene.graph);
+
+        let save_scene = |scene: &mut Scene| {
+            let mut visitor = Visitor::new();
+            scene.save("Scene", &mut visitor).unwrap();
+            visitor.save_text()
+        };
+
+        // The surface must reference the resource only.
+        let text = save_scene(&mut scene);
+        assert!(text.contains("\tMaterialResource["));
+        assert!(!text.contains("\tMaterial["));
+
+        // Overridden material must be embedded.
+        scene.graph[mesh].as_mesh_mut().surfaces_mut()[0]
+            .set_material(SharedMaterial::new(Material::standard()));
+        let text = save_scene(&mut scene);
+        assert!(text.contains("\tMaterial["));
+    }
+}
@@ -140,7 +140,7 @@ impl BatchStorage {
                         mesh.global_transform()
                     };
 
-                    let data = surface.data();
+                    let data = surface.render_data();
                     let batch_id = surface.batch_id();
 
                     let batch = if let Some(&batch_index) = self.batch_map.get(&batch_id) {
@@ -11,12 +11,16 @@ pub mod error;
 mod scene;
 
 use crate::{
-    animation::{track::Track, Animation, AnimationContainer},
+    animation::{
+        container::{TrackDataContainer, TrackValueKind},
+        track::Track,
+        value::{ValueBinding, ValueType},
+        Animation, AnimationContainer,
+    },
     core::{
         algebra::{Matrix4, Point3, UnitQuaternion, Vector2, Vector3, Vector4},
         curve::{CurveKey, CurveKeyKind},
         instant::Instant,
-        io,
         math::{self, triangulator::triangulate, RotationOrder},
         pool::Handle,
         sstorage::ImmutableString,
@@ -30,12 +34,13 @@ use crate::{
             error::FbxError,
             scene::{
                 animation::{FbxAnimationCurveNode, FbxAnimationCurveNodeType},
+                blend_shape::FbxBlendShapeChannel,
                 geometry::FbxGeometry,
                 model::FbxModel,
                 FbxComponent, FbxMapping, FbxScene,
             },
         },
-        model::{MaterialSearchOptions, ModelImportOptions},
+        model::ModelImportOptions,
     },
     scene::{
         animation::AnimationPlayerBuilder,
@@ -43,9 +48,9 @@ use crate::{
         graph::Graph,
         mesh::{
             buffer::{VertexAttributeUsage, VertexWriteTrait},
-            surface::{Surface, SurfaceData, SurfaceSharedData, VertexWeightSet},
+            surface::{BlendShapeTarget, Surface, SurfaceData, SurfaceSharedData, VertexWeightSet},
             vertex::{AnimatedVertex, StaticVertex},
-            Mesh, MeshBuilder,
+            BlendShape, Mesh, MeshBuilder,
         },
         node::Node,
         pivot::PivotBuilder,
@@ -64,7 +69,6 @@ use std::{
     hash::{Hash, Hasher},
     path::Path,
 };
-use walkdir::WalkDir;
 
 /// Input angles in degrees
 fn quat_from_euler(euler: Vector3<f32>) -> UnitQuaternion<f32> {
@@ -253,6 +257,100 @@ impl FbxMeshBuilder {
 struct FbxSurfaceData {
     builder: FbxMeshBuilder,
     skin_data: Vec<VertexWeightSet>,
+    // Index of a control point for each unique vertex, it is used to map blend shape offsets
+    // to vertices.
+    vertex_sources: Vec<usize>,
+    blend_shape_targets: Vec<BlendShapeTarget>,
+}
+
+impl FbxSurfaceData {
+    fn into_surface(self) -> Surface {
+        let mut data = self.builder.build();
+        data.blend_shape_targets = self.blend_shape_targets;
+        let mut surface = Surface::new(SurfaceSharedData::new(data));
+        surface.vertex_weights = self.skin_data;
+        surface
+    }
+}
+
+/// Collects unique (by name) blend shape channels of every geometry of the model.
+fn collect_blend_shape_channels<'a>(
+    fbx_scene: &'a FbxScene,
+    model: &FbxModel,
+) -> Result<Vec<&'a FbxBlendShapeChannel>, FbxError> {
+    let mut channels = Vec::<&FbxBlendShapeChannel>::new();
+    for &geom_handle in &model.geoms {
+        let geom = fbx_scene.get(geom_handle).as_geometry()?;
+        for &blend_shape_handle in geom.blend_shapes.iter() {
+            let blend_shape = fbx_scene.get(blend_shape_handle).as_blend_shape()?;
+            for &channel_handle in blend_shape.channels.iter() {
+                let channel = fbx_scene.get(channel_handle).as_blend_shape_channel()?;
+                if channels.iter().all(|c| c.name != channel.name) {
+                    channels.push(channel);
+                }
+            }
+        }
+    }
+    Ok(channels)
+}
+
+/// Creates blend shape targets for a surface from blend shape channels of a geometry. Targets that
+/// does not affect any vertex of the surface are skipped.
+fn make_blend_shape_targets(
+    fbx_scene: &FbxScene,
+    geom: &FbxGeometry,
+    geometric_transform: &Matrix4<f32>,
+    vertex_sources: &[usize],
+) -> Result<Vec<BlendShapeTarget>, FbxError> {
+    let mut targets = Vec::new();
+    for &blend_shape_handle in geom.blend_shapes.iter() {
+        let blend_shape = fbx_scene.get(blend_shape_handle).as_blend_shape()?;
+        for &channel_handle in blend_shape.channels.iter() {
+            let channel = fbx_scene.get(channel_handle).as_blend_shape_channel()?;
+
+            // In-between shapes are not supported, use the last one which has full influence.
+            let shape = match channel.shapes.last() {
+                Some(shape_handle) => fbx_scene.get(*shape_handle).as_shape()?,
+                None => continue,
+            };
+            let offsets = shape.offsets();
+            let has_normals = !shape.normals.is_empty();
+
+            let mut target = BlendShapeTarget {
+                name: channel.name.clone(),
+                positions: Vec::with_capacity(vertex_sources.len()),
+                normals: if has_normals {
+                    Vec::with_capacity(vertex_sources.len())
+                } else {
+                    Default::default()
+                },
+                tangents: Default::default(),
+            };
+
+            let mut affects_surface = false;
+            for source in vertex_sources {
+                let (position, normal) = match offsets.get(source) {
+                    Some((position, normal)) => {
+                        affects_surface = true;
+                        (
+                            geometric_transform.transform_vector(position),
+                            geometric_transform.transform_vector(normal),
+                        )
+                    }
+                    None => (Vector3::default(), Vector3::default()),
+                };
+                target.positions.push(position);
+                if has_normals {
+                    target.normals.push(normal);
+                }
+            }
+
+            if affects_surface {
+                targets.push(target);
+            }
+        }
+    }
+    Ok(targets)
 }
 
 async fn create_surfaces(
@@ -269,14 +367,11 @@ async fn create_surfaces(
     if model.materials.is_empty() {
         assert_eq!(data_set.len(), 1);
         let data = data_set.into_iter().next().unwrap();
-        let mut surface = Surface::new(SurfaceSharedData::new(data.builder.build()));
-        surface.vertex_weights = data.skin_data;
-        surfaces.push(surface);
+        surfaces.push(data.into_surface());
     } else {
         assert_eq!(data_set.len(), model.materials.len());
         for (&material_handle, data) in model.materials.iter().zip(data_set.into_iter()) {
-            let mut surface = Surface::new(SurfaceSharedData::new(data.builder.build()));
-            surface.vertex_weights = data.skin_data;
+            let surface = data.into_surface();
             let material = fbx_scene.get(material_handle).as_material()?;
             if let Err(e) = surface.material().lock().set_property(
                 &ImmutableString::new("diffuseColor"),
@@ -294,38 +389,10 @@ async fn create_surfaces(
                 let texture = fbx_scene.get(*texture_handle).as_texture()?;
                 let path = texture.get_file_path();
                 if let Some(filename) = path.file_name() {
-                    let texture_path = match model_import_options.material_search_options {
-                        MaterialSearchOptions::MaterialsDirectory(ref directory) => {
-                            Some(directory.join(filename))
-                        }
-                        MaterialSearchOptions::RecursiveUp => {
-                            let mut texture_path = None;
-                            let mut path = model_path.to_owned();
-                            while let Some(parent) = path.parent() {
-                                let candidate = parent.join(filename);
-                                if io::exists(&candidate).await {
-                                    texture_path = Some(candidate);
-                                    break;
-                                }
-                                path.pop();
-                            }
-                            texture_path
-                        }
-                        MaterialSearchOptions::WorkingDirectory => {
-                            let mut texture_path = None;
-                            for dir in WalkDir::new(".").into_iter().flatten() {
-                                if dir.path().is_dir() {
-                                    let candidate = dir.path().join(filename);
-                                    if candidate.exists() {
-                                        texture_path = Some(candidate);
-                                        break;
-                                    }
-                                }
-                            }
-                            texture_path
-                        }
-                        MaterialSearchOptions::UsePathDirectly => Some(path.clone()),
-                    };
+                    let texture_path = model_import_options
+                        .material_search_options
+                        .find_texture_path(model_path, &path)
+                        .await;
 
                     if let Some(texture_path) = texture_path {
                         let texture = resource_manager.request_texture(texture_path.as_path());
@@ -435,6 +502,8 @@ async fn convert_mesh(
                     FbxMeshBuilder::Animated(RawMeshBuilder::new(1024, 1024))
                 },
                 skin_data: Default::default(),
+                vertex_sources: Default::default(),
+                blend_shape_targets: Default::default(),
             };
             model.materials.len().max(1)
         ];
@@ -471,6 +540,7 @@ async fn convert_mesh(
                         if let Some(skin_data) = weights {
                             data.skin_data.push(skin_data);
                         }
+                        data.vertex_sources.push(index);
                     }
                 }
             }
@@ -481,6 +551,17 @@ async fn convert_mesh(
             }
         }
 
+        if !geom.blend_shapes.is_empty() {
+            for data in data_set.iter_mut() {
+                data.blend_shape_targets = make_blend_shape_targets(
+                    fbx_scene,
+                    geom,
+                    &geometric_transform,
+                    &data.vertex_sources,
+                )?;
+            }
+        }
+
         let mut surfaces = create_surfaces(
             fbx_scene,
             data_set,
@@ -497,13 +578,23 @@ async fn convert_mesh(
             }
         }
 
+        for surface in surfaces.iter_mut() {
+            Log::verify(surface.data().lock().calculate_blend_shape_tangents());
+        }
+
         for surface in surfaces {
             mesh_surfaces.push(surface);
         }
     }
 
+    let blend_shapes = collect_blend_shape_channels(fbx_scene, model)?
+        .into_iter()
+        .map(|channel| BlendShape::new(channel.name.clone(), channel.deform_percent / 100.0))
+        .collect();
+
     Ok(MeshBuilder::new(base)
         .with_surfaces(mesh_surfaces)
+        .with_blend_shapes(blend_shapes)
         .build(graph))
 }
 
@@ -684,6 +775,47 @@ async fn convert_model(
         animation.add_track(scale_track);
     }
 
+    // Convert blend shape weight animations. FBX stores weights in [0; 100] range.
+    for (index, channel) in collect_blend_shape_channels(fbx_scene, model)?
+        .into_iter()
+        .enumerate()
+    {
+        if channel.animation_curve_node.is_none() {
+            continue;
+        }
+
+        if let FbxComponent::AnimationCurveNode(curve_node) =
+            fbx_scene.get(channel.animation_curve_node)
+        {
+            if let Some(FbxComponent::AnimationCurve(fbx_curve)) = curve_node
+                .curves
+                .get("d|DeformPercent")
+                .map(|curve_handle| fbx_scene.get(*curve_handle))
+            {
+                if fbx_curve.keys.is_empty() {
+                    continue;
+                }
+
+                let mut track = Track::new(
+                    TrackDataContainer::new(TrackValueKind::Real),
+                    ValueBinding::Property {
+                        name: format!("blend_shapes[{}].weight", index),
+                        value_type: ValueType::F32,
+                    },
+                );
+                track.set_target(node_handle);
+                for pair in fbx_curve.keys.iter() {
+                    track.data_container_mut().curves_mut()[0].add_key(CurveKey::new(
+                        pair.time,
+                        pair.value / 100.0,
+                        CurveKeyKind::Linear,
+                    ));
+                }
+                animation.add_track(track);
+            }
+        }
+    }
+
     animation.fit_length_to_content();
 
     Ok(node_handle)
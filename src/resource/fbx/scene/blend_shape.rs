@@ -0,0 +1,131 @@
+use crate::{
+    core::{algebra::Vector3, pool::Handle},
+    resource::fbx::{
+        document::{FbxNode, FbxNodeContainer},
+        error::FbxError,
+        scene::FbxComponent,
+    },
+};
+use fxhash::FxHashMap;
+
+/// Shape is a special kind of geometry, that contains offsets for a sub-set of vertices of a geometry
+/// it is attached to (via blend shape channel and blend shape deformer).
+pub struct FbxShape {
+    /// Indices of control points of the geometry.
+    pub indices: Vec<usize>,
+    /// Offsets of the control points.
+    pub vertices: Vec<Vector3<f32>>,
+    /// Offsets of the normals of the control points, could be empty.
+    pub normals: Vec<Vector3<f32>>,
+}
+
+fn read_vec3_array(
+    shape_handle: Handle<FbxNode>,
+    nodes: &FbxNodeContainer,
+    name: &str,
+) -> Result<Vec<Vector3<f32>>, FbxError> {
+    let array_node_handle = nodes.find(shape_handle, name)?;
+    let array_node = nodes.get_by_name(array_node_handle, "a")?;
+    let mut array = Vec::with_capacity(array_node.attrib_count() / 3);
+    for v in array_node.attributes().chunks_exact(3) {
+        array.push(Vector3::new(v[0].as_f32()?, v[1].as_f32()?, v[2].as_f32()?));
+    }
+    Ok(array)
+}
+
+impl FbxShape {
+    pub(in crate::resource::fbx) fn read(
+        shape_handle: Handle<FbxNode>,
+        nodes: &FbxNodeContainer,
+    ) -> Result<Self, FbxError> {
+        let indices_handle = nodes.find(shape_handle, "Indexes")?;
+        let indices_array = nodes.get_by_name(indices_handle, "a")?;
+        let mut indices = Vec::with_capacity(indices_array.attrib_count());
+        for index in indices_array.attributes() {
+            indices.push(index.as_i32()? as usize);
+        }
+
+        let vertices = read_vec3_array(shape_handle, nodes, "Vertices")?;
+        if vertices.len() != indices.len() {
+            return Err(FbxError::Custom(Box::new(String::from(
+                "FBX: Shape has different amount of indices and vertices!",
+            ))));
+        }
+
+        // Normals are optional.
+        let mut normals = read_vec3_array(shape_handle, nodes, "Normals").unwrap_or_default();
+        if normals.len() != indices.len() {
+            normals.clear();
+        }
+
+        Ok(Self {
+            indices,
+            vertices,
+            normals,
+        })
+    }
+
+    /// Returns a map of control point index to its position and normal offsets.
+    pub fn offsets(&self) -> FxHashMap<usize, (Vector3<f32>, Vector3<f32>)> {
+        self.indices
+            .iter()
+            .enumerate()
+            .map(|(i, index)| {
+                (
+                    *index,
+                    (
+                        self.vertices[i],
+                        self.normals.get(i).cloned().unwrap_or_default(),
+                    ),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Blend shape deformer, it is just a set of channels.
+pub struct FbxBlendShape {
+    pub channels: Vec<Handle<FbxComponent>>,
+}
+
+/// Blend shape channel defines a weight of a shape. Channels could have multiple shapes (in-between
+/// shapes), in this case the last shape is used (it corresponds to full weight).
+pub struct FbxBlendShapeChannel {
+    pub name: String,
+    /// Default weight of the channel in `[0; 100]` range.
+    pub deform_percent: f32,
+    pub shapes: Vec<Handle<FbxComponent>>,
+    /// An animation curve node that animates the weight of the channel.
+    pub animation_curve_node: Handle<FbxComponent>,
+}
+
+impl FbxBlendShapeChannel {
+    pub(in crate::resource::fbx) fn read(
+        channel_handle: Handle<FbxNode>,
+        nodes: &FbxNodeContainer,
+    ) -> Result<Self, FbxError> {
+        let channel_node = nodes.get(channel_handle);
+
+        let mut name = match channel_node.get_attrib(1) {
+            Ok(name) => name.as_string(),
+            Err(_) => String::from("Unnamed"),
+        };
+
+        // Remove prefix
+        if let Some(stripped) = name.strip_prefix("SubDeformer::") {
+            name = stripped.to_string();
+        }
+
+        let deform_percent = match nodes.get_by_name(channel_handle, "DeformPercent") {
+            Ok(deform_percent_node) => deform_percent_node.get_attrib(0)?.as_f32()?,
+            Err(_) => 0.0,
+        };
+
+        Ok(Self {
+            name,
+            deform_percent,
+            shapes: Default::default(),
+            animation_curve_node: Default::default(),
+        })
+    }
+}
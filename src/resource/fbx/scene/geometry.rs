@@ -25,6 +25,7 @@ pub struct FbxGeometry {
     pub binormals: Option<FbxContainer<Vector3<f32>>>,
 
     pub deformers: Vec<Handle<FbxComponent>>,
+    pub blend_shapes: Vec<Handle<FbxComponent>>,
 }
 
 fn read_vertices(
@@ -163,6 +164,7 @@ impl FbxGeometry {
             tangents: read_tangents(geom_node_handle, nodes)?,
             binormals: read_binormals(geom_node_handle, nodes)?,
             deformers: Vec::new(),
+            blend_shapes: Vec::new(),
         })
     }
 
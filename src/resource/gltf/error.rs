@@ -0,0 +1,83 @@
+//! Contains all possible errors that can occur during glTF parsing and conversion.
+
+use crate::core::io::FileLoadError;
+use std::fmt::{Display, Formatter};
+
+/// See module docs.
+#[derive(Debug)]
+pub enum GltfError {
+    /// The document is malformed and cannot be parsed or validated.
+    Gltf(::gltf::Error),
+
+    /// An error occurred during file loading.
+    FileLoadError(FileLoadError),
+
+    /// The document references the binary chunk of a `.glb` file, but there's no such chunk.
+    MissingBlob,
+
+    /// A buffer or an image uses a data URI with unsupported encoding.
+    UnsupportedUri(String),
+
+    /// Base64-encoded data URI has invalid content.
+    InvalidBase64(String),
+
+    /// A buffer is shorter than its declared length.
+    BufferTooShort {
+        /// Index of the buffer in the document.
+        index: usize,
+        /// Declared length of the buffer.
+        expected: usize,
+        /// Actual length of the data.
+        actual: usize,
+    },
+
+    /// A primitive does not have positions attribute.
+    MissingPositions,
+}
+
+impl Display for GltfError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GltfError::Gltf(v) => {
+                write!(f, "glTF: Malformed document: {v}")
+            }
+            GltfError::FileLoadError(v) => {
+                write!(f, "glTF: File load error {v:?}.")
+            }
+            GltfError::MissingBlob => {
+                write!(f, "glTF: Binary chunk is missing.")
+            }
+            GltfError::UnsupportedUri(v) => {
+                write!(f, "glTF: Unsupported URI {v}.")
+            }
+            GltfError::InvalidBase64(v) => {
+                write!(f, "glTF: Invalid base64 data: {v}.")
+            }
+            GltfError::BufferTooShort {
+                index,
+                expected,
+                actual,
+            } => {
+                write!(
+                    f,
+                    "glTF: Buffer {index} is too short. Expected at least {expected} bytes, got {actual}."
+                )
+            }
+            GltfError::MissingPositions => {
+                write!(f, "glTF: A primitive does not have positions.")
+            }
+        }
+    }
+}
+
+impl From<::gltf::Error> for GltfError {
+    fn from(err: ::gltf::Error) -> Self {
+        GltfError::Gltf(err)
+    }
+}
+
+impl From<FileLoadError> for GltfError {
+    fn from(err: FileLoadError) -> Self {
+        GltfError::FileLoadError(err)
+    }
+}
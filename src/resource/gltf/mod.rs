@@ -0,0 +1,1324 @@
+//! Contains all methods to load and convert glTF 2.0 model format.
+//!
+//! glTF is an open format for transmission of 3D scenes, it supports node hierarchies, meshes,
+//! skinning, PBR materials and keyframe animation. Both flavours of the format are supported:
+//! `.gltf` (JSON document with external or embedded buffers) and `.glb` (binary container).
+//!
+//! Normally you should never use methods from this module directly, use resource manager to load
+//! models and create their instances.
+
+pub mod error;
+
+use crate::{
+    animation::{
+        container::{TrackDataContainer, TrackValueKind},
+        track::Track,
+        value::{ValueBinding, ValueType},
+        Animation, AnimationContainer,
+    },
+    core::{
+        algebra::{Matrix4, Quaternion, UnitQuaternion, Vector2, Vector3, Vector4},
+        color::Color,
+        curve::{CurveKey, CurveKeyKind},
+        instant::Instant,
+        io,
+        math::TriangleDefinition,
+        pool::Handle,
+        sstorage::ImmutableString,
+        uuid::Uuid,
+    },
+    engine::resource_manager::ResourceManager,
+    material::{
+        shader::{SamplerFallback, Shader},
+        Material, PropertyValue, SharedMaterial,
+    },
+    renderer::batch::BONE_MATRICES_COUNT,
+    resource::{
+        gltf::error::GltfError,
+        model::ModelImportOptions,
+        texture::{CompressionOptions, Texture, TextureKind, TexturePixelKind},
+    },
+    scene::{
+        animation::AnimationPlayerBuilder,
+        base::{BaseBuilder, InstanceId},
+        mesh::{
+            buffer::{TriangleBuffer, VertexBuffer},
+            surface::{BlendShapeTarget, Surface, SurfaceData, SurfaceSharedData},
+            vertex::{AnimatedVertex, StaticVertex},
+            BlendShape, Mesh, MeshBuilder,
+        },
+        node::Node,
+        pivot::PivotBuilder,
+        transform::TransformBuilder,
+        Scene,
+    },
+    utils::log::{Log, MessageKind},
+};
+use ::gltf::{
+    animation::{util::ReadOutputs, Interpolation},
+    buffer::Source as BufferSource,
+    image::Source as ImageSource,
+    mesh::Mode,
+    Document, Gltf,
+};
+use base64::Engine;
+use fxhash::FxHashMap;
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
+/// Location of the data of an image referenced by a glTF document.
+enum ImageLocation {
+    /// The image is stored in a separate file and can be requested from the resource manager.
+    File(PathBuf),
+    /// The image is embedded in the document (either in a buffer view or in a data URI).
+    Embedded(Vec<u8>),
+}
+
+/// Decodes `%XX` sequences in relative URIs, glTF exporters percent-encode spaces and other
+/// reserved characters in file names.
+fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Some(value) = std::str::from_utf8(&bytes[i + 1..i + 3])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                decoded.push(value);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn decode_data_uri(uri: &str) -> Result<Vec<u8>, GltfError> {
+    match uri.split_once(";base64,") {
+        Some((_, data)) => base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .map_err(|e| GltfError::InvalidBase64(e.to_string())),
+        None => Err(GltfError::UnsupportedUri(uri.chars().take(64).collect())),
+    }
+}
+
+fn uri_relative_path(model_path: &Path, uri: &str) -> PathBuf {
+    model_path
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(percent_decode(uri))
+}
+
+async fn load_buffers(gltf: &Gltf, model_path: &Path) -> Result<Vec<Vec<u8>>, GltfError> {
+    let mut buffers = Vec::new();
+    for buffer in gltf.document.buffers() {
+        let data = match buffer.source() {
+            BufferSource::Bin => gltf.blob.clone().ok_or(GltfError::MissingBlob)?,
+            BufferSource::Uri(uri) if uri.starts_with("data:") => decode_data_uri(uri)?,
+            BufferSource::Uri(uri) => io::load_file(uri_relative_path(model_path, uri)).await?,
+        };
+        if data.len() < buffer.length() {
+            return Err(GltfError::BufferTooShort {
+                index: buffer.index(),
+                expected: buffer.length(),
+                actual: data.len(),
+            });
+        }
+        buffers.push(data);
+    }
+    Ok(buffers)
+}
+
+async fn locate_image(
+    image: &::gltf::Image<'_>,
+    buffers: &[Vec<u8>],
+    model_path: &Path,
+    model_import_options: &ModelImportOptions,
+) -> Option<ImageLocation> {
+    match image.source() {
+        ImageSource::View { view, .. } => {
+            let buffer = buffers.get(view.buffer().index())?;
+            let data = buffer.get(view.offset()..view.offset() + view.length())?;
+            Some(ImageLocation::Embedded(data.to_vec()))
+        }
+        ImageSource::Uri { uri, .. } => {
+            if uri.starts_with("data:") {
+                match decode_data_uri(uri) {
+                    Ok(data) => Some(ImageLocation::Embedded(data)),
+                    Err(e) => {
+                        Log::err(format!(
+                            "Unable to decode embedded image of {:?}. Reason: {}",
+                            model_path, e
+                        ));
+                        None
+                    }
+                }
+            } else {
+                // URIs are relative to the document, try it first and only then use the
+                // search options.
+                let relative_path = uri_relative_path(model_path, uri);
+                if io::exists(&relative_path).await {
+                    return Some(ImageLocation::File(relative_path));
+                }
+                let texture_path = model_import_options
+                    .material_search_options
+                    .find_texture_path(model_path, &relative_path)
+                    .await;
+                if texture_path.is_none() {
+                    Log::writeln(
+                        MessageKind::Warning,
+                        format!(
+                            "Unable to find a texture {:?} for 3D model {:?} using {:?} option!",
+                            uri, model_path, model_import_options
+                        ),
+                    );
+                }
+                texture_path.map(ImageLocation::File)
+            }
+        }
+    }
+}
+
+fn load_texture(location: ImageLocation, resource_manager: &ResourceManager) -> Option<Texture> {
+    match location {
+        ImageLocation::File(path) => Some(resource_manager.request_texture(path)),
+        ImageLocation::Embedded(data) => {
+            match Texture::load_from_memory(&data, CompressionOptions::NoCompression, true) {
+                Ok(texture) => Some(texture),
+                Err(e) => {
+                    Log::err(format!("Unable to load embedded texture. Reason: {:?}", e));
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// glTF packs metallic (blue channel) and roughness (green channel) into a single texture, while
+/// standard shader reads both values from red channel of separate textures. Split the image and
+/// bake the scalar factors into the result.
+async fn split_metallic_roughness(
+    location: ImageLocation,
+    metallic_factor: f32,
+    roughness_factor: f32,
+) -> Option<(Texture, Texture)> {
+    let data = match location {
+        ImageLocation::File(path) => match io::load_file(&path).await {
+            Ok(data) => data,
+            Err(e) => {
+                Log::err(format!(
+                    "Unable to load metallic-roughness texture {:?}. Reason: {:?}",
+                    path, e
+                ));
+                return None;
+            }
+        },
+        ImageLocation::Embedded(data) => data,
+    };
+
+    let image = match image::load_from_memory(&data) {
+        Ok(image) => image.to_rgba8(),
+        Err(e) => {
+            Log::err(format!(
+                "Unable to decode metallic-roughness texture. Reason: {:?}",
+                e
+            ));
+            return None;
+        }
+    };
+
+    let (width, height) = image.dimensions();
+    let mut metallic = Vec::with_capacity((width * height) as usize);
+    let mut roughness = Vec::with_capacity((width * height) as usize);
+    for pixel in image.pixels() {
+        roughness.push((pixel[1] as f32 * roughness_factor).min(255.0) as u8);
+        metallic.push((pixel[2] as f32 * metallic_factor).min(255.0) as u8);
+    }
+
+    let kind = TextureKind::Rectangle { width, height };
+    Some((
+        Texture::from_bytes(kind, TexturePixelKind::R8, metallic, true)?,
+        Texture::from_bytes(kind, TexturePixelKind::R8, roughness, true)?,
+    ))
+}
+
+/// Creates 1x1 texture that holds a constant value, it is used to pass scalar factors to the shader
+/// when there's no texture for them.
+fn make_constant_texture(value: f32) -> Option<Texture> {
+    Texture::from_bytes(
+        TextureKind::Rectangle {
+            width: 1,
+            height: 1,
+        },
+        TexturePixelKind::R8,
+        vec![(value.clamp(0.0, 1.0) * 255.0) as u8],
+        true,
+    )
+}
+
+fn set_material_property(material: &mut Material, name: &str, value: PropertyValue) {
+    if let Err(e) = material.set_property(&ImmutableString::new(name), value) {
+        Log::writeln(
+            MessageKind::Error,
+            format!(
+                "Unable to set material property {} for glTF material! Reason: {:?}",
+                name, e
+            ),
+        );
+    }
+}
+
+fn set_material_texture(
+    material: &mut Material,
+    name: &str,
+    texture: Option<Texture>,
+    fallback: SamplerFallback,
+) {
+    if let Some(texture) = texture {
+        set_material_property(
+            material,
+            name,
+            PropertyValue::Sampler {
+                value: Some(texture),
+                fallback,
+            },
+        );
+    }
+}
+
+async fn convert_material(
+    material: ::gltf::Material<'_>,
+    buffers: &[Vec<u8>],
+    resource_manager: &ResourceManager,
+    model_path: &Path,
+    model_import_options: &ModelImportOptions,
+) -> SharedMaterial {
+    let mut result = if material.double_sided() {
+        Material::from_shader(Shader::standard_twosides(), None)
+    } else {
+        Material::standard()
+    };
+
+    let pbr = material.pbr_metallic_roughness();
+
+    set_material_property(
+        &mut result,
+        "diffuseColor",
+        PropertyValue::Color(Color::from(Vector4::from(pbr.base_color_factor()))),
+    );
+
+    if let Some(info) = pbr.base_color_texture() {
+        if let Some(location) = locate_image(
+            &info.texture().source(),
+            buffers,
+            model_path,
+            model_import_options,
+        )
+        .await
+        {
+            set_material_texture(
+                &mut result,
+                "diffuseTexture",
+                load_texture(location, resource_manager),
+                SamplerFallback::White,
+            );
+        }
+    }
+
+    if let Some(info) = material.normal_texture() {
+        if let Some(location) = locate_image(
+            &info.texture().source(),
+            buffers,
+            model_path,
+            model_import_options,
+        )
+        .await
+        {
+            set_material_texture(
+                &mut result,
+                "normalTexture",
+                load_texture(location, resource_manager),
+                SamplerFallback::Normal,
+            );
+        }
+    }
+
+    if let Some(info) = material.occlusion_texture() {
+        if let Some(location) = locate_image(
+            &info.texture().source(),
+            buffers,
+            model_path,
+            model_import_options,
+        )
+        .await
+        {
+            set_material_texture(
+                &mut result,
+                "aoTexture",
+                load_texture(location, resource_manager),
+                SamplerFallback::White,
+            );
+        }
+    }
+
+    if let Some(info) = material.emissive_texture() {
+        if let Some(location) = locate_image(
+            &info.texture().source(),
+            buffers,
+            model_path,
+            model_import_options,
+        )
+        .await
+        {
+            set_material_texture(
+                &mut result,
+                "emissionTexture",
+                load_texture(location, resource_manager),
+                SamplerFallback::Black,
+            );
+        }
+        set_material_property(
+            &mut result,
+            "emissionStrength",
+            PropertyValue::Vector3(Vector3::from(material.emissive_factor())),
+        );
+    }
+
+    let metallic_factor = pbr.metallic_factor();
+    let roughness_factor = pbr.roughness_factor();
+    let mut metallic_roughness = None;
+    if let Some(info) = pbr.metallic_roughness_texture() {
+        if let Some(location) = locate_image(
+            &info.texture().source(),
+            buffers,
+            model_path,
+            model_import_options,
+        )
+        .await
+        {
+            metallic_roughness =
+                split_metallic_roughness(location, metallic_factor, roughness_factor).await;
+        }
+    }
+    let (metallic, roughness) = match metallic_roughness {
+        Some((metallic, roughness)) => (Some(metallic), Some(roughness)),
+        None => (
+            make_constant_texture(metallic_factor),
+            make_constant_texture(roughness_factor),
+        ),
+    };
+    set_material_texture(
+        &mut result,
+        "metallicTexture",
+        metallic,
+        SamplerFallback::Black,
+    );
+    set_material_texture(
+        &mut result,
+        "roughnessTexture",
+        roughness,
+        SamplerFallback::White,
+    );
+
+    SharedMaterial::new(result)
+}
+
+/// Converted primitive of a glTF mesh. Primitives could be shared across multiple nodes, so the
+/// data is converted only once.
+#[derive(Clone)]
+struct ConvertedPrimitive {
+    data: SurfaceSharedData,
+    material: Option<usize>,
+    // Indices of joints (in the list of joints of a skin) that are used by the primitive. Bone
+    // indices in vertices point into this array.
+    used_joints: Vec<usize>,
+}
+
+/// Vertex attributes of a glTF primitive.
+struct PrimitiveAttributes {
+    positions: Vec<Vector3<f32>>,
+    normals: Option<Vec<Vector3<f32>>>,
+    tangents: Option<Vec<Vector4<f32>>>,
+    uvs: Option<Vec<Vector2<f32>>>,
+    morph_targets: Vec<BlendShapeTarget>,
+}
+
+impl PrimitiveAttributes {
+    fn tex_coord(&self, i: usize) -> Vector2<f32> {
+        self.uvs
+            .as_ref()
+            .and_then(|uvs| uvs.get(i).cloned())
+            .unwrap_or_default()
+    }
+
+    fn normal(&self, i: usize) -> Vector3<f32> {
+        self.normals
+            .as_ref()
+            .and_then(|normals| normals.get(i).cloned())
+            .unwrap_or_else(Vector3::y)
+    }
+
+    fn tangent(&self, i: usize) -> Vector4<f32> {
+        self.tangents
+            .as_ref()
+            .and_then(|tangents| tangents.get(i).cloned())
+            .unwrap_or_else(|| Vector4::new(0.0, 1.0, 0.0, 1.0))
+    }
+
+    // Morph targets store offsets for every vertex of the primitive, pick the offsets of the
+    // vertices that were taken to a surface.
+    fn morph_targets(&self, sources: &[usize]) -> Vec<BlendShapeTarget> {
+        fn pick(offsets: &[Vector3<f32>], sources: &[usize]) -> Vec<Vector3<f32>> {
+            if offsets.is_empty() {
+                Default::default()
+            } else {
+                sources
+                    .iter()
+                    .map(|&i| offsets.get(i).cloned().unwrap_or_default())
+                    .collect()
+            }
+        }
+
+        self.morph_targets
+            .iter()
+            .map(|target| BlendShapeTarget {
+                name: target.name.clone(),
+                positions: pick(&target.positions, sources),
+                normals: pick(&target.normals, sources),
+                tangents: pick(&target.tangents, sources),
+            })
+            .collect()
+    }
+}
+
+/// A part of a skinned primitive that is influenced by at most [`BONE_MATRICES_COUNT`] joints, so
+/// it can be rendered as a single surface.
+#[derive(Default)]
+struct BonePalette {
+    triangles: Vec<TriangleDefinition>,
+    // Indices of joints (in the list of joints of a skin) used by the triangles of the palette.
+    joints: Vec<usize>,
+    joint_to_bone: FxHashMap<usize, usize>,
+}
+
+/// Splits triangles of a skinned primitive into groups, such that each group is influenced by at
+/// most [`BONE_MATRICES_COUNT`] joints. Triangles are taken in order and a new group is started when
+/// a triangle does not fit in the current one.
+fn split_by_bone_palette(
+    triangles: &[TriangleDefinition],
+    joints: &[[u16; 4]],
+    weights: &[[f32; 4]],
+) -> Vec<BonePalette> {
+    let mut palettes = vec![BonePalette::default()];
+    for triangle in triangles {
+        let mut triangle_joints = Vec::new();
+        for &vertex in triangle.0.iter() {
+            if let (Some(vertex_joints), Some(vertex_weights)) =
+                (joints.get(vertex as usize), weights.get(vertex as usize))
+            {
+                for (joint, weight) in vertex_joints.iter().zip(vertex_weights.iter()) {
+                    let joint = *joint as usize;
+                    if *weight > 0.0 && !triangle_joints.contains(&joint) {
+                        triangle_joints.push(joint);
+                    }
+                }
+            }
+        }
+
+        let mut palette = palettes.last_mut().unwrap();
+        let new_joints = triangle_joints
+            .iter()
+            .filter(|joint| !palette.joint_to_bone.contains_key(joint))
+            .count();
+        if palette.joints.len() + new_joints > BONE_MATRICES_COUNT && !palette.triangles.is_empty()
+        {
+            palettes.push(BonePalette::default());
+            palette = palettes.last_mut().unwrap();
+        }
+
+        for joint in triangle_joints {
+            let joints = &mut palette.joints;
+            palette.joint_to_bone.entry(joint).or_insert_with(|| {
+                joints.push(joint);
+                joints.len() - 1
+            });
+        }
+        palette.triangles.push(triangle.clone());
+    }
+    palettes
+}
+
+fn convert_primitive(
+    primitive: &::gltf::Primitive<'_>,
+    buffers: &[Vec<u8>],
+) -> Result<Vec<ConvertedPrimitive>, GltfError> {
+    let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(|b| b.as_slice()));
+
+    let attributes = PrimitiveAttributes {
+        positions: reader
+            .read_positions()
+            .ok_or(GltfError::MissingPositions)?
+            .map(Vector3::from)
+            .collect(),
+        normals: reader
+            .read_normals()
+            .map(|it| it.map(Vector3::from).collect()),
+        tangents: reader
+            .read_tangents()
+            .map(|it| it.map(Vector4::from).collect()),
+        uvs: reader
+            .read_tex_coords(0)
+            .map(|it| it.into_f32().map(Vector2::from).collect()),
+        morph_targets: reader
+            .read_morph_targets()
+            .enumerate()
+            .map(|(index, (positions, normals, tangents))| BlendShapeTarget {
+                name: morph_target_name(index),
+                positions: positions
+                    .map(|it| it.map(Vector3::from).collect())
+                    .unwrap_or_default(),
+                normals: normals
+                    .map(|it| it.map(Vector3::from).collect())
+                    .unwrap_or_default(),
+                tangents: tangents
+                    .map(|it| it.map(Vector3::from).collect())
+                    .unwrap_or_default(),
+            })
+            .collect(),
+    };
+    let joints = reader
+        .read_joints(0)
+        .map(|it| it.into_u16().collect::<Vec<_>>());
+    let weights = reader
+        .read_weights(0)
+        .map(|it| it.into_f32().collect::<Vec<_>>());
+
+    let vertex_count = attributes.positions.len();
+
+    let indices = match reader.read_indices() {
+        Some(indices) => indices.into_u32().collect::<Vec<_>>(),
+        None => (0..vertex_count as u32).collect(),
+    };
+
+    let mut triangles = match primitive.mode() {
+        Mode::Triangles => indices
+            .chunks_exact(3)
+            .map(|t| TriangleDefinition([t[0], t[1], t[2]]))
+            .collect::<Vec<_>>(),
+        Mode::TriangleStrip => (2..indices.len())
+            .map(|i| {
+                // Keep consistent winding for every odd triangle of the strip.
+                if i % 2 == 0 {
+                    TriangleDefinition([indices[i - 2], indices[i - 1], indices[i]])
+                } else {
+                    TriangleDefinition([indices[i - 1], indices[i - 2], indices[i]])
+                }
+            })
+            .collect(),
+        Mode::TriangleFan => (2..indices.len())
+            .map(|i| TriangleDefinition([indices[0], indices[i - 1], indices[i]]))
+            .collect(),
+        mode => {
+            Log::writeln(
+                MessageKind::Warning,
+                format!(
+                    "glTF: Primitive mode {:?} is not supported, skipping.",
+                    mode
+                ),
+            );
+            return Ok(Vec::new());
+        }
+    };
+
+    // Some exporters may produce invalid indices, filter such triangles out so they won't blow
+    // up the renderer.
+    triangles.retain(|t| t.0.iter().all(|&i| (i as usize) < vertex_count));
+
+    let mut surfaces = Vec::new();
+    if let (Some(joints), Some(weights)) = (joints.as_ref(), weights.as_ref()) {
+        // The renderer supports a limited amount of bones per surface, so a primitive with
+        // more joints is split into multiple surfaces.
+        for palette in split_by_bone_palette(&triangles, joints, weights) {
+            // Vertices are shared between triangles of different palettes, so each palette
+            // gets its own copy of the vertices it uses.
+            let mut vertex_map = FxHashMap::default();
+            let mut sources = Vec::new();
+            let triangles = palette
+                .triangles
+                .iter()
+                .map(|triangle| {
+                    TriangleDefinition(triangle.0.map(|index| {
+                        *vertex_map.entry(index).or_insert_with(|| {
+                            sources.push(index as usize);
+                            sources.len() as u32 - 1
+                        })
+                    }))
+                })
+                .collect::<Vec<_>>();
+
+            let vertices = sources
+                .iter()
+                .map(|&i| {
+                    let mut bone_weights = [0.0; 4];
+                    let mut bone_indices = [0u8; 4];
+                    if let (Some(vertex_joints), Some(vertex_weights)) =
+                        (joints.get(i), weights.get(i))
+                    {
+                        for k in 0..4 {
+                            if vertex_weights[k] > 0.0 {
+                                bone_weights[k] = vertex_weights[k];
+                                // Palette holds at most BONE_MATRICES_COUNT joints, so the index
+                                // always fits.
+                                bone_indices[k] =
+                                    palette.joint_to_bone[&(vertex_joints[k] as usize)] as u8;
+                            }
+                        }
+                    }
+                    AnimatedVertex {
+                        position: attributes.positions[i],
+                        tex_coord: attributes.tex_coord(i),
+                        normal: attributes.normal(i),
+                        tangent: attributes.tangent(i),
+                        bone_weights,
+                        bone_indices,
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let mut data = SurfaceData::new(
+                VertexBuffer::new(vertices.len(), AnimatedVertex::layout(), vertices).unwrap(),
+                TriangleBuffer::new(triangles),
+                false,
+            );
+            data.blend_shape_targets = attributes.morph_targets(&sources);
+            surfaces.push((data, palette.joints));
+        }
+    } else {
+        let vertices = (0..vertex_count)
+            .map(|i| StaticVertex {
+                position: attributes.positions[i],
+                tex_coord: attributes.tex_coord(i),
+                normal: attributes.normal(i),
+                tangent: attributes.tangent(i),
+            })
+            .collect::<Vec<_>>();
+        let mut data = SurfaceData::new(
+            VertexBuffer::new(vertices.len(), StaticVertex::layout(), vertices).unwrap(),
+            TriangleBuffer::new(triangles),
+            false,
+        );
+        data.blend_shape_targets = attributes.morph_targets(&(0..vertex_count).collect::<Vec<_>>());
+        surfaces.push((data, Vec::new()));
+    }
+
+    Ok(surfaces
+        .into_iter()
+        .map(|(mut data, used_joints)| {
+            if attributes.normals.is_none() {
+                data.calculate_normals().unwrap();
+            }
+            if attributes.tangents.is_none() {
+                data.calculate_tangents().unwrap();
+            }
+            if attributes
+                .morph_targets
+                .iter()
+                .any(|target| target.tangents.is_empty())
+            {
+                Log::verify(data.calculate_blend_shape_tangents());
+            }
+
+            ConvertedPrimitive {
+                data: SurfaceSharedData::new(data),
+                material: primitive.material().index(),
+                used_joints,
+            }
+        })
+        .collect())
+}
+
+/// glTF does not have names for morph targets (some exporters put them in `extras`, which are not
+/// loaded), so names are generated from indices of the targets. Every primitive of a mesh has the
+/// same set of targets.
+fn morph_target_name(index: usize) -> String {
+    format!("MorphTarget{}", index)
+}
+
+fn morph_target_count(mesh: &::gltf::Mesh<'_>) -> usize {
+    mesh.primitives()
+        .next()
+        .map(|primitive| primitive.morph_targets().len())
+        .unwrap_or_default()
+}
+
+fn node_name(node: &::gltf::Node<'_>) -> String {
+    node.name()
+        .map(|name| name.to_owned())
+        .unwrap_or_else(|| format!("Node{}", node.index()))
+}
+
+fn convert_node_to_base(node: &::gltf::Node<'_>, inv_bind_pose: Matrix4<f32>) -> BaseBuilder {
+    let name = node_name(node);
+
+    // Use the name of the node to generate instance id, the same way as FBX importer does. glTF
+    // does not have stable unique ids for its nodes either, indices are changed if an artist adds
+    // or removes a node.
+    let mut hasher = DefaultHasher::new();
+    name.hash(&mut hasher);
+    let hash = hasher.finish();
+    let instance_id = InstanceId(Uuid::from_u64_pair(hash, hash));
+
+    let (translation, rotation, scale) = node.transform().decomposed();
+
+    BaseBuilder::new()
+        .with_inv_bind_pose_transform(inv_bind_pose)
+        .with_name(name)
+        .with_instance_id(instance_id)
+        .with_local_transform(
+            TransformBuilder::new()
+                .with_local_position(Vector3::from(translation))
+                .with_local_rotation(quat_from_gltf(rotation))
+                .with_local_scale(Vector3::from(scale))
+                .build(),
+        )
+}
+
+fn quat_from_gltf(q: [f32; 4]) -> UnitQuaternion<f32> {
+    // glTF stores quaternions in (x, y, z, w) order.
+    UnitQuaternion::from_quaternion(Quaternion::new(q[3], q[0], q[1], q[2]))
+}
+
+/// Converts a sequence of rotations to Euler angles, which is the representation used by rotation
+/// tracks. Angles are unwrapped, so interpolation between adjacent keys takes the shortest path.
+fn rotations_to_euler(rotations: &[UnitQuaternion<f32>]) -> Vec<Vector3<f32>> {
+    let mut result: Vec<Vector3<f32>> = Vec::with_capacity(rotations.len());
+    for rotation in rotations {
+        let (x, y, z) = rotation.euler_angles();
+        let mut angles = Vector3::new(x, y, z);
+        if let Some(prev) = result.last() {
+            for i in 0..3 {
+                let delta = angles[i] - prev[i];
+                angles[i] -= (delta / std::f32::consts::TAU).round() * std::f32::consts::TAU;
+            }
+        }
+        result.push(angles);
+    }
+    result
+}
+
+/// Cubic spline samplers store a triple (in-tangent, value, out-tangent) per key, take the values
+/// only. Curves of the engine will use linear interpolation between them.
+fn key_values<T: Clone>(values: Vec<T>, interpolation: Interpolation) -> Vec<T> {
+    if let Interpolation::CubicSpline = interpolation {
+        values.chunks_exact(3).map(|c| c[1].clone()).collect()
+    } else {
+        values
+    }
+}
+
+fn convert_animation(
+    animation: &::gltf::Animation<'_>,
+    buffers: &[Vec<u8>],
+    node_map: &[Handle<Node>],
+) -> Animation {
+    let mut result = Animation::default();
+    result.set_name(
+        animation
+            .name()
+            .map(|name| name.to_owned())
+            .unwrap_or_else(|| format!("Animation{}", animation.index())),
+    );
+
+    for channel in animation.channels() {
+        let node_handle = match node_map.get(channel.target().node().index()) {
+            Some(handle) => *handle,
+            None => continue,
+        };
+
+        let reader = channel.reader(|buffer| buffers.get(buffer.index()).map(|b| b.as_slice()));
+
+        let times = match reader.read_inputs() {
+            Some(inputs) => inputs.collect::<Vec<_>>(),
+            None => continue,
+        };
+
+        let interpolation = channel.sampler().interpolation();
+        let key_kind = match interpolation {
+            Interpolation::Step => CurveKeyKind::Constant,
+            Interpolation::Linear | Interpolation::CubicSpline => CurveKeyKind::Linear,
+        };
+
+        if let Some(ReadOutputs::MorphTargetWeights(weights)) = reader.read_outputs() {
+            let target_count = channel
+                .target()
+                .node()
+                .mesh()
+                .map(|mesh| morph_target_count(&mesh))
+                .unwrap_or_default();
+            if target_count == 0 {
+                continue;
+            }
+
+            // Weights of all targets are stored sequentially for each key.
+            let weights = key_values(
+                weights
+                    .into_f32()
+                    .collect::<Vec<_>>()
+                    .chunks_exact(target_count)
+                    .map(|chunk| chunk.to_vec())
+                    .collect(),
+                interpolation,
+            );
+
+            for index in 0..target_count {
+                let mut track = Track::new(
+                    TrackDataContainer::new(TrackValueKind::Real),
+                    ValueBinding::Property {
+                        name: format!("blend_shapes[{}].weight", index),
+                        value_type: ValueType::F32,
+                    },
+                );
+                track.set_target(node_handle);
+                for (time, key_weights) in times.iter().zip(weights.iter()) {
+                    track.data_container_mut().curves_mut()[0].add_key(CurveKey::new(
+                        *time,
+                        key_weights[index],
+                        key_kind.clone(),
+                    ));
+                }
+                result.add_track(track);
+            }
+
+            continue;
+        }
+
+        let (mut track, values) = match reader.read_outputs() {
+            Some(ReadOutputs::Translations(translations)) => (
+                Track::new_position(),
+                key_values(translations.map(Vector3::from).collect(), interpolation),
+            ),
+            Some(ReadOutputs::Scales(scales)) => (
+                Track::new_scale(),
+                key_values(scales.map(Vector3::from).collect(), interpolation),
+            ),
+            Some(ReadOutputs::Rotations(rotations)) => (
+                Track::new_rotation(),
+                rotations_to_euler(&key_values(
+                    rotations.into_f32().map(quat_from_gltf).collect(),
+                    interpolation,
+                )),
+            ),
+            // Morph target weights are handled above.
+            Some(ReadOutputs::MorphTargetWeights(_)) | None => continue,
+        };
+
+        track.set_target(node_handle);
+
+        let curves = track.data_container_mut().curves_mut();
+        for (time, value) in times.iter().zip(values.iter()) {
+            for (curve, component) in curves.iter_mut().zip(value.iter()) {
+                curve.add_key(CurveKey::new(*time, *component, key_kind.clone()));
+            }
+        }
+
+        result.add_track(track);
+    }
+
+    result.fit_length_to_content();
+
+    result
+}
+
+async fn convert(
+    document: &Document,
+    buffers: &[Vec<u8>],
+    resource_manager: ResourceManager,
+    scene: &mut Scene,
+    model_path: &Path,
+    model_import_options: &ModelImportOptions,
+) -> Result<(), GltfError> {
+    // Inverse bind matrices are stored per skin, but the engine stores them in bones directly.
+    let mut inv_bind_poses = FxHashMap::default();
+    for skin in document.skins() {
+        let reader = skin.reader(|buffer| buffers.get(buffer.index()).map(|b| b.as_slice()));
+        if let Some(matrices) = reader.read_inverse_bind_matrices() {
+            for (joint, matrix) in skin.joints().zip(matrices) {
+                inv_bind_poses
+                    .entry(joint.index())
+                    .or_insert_with(|| Matrix4::from(matrix));
+            }
+        }
+    }
+
+    let mut materials = Vec::new();
+    for material in document.materials() {
+        materials.push(
+            convert_material(
+                material,
+                buffers,
+                &resource_manager,
+                model_path,
+                model_import_options,
+            )
+            .await,
+        );
+    }
+
+    let mut meshes = Vec::new();
+    for mesh in document.meshes() {
+        let mut primitives = Vec::new();
+        for primitive in mesh.primitives() {
+            primitives.extend(convert_primitive(&primitive, buffers)?);
+        }
+        meshes.push(primitives);
+    }
+
+    // Create nodes first, hierarchy and bones will be resolved later when every node will have
+    // its handle.
+    let mut node_map = Vec::new();
+    for node in document.nodes() {
+        let base = convert_node_to_base(
+            &node,
+            inv_bind_poses
+                .get(&node.index())
+                .cloned()
+                .unwrap_or_else(Matrix4::identity),
+        );
+
+        let handle = if let Some(mesh) = node.mesh() {
+            let surfaces = meshes[mesh.index()]
+                .iter()
+                .map(|primitive| {
+                    let mut surface = Surface::new(primitive.data.clone());
+                    match primitive.material.and_then(|index| materials.get(index)) {
+                        Some(material) => surface.set_material(material.clone()),
+                        None => surface.set_material(SharedMaterial::new(Material::standard())),
+                    }
+                    surface
+                })
+                .collect();
+            // Weights of a node override default weights of its mesh.
+            let weights = node
+                .weights()
+                .or_else(|| mesh.weights())
+                .unwrap_or_default();
+            let blend_shapes = (0..morph_target_count(&mesh))
+                .map(|index| {
+                    BlendShape::new(
+                        morph_target_name(index),
+                        weights.get(index).cloned().unwrap_or_default(),
+                    )
+                })
+                .collect();
+            MeshBuilder::new(base)
+                .with_surfaces(surfaces)
+                .with_blend_shapes(blend_shapes)
+                .build(&mut scene.graph)
+        } else {
+            PivotBuilder::new(base).build(&mut scene.graph)
+        };
+
+        node_map.push(handle);
+    }
+
+    // Link according to hierarchy.
+    for node in document.nodes() {
+        for child in node.children() {
+            scene
+                .graph
+                .link_nodes(node_map[child.index()], node_map[node.index()]);
+        }
+    }
+
+    // Fill bones of skinned surfaces.
+    for node in document.nodes() {
+        if let (Some(mesh), Some(skin)) = (node.mesh(), node.skin()) {
+            let joints = skin
+                .joints()
+                .map(|joint| node_map[joint.index()])
+                .collect::<Vec<_>>();
+            if let Some(mesh_node) = scene.graph[node_map[node.index()]].cast_mut::<Mesh>() {
+                for (surface, primitive) in mesh_node
+                    .surfaces_mut()
+                    .iter_mut()
+                    .zip(meshes[mesh.index()].iter())
+                {
+                    surface.bones.set_value_silent(
+                        primitive
+                            .used_joints
+                            .iter()
+                            .filter_map(|joint| joints.get(*joint).cloned())
+                            .collect(),
+                    );
+                }
+            }
+        }
+    }
+
+    scene.graph.update_hierarchical_data();
+
+    let mut animations_container = AnimationContainer::new();
+    for (index, animation) in document.animations().enumerate() {
+        let mut animation = convert_animation(&animation, buffers, &node_map);
+        // Every animation of a document usually animates the same skeleton, so enable only the first
+        // one to prevent them from fighting each other.
+        animation.set_enabled(index == 0);
+        animations_container.add(animation);
+    }
+
+    // Do not create animation player if there's no animation content.
+    if animations_container.alive_count() > 0 {
+        AnimationPlayerBuilder::new(BaseBuilder::new().with_name("AnimationPlayer"))
+            .with_animations(animations_container)
+            .build(&mut scene.graph);
+    }
+
+    Ok(())
+}
+
+/// Tries to load and convert glTF 2.0 (`.gltf` or `.glb`) from given path.
+///
+/// Normally you should never use this method, use resource manager to load models.
+pub async fn load_to_scene<P: AsRef<Path>>(
+    scene: &mut Scene,
+    resource_manager: ResourceManager,
+    path: P,
+    model_import_options: &ModelImportOptions,
+) -> Result<(), GltfError> {
+    let start_time = Instant::now();
+
+    Log::writeln(
+        MessageKind::Information,
+        format!("Trying to load {:?}", path.as_ref()),
+    );
+
+    let now = Instant::now();
+    let data = io::load_file(path.as_ref()).await?;
+    let gltf = Gltf::from_slice(&data)?;
+    let buffers = load_buffers(&gltf, path.as_ref()).await?;
+    let parsing_time = now.elapsed().as_millis();
+
+    let now = Instant::now();
+    convert(
+        &gltf.document,
+        &buffers,
+        resource_manager,
+        scene,
+        path.as_ref(),
+        model_import_options,
+    )
+    .await?;
+    let conversion_time = now.elapsed().as_millis();
+
+    Log::writeln(
+        MessageKind::Information,
+        format!(
+            "glTF {:?} loaded in {} ms\n\t- Parsing - {} ms\n\t- Conversion - {} ms",
+            path.as_ref(),
+            start_time.elapsed().as_millis(),
+            parsing_time,
+            conversion_time
+        ),
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::value::ValueBinding,
+        core::{
+            algebra::{Matrix4, UnitQuaternion, Vector3, Vector4},
+            color::Color,
+            futures::executor::block_on,
+            math::TriangleDefinition,
+            sstorage::ImmutableString,
+        },
+        engine::resource_manager::ResourceManager,
+        material::PropertyValue,
+        renderer::batch::BONE_MATRICES_COUNT,
+        resource::gltf::{
+            load_to_scene, percent_decode, rotations_to_euler, split_by_bone_palette,
+        },
+        scene::{
+            animation::AnimationPlayer,
+            mesh::{
+                buffer::{VertexAttributeUsage, VertexReadTrait},
+                BlendShape, Mesh,
+            },
+            Scene,
+        },
+    };
+    use std::{env, path::PathBuf};
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("my%20texture.png"), "my texture.png");
+        assert_eq!(percent_decode("plain.png"), "plain.png");
+        assert_eq!(percent_decode("broken%2"), "broken%2");
+    }
+
+    #[test]
+    fn test_rotations_to_euler_unwrapping() {
+        let rotations = [
+            UnitQuaternion::from_axis_angle(&Vector3::x_axis(), 170.0f32.to_radians()),
+            UnitQuaternion::from_axis_angle(&Vector3::x_axis(), -170.0f32.to_radians()),
+        ];
+        let angles = rotations_to_euler(&rotations);
+        // -170 degrees must be unwrapped to 190 degrees, so interpolation goes through 180.
+        assert!((angles[1].x - 190.0f32.to_radians()).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_split_by_bone_palette() {
+        // Every vertex is influenced by its own joint.
+        let triangle_count = 40;
+        let triangles = (0..triangle_count)
+            .map(|i| TriangleDefinition([i * 3, i * 3 + 1, i * 3 + 2]))
+            .collect::<Vec<_>>();
+        let joints = (0..triangle_count as u16 * 3)
+            .map(|i| [i, 0, 0, 0])
+            .collect::<Vec<_>>();
+        let weights = vec![[1.0, 0.0, 0.0, 0.0]; joints.len()];
+
+        let palettes = split_by_bone_palette(&triangles, &joints, &weights);
+        assert_eq!(palettes.len(), 2);
+        assert_eq!(
+            palettes.iter().map(|p| p.triangles.len()).sum::<usize>(),
+            triangles.len()
+        );
+        for palette in palettes.iter() {
+            assert!(palette.joints.len() <= BONE_MATRICES_COUNT);
+            for triangle in palette.triangles.iter() {
+                for &vertex in triangle.0.iter() {
+                    let joint = joints[vertex as usize][0] as usize;
+                    assert_eq!(palette.joints[palette.joint_to_bone[&joint]], joint);
+                }
+            }
+        }
+
+        // Joints with zero weight do not occupy the palette.
+        let weights = vec![[0.0; 4]; joints.len()];
+        let palettes = split_by_bone_palette(&triangles, &joints, &weights);
+        assert_eq!(palettes.len(), 1);
+        assert!(palettes[0].joints.is_empty());
+    }
+
+    fn load(file_name: &str) -> Scene {
+        let path = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap())
+            .join("test_data/gltf")
+            .join(file_name);
+        let mut scene = Scene::new();
+        block_on(load_to_scene(
+            &mut scene,
+            ResourceManager::new(Default::default()),
+            path,
+            &Default::default(),
+        ))
+        .unwrap();
+        scene
+    }
+
+    fn check_skinned_quad(scene: &Scene) {
+        let graph = &scene.graph;
+        let (mesh, mesh_node) = graph.find_by_name_from_root("Mesh").unwrap();
+        let (root, _) = graph.find_by_name_from_root("Root").unwrap();
+        let (bone, bone_node) = graph.find_by_name_from_root("Bone").unwrap();
+        assert_eq!(bone_node.parent(), root);
+        assert_eq!(
+            bone_node.inv_bind_pose_transform(),
+            Matrix4::new_translation(&Vector3::new(0.0, -1.0, 0.0))
+        );
+
+        // Mesh.
+        let mesh_node = mesh_node.cast::<Mesh>().unwrap();
+        assert_eq!(mesh_node.surfaces().len(), 1);
+        let surface = &mesh_node.surfaces()[0];
+        let data = surface.data();
+        let data = data.lock();
+        assert_eq!(data.vertex_buffer.vertex_count(), 4);
+        assert_eq!(data.geometry_buffer.len(), 2);
+        let positions = data
+            .vertex_buffer
+            .iter()
+            .map(|v| v.read_3_f32(VertexAttributeUsage::Position).unwrap())
+            .collect::<Vec<_>>();
+        for position in [
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(1.0, 1.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+        ] {
+            assert!(positions.contains(&position));
+        }
+
+        // Morph targets.
+        assert_eq!(data.blend_shape_targets.len(), 1);
+        assert_eq!(data.blend_shape_targets[0].positions.len(), 4);
+        assert!(data.blend_shape_targets[0]
+            .positions
+            .iter()
+            .all(|p| *p == Vector3::new(0.0, 0.0, 1.0)));
+        assert_eq!(
+            mesh_node.blend_shapes(),
+            &[BlendShape::new(&data.blend_shape_targets[0].name, 0.5)]
+        );
+
+        // Skin.
+        assert!(data
+            .vertex_buffer
+            .has_attribute(VertexAttributeUsage::BoneWeight));
+        assert_eq!(surface.bones(), &[root, bone]);
+        for vertex in data.vertex_buffer.iter() {
+            let position = vertex.read_3_f32(VertexAttributeUsage::Position).unwrap();
+            let weights = vertex.read_4_f32(VertexAttributeUsage::BoneWeight).unwrap();
+            let indices = vertex.read_4_u8(VertexAttributeUsage::BoneIndices).unwrap();
+            if position.y < 0.5 {
+                assert_eq!(weights, Vector4::new(1.0, 0.0, 0.0, 0.0));
+                assert_eq!(indices[0], 0);
+            } else {
+                assert_eq!(weights, Vector4::new(0.5, 0.5, 0.0, 0.0));
+                assert_eq!((indices[0], indices[1]), (0, 1));
+            }
+        }
+
+        // Material.
+        let material = surface.material().lock();
+        assert!(matches!(
+            material.property_ref(&ImmutableString::new("diffuseColor")),
+            Some(PropertyValue::Color(color)) if *color == Color::from(Vector4::new(1.0, 0.0, 0.0, 1.0))
+        ));
+
+        // Animation.
+        let player = graph
+            .linear_iter()
+            .find_map(|n| n.cast::<AnimationPlayer>())
+            .unwrap();
+        let animation = player.animations().iter().next().unwrap();
+        assert_eq!(animation.name(), "Wave");
+        assert!(animation.tracks().iter().any(|t| t.target() == mesh
+            && matches!(t.binding(), ValueBinding::Property { name, .. } if name == "blend_shapes[0].weight")));
+        assert!(animation
+            .tracks()
+            .iter()
+            .any(|t| t.target() == bone && matches!(t.binding(), ValueBinding::Position)));
+    }
+
+    #[test]
+    fn test_load_gltf() {
+        check_skinned_quad(&load("skinned_quad.gltf"));
+    }
+
+    #[test]
+    fn test_load_glb() {
+        check_skinned_quad(&load("skinned_quad.glb"));
+    }
+}
@@ -15,14 +15,16 @@
 //!
 //! # Supported formats
 //!
-//! Currently only FBX (common format in game industry for storing complex 3d models)
-//! and RGS (native Fyroxed format) formats are supported.
+//! Currently FBX (common format in game industry for storing complex 3d models), glTF 2.0
+//! (both `.gltf` and `.glb` flavours), Wavefront OBJ (static meshes only) and RGS (native
+//! Fyroxed format) formats are supported.
 
 use crate::{
     animation::Animation,
     asset::{define_new_resource, Resource, ResourceData},
     core::{
         algebra::{UnitQuaternion, Vector3},
+        io,
         pool::Handle,
         reflect::prelude::*,
         variable::reset_inheritable_properties,
@@ -32,7 +34,11 @@ use crate::{
         resource_manager::{options::ImportOptions, ResourceManager},
         SerializationContext,
     },
-    resource::fbx::{self, error::FbxError},
+    resource::{
+        fbx::{self, error::FbxError},
+        gltf::{self, error::GltfError},
+        obj::{self, error::ObjError},
+    },
     scene::{
         animation::AnimationPlayer,
         graph::{map::NodeHandleMap, Graph},
@@ -49,6 +55,7 @@ use std::{
     sync::Arc,
 };
 use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+use walkdir::WalkDir;
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 #[repr(u32)]
@@ -370,6 +377,45 @@ impl MaterialSearchOptions {
     pub fn materials_directory<P: AsRef<Path>>(path: P) -> Self {
         Self::MaterialsDirectory(path.as_ref().to_path_buf())
     }
+
+    /// Tries to find a texture with the file name taken from `texture_path` using the current search
+    /// method. `model_path` is a path of the model resource that references the texture, it is used
+    /// as a starting point for [`MaterialSearchOptions::RecursiveUp`] search.
+    pub(crate) async fn find_texture_path(
+        &self,
+        model_path: &Path,
+        texture_path: &Path,
+    ) -> Option<PathBuf> {
+        let filename = texture_path.file_name()?;
+        match self {
+            MaterialSearchOptions::MaterialsDirectory(ref directory) => {
+                Some(directory.join(filename))
+            }
+            MaterialSearchOptions::RecursiveUp => {
+                let mut path = model_path.to_owned();
+                while let Some(parent) = path.parent() {
+                    let candidate = parent.join(filename);
+                    if io::exists(&candidate).await {
+                        return Some(candidate);
+                    }
+                    path.pop();
+                }
+                None
+            }
+            MaterialSearchOptions::WorkingDirectory => {
+                for dir in WalkDir::new(".").into_iter().flatten() {
+                    if dir.path().is_dir() {
+                        let candidate = dir.path().join(filename);
+                        if candidate.exists() {
+                            return Some(candidate);
+                        }
+                    }
+                }
+                None
+            }
+            MaterialSearchOptions::UsePathDirectly => Some(texture_path.to_owned()),
+        }
+    }
 }
 
 /// A set of options that will be applied to a model resource when loading it from external source.
@@ -406,6 +452,10 @@ pub enum ModelLoadError {
     NotSupported(String),
     /// An error occurred while loading FBX file.
     Fbx(FbxError),
+    /// An error occurred while loading glTF file.
+    Gltf(GltfError),
+    /// An error occurred while loading OBJ file.
+    Obj(ObjError),
 }
 
 impl Display for ModelLoadError {
@@ -418,6 +468,8 @@ impl Display for ModelLoadError {
                 write!(f, "Model format is not supported: {v}")
             }
             ModelLoadError::Fbx(v) => v.fmt(f),
+            ModelLoadError::Gltf(v) => v.fmt(f),
+            ModelLoadError::Obj(v) => v.fmt(f),
         }
     }
 }
@@ -428,6 +480,18 @@ impl From<FbxError> for ModelLoadError {
     }
 }
 
+impl From<GltfError> for ModelLoadError {
+    fn from(gltf: GltfError) -> Self {
+        ModelLoadError::Gltf(gltf)
+    }
+}
+
+impl From<ObjError> for ModelLoadError {
+    fn from(obj: ObjError) -> Self {
+        ModelLoadError::Obj(obj)
+    }
+}
+
 impl From<VisitError> for ModelLoadError {
     fn from(e: VisitError) -> Self {
         ModelLoadError::Visit(e)
@@ -466,6 +530,39 @@ impl ModelData {
                 // any persistent unique ids, and we have to use names.
                 (scene, NodeMapping::UseNames)
             }
+            "gltf" | "glb" => {
+                let mut scene = Scene::new();
+                if let Some(filename) = path.as_ref().file_name() {
+                    let root = scene.graph.get_root();
+                    scene.graph[root].set_name(&filename.to_string_lossy());
+                }
+                gltf::load_to_scene(
+                    &mut scene,
+                    resource_manager,
+                    path.as_ref(),
+                    &model_import_options,
+                )
+                .await?;
+                // glTF nodes are addressed by indices, which are not stable across exports,
+                // so names are used for mapping as well.
+                (scene, NodeMapping::UseNames)
+            }
+            "obj" => {
+                let mut scene = Scene::new();
+                if let Some(filename) = path.as_ref().file_name() {
+                    let root = scene.graph.get_root();
+                    scene.graph[root].set_name(&filename.to_string_lossy());
+                }
+                obj::load_to_scene(
+                    &mut scene,
+                    resource_manager,
+                    path.as_ref(),
+                    &model_import_options,
+                )
+                .await?;
+                // OBJ does not have any ids, objects are addressed by names.
+                (scene, NodeMapping::UseNames)
+            }
             // Scene can be used directly as model resource. Such scenes can be created in
             // Fyroxed.
             "rgs" => (
@@ -0,0 +1,61 @@
+//! Contains all possible errors that can occur during OBJ/MTL parsing and conversion.
+
+use crate::core::io::FileLoadError;
+use std::fmt::{Display, Formatter};
+
+/// See module docs.
+#[derive(Debug)]
+pub enum ObjError {
+    /// An error occurred during file loading.
+    FileLoadError(FileLoadError),
+
+    /// A file has invalid content (non UTF8-compliant).
+    InvalidString,
+
+    /// A statement has malformed or missing arguments.
+    MalformedStatement {
+        /// Number of the line (starting from 1) with the statement.
+        line: usize,
+        /// Description of the problem.
+        reason: String,
+    },
+
+    /// A face references a non-existing vertex attribute.
+    IndexOutOfBounds {
+        /// Number of the line (starting from 1) with the face.
+        line: usize,
+        /// The index as it written in the file.
+        index: i64,
+    },
+}
+
+impl Display for ObjError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjError::FileLoadError(v) => {
+                write!(f, "OBJ: File load error {v:?}.")
+            }
+            ObjError::InvalidString => {
+                write!(f, "OBJ: A file has invalid content (non UTF8-compliant)")
+            }
+            ObjError::MalformedStatement { line, reason } => {
+                write!(f, "OBJ: Malformed statement at line {line}: {reason}")
+            }
+            ObjError::IndexOutOfBounds { line, index } => {
+                write!(f, "OBJ: Index {index} at line {line} is out-of-bounds.")
+            }
+        }
+    }
+}
+
+impl From<FileLoadError> for ObjError {
+    fn from(err: FileLoadError) -> Self {
+        ObjError::FileLoadError(err)
+    }
+}
+
+impl From<std::string::FromUtf8Error> for ObjError {
+    fn from(_: std::string::FromUtf8Error) -> Self {
+        ObjError::InvalidString
+    }
+}
@@ -0,0 +1,775 @@
+//! Contains all methods to load and convert Wavefront OBJ model format.
+//!
+//! OBJ is a simple text format for static meshes, it is widely used for props and photogrammetry
+//! scans. Materials are stored in separate material libraries (MTL files) referenced by `mtllib`
+//! statements. The loader creates a mesh node per object (`o` statement, or `g` statement if there
+//! are no objects) with a surface per material group (`usemtl` statement). Polygons with more than
+//! three vertices are triangulated.
+//!
+//! Normally you should never use methods from this module directly, use resource manager to load
+//! models and create their instances.
+
+pub mod error;
+pub mod mtl;
+
+use crate::{
+    core::{
+        algebra::{Vector2, Vector3, Vector4},
+        color::Color,
+        instant::Instant,
+        io,
+        math::triangulator::triangulate,
+        sstorage::ImmutableString,
+        uuid::Uuid,
+    },
+    engine::resource_manager::ResourceManager,
+    material::{shader::SamplerFallback, Material, PropertyValue, SharedMaterial},
+    resource::{
+        model::ModelImportOptions,
+        obj::{error::ObjError, mtl::MtlMaterial},
+    },
+    scene::{
+        base::{BaseBuilder, InstanceId},
+        mesh::{
+            surface::{Surface, SurfaceData, SurfaceSharedData},
+            vertex::StaticVertex,
+            MeshBuilder,
+        },
+        Scene,
+    },
+    utils::{
+        log::{Log, MessageKind},
+        raw_mesh::RawMeshBuilder,
+    },
+};
+use fxhash::FxHashMap;
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
+/// A vertex of a face, every index is already resolved to zero-based index.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct FaceVertex {
+    position: usize,
+    tex_coord: Option<usize>,
+    normal: Option<usize>,
+}
+
+/// A set of faces that share the same material.
+#[derive(Debug, Default)]
+struct ObjGroup {
+    material: Option<String>,
+    faces: Vec<Vec<FaceVertex>>,
+}
+
+#[derive(Debug)]
+struct ObjObject {
+    name: String,
+    groups: Vec<ObjGroup>,
+}
+
+impl ObjObject {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            groups: Default::default(),
+        }
+    }
+
+    fn group_mut(&mut self, material: &Option<String>) -> &mut ObjGroup {
+        // Reuse existing group, so multiple `usemtl` statements with the same material produce
+        // single surface.
+        match self.groups.iter().position(|g| &g.material == material) {
+            Some(index) => &mut self.groups[index],
+            None => {
+                self.groups.push(ObjGroup {
+                    material: material.clone(),
+                    faces: Default::default(),
+                });
+                self.groups.last_mut().unwrap()
+            }
+        }
+    }
+}
+
+/// Parsed content of an OBJ file.
+#[derive(Debug, Default)]
+struct ObjDocument {
+    positions: Vec<Vector3<f32>>,
+    tex_coords: Vec<Vector2<f32>>,
+    normals: Vec<Vector3<f32>>,
+    objects: Vec<ObjObject>,
+    material_libraries: Vec<String>,
+}
+
+fn parse_floats<const N: usize>(args: &[&str], line: usize) -> Result<[f32; N], ObjError> {
+    let mut result = [0.0; N];
+    for (i, value) in result.iter_mut().enumerate() {
+        *value = args
+            .get(i)
+            .ok_or_else(|| ObjError::MalformedStatement {
+                line,
+                reason: format!("Expected {} numbers", N),
+            })?
+            .parse::<f32>()
+            .map_err(|e| ObjError::MalformedStatement {
+                line,
+                reason: e.to_string(),
+            })?;
+    }
+    Ok(result)
+}
+
+/// Converts an index as it written in a file to zero-based index. Positive indices are one-based,
+/// negative indices are relative to the end of the list of attributes defined so far.
+fn resolve_index(index: &str, count: usize, line: usize) -> Result<usize, ObjError> {
+    let value = index
+        .parse::<i64>()
+        .map_err(|e| ObjError::MalformedStatement {
+            line,
+            reason: e.to_string(),
+        })?;
+    let resolved = if value > 0 {
+        value - 1
+    } else {
+        count as i64 + value
+    };
+    if value == 0 || resolved < 0 || resolved >= count as i64 {
+        Err(ObjError::IndexOutOfBounds { line, index: value })
+    } else {
+        Ok(resolved as usize)
+    }
+}
+
+impl ObjDocument {
+    fn parse(text: &str) -> Result<Self, ObjError> {
+        let mut document = ObjDocument::default();
+
+        // Split by groups only if there are no objects, some exporters use groups instead of
+        // objects.
+        let split_by_groups = !text.lines().any(|line| line.trim_start().starts_with("o "));
+
+        let mut current_material = None;
+
+        for (line_index, line) in text.lines().enumerate() {
+            let line_number = line_index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut tokens = line.split_whitespace();
+            let statement = tokens.next().unwrap_or_default();
+            let args = tokens.collect::<Vec<_>>();
+
+            match statement {
+                "v" => {
+                    let [x, y, z] = parse_floats::<3>(&args, line_number)?;
+                    document.positions.push(Vector3::new(x, y, z));
+                }
+                "vt" => {
+                    // W component is optional and ignored, V is optional too.
+                    let u = parse_floats::<1>(&args, line_number)?[0];
+                    let v = parse_floats::<2>(&args, line_number)
+                        .map(|uv| uv[1])
+                        .unwrap_or_default();
+                    document.tex_coords.push(Vector2::new(u, v));
+                }
+                "vn" => {
+                    let [x, y, z] = parse_floats::<3>(&args, line_number)?;
+                    document.normals.push(Vector3::new(x, y, z));
+                }
+                "f" => {
+                    let mut face = Vec::with_capacity(args.len());
+                    for arg in args.iter() {
+                        // Possible formats: v, v/vt, v//vn, v/vt/vn
+                        let mut parts = arg.split('/');
+                        let position = resolve_index(
+                            parts.next().unwrap_or_default(),
+                            document.positions.len(),
+                            line_number,
+                        )?;
+                        let tex_coord = match parts.next() {
+                            Some(index) if !index.is_empty() => Some(resolve_index(
+                                index,
+                                document.tex_coords.len(),
+                                line_number,
+                            )?),
+                            _ => None,
+                        };
+                        let normal = match parts.next() {
+                            Some(index) if !index.is_empty() => {
+                                Some(resolve_index(index, document.normals.len(), line_number)?)
+                            }
+                            _ => None,
+                        };
+                        face.push(FaceVertex {
+                            position,
+                            tex_coord,
+                            normal,
+                        });
+                    }
+                    if face.len() < 3 {
+                        // Silently ignore degenerated faces.
+                        continue;
+                    }
+                    if document.objects.is_empty() {
+                        document.objects.push(ObjObject::new("Object".to_string()));
+                    }
+                    document
+                        .objects
+                        .last_mut()
+                        .unwrap()
+                        .group_mut(&current_material)
+                        .faces
+                        .push(face);
+                }
+                "o" => {
+                    document.objects.push(ObjObject::new(args.join(" ")));
+                }
+                "g" if split_by_groups => {
+                    document.objects.push(ObjObject::new(args.join(" ")));
+                }
+                "usemtl" => {
+                    current_material = Some(args.join(" "));
+                }
+                "mtllib" => {
+                    document.material_libraries.push(args.join(" "));
+                }
+                _ => {
+                    // Ignore everything else: smoothing groups, lines, points, etc.
+                }
+            }
+        }
+
+        // Remove objects without geometry, they're usually produced by group statements at the
+        // beginning of a file.
+        document.objects.retain(|o| !o.groups.is_empty());
+
+        Ok(document)
+    }
+}
+
+async fn find_texture(
+    name: &str,
+    model_path: &Path,
+    model_import_options: &ModelImportOptions,
+) -> Option<PathBuf> {
+    // Paths in material libraries are relative to the model, try it first and only then use
+    // the search options.
+    let relative_path = model_path
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(name.replace('\\', "/"));
+    if io::exists(&relative_path).await {
+        return Some(relative_path);
+    }
+    let texture_path = model_import_options
+        .material_search_options
+        .find_texture_path(model_path, &relative_path)
+        .await;
+    if texture_path.is_none() {
+        Log::writeln(
+            MessageKind::Warning,
+            format!(
+                "Unable to find a texture {:?} for 3D model {:?} using {:?} option!",
+                name, model_path, model_import_options
+            ),
+        );
+    }
+    texture_path
+}
+
+async fn convert_material(
+    mtl: &MtlMaterial,
+    resource_manager: &ResourceManager,
+    model_path: &Path,
+    model_import_options: &ModelImportOptions,
+) -> SharedMaterial {
+    let mut material = Material::standard();
+
+    let diffuse_color = Vector4::new(
+        mtl.diffuse_color.x,
+        mtl.diffuse_color.y,
+        mtl.diffuse_color.z,
+        mtl.opacity,
+    );
+    if let Err(e) = material.set_property(
+        &ImmutableString::new("diffuseColor"),
+        PropertyValue::Color(Color::from(diffuse_color)),
+    ) {
+        Log::writeln(
+            MessageKind::Error,
+            format!(
+                "Failed to set diffuseColor property for material. Reason: {:?}",
+                e,
+            ),
+        )
+    }
+
+    // Specular color and specular exponent maps of the Phong model do not have an equivalent in the
+    // metallic-roughness model of the standard shader, so they are not used. PBR extension maps
+    // should be used instead.
+    for (map, statement) in [
+        (&mtl.specular_map, "map_Ks"),
+        (&mtl.shininess_map, "map_Ns"),
+    ] {
+        if let Some(map) = map {
+            Log::writeln(
+                MessageKind::Warning,
+                format!(
+                    "OBJ: {} {} is not supported, use PBR maps (map_Pm, map_Pr) instead.",
+                    statement, map
+                ),
+            );
+        }
+    }
+
+    let maps = [
+        (&mtl.diffuse_map, "diffuseTexture", SamplerFallback::White),
+        (&mtl.normal_map, "normalTexture", SamplerFallback::Normal),
+        (&mtl.metallic_map, "metallicTexture", SamplerFallback::Black),
+        (
+            &mtl.roughness_map,
+            "roughnessTexture",
+            SamplerFallback::White,
+        ),
+        (&mtl.emission_map, "emissionTexture", SamplerFallback::Black),
+        (
+            &mtl.displacement_map,
+            "heightTexture",
+            SamplerFallback::Black,
+        ),
+        (&mtl.ambient_map, "aoTexture", SamplerFallback::White),
+    ];
+
+    for (map, property_name, fallback) in maps {
+        if let Some(map) = map {
+            if let Some(path) = find_texture(map, model_path, model_import_options).await {
+                if let Err(e) = material.set_property(
+                    &ImmutableString::new(property_name),
+                    PropertyValue::Sampler {
+                        value: Some(resource_manager.request_texture(path)),
+                        fallback,
+                    },
+                ) {
+                    Log::writeln(
+                        MessageKind::Error,
+                        format!(
+                            "Unable to set material property {} for OBJ material! Reason: {:?}",
+                            property_name, e
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    SharedMaterial::new(material)
+}
+
+async fn load_materials(
+    document: &ObjDocument,
+    resource_manager: &ResourceManager,
+    model_path: &Path,
+    model_import_options: &ModelImportOptions,
+) -> FxHashMap<String, SharedMaterial> {
+    let mut materials = FxHashMap::default();
+    for library in document.material_libraries.iter() {
+        let library_path = model_path
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(library);
+
+        let library = match io::load_file(&library_path).await {
+            Ok(data) => match String::from_utf8(data)
+                .map_err(ObjError::from)
+                .and_then(|text| mtl::parse(&text))
+            {
+                Ok(library) => library,
+                Err(e) => {
+                    Log::err(format!(
+                        "Unable to parse material library {:?}. Reason: {}",
+                        library_path, e
+                    ));
+                    continue;
+                }
+            },
+            Err(e) => {
+                Log::writeln(
+                    MessageKind::Warning,
+                    format!(
+                        "Unable to load material library {:?}. Reason: {:?}",
+                        library_path, e
+                    ),
+                );
+                continue;
+            }
+        };
+
+        for (name, mtl) in library {
+            let material =
+                convert_material(&mtl, resource_manager, model_path, model_import_options).await;
+            materials.insert(name, material);
+        }
+    }
+    materials
+}
+
+fn convert_group(document: &ObjDocument, group: &ObjGroup) -> SurfaceData {
+    let mut builder = RawMeshBuilder::<StaticVertex>::new(1024, 1024);
+    let mut face_positions = Vec::new();
+    let mut face_triangles = Vec::new();
+    let mut has_normals = true;
+
+    for face in group.faces.iter() {
+        face_positions.clear();
+        face_positions.extend(face.iter().map(|v| document.positions[v.position]));
+        triangulate(&face_positions, &mut face_triangles);
+
+        for triangle in face_triangles.iter() {
+            for &index in triangle.iter() {
+                let vertex = &face[index];
+                let normal = match vertex.normal {
+                    Some(normal) => document.normals[normal],
+                    None => {
+                        has_normals = false;
+                        Vector3::default()
+                    }
+                };
+                let tex_coord = vertex
+                    .tex_coord
+                    .map(|tex_coord| {
+                        let uv = document.tex_coords[tex_coord];
+                        // Invert Y because OBJ has origin at left *bottom* corner.
+                        Vector2::new(uv.x, 1.0 - uv.y)
+                    })
+                    .unwrap_or_default();
+                builder.insert(StaticVertex {
+                    position: document.positions[vertex.position],
+                    tex_coord,
+                    normal,
+                    tangent: Vector4::default(),
+                });
+            }
+        }
+    }
+
+    let mut data = SurfaceData::from_raw_mesh(builder.build(), StaticVertex::layout(), false);
+    if !has_normals {
+        data.calculate_normals().unwrap();
+    }
+    data.calculate_tangents().unwrap();
+    data
+}
+
+async fn convert(
+    document: &ObjDocument,
+    resource_manager: ResourceManager,
+    scene: &mut Scene,
+    model_path: &Path,
+    model_import_options: &ModelImportOptions,
+) {
+    let materials = load_materials(
+        document,
+        &resource_manager,
+        model_path,
+        model_import_options,
+    )
+    .await;
+
+    for object in document.objects.iter() {
+        let mut surfaces = Vec::new();
+        for group in object.groups.iter() {
+            let mut surface = Surface::new(SurfaceSharedData::new(convert_group(document, group)));
+            if let Some(name) = group.material.as_ref() {
+                match materials.get(name) {
+                    Some(material) => surface.set_material(material.clone()),
+                    None => Log::writeln(
+                        MessageKind::Warning,
+                        format!("OBJ: Material {} is not defined in any library!", name),
+                    ),
+                }
+            }
+            surfaces.push(surface);
+        }
+
+        // Use the name of the object to generate instance id, the same way as FBX importer does.
+        let mut hasher = DefaultHasher::new();
+        object.name.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        MeshBuilder::new(
+            BaseBuilder::new()
+                .with_name(&object.name)
+                .with_instance_id(InstanceId(Uuid::from_u64_pair(hash, hash))),
+        )
+        .with_surfaces(surfaces)
+        .build(&mut scene.graph);
+    }
+
+    scene.graph.update_hierarchical_data();
+}
+
+/// Tries to load and convert OBJ from given path.
+///
+/// Normally you should never use this method, use resource manager to load models.
+pub async fn load_to_scene<P: AsRef<Path>>(
+    scene: &mut Scene,
+    resource_manager: ResourceManager,
+    path: P,
+    model_import_options: &ModelImportOptions,
+) -> Result<(), ObjError> {
+    let start_time = Instant::now();
+
+    Log::writeln(
+        MessageKind::Information,
+        format!("Trying to load {:?}", path.as_ref()),
+    );
+
+    let now = Instant::now();
+    let text = String::from_utf8(io::load_file(path.as_ref()).await?)?;
+    let document = ObjDocument::parse(&text)?;
+    let parsing_time = now.elapsed().as_millis();
+
+    let now = Instant::now();
+    convert(
+        &document,
+        resource_manager,
+        scene,
+        path.as_ref(),
+        model_import_options,
+    )
+    .await;
+    let conversion_time = now.elapsed().as_millis();
+
+    Log::writeln(
+        MessageKind::Information,
+        format!(
+            "OBJ {:?} loaded in {} ms\n\t- Parsing - {} ms\n\t- Conversion - {} ms",
+            path.as_ref(),
+            start_time.elapsed().as_millis(),
+            parsing_time,
+            conversion_time
+        ),
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::Vector4, color::Color, futures::executor::block_on, math::TriangleDefinition,
+            sstorage::ImmutableString,
+        },
+        engine::resource_manager::ResourceManager,
+        material::{Material, PropertyValue},
+        resource::obj::{convert_group, convert_material, mtl, FaceVertex, ObjDocument},
+        scene::mesh::buffer::{VertexAttributeUsage, VertexReadTrait},
+    };
+    use std::{env, path::PathBuf};
+
+    #[test]
+    fn test_parse_quad_with_relative_indices() {
+        let document = ObjDocument::parse(
+            r#"
+            o Quad
+            v 0 0 0
+            v 1 0 0
+            v 1 1 0
+            v 0 1 0
+            vt 0 0
+            vn 0 0 1
+            usemtl A
+            f -4/1/1 -3/1/1 -2/1/1 -1/1/1
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(document.objects.len(), 1);
+        let object = &document.objects[0];
+        assert_eq!(object.name, "Quad");
+        assert_eq!(object.groups.len(), 1);
+        assert_eq!(object.groups[0].material.as_deref(), Some("A"));
+        let face = &object.groups[0].faces[0];
+        assert_eq!(face.len(), 4);
+        assert_eq!(
+            face[3],
+            FaceVertex {
+                position: 3,
+                tex_coord: Some(0),
+                normal: Some(0)
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_groups_by_material() {
+        let document = ObjDocument::parse(
+            r#"
+            v 0 0 0
+            v 1 0 0
+            v 1 1 0
+            usemtl A
+            f 1 2 3
+            usemtl B
+            f 1//1 2//1 3//1
+            usemtl A
+            f 3 2 1
+            vn 0 0 1
+            "#,
+        );
+        // Normal is referenced before it was defined.
+        assert!(document.is_err());
+
+        let document = ObjDocument::parse(
+            r#"
+            v 0 0 0
+            v 1 0 0
+            v 1 1 0
+            usemtl A
+            f 1 2 3
+            usemtl B
+            f 1 2 3
+            usemtl A
+            f 3 2 1
+            "#,
+        )
+        .unwrap();
+        let object = &document.objects[0];
+        assert_eq!(object.groups.len(), 2);
+        assert_eq!(object.groups[0].faces.len(), 2);
+        assert_eq!(object.groups[1].faces.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_invalid_index() {
+        assert!(ObjDocument::parse("v 0 0 0\nf 1 2 0").is_err());
+    }
+
+    #[test]
+    fn test_parse_mtl() {
+        let library = mtl::parse(
+            r#"
+            newmtl Brick
+            Kd 0.5 0.25 1.0
+            d 0.5
+            map_Kd brick.png
+            map_Bump -bm 1.0 brick_normal.png
+
+            newmtl Empty
+            "#,
+        )
+        .unwrap();
+        let brick = &library["Brick"];
+        assert_eq!(brick.diffuse_color.y, 0.25);
+        assert_eq!(brick.opacity, 0.5);
+        assert_eq!(brick.diffuse_map.as_deref(), Some("brick.png"));
+        assert_eq!(brick.normal_map.as_deref(), Some("brick_normal.png"));
+        assert!(library.contains_key("Empty"));
+    }
+
+    fn texture_path(material: &Material, name: &str) -> Option<PathBuf> {
+        match material.property_ref(&ImmutableString::new(name)) {
+            Some(PropertyValue::Sampler { value, .. }) => value
+                .as_ref()
+                .map(|texture| texture.state().path().to_path_buf()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_convert_material() {
+        let library = mtl::parse(
+            r#"
+            newmtl Brick
+            Kd 0.5 0.25 1.0
+            d 0.5
+            map_Kd diffuse.png
+            map_Ks specular.png
+            map_Ns shininess.png
+            map_Pr roughness.png
+            "#,
+        )
+        .unwrap();
+
+        let data_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("test_data/obj");
+        let resource_manager = ResourceManager::new(Default::default());
+        let material = block_on(convert_material(
+            &library["Brick"],
+            &resource_manager,
+            &data_dir.join("model.obj"),
+            &Default::default(),
+        ));
+        let material = material.lock();
+
+        assert!(matches!(
+            material.property_ref(&ImmutableString::new("diffuseColor")),
+            Some(PropertyValue::Color(color)) if *color == Color::from(Vector4::new(0.5, 0.25, 1.0, 0.5))
+        ));
+        assert_eq!(
+            texture_path(&material, "diffuseTexture"),
+            Some(data_dir.join("diffuse.png"))
+        );
+        assert_eq!(
+            texture_path(&material, "roughnessTexture"),
+            Some(data_dir.join("roughness.png"))
+        );
+        // Specular color and exponent maps must not be used as metallic or roughness maps.
+        assert_eq!(texture_path(&material, "metallicTexture"), None);
+    }
+
+    #[test]
+    fn test_polygon_triangulation() {
+        // Concave pentagon and a quad.
+        let document = ObjDocument::parse(
+            r#"
+            v 0 0 0
+            v 2 0 0
+            v 2 2 0
+            v 1 1 0
+            v 0 2 0
+            f 1 2 3 4 5
+            f 1 2 3 5
+            "#,
+        )
+        .unwrap();
+        let data = convert_group(&document, &document.objects[0].groups[0]);
+
+        // Each polygon with N vertices must be split into N - 2 triangles.
+        assert_eq!(data.geometry_buffer.len(), 3 + 2);
+
+        let position = |index: u32| {
+            data.vertex_buffer
+                .get(index as usize)
+                .unwrap()
+                .read_3_f32(VertexAttributeUsage::Position)
+                .unwrap()
+        };
+        let area = |triangles: &[TriangleDefinition]| -> f32 {
+            triangles
+                .iter()
+                .map(|triangle| {
+                    let [a, b, c] = triangle.0.map(position);
+                    (b - a).cross(&(c - a)).norm() * 0.5
+                })
+                .sum()
+        };
+
+        // Triangles must cover the polygons exactly, without overlaps and without going outside
+        // of the concave polygon.
+        let triangles = data.geometry_buffer.iter().cloned().collect::<Vec<_>>();
+        assert!((area(&triangles[..3]) - 3.0).abs() < 1.0e-5);
+        assert!((area(&triangles[3..]) - 4.0).abs() < 1.0e-5);
+
+        // Every triangle must have the same winding as the polygon.
+        for triangle in triangles {
+            let [a, b, c] = triangle.0.map(position);
+            assert!((b - a).cross(&(c - a)).z > 0.0);
+        }
+    }
+}
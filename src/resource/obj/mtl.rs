@@ -0,0 +1,146 @@
+//! Material library (MTL) parser. MTL files are companions of OBJ files, they describe materials
+//! that are referenced by `usemtl` statements.
+
+use crate::{core::algebra::Vector3, resource::obj::error::ObjError};
+use fxhash::FxHashMap;
+
+/// A material parsed from a material library.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MtlMaterial {
+    /// Diffuse color (`Kd`).
+    pub diffuse_color: Vector3<f32>,
+    /// Opacity (`d` or inverted `Tr`).
+    pub opacity: f32,
+    /// Diffuse map (`map_Kd`).
+    pub diffuse_map: Option<String>,
+    /// Normal map (`norm`, `map_Bump`, `bump`).
+    pub normal_map: Option<String>,
+    /// Specular color map (`map_Ks`).
+    pub specular_map: Option<String>,
+    /// Specular exponent map (`map_Ns`).
+    pub shininess_map: Option<String>,
+    /// Emission map (`map_Ke`).
+    pub emission_map: Option<String>,
+    /// Displacement map (`disp`).
+    pub displacement_map: Option<String>,
+    /// Ambient occlusion map (`map_Ka`).
+    pub ambient_map: Option<String>,
+    /// Roughness map of the PBR extension (`map_Pr`).
+    pub roughness_map: Option<String>,
+    /// Metallic map of the PBR extension (`map_Pm`).
+    pub metallic_map: Option<String>,
+}
+
+impl Default for MtlMaterial {
+    fn default() -> Self {
+        Self {
+            diffuse_color: Vector3::new(1.0, 1.0, 1.0),
+            opacity: 1.0,
+            diffuse_map: None,
+            normal_map: None,
+            specular_map: None,
+            shininess_map: None,
+            emission_map: None,
+            displacement_map: None,
+            ambient_map: None,
+            roughness_map: None,
+            metallic_map: None,
+        }
+    }
+}
+
+fn parse_color<'a, I: Iterator<Item = &'a str>>(
+    args: I,
+    line: usize,
+) -> Result<Vector3<f32>, ObjError> {
+    let components = args
+        .take(3)
+        .map(|v| v.parse::<f32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| ObjError::MalformedStatement {
+            line,
+            reason: e.to_string(),
+        })?;
+    match components.as_slice() {
+        [r, g, b] => Ok(Vector3::new(*r, *g, *b)),
+        // A single value means gray scale color.
+        [v] => Ok(Vector3::new(*v, *v, *v)),
+        _ => Err(ObjError::MalformedStatement {
+            line,
+            reason: "Expected a color".to_string(),
+        }),
+    }
+}
+
+fn parse_scalar(args: &[&str], line: usize) -> Result<f32, ObjError> {
+    args.first()
+        .ok_or_else(|| ObjError::MalformedStatement {
+            line,
+            reason: "Expected a number".to_string(),
+        })?
+        .parse::<f32>()
+        .map_err(|e| ObjError::MalformedStatement {
+            line,
+            reason: e.to_string(),
+        })
+}
+
+/// Texture map statements could have options before the file name (`-bm 1.0 normal.png`), the
+/// file name is always the last argument.
+fn parse_map(args: &[&str]) -> Option<String> {
+    args.last().map(|name| name.to_string())
+}
+
+/// Parses material library from its text representation. Unknown statements are ignored.
+pub fn parse(text: &str) -> Result<FxHashMap<String, MtlMaterial>, ObjError> {
+    let mut materials = FxHashMap::default();
+    let mut current: Option<(String, MtlMaterial)> = None;
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut tokens = line.split_whitespace();
+        let statement = tokens.next().unwrap_or_default();
+        let args = tokens.collect::<Vec<_>>();
+
+        if statement == "newmtl" {
+            if let Some((name, material)) = current.take() {
+                materials.insert(name, material);
+            }
+            current = Some((args.join(" "), MtlMaterial::default()));
+            continue;
+        }
+
+        // Every other statement needs a material.
+        let material = match current.as_mut() {
+            Some((_, material)) => material,
+            None => continue,
+        };
+
+        match statement {
+            "Kd" => material.diffuse_color = parse_color(args.iter().copied(), line_number)?,
+            "d" => material.opacity = parse_scalar(&args, line_number)?,
+            "Tr" => material.opacity = 1.0 - parse_scalar(&args, line_number)?,
+            "map_Kd" => material.diffuse_map = parse_map(&args),
+            "map_Bump" | "map_bump" | "bump" | "norm" => material.normal_map = parse_map(&args),
+            "map_Ks" => material.specular_map = parse_map(&args),
+            "map_Ns" => material.shininess_map = parse_map(&args),
+            "map_Ke" => material.emission_map = parse_map(&args),
+            "map_Ka" => material.ambient_map = parse_map(&args),
+            "disp" | "map_disp" => material.displacement_map = parse_map(&args),
+            "map_Pr" => material.roughness_map = parse_map(&args),
+            "map_Pm" => material.metallic_map = parse_map(&args),
+            _ => (),
+        }
+    }
+
+    if let Some((name, material)) = current.take() {
+        materials.insert(name, material);
+    }
+
+    Ok(materials)
+}
@@ -2,7 +2,7 @@
 //! mixes them in arbitrary way into one animation. See [`AnimationBlendingStateMachine`] docs for more info.
 
 use crate::{
-    animation::machine::Machine,
+    animation::{ik::IkSolver, machine::Machine},
     core::{
         math::aabb::AxisAlignedBoundingBox,
         pool::Handle,
@@ -107,6 +107,11 @@ pub struct AnimationBlendingStateMachine {
     base: Base,
     machine: InheritableVariable<Machine>,
     animation_player: InheritableVariable<Handle<Node>>,
+    #[visit(optional)]
+    #[reflect(
+        description = "A set of IK solvers that will be applied to the final pose of the machine."
+    )]
+    ik_solvers: InheritableVariable<Vec<IkSolver>>,
 }
 
 impl AnimationBlendingStateMachine {
@@ -136,6 +141,22 @@ impl AnimationBlendingStateMachine {
     pub fn animation_player(&self) -> Handle<Node> {
         *self.animation_player
     }
+
+    /// Sets new set of IK solvers of the node. The solvers are applied to the final pose of the state machine,
+    /// in the order they're specified. See [`IkSolver`] docs for more info.
+    pub fn set_ik_solvers(&mut self, ik_solvers: Vec<IkSolver>) {
+        self.ik_solvers.set_value_and_mark_modified(ik_solvers);
+    }
+
+    /// Returns a reference to the set of IK solvers used by the node.
+    pub fn ik_solvers(&self) -> &InheritableVariable<Vec<IkSolver>> {
+        &self.ik_solvers
+    }
+
+    /// Returns a mutable reference to the set of IK solvers used by the node.
+    pub fn ik_solvers_mut(&mut self) -> &mut InheritableVariable<Vec<IkSolver>> {
+        &mut self.ik_solvers
+    }
 }
 
 impl TypeUuidProvider for AnimationBlendingStateMachine {
@@ -174,6 +195,8 @@ impl NodeTrait for AnimationBlendingStateMachine {
     }
 
     fn update(&mut self, context: &mut UpdateContext) {
+        let mut evaluated = false;
+
         if let Some(animation_player) = context
             .nodes
             .try_borrow_mut(*self.animation_player)
@@ -183,12 +206,19 @@ impl NodeTrait for AnimationBlendingStateMachine {
             // do than instead.
             animation_player.set_auto_apply(false);
 
-            let pose = self
-                .machine
+            self.machine
                 .get_value_mut_silent()
                 .evaluate_pose(&animation_player.animations, context.dt);
 
-            pose.apply_internal(context.nodes);
+            evaluated = true;
+        }
+
+        if evaluated {
+            let machine = self.machine.get_value_mut_silent();
+
+            machine.apply_ik_solvers(&self.ik_solvers, context.nodes);
+
+            machine.pose().apply_internal(context.nodes);
         }
     }
 
@@ -204,6 +234,15 @@ impl NodeTrait for AnimationBlendingStateMachine {
             machine won't operate! Set the animation player handle in the Inspector."
                     .to_string(),
             )
+        } else if let Some(solver) = self.ik_solvers.iter().find(|solver| {
+            scene.graph.try_get(solver.end_effector).is_none()
+                || scene.graph.try_get(solver.target).is_none()
+        }) {
+            Err(format!(
+                "IK solver {} has invalid end effector or target handle! The solver won't \
+            operate! Set the handles in the Inspector.",
+                solver.name
+            ))
         } else {
             Ok(())
         }
@@ -215,6 +254,7 @@ pub struct AnimationBlendingStateMachineBuilder {
     base_builder: BaseBuilder,
     machine: Machine,
     animation_player: Handle<Node>,
+    ik_solvers: Vec<IkSolver>,
 }
 
 impl AnimationBlendingStateMachineBuilder {
@@ -224,6 +264,7 @@ impl AnimationBlendingStateMachineBuilder {
             base_builder,
             machine: Default::default(),
             animation_player: Default::default(),
+            ik_solvers: Default::default(),
         }
     }
 
@@ -239,12 +280,19 @@ impl AnimationBlendingStateMachineBuilder {
         self
     }
 
+    /// Sets the desired set of IK solvers.
+    pub fn with_ik_solvers(mut self, ik_solvers: Vec<IkSolver>) -> Self {
+        self.ik_solvers = ik_solvers;
+        self
+    }
+
     /// Creates new node.
     pub fn build_node(self) -> Node {
         Node::new(AnimationBlendingStateMachine {
             base: self.base_builder.build_base(),
             machine: self.machine.into(),
             animation_player: self.animation_player.into(),
+            ik_solvers: self.ik_solvers.into(),
         })
     }
 
@@ -0,0 +1,895 @@
+//! Character controller is a kinematic physics entity that moves a capsule through the physics world
+//! and resolves collisions with other colliders. See [`CharacterController`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Vector3},
+        math::{aabb::AxisAlignedBoundingBox, m4x4_approx_eq},
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        collider::InteractionGroups,
+        graph::Graph,
+        node::{Node, NodeTrait, SyncContext, TypeUuidProvider, UpdateContext},
+    },
+    utils::log::Log,
+};
+use rapier3d::prelude::{ColliderHandle, RigidBodyHandle};
+use std::{
+    cell::Cell,
+    fmt::{Debug, Formatter},
+    ops::{Deref, DerefMut},
+};
+
+/// Collision state of a character controller. It is updated every frame after the controller was
+/// moved.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CharacterControllerState {
+    /// True if the character is standing on the ground (or on a slope that is not steeper than
+    /// maximum climb angle).
+    pub grounded: bool,
+    /// True if the character hit a ceiling during the last movement.
+    pub touching_ceiling: bool,
+    /// True if the character hit a wall (or a slope that is too steep to climb) during the last
+    /// movement.
+    pub touching_wall: bool,
+    /// Actual translation of the character during the last movement. It could be different from
+    /// the desired translation, because of collisions.
+    pub translation: Vector3<f32>,
+    /// A list of colliders, that were hit during the last movement.
+    pub collisions: Vec<Handle<Node>>,
+}
+
+/// Character controller is a kinematic capsule, that moves through the physics world using
+/// "move-and-slide" approach: when the capsule hits an obstacle, the rest of the movement is projected
+/// on the obstacle's surface. It also can climb stairs (steps), slide down too steep slopes, snap to the
+/// ground when moving down a slope and push dynamic rigid bodies.
+///
+/// The controller does not have any gravity on its own, it just moves the capsule by the desired
+/// velocity. Gravity (as well as jumping) must be added to the desired velocity manually.
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::{algebra::Vector3, pool::Handle},
+///     scene::{character_controller::CharacterController, graph::Graph, node::Node},
+/// };
+///
+/// fn move_character(
+///     graph: &mut Graph,
+///     character: Handle<Node>,
+///     input: Vector3<f32>,
+///     vertical_speed: &mut f32,
+///     dt: f32,
+/// ) {
+///     if let Some(character) = graph[character].cast_mut::<CharacterController>() {
+///         if character.is_grounded() {
+///             *vertical_speed = 0.0;
+///         } else {
+///             *vertical_speed -= 9.81 * dt;
+///         }
+///
+///         character.set_desired_velocity(Vector3::new(input.x, *vertical_speed, input.z));
+///     }
+/// }
+/// ```
+#[derive(Visit, Reflect)]
+pub struct CharacterController {
+    base: Base,
+
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[reflect(setter = "set_radius")]
+    pub(crate) radius: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.05,
+        description = "Half of the height of the cylindrical part of the capsule."
+    )]
+    #[reflect(setter = "set_half_height")]
+    pub(crate) half_height: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.005,
+        description = "A small gap to preserve between the character and its surroundings."
+    )]
+    #[reflect(setter = "set_offset")]
+    pub(crate) offset: InheritableVariable<f32>,
+
+    #[reflect(description = "Whether the character should slide along obstacles or not.")]
+    #[reflect(setter = "set_slide")]
+    pub(crate) slide: InheritableVariable<bool>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.05,
+        description = "Maximum height of a step the character can climb. Zero disables step climbing."
+    )]
+    #[reflect(setter = "set_max_step_height")]
+    pub(crate) max_step_height: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.05,
+        description = "Minimum width of free space that must be available after climbing a step."
+    )]
+    #[reflect(setter = "set_min_step_width")]
+    pub(crate) min_step_width: InheritableVariable<f32>,
+
+    #[reflect(description = "Whether the character can climb on dynamic rigid bodies or not.")]
+    #[reflect(setter = "set_step_on_dynamic_bodies")]
+    pub(crate) step_on_dynamic_bodies: InheritableVariable<bool>,
+
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.5708,
+        step = 0.01,
+        description = "Maximum angle (in radians) of a slope the character can climb."
+    )]
+    #[reflect(setter = "set_max_slope_climb_angle")]
+    pub(crate) max_slope_climb_angle: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.5708,
+        step = 0.01,
+        description = "Minimum angle (in radians) of a slope the character will slide down automatically."
+    )]
+    #[reflect(setter = "set_min_slope_slide_angle")]
+    pub(crate) min_slope_slide_angle: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.05,
+        description = "Maximum distance to the ground at which the character will be snapped to it. \
+        Zero disables snapping."
+    )]
+    #[reflect(setter = "set_snap_to_ground")]
+    pub(crate) snap_to_ground: InheritableVariable<f32>,
+
+    #[reflect(description = "Whether the character should push dynamic rigid bodies or not.")]
+    #[reflect(setter = "set_push_dynamic_bodies")]
+    pub(crate) push_dynamic_bodies: InheritableVariable<bool>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.1,
+        description = "Mass of the character, it is used to push dynamic rigid bodies."
+    )]
+    #[reflect(setter = "set_mass")]
+    pub(crate) mass: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_collision_groups")]
+    pub(crate) collision_groups: InheritableVariable<InteractionGroups>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) desired_velocity: Vector3<f32>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) state: CharacterControllerState,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) native_body: Cell<RigidBodyHandle>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) native_collider: Cell<ColliderHandle>,
+}
+
+impl Debug for CharacterController {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CharacterController")
+    }
+}
+
+impl Default for CharacterController {
+    fn default() -> Self {
+        CharacterControllerBuilder::new(BaseBuilder::new()).build_character_controller()
+    }
+}
+
+impl Deref for CharacterController {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for CharacterController {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl Clone for CharacterController {
+    fn clone(&self) -> Self {
+        Self {
+            base: self.base.clone(),
+            radius: self.radius.clone(),
+            half_height: self.half_height.clone(),
+            offset: self.offset.clone(),
+            slide: self.slide.clone(),
+            max_step_height: self.max_step_height.clone(),
+            min_step_width: self.min_step_width.clone(),
+            step_on_dynamic_bodies: self.step_on_dynamic_bodies.clone(),
+            max_slope_climb_angle: self.max_slope_climb_angle.clone(),
+            min_slope_slide_angle: self.min_slope_slide_angle.clone(),
+            snap_to_ground: self.snap_to_ground.clone(),
+            push_dynamic_bodies: self.push_dynamic_bodies.clone(),
+            mass: self.mass.clone(),
+            collision_groups: self.collision_groups.clone(),
+            desired_velocity: self.desired_velocity,
+            state: self.state.clone(),
+            // Do not copy. The copy will have its own native representation.
+            native_body: Cell::new(RigidBodyHandle::invalid()),
+            native_collider: Cell::new(ColliderHandle::invalid()),
+        }
+    }
+}
+
+impl TypeUuidProvider for CharacterController {
+    fn type_uuid() -> Uuid {
+        uuid!("395d756c-540e-455f-8df0-c3bcdcff3afd")
+    }
+}
+
+impl CharacterController {
+    /// Sets the radius of the capsule of the character.
+    pub fn set_radius(&mut self, radius: f32) -> f32 {
+        self.radius.set_value_and_mark_modified(radius.max(0.0))
+    }
+
+    /// Returns the radius of the capsule of the character.
+    pub fn radius(&self) -> f32 {
+        *self.radius
+    }
+
+    /// Sets the half height of the cylindrical part of the capsule of the character. Full height of
+    /// the capsule is `2.0 * (half_height + radius)`.
+    pub fn set_half_height(&mut self, half_height: f32) -> f32 {
+        self.half_height
+            .set_value_and_mark_modified(half_height.max(0.0))
+    }
+
+    /// Returns the half height of the cylindrical part of the capsule of the character.
+    pub fn half_height(&self) -> f32 {
+        *self.half_height
+    }
+
+    /// Sets a small gap to preserve between the character and its surroundings. It must be small
+    /// positive value, that prevents numerical issues when the character is very close to obstacles.
+    pub fn set_offset(&mut self, offset: f32) -> f32 {
+        self.offset.set_value_and_mark_modified(offset.max(0.0))
+    }
+
+    /// Returns current offset.
+    pub fn offset(&self) -> f32 {
+        *self.offset
+    }
+
+    /// Sets whether the character should slide along obstacles or stop when hitting them.
+    pub fn set_slide(&mut self, slide: bool) -> bool {
+        self.slide.set_value_and_mark_modified(slide)
+    }
+
+    /// Returns true if the character slides along obstacles, false - otherwise.
+    pub fn is_slide(&self) -> bool {
+        *self.slide
+    }
+
+    /// Sets maximum height of a step the character can climb. Zero disables step climbing.
+    pub fn set_max_step_height(&mut self, height: f32) -> f32 {
+        self.max_step_height
+            .set_value_and_mark_modified(height.max(0.0))
+    }
+
+    /// Returns maximum height of a step the character can climb.
+    pub fn max_step_height(&self) -> f32 {
+        *self.max_step_height
+    }
+
+    /// Sets minimum width of free space that must be available after climbing a step.
+    pub fn set_min_step_width(&mut self, width: f32) -> f32 {
+        self.min_step_width
+            .set_value_and_mark_modified(width.max(0.0))
+    }
+
+    /// Returns minimum width of free space that must be available after climbing a step.
+    pub fn min_step_width(&self) -> f32 {
+        *self.min_step_width
+    }
+
+    /// Sets whether the character can climb on dynamic rigid bodies or not.
+    pub fn set_step_on_dynamic_bodies(&mut self, enabled: bool) -> bool {
+        self.step_on_dynamic_bodies
+            .set_value_and_mark_modified(enabled)
+    }
+
+    /// Returns true if the character can climb on dynamic rigid bodies, false - otherwise.
+    pub fn is_step_on_dynamic_bodies(&self) -> bool {
+        *self.step_on_dynamic_bodies
+    }
+
+    /// Sets maximum angle (in radians) of a slope the character can climb.
+    pub fn set_max_slope_climb_angle(&mut self, angle: f32) -> f32 {
+        self.max_slope_climb_angle
+            .set_value_and_mark_modified(angle)
+    }
+
+    /// Returns maximum angle (in radians) of a slope the character can climb.
+    pub fn max_slope_climb_angle(&self) -> f32 {
+        *self.max_slope_climb_angle
+    }
+
+    /// Sets minimum angle (in radians) of a slope the character will slide down automatically.
+    pub fn set_min_slope_slide_angle(&mut self, angle: f32) -> f32 {
+        self.min_slope_slide_angle
+            .set_value_and_mark_modified(angle)
+    }
+
+    /// Returns minimum angle (in radians) of a slope the character will slide down automatically.
+    pub fn min_slope_slide_angle(&self) -> f32 {
+        *self.min_slope_slide_angle
+    }
+
+    /// Sets maximum distance to the ground at which the character will be snapped to it. It prevents
+    /// the character from "flying" when moving down a slope or stairs. Zero disables snapping.
+    pub fn set_snap_to_ground(&mut self, distance: f32) -> f32 {
+        self.snap_to_ground
+            .set_value_and_mark_modified(distance.max(0.0))
+    }
+
+    /// Returns maximum distance to the ground at which the character will be snapped to it.
+    pub fn snap_to_ground(&self) -> f32 {
+        *self.snap_to_ground
+    }
+
+    /// Sets whether the character should push dynamic rigid bodies or not.
+    pub fn set_push_dynamic_bodies(&mut self, enabled: bool) -> bool {
+        self.push_dynamic_bodies
+            .set_value_and_mark_modified(enabled)
+    }
+
+    /// Returns true if the character pushes dynamic rigid bodies, false - otherwise.
+    pub fn is_push_dynamic_bodies(&self) -> bool {
+        *self.push_dynamic_bodies
+    }
+
+    /// Sets mass of the character, it is used to calculate impulses applied to dynamic rigid bodies.
+    pub fn set_mass(&mut self, mass: f32) -> f32 {
+        self.mass.set_value_and_mark_modified(mass.max(0.0))
+    }
+
+    /// Returns mass of the character.
+    pub fn mass(&self) -> f32 {
+        *self.mass
+    }
+
+    /// Sets new collision filtering options. See [`InteractionGroups`] docs for more info.
+    pub fn set_collision_groups(&mut self, groups: InteractionGroups) -> InteractionGroups {
+        self.collision_groups.set_value_and_mark_modified(groups)
+    }
+
+    /// Returns current collision filtering options.
+    pub fn collision_groups(&self) -> InteractionGroups {
+        *self.collision_groups
+    }
+
+    /// Sets desired velocity of the character. The character will try to move by `velocity * dt`
+    /// on each update, resolving collisions with other colliders.
+    pub fn set_desired_velocity(&mut self, velocity: Vector3<f32>) {
+        self.desired_velocity = velocity;
+    }
+
+    /// Returns desired velocity of the character.
+    pub fn desired_velocity(&self) -> Vector3<f32> {
+        self.desired_velocity
+    }
+
+    /// Returns collision state of the character after last movement.
+    pub fn state(&self) -> &CharacterControllerState {
+        &self.state
+    }
+
+    /// Returns true if the character is standing on the ground.
+    pub fn is_grounded(&self) -> bool {
+        self.state.grounded
+    }
+
+    /// Returns true if the character hit a ceiling during the last movement.
+    pub fn is_touching_ceiling(&self) -> bool {
+        self.state.touching_ceiling
+    }
+
+    /// Returns true if the character hit a wall during the last movement.
+    pub fn is_touching_wall(&self) -> bool {
+        self.state.touching_wall
+    }
+
+    pub(crate) fn need_sync_shape(&self) -> bool {
+        self.radius.need_sync() || self.half_height.need_sync()
+    }
+}
+
+impl NodeTrait for CharacterController {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        let half_extents =
+            Vector3::new(*self.radius, *self.half_height + *self.radius, *self.radius);
+        AxisAlignedBoundingBox::from_min_max(-half_extents, half_extents)
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn on_removed_from_graph(&mut self, graph: &mut Graph) {
+        graph.physics.remove_body(self.native_body.get());
+        self.native_body.set(RigidBodyHandle::invalid());
+        self.native_collider.set(ColliderHandle::invalid());
+
+        Log::info(format!(
+            "Native character controller was removed for node: {}",
+            self.name()
+        ));
+    }
+
+    fn sync_native(&self, self_handle: Handle<Node>, context: &mut SyncContext) {
+        context
+            .physics
+            .sync_to_character_controller_node(self_handle, self);
+    }
+
+    fn sync_transform(&self, new_global_transform: &Matrix4<f32>, context: &mut SyncContext) {
+        if !m4x4_approx_eq(new_global_transform, &self.global_transform()) {
+            context
+                .physics
+                .set_character_controller_position(self, new_global_transform);
+        }
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        context.physics.move_character_controller(
+            self,
+            context.dt,
+            // Character controller can be root node of a scene, in this case it does not have a parent.
+            context
+                .nodes
+                .try_borrow(self.parent)
+                .map(|p| p.global_transform())
+                .unwrap_or_else(Matrix4::identity),
+        );
+    }
+}
+
+/// Allows you to create character controller in declarative manner.
+pub struct CharacterControllerBuilder {
+    base_builder: BaseBuilder,
+    radius: f32,
+    half_height: f32,
+    offset: f32,
+    slide: bool,
+    max_step_height: f32,
+    min_step_width: f32,
+    step_on_dynamic_bodies: bool,
+    max_slope_climb_angle: f32,
+    min_slope_slide_angle: f32,
+    snap_to_ground: f32,
+    push_dynamic_bodies: bool,
+    mass: f32,
+    collision_groups: InteractionGroups,
+}
+
+impl CharacterControllerBuilder {
+    /// Creates new character controller builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            radius: 0.3,
+            half_height: 0.6,
+            offset: 0.01,
+            slide: true,
+            max_step_height: 0.3,
+            min_step_width: 0.2,
+            step_on_dynamic_bodies: false,
+            max_slope_climb_angle: 45.0f32.to_radians(),
+            min_slope_slide_angle: 30.0f32.to_radians(),
+            snap_to_ground: 0.2,
+            push_dynamic_bodies: true,
+            mass: 80.0,
+            collision_groups: Default::default(),
+        }
+    }
+
+    /// Sets desired radius of the capsule.
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets desired half height of the cylindrical part of the capsule.
+    pub fn with_half_height(mut self, half_height: f32) -> Self {
+        self.half_height = half_height;
+        self
+    }
+
+    /// Sets desired offset (gap) between the character and its surroundings.
+    pub fn with_offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Sets whether the character should slide along obstacles or not.
+    pub fn with_slide(mut self, slide: bool) -> Self {
+        self.slide = slide;
+        self
+    }
+
+    /// Sets desired maximum height of a step the character can climb.
+    pub fn with_max_step_height(mut self, height: f32) -> Self {
+        self.max_step_height = height;
+        self
+    }
+
+    /// Sets desired minimum width of free space that must be available after climbing a step.
+    pub fn with_min_step_width(mut self, width: f32) -> Self {
+        self.min_step_width = width;
+        self
+    }
+
+    /// Sets whether the character can climb on dynamic rigid bodies or not.
+    pub fn with_step_on_dynamic_bodies(mut self, enabled: bool) -> Self {
+        self.step_on_dynamic_bodies = enabled;
+        self
+    }
+
+    /// Sets desired maximum angle (in radians) of a slope the character can climb.
+    pub fn with_max_slope_climb_angle(mut self, angle: f32) -> Self {
+        self.max_slope_climb_angle = angle;
+        self
+    }
+
+    /// Sets desired minimum angle (in radians) of a slope the character will slide down.
+    pub fn with_min_slope_slide_angle(mut self, angle: f32) -> Self {
+        self.min_slope_slide_angle = angle;
+        self
+    }
+
+    /// Sets desired snap-to-ground distance.
+    pub fn with_snap_to_ground(mut self, distance: f32) -> Self {
+        self.snap_to_ground = distance;
+        self
+    }
+
+    /// Sets whether the character should push dynamic rigid bodies or not.
+    pub fn with_push_dynamic_bodies(mut self, enabled: bool) -> Self {
+        self.push_dynamic_bodies = enabled;
+        self
+    }
+
+    /// Sets desired mass of the character.
+    pub fn with_mass(mut self, mass: f32) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    /// Sets desired collision groups.
+    pub fn with_collision_groups(mut self, groups: InteractionGroups) -> Self {
+        self.collision_groups = groups;
+        self
+    }
+
+    /// Creates CharacterController node but does not add it to the graph.
+    pub fn build_character_controller(self) -> CharacterController {
+        CharacterController {
+            base: self.base_builder.build_base(),
+            radius: self.radius.into(),
+            half_height: self.half_height.into(),
+            offset: self.offset.into(),
+            slide: self.slide.into(),
+            max_step_height: self.max_step_height.into(),
+            min_step_width: self.min_step_width.into(),
+            step_on_dynamic_bodies: self.step_on_dynamic_bodies.into(),
+            max_slope_climb_angle: self.max_slope_climb_angle.into(),
+            min_slope_slide_angle: self.min_slope_slide_angle.into(),
+            snap_to_ground: self.snap_to_ground.into(),
+            push_dynamic_bodies: self.push_dynamic_bodies.into(),
+            mass: self.mass.into(),
+            collision_groups: self.collision_groups.into(),
+            desired_velocity: Default::default(),
+            state: Default::default(),
+            native_body: Cell::new(RigidBodyHandle::invalid()),
+            native_collider: Cell::new(ColliderHandle::invalid()),
+        }
+    }
+
+    /// Creates CharacterController node but does not add it to the graph.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_character_controller())
+    }
+
+    /// Creates CharacterController node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{UnitQuaternion, Vector2, Vector3},
+            pool::Handle,
+        },
+        scene::{
+            base::{
+                test::{check_inheritable_properties_equality, inherit_node_properties},
+                BaseBuilder,
+            },
+            character_controller::{CharacterController, CharacterControllerBuilder},
+            collider::{ColliderBuilder, ColliderShape},
+            graph::Graph,
+            node::Node,
+            rigidbody::{RigidBodyBuilder, RigidBodyType},
+            transform::TransformBuilder,
+        },
+    };
+
+    const DT: f32 = 1.0 / 60.0;
+
+    // Radius and half height of the capsule of the controller, the lowest point of the capsule
+    // is 0.9 below its center.
+    const RADIUS: f32 = 0.3;
+    const HALF_HEIGHT: f32 = 0.6;
+
+    fn make_graph() -> Graph {
+        let mut graph = Graph::new();
+        graph.physics.integration_parameters.dt = Some(DT);
+        graph
+    }
+
+    // Creates static box and returns a handle of its collider.
+    fn add_box(
+        graph: &mut Graph,
+        position: Vector3<f32>,
+        rotation: UnitQuaternion<f32>,
+        half_extents: Vector3<f32>,
+    ) -> Handle<Node> {
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(
+                half_extents.x,
+                half_extents.y,
+                half_extents.z,
+            ))
+            .build(graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .with_local_rotation(rotation)
+                        .build(),
+                )
+                .with_children(&[collider]),
+        )
+        .with_body_type(RigidBodyType::Static)
+        .build(graph);
+        collider
+    }
+
+    // Creates a floor with its top surface at zero height.
+    fn add_floor(graph: &mut Graph) -> Handle<Node> {
+        add_box(
+            graph,
+            Vector3::new(0.0, -0.5, 0.0),
+            UnitQuaternion::identity(),
+            Vector3::new(20.0, 0.5, 20.0),
+        )
+    }
+
+    fn add_character(graph: &mut Graph, position: Vector3<f32>) -> Handle<Node> {
+        CharacterControllerBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(position)
+                    .build(),
+            ),
+        )
+        .with_radius(RADIUS)
+        .with_half_height(HALF_HEIGHT)
+        .build(graph)
+    }
+
+    fn move_character(
+        graph: &mut Graph,
+        character: Handle<Node>,
+        velocity: Vector3<f32>,
+        steps: usize,
+    ) -> &CharacterController {
+        for _ in 0..steps {
+            graph[character]
+                .cast_mut::<CharacterController>()
+                .unwrap()
+                .set_desired_velocity(velocity);
+            graph.update(Vector2::new(800.0, 600.0), DT, Default::default());
+        }
+        graph[character].cast::<CharacterController>().unwrap()
+    }
+
+    #[test]
+    fn test_character_controller_inheritance() {
+        let parent = CharacterControllerBuilder::new(BaseBuilder::new())
+            .with_radius(0.5)
+            .with_half_height(1.0)
+            .with_offset(0.02)
+            .with_slide(false)
+            .with_max_step_height(0.5)
+            .with_min_step_width(0.1)
+            .with_step_on_dynamic_bodies(true)
+            .with_max_slope_climb_angle(0.5)
+            .with_min_slope_slide_angle(0.4)
+            .with_snap_to_ground(0.0)
+            .with_push_dynamic_bodies(false)
+            .with_mass(100.0)
+            .build_node();
+
+        let mut child =
+            CharacterControllerBuilder::new(BaseBuilder::new()).build_character_controller();
+
+        inherit_node_properties(&mut child, &parent);
+
+        let parent = parent.cast::<CharacterController>().unwrap();
+
+        check_inheritable_properties_equality(&child, parent);
+    }
+
+    #[test]
+    fn test_character_controller_grounded() {
+        let mut graph = make_graph();
+        let floor = add_floor(&mut graph);
+        let character = add_character(&mut graph, Vector3::new(0.0, 1.5, 0.0));
+
+        let controller = move_character(&mut graph, character, Vector3::new(0.0, -5.0, 0.0), 60);
+        assert!(controller.is_grounded());
+        assert!(!controller.is_touching_wall());
+        assert!(!controller.is_touching_ceiling());
+        assert!(controller.state().collisions.contains(&floor));
+        // The character stands on the floor, keeping the offset.
+        let y = controller.global_position().y;
+        assert!((y - (RADIUS + HALF_HEIGHT)).abs() < 0.05, "{}", y);
+
+        // Character in the air is not grounded.
+        let character = add_character(&mut graph, Vector3::new(5.0, 5.0, 0.0));
+        let controller = move_character(&mut graph, character, Vector3::new(0.0, -1.0, 0.0), 10);
+        assert!(!controller.is_grounded());
+        assert!(controller.state().collisions.is_empty());
+    }
+
+    fn climb_step(step_height: f32) -> (Vector3<f32>, bool) {
+        let mut graph = make_graph();
+        add_floor(&mut graph);
+        // Step starts at x = 1.
+        add_box(
+            &mut graph,
+            Vector3::new(3.0, step_height * 0.5, 0.0),
+            UnitQuaternion::identity(),
+            Vector3::new(2.0, step_height * 0.5, 5.0),
+        );
+        let character = add_character(&mut graph, Vector3::new(0.0, RADIUS + HALF_HEIGHT, 0.0));
+        let controller = move_character(&mut graph, character, Vector3::new(2.0, 0.0, 0.0), 90);
+        (controller.global_position(), controller.is_touching_wall())
+    }
+
+    #[test]
+    fn test_character_controller_step_climbing() {
+        // Default max step height is 0.3.
+        let (position, touching_wall) = climb_step(0.2);
+        assert!(!touching_wall);
+        assert!(position.x > 2.0, "{:?}", position);
+        assert!(
+            position.y > 0.2 + RADIUS + HALF_HEIGHT - 0.05,
+            "{:?}",
+            position
+        );
+
+        // Too high step blocks the character.
+        let (position, touching_wall) = climb_step(0.5);
+        assert!(touching_wall);
+        assert!(position.x < 1.0 - RADIUS + 0.05, "{:?}", position);
+        assert!(position.y < RADIUS + HALF_HEIGHT + 0.05, "{:?}", position);
+    }
+
+    // Creates a slope that goes up along X axis and moves a character up the slope. Returns the
+    // height difference and grounded and touching wall flags.
+    fn climb_slope(angle: f32) -> (f32, bool, bool) {
+        let mut graph = make_graph();
+        let rotation = UnitQuaternion::from_axis_angle(&Vector3::z_axis(), angle);
+        let normal = rotation * Vector3::y();
+        // Top surface of the slope goes through the origin.
+        add_box(
+            &mut graph,
+            -normal.scale(0.5),
+            rotation,
+            Vector3::new(20.0, 0.5, 20.0),
+        );
+        let start = normal.scale(RADIUS + 0.02) + Vector3::new(0.0, HALF_HEIGHT, 0.0);
+        let character = add_character(&mut graph, start);
+        let controller = move_character(&mut graph, character, Vector3::new(2.0, -1.0, 0.0), 60);
+        (
+            controller.global_position().y - start.y,
+            controller.is_grounded(),
+            controller.is_touching_wall(),
+        )
+    }
+
+    #[test]
+    fn test_character_controller_slope_limit() {
+        // Default max climb angle is 45 degrees.
+        let (height, grounded, touching_wall) = climb_slope(20.0f32.to_radians());
+        assert!(height > 0.5, "{}", height);
+        assert!(grounded);
+        assert!(!touching_wall);
+
+        // Too steep slope is a wall.
+        let (height, _, touching_wall) = climb_slope(70.0f32.to_radians());
+        assert!(height < 0.1, "{}", height);
+        assert!(touching_wall);
+    }
+
+    #[test]
+    fn test_character_controller_wall_contact() {
+        let mut graph = make_graph();
+        add_floor(&mut graph);
+        // Wall surface is at x = 1.
+        let wall = add_box(
+            &mut graph,
+            Vector3::new(1.5, 5.0, 0.0),
+            UnitQuaternion::identity(),
+            Vector3::new(0.5, 5.0, 5.0),
+        );
+        let character = add_character(&mut graph, Vector3::new(0.0, RADIUS + HALF_HEIGHT, 0.0));
+
+        let controller = move_character(&mut graph, character, Vector3::new(2.0, -1.0, 0.0), 60);
+        assert!(controller.is_touching_wall());
+        assert!(!controller.is_touching_ceiling());
+        assert!(controller.is_grounded());
+        assert!(controller.state().collisions.contains(&wall));
+        let x = controller.global_position().x;
+        assert!((x - (1.0 - RADIUS)).abs() < 0.05, "{}", x);
+    }
+
+    #[test]
+    fn test_character_controller_ceiling_contact() {
+        let mut graph = make_graph();
+        // Ceiling surface is at y = 3.
+        let ceiling = add_box(
+            &mut graph,
+            Vector3::new(0.0, 3.5, 0.0),
+            UnitQuaternion::identity(),
+            Vector3::new(5.0, 0.5, 5.0),
+        );
+        let character = add_character(&mut graph, Vector3::new(0.0, 1.0, 0.0));
+
+        let controller = move_character(&mut graph, character, Vector3::new(0.0, 5.0, 0.0), 60);
+        assert!(controller.is_touching_ceiling());
+        assert!(!controller.is_touching_wall());
+        assert!(!controller.is_grounded());
+        assert!(controller.state().collisions.contains(&ceiling));
+        let y = controller.global_position().y;
+        assert!((y - (3.0 - RADIUS - HALF_HEIGHT)).abs() < 0.05, "{}", y);
+    }
+}
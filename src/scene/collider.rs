@@ -20,6 +20,7 @@ use crate::{
         },
         node::{Node, NodeTrait, SyncContext, TypeUuidProvider},
         rigidbody::RigidBody,
+        sound::occlusion::AcousticMaterial,
         Scene,
     },
     utils::log::Log,
@@ -475,6 +476,14 @@ pub struct Collider {
     #[reflect(setter = "set_restitution_combine_rule")]
     pub(crate) restitution_combine_rule: InheritableVariable<CoefficientCombineRule>,
 
+    #[visit(optional)]
+    #[reflect(setter = "set_contact_force_event_threshold")]
+    pub(crate) contact_force_event_threshold: InheritableVariable<Option<f32>>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_acoustic_material")]
+    pub(crate) acoustic_material: InheritableVariable<AcousticMaterial>,
+
     #[visit(skip)]
     #[reflect(hidden)]
     pub(crate) native: Cell<ColliderHandle>,
@@ -493,6 +502,8 @@ impl Default for Collider {
             solver_groups: Default::default(),
             friction_combine_rule: Default::default(),
             restitution_combine_rule: Default::default(),
+            contact_force_event_threshold: InheritableVariable::new(None),
+            acoustic_material: Default::default(),
             native: Cell::new(ColliderHandle::invalid()),
         }
     }
@@ -525,6 +536,8 @@ impl Clone for Collider {
             solver_groups: self.solver_groups.clone(),
             friction_combine_rule: self.friction_combine_rule.clone(),
             restitution_combine_rule: self.restitution_combine_rule.clone(),
+            contact_force_event_threshold: self.contact_force_event_threshold.clone(),
+            acoustic_material: self.acoustic_material.clone(),
             // Do not copy. The copy will have its own native representation (for example - Rapier's collider)
             native: Cell::new(ColliderHandle::invalid()),
         }
@@ -717,6 +730,31 @@ impl Collider {
         *self.restitution_combine_rule
     }
 
+    /// Sets the new contact force event threshold. When set to `Some(threshold)`, the physics world
+    /// will produce a [`crate::scene::graph::physics::ContactForceEvent`] each time the total force
+    /// magnitude of a contact of the collider exceeds the threshold. `None` disables contact force
+    /// events for the collider.
+    pub fn set_contact_force_event_threshold(&mut self, threshold: Option<f32>) -> Option<f32> {
+        self.contact_force_event_threshold
+            .set_value_and_mark_modified(threshold)
+    }
+
+    /// Returns current contact force event threshold of the collider.
+    pub fn contact_force_event_threshold(&self) -> Option<f32> {
+        *self.contact_force_event_threshold
+    }
+
+    /// Sets new acoustic material of the collider. It defines how sounds pass through the collider
+    /// when sound occlusion is enabled, see [`crate::scene::sound::Sound::set_occlusion_enabled`].
+    pub fn set_acoustic_material(&mut self, material: AcousticMaterial) -> AcousticMaterial {
+        self.acoustic_material.set_value_and_mark_modified(material)
+    }
+
+    /// Returns current acoustic material of the collider.
+    pub fn acoustic_material(&self) -> AcousticMaterial {
+        *self.acoustic_material
+    }
+
     /// Returns an iterator that yields contact information for the collider.
     /// Contacts checks between two regular colliders
     pub fn contacts<'a>(
@@ -745,6 +783,7 @@ impl Collider {
             || self.solver_groups.need_sync()
             || self.friction_combine_rule.need_sync()
             || self.restitution_combine_rule.need_sync()
+            || self.contact_force_event_threshold.need_sync()
     }
 }
 
@@ -809,6 +848,8 @@ pub struct ColliderBuilder {
     solver_groups: InteractionGroups,
     friction_combine_rule: CoefficientCombineRule,
     restitution_combine_rule: CoefficientCombineRule,
+    contact_force_event_threshold: Option<f32>,
+    acoustic_material: AcousticMaterial,
 }
 
 impl ColliderBuilder {
@@ -825,6 +866,8 @@ impl ColliderBuilder {
             solver_groups: Default::default(),
             friction_combine_rule: Default::default(),
             restitution_combine_rule: Default::default(),
+            contact_force_event_threshold: None,
+            acoustic_material: Default::default(),
         }
     }
 
@@ -882,6 +925,19 @@ impl ColliderBuilder {
         self
     }
 
+    /// Sets desired contact force event threshold. See [`Collider::set_contact_force_event_threshold`]
+    /// for more info.
+    pub fn with_contact_force_event_threshold(mut self, threshold: Option<f32>) -> Self {
+        self.contact_force_event_threshold = threshold;
+        self
+    }
+
+    /// Sets desired acoustic material. See [`Collider::set_acoustic_material`] for more info.
+    pub fn with_acoustic_material(mut self, material: AcousticMaterial) -> Self {
+        self.acoustic_material = material;
+        self
+    }
+
     /// Creates collider node, but does not add it to a graph.
     pub fn build_collider(self) -> Collider {
         Collider {
@@ -895,6 +951,8 @@ impl ColliderBuilder {
             solver_groups: self.solver_groups.into(),
             friction_combine_rule: self.friction_combine_rule.into(),
             restitution_combine_rule: self.restitution_combine_rule.into(),
+            contact_force_event_threshold: self.contact_force_event_threshold.into(),
+            acoustic_material: self.acoustic_material.into(),
             native: Cell::new(ColliderHandle::invalid()),
         }
     }
@@ -921,6 +979,7 @@ mod test {
         graph::physics::CoefficientCombineRule,
         graph::Graph,
         rigidbody::{RigidBodyBuilder, RigidBodyType},
+        sound::occlusion::AcousticMaterial,
     };
 
     #[test]
@@ -935,6 +994,8 @@ mod test {
             .with_friction_combine_rule(CoefficientCombineRule::Max)
             .with_collision_groups(InteractionGroups::new(BitMask(1), BitMask(2)))
             .with_solver_groups(InteractionGroups::new(BitMask(1), BitMask(2)))
+            .with_contact_force_event_threshold(Some(10.0))
+            .with_acoustic_material(AcousticMaterial::new(0.5, 1000.0))
             .build_node();
 
         let mut child = ColliderBuilder::new(BaseBuilder::new()).build_collider();
@@ -0,0 +1,666 @@
+//! 2D character controller is a kinematic physics entity that moves a capsule through the 2D physics
+//! world and resolves collisions with other 2D colliders. See [`CharacterController`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Vector2, Vector3},
+        math::{aabb::AxisAlignedBoundingBox, m4x4_approx_eq},
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        collider::InteractionGroups,
+        graph::Graph,
+        node::{Node, NodeTrait, SyncContext, TypeUuidProvider, UpdateContext},
+    },
+    utils::log::Log,
+};
+use rapier2d::prelude::{ColliderHandle, RigidBodyHandle};
+use std::{
+    cell::Cell,
+    fmt::{Debug, Formatter},
+    ops::{Deref, DerefMut},
+};
+
+/// Collision state of a character controller. It is updated every frame after the controller was
+/// moved.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CharacterControllerState {
+    /// True if the character is standing on the ground (or on a slope that is not steeper than
+    /// maximum climb angle).
+    pub grounded: bool,
+    /// True if the character hit a ceiling during the last movement.
+    pub touching_ceiling: bool,
+    /// True if the character hit a wall (or a slope that is too steep to climb) during the last
+    /// movement.
+    pub touching_wall: bool,
+    /// Actual translation of the character during the last movement. It could be different from
+    /// the desired translation, because of collisions.
+    pub translation: Vector2<f32>,
+    /// A list of colliders, that were hit during the last movement.
+    pub collisions: Vec<Handle<Node>>,
+}
+
+/// 2D character controller is a kinematic capsule, that moves through the physics world using
+/// "move-and-slide" approach: when the capsule hits an obstacle, the rest of the movement is projected
+/// on the obstacle's surface. It also can climb stairs (steps), slide down too steep slopes, snap to the
+/// ground when moving down a slope and push dynamic rigid bodies.
+///
+/// The controller does not have any gravity on its own, it just moves the capsule by the desired
+/// velocity. Gravity (as well as jumping) must be added to the desired velocity manually.
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::{algebra::Vector2, pool::Handle},
+///     scene::{dim2::character_controller::CharacterController, graph::Graph, node::Node},
+/// };
+///
+/// fn move_character(
+///     graph: &mut Graph,
+///     character: Handle<Node>,
+///     input: f32,
+///     vertical_speed: &mut f32,
+///     dt: f32,
+/// ) {
+///     if let Some(character) = graph[character].cast_mut::<CharacterController>() {
+///         if character.is_grounded() {
+///             *vertical_speed = 0.0;
+///         } else {
+///             *vertical_speed -= 9.81 * dt;
+///         }
+///
+///         character.set_desired_velocity(Vector2::new(input, *vertical_speed));
+///     }
+/// }
+/// ```
+#[derive(Visit, Reflect)]
+pub struct CharacterController {
+    base: Base,
+
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[reflect(setter = "set_radius")]
+    pub(crate) radius: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.05,
+        description = "Half of the height of the cylindrical part of the capsule."
+    )]
+    #[reflect(setter = "set_half_height")]
+    pub(crate) half_height: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.005,
+        description = "A small gap to preserve between the character and its surroundings."
+    )]
+    #[reflect(setter = "set_offset")]
+    pub(crate) offset: InheritableVariable<f32>,
+
+    #[reflect(description = "Whether the character should slide along obstacles or not.")]
+    #[reflect(setter = "set_slide")]
+    pub(crate) slide: InheritableVariable<bool>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.05,
+        description = "Maximum height of a step the character can climb. Zero disables step climbing."
+    )]
+    #[reflect(setter = "set_max_step_height")]
+    pub(crate) max_step_height: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.05,
+        description = "Minimum width of free space that must be available after climbing a step."
+    )]
+    #[reflect(setter = "set_min_step_width")]
+    pub(crate) min_step_width: InheritableVariable<f32>,
+
+    #[reflect(description = "Whether the character can climb on dynamic rigid bodies or not.")]
+    #[reflect(setter = "set_step_on_dynamic_bodies")]
+    pub(crate) step_on_dynamic_bodies: InheritableVariable<bool>,
+
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.5708,
+        step = 0.01,
+        description = "Maximum angle (in radians) of a slope the character can climb."
+    )]
+    #[reflect(setter = "set_max_slope_climb_angle")]
+    pub(crate) max_slope_climb_angle: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.5708,
+        step = 0.01,
+        description = "Minimum angle (in radians) of a slope the character will slide down automatically."
+    )]
+    #[reflect(setter = "set_min_slope_slide_angle")]
+    pub(crate) min_slope_slide_angle: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.05,
+        description = "Maximum distance to the ground at which the character will be snapped to it. \
+        Zero disables snapping."
+    )]
+    #[reflect(setter = "set_snap_to_ground")]
+    pub(crate) snap_to_ground: InheritableVariable<f32>,
+
+    #[reflect(description = "Whether the character should push dynamic rigid bodies or not.")]
+    #[reflect(setter = "set_push_dynamic_bodies")]
+    pub(crate) push_dynamic_bodies: InheritableVariable<bool>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.1,
+        description = "Mass of the character, it is used to push dynamic rigid bodies."
+    )]
+    #[reflect(setter = "set_mass")]
+    pub(crate) mass: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_collision_groups")]
+    pub(crate) collision_groups: InheritableVariable<InteractionGroups>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) desired_velocity: Vector2<f32>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) state: CharacterControllerState,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) native_body: Cell<RigidBodyHandle>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) native_collider: Cell<ColliderHandle>,
+}
+
+impl Debug for CharacterController {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CharacterController")
+    }
+}
+
+impl Default for CharacterController {
+    fn default() -> Self {
+        CharacterControllerBuilder::new(BaseBuilder::new()).build_character_controller()
+    }
+}
+
+impl Deref for CharacterController {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for CharacterController {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl Clone for CharacterController {
+    fn clone(&self) -> Self {
+        Self {
+            base: self.base.clone(),
+            radius: self.radius.clone(),
+            half_height: self.half_height.clone(),
+            offset: self.offset.clone(),
+            slide: self.slide.clone(),
+            max_step_height: self.max_step_height.clone(),
+            min_step_width: self.min_step_width.clone(),
+            step_on_dynamic_bodies: self.step_on_dynamic_bodies.clone(),
+            max_slope_climb_angle: self.max_slope_climb_angle.clone(),
+            min_slope_slide_angle: self.min_slope_slide_angle.clone(),
+            snap_to_ground: self.snap_to_ground.clone(),
+            push_dynamic_bodies: self.push_dynamic_bodies.clone(),
+            mass: self.mass.clone(),
+            collision_groups: self.collision_groups.clone(),
+            desired_velocity: self.desired_velocity,
+            state: self.state.clone(),
+            // Do not copy. The copy will have its own native representation.
+            native_body: Cell::new(RigidBodyHandle::invalid()),
+            native_collider: Cell::new(ColliderHandle::invalid()),
+        }
+    }
+}
+
+impl TypeUuidProvider for CharacterController {
+    fn type_uuid() -> Uuid {
+        uuid!("133f4302-824c-48da-9df0-564718e0a57a")
+    }
+}
+
+impl CharacterController {
+    /// Sets the radius of the capsule of the character.
+    pub fn set_radius(&mut self, radius: f32) -> f32 {
+        self.radius.set_value_and_mark_modified(radius.max(0.0))
+    }
+
+    /// Returns the radius of the capsule of the character.
+    pub fn radius(&self) -> f32 {
+        *self.radius
+    }
+
+    /// Sets the half height of the cylindrical part of the capsule of the character. Full height of
+    /// the capsule is `2.0 * (half_height + radius)`.
+    pub fn set_half_height(&mut self, half_height: f32) -> f32 {
+        self.half_height
+            .set_value_and_mark_modified(half_height.max(0.0))
+    }
+
+    /// Returns the half height of the cylindrical part of the capsule of the character.
+    pub fn half_height(&self) -> f32 {
+        *self.half_height
+    }
+
+    /// Sets a small gap to preserve between the character and its surroundings. It must be small
+    /// positive value, that prevents numerical issues when the character is very close to obstacles.
+    pub fn set_offset(&mut self, offset: f32) -> f32 {
+        self.offset.set_value_and_mark_modified(offset.max(0.0))
+    }
+
+    /// Returns current offset.
+    pub fn offset(&self) -> f32 {
+        *self.offset
+    }
+
+    /// Sets whether the character should slide along obstacles or stop when hitting them.
+    pub fn set_slide(&mut self, slide: bool) -> bool {
+        self.slide.set_value_and_mark_modified(slide)
+    }
+
+    /// Returns true if the character slides along obstacles, false - otherwise.
+    pub fn is_slide(&self) -> bool {
+        *self.slide
+    }
+
+    /// Sets maximum height of a step the character can climb. Zero disables step climbing.
+    pub fn set_max_step_height(&mut self, height: f32) -> f32 {
+        self.max_step_height
+            .set_value_and_mark_modified(height.max(0.0))
+    }
+
+    /// Returns maximum height of a step the character can climb.
+    pub fn max_step_height(&self) -> f32 {
+        *self.max_step_height
+    }
+
+    /// Sets minimum width of free space that must be available after climbing a step.
+    pub fn set_min_step_width(&mut self, width: f32) -> f32 {
+        self.min_step_width
+            .set_value_and_mark_modified(width.max(0.0))
+    }
+
+    /// Returns minimum width of free space that must be available after climbing a step.
+    pub fn min_step_width(&self) -> f32 {
+        *self.min_step_width
+    }
+
+    /// Sets whether the character can climb on dynamic rigid bodies or not.
+    pub fn set_step_on_dynamic_bodies(&mut self, enabled: bool) -> bool {
+        self.step_on_dynamic_bodies
+            .set_value_and_mark_modified(enabled)
+    }
+
+    /// Returns true if the character can climb on dynamic rigid bodies, false - otherwise.
+    pub fn is_step_on_dynamic_bodies(&self) -> bool {
+        *self.step_on_dynamic_bodies
+    }
+
+    /// Sets maximum angle (in radians) of a slope the character can climb.
+    pub fn set_max_slope_climb_angle(&mut self, angle: f32) -> f32 {
+        self.max_slope_climb_angle
+            .set_value_and_mark_modified(angle)
+    }
+
+    /// Returns maximum angle (in radians) of a slope the character can climb.
+    pub fn max_slope_climb_angle(&self) -> f32 {
+        *self.max_slope_climb_angle
+    }
+
+    /// Sets minimum angle (in radians) of a slope the character will slide down automatically.
+    pub fn set_min_slope_slide_angle(&mut self, angle: f32) -> f32 {
+        self.min_slope_slide_angle
+            .set_value_and_mark_modified(angle)
+    }
+
+    /// Returns minimum angle (in radians) of a slope the character will slide down automatically.
+    pub fn min_slope_slide_angle(&self) -> f32 {
+        *self.min_slope_slide_angle
+    }
+
+    /// Sets maximum distance to the ground at which the character will be snapped to it. It prevents
+    /// the character from "flying" when moving down a slope or stairs. Zero disables snapping.
+    pub fn set_snap_to_ground(&mut self, distance: f32) -> f32 {
+        self.snap_to_ground
+            .set_value_and_mark_modified(distance.max(0.0))
+    }
+
+    /// Returns maximum distance to the ground at which the character will be snapped to it.
+    pub fn snap_to_ground(&self) -> f32 {
+        *self.snap_to_ground
+    }
+
+    /// Sets whether the character should push dynamic rigid bodies or not.
+    pub fn set_push_dynamic_bodies(&mut self, enabled: bool) -> bool {
+        self.push_dynamic_bodies
+            .set_value_and_mark_modified(enabled)
+    }
+
+    /// Returns true if the character pushes dynamic rigid bodies, false - otherwise.
+    pub fn is_push_dynamic_bodies(&self) -> bool {
+        *self.push_dynamic_bodies
+    }
+
+    /// Sets mass of the character, it is used to calculate impulses applied to dynamic rigid bodies.
+    pub fn set_mass(&mut self, mass: f32) -> f32 {
+        self.mass.set_value_and_mark_modified(mass.max(0.0))
+    }
+
+    /// Returns mass of the character.
+    pub fn mass(&self) -> f32 {
+        *self.mass
+    }
+
+    /// Sets new collision filtering options. See [`InteractionGroups`] docs for more info.
+    pub fn set_collision_groups(&mut self, groups: InteractionGroups) -> InteractionGroups {
+        self.collision_groups.set_value_and_mark_modified(groups)
+    }
+
+    /// Returns current collision filtering options.
+    pub fn collision_groups(&self) -> InteractionGroups {
+        *self.collision_groups
+    }
+
+    /// Sets desired velocity of the character. The character will try to move by `velocity * dt`
+    /// on each update, resolving collisions with other colliders.
+    pub fn set_desired_velocity(&mut self, velocity: Vector2<f32>) {
+        self.desired_velocity = velocity;
+    }
+
+    /// Returns desired velocity of the character.
+    pub fn desired_velocity(&self) -> Vector2<f32> {
+        self.desired_velocity
+    }
+
+    /// Returns collision state of the character after last movement.
+    pub fn state(&self) -> &CharacterControllerState {
+        &self.state
+    }
+
+    /// Returns true if the character is standing on the ground.
+    pub fn is_grounded(&self) -> bool {
+        self.state.grounded
+    }
+
+    /// Returns true if the character hit a ceiling during the last movement.
+    pub fn is_touching_ceiling(&self) -> bool {
+        self.state.touching_ceiling
+    }
+
+    /// Returns true if the character hit a wall during the last movement.
+    pub fn is_touching_wall(&self) -> bool {
+        self.state.touching_wall
+    }
+
+    pub(crate) fn need_sync_shape(&self) -> bool {
+        self.radius.need_sync() || self.half_height.need_sync()
+    }
+}
+
+impl NodeTrait for CharacterController {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        let half_extents = Vector3::new(*self.radius, *self.half_height + *self.radius, 0.0);
+        AxisAlignedBoundingBox::from_min_max(-half_extents, half_extents)
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn on_removed_from_graph(&mut self, graph: &mut Graph) {
+        graph.physics2d.remove_body(self.native_body.get());
+        self.native_body.set(RigidBodyHandle::invalid());
+        self.native_collider.set(ColliderHandle::invalid());
+
+        Log::info(format!(
+            "Native character controller was removed for node: {}",
+            self.name()
+        ));
+    }
+
+    fn sync_native(&self, self_handle: Handle<Node>, context: &mut SyncContext) {
+        context
+            .physics2d
+            .sync_to_character_controller_node(self_handle, self);
+    }
+
+    fn sync_transform(&self, new_global_transform: &Matrix4<f32>, context: &mut SyncContext) {
+        if !m4x4_approx_eq(new_global_transform, &self.global_transform()) {
+            context
+                .physics2d
+                .set_character_controller_position(self, new_global_transform);
+        }
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        context.physics2d.move_character_controller(
+            self,
+            context.dt,
+            // Character controller 2D can be root node of a scene, in this case it does not have a parent.
+            context
+                .nodes
+                .try_borrow(self.parent)
+                .map(|p| p.global_transform())
+                .unwrap_or_else(Matrix4::identity),
+        );
+    }
+}
+
+/// Allows you to create character controller in declarative manner.
+pub struct CharacterControllerBuilder {
+    base_builder: BaseBuilder,
+    radius: f32,
+    half_height: f32,
+    offset: f32,
+    slide: bool,
+    max_step_height: f32,
+    min_step_width: f32,
+    step_on_dynamic_bodies: bool,
+    max_slope_climb_angle: f32,
+    min_slope_slide_angle: f32,
+    snap_to_ground: f32,
+    push_dynamic_bodies: bool,
+    mass: f32,
+    collision_groups: InteractionGroups,
+}
+
+impl CharacterControllerBuilder {
+    /// Creates new character controller builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            radius: 0.3,
+            half_height: 0.6,
+            offset: 0.01,
+            slide: true,
+            max_step_height: 0.3,
+            min_step_width: 0.2,
+            step_on_dynamic_bodies: false,
+            max_slope_climb_angle: 45.0f32.to_radians(),
+            min_slope_slide_angle: 30.0f32.to_radians(),
+            snap_to_ground: 0.2,
+            push_dynamic_bodies: true,
+            mass: 80.0,
+            collision_groups: Default::default(),
+        }
+    }
+
+    /// Sets desired radius of the capsule.
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets desired half height of the cylindrical part of the capsule.
+    pub fn with_half_height(mut self, half_height: f32) -> Self {
+        self.half_height = half_height;
+        self
+    }
+
+    /// Sets desired offset (gap) between the character and its surroundings.
+    pub fn with_offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Sets whether the character should slide along obstacles or not.
+    pub fn with_slide(mut self, slide: bool) -> Self {
+        self.slide = slide;
+        self
+    }
+
+    /// Sets desired maximum height of a step the character can climb.
+    pub fn with_max_step_height(mut self, height: f32) -> Self {
+        self.max_step_height = height;
+        self
+    }
+
+    /// Sets desired minimum width of free space that must be available after climbing a step.
+    pub fn with_min_step_width(mut self, width: f32) -> Self {
+        self.min_step_width = width;
+        self
+    }
+
+    /// Sets whether the character can climb on dynamic rigid bodies or not.
+    pub fn with_step_on_dynamic_bodies(mut self, enabled: bool) -> Self {
+        self.step_on_dynamic_bodies = enabled;
+        self
+    }
+
+    /// Sets desired maximum angle (in radians) of a slope the character can climb.
+    pub fn with_max_slope_climb_angle(mut self, angle: f32) -> Self {
+        self.max_slope_climb_angle = angle;
+        self
+    }
+
+    /// Sets desired minimum angle (in radians) of a slope the character will slide down.
+    pub fn with_min_slope_slide_angle(mut self, angle: f32) -> Self {
+        self.min_slope_slide_angle = angle;
+        self
+    }
+
+    /// Sets desired snap-to-ground distance.
+    pub fn with_snap_to_ground(mut self, distance: f32) -> Self {
+        self.snap_to_ground = distance;
+        self
+    }
+
+    /// Sets whether the character should push dynamic rigid bodies or not.
+    pub fn with_push_dynamic_bodies(mut self, enabled: bool) -> Self {
+        self.push_dynamic_bodies = enabled;
+        self
+    }
+
+    /// Sets desired mass of the character.
+    pub fn with_mass(mut self, mass: f32) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    /// Sets desired collision groups.
+    pub fn with_collision_groups(mut self, groups: InteractionGroups) -> Self {
+        self.collision_groups = groups;
+        self
+    }
+
+    /// Creates CharacterController node but does not add it to the graph.
+    pub fn build_character_controller(self) -> CharacterController {
+        CharacterController {
+            base: self.base_builder.build_base(),
+            radius: self.radius.into(),
+            half_height: self.half_height.into(),
+            offset: self.offset.into(),
+            slide: self.slide.into(),
+            max_step_height: self.max_step_height.into(),
+            min_step_width: self.min_step_width.into(),
+            step_on_dynamic_bodies: self.step_on_dynamic_bodies.into(),
+            max_slope_climb_angle: self.max_slope_climb_angle.into(),
+            min_slope_slide_angle: self.min_slope_slide_angle.into(),
+            snap_to_ground: self.snap_to_ground.into(),
+            push_dynamic_bodies: self.push_dynamic_bodies.into(),
+            mass: self.mass.into(),
+            collision_groups: self.collision_groups.into(),
+            desired_velocity: Default::default(),
+            state: Default::default(),
+            native_body: Cell::new(RigidBodyHandle::invalid()),
+            native_collider: Cell::new(ColliderHandle::invalid()),
+        }
+    }
+
+    /// Creates CharacterController node but does not add it to the graph.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_character_controller())
+    }
+
+    /// Creates CharacterController node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::scene::{
+        base::{
+            test::{check_inheritable_properties_equality, inherit_node_properties},
+            BaseBuilder,
+        },
+        dim2::character_controller::{CharacterController, CharacterControllerBuilder},
+    };
+
+    #[test]
+    fn test_character_controller_2d_inheritance() {
+        let parent = CharacterControllerBuilder::new(BaseBuilder::new())
+            .with_radius(0.5)
+            .with_half_height(1.0)
+            .with_offset(0.02)
+            .with_slide(false)
+            .with_max_step_height(0.5)
+            .with_min_step_width(0.1)
+            .with_step_on_dynamic_bodies(true)
+            .with_max_slope_climb_angle(0.5)
+            .with_min_slope_slide_angle(0.4)
+            .with_snap_to_ground(0.0)
+            .with_push_dynamic_bodies(false)
+            .with_mass(100.0)
+            .build_node();
+
+        let mut child =
+            CharacterControllerBuilder::new(BaseBuilder::new()).build_character_controller();
+
+        inherit_node_properties(&mut child, &parent);
+
+        let parent = parent.cast::<CharacterController>().unwrap();
+
+        check_inheritable_properties_equality(&child, parent);
+    }
+}
@@ -257,6 +257,10 @@ pub struct Collider {
     #[reflect(setter = "set_restitution_combine_rule")]
     pub(crate) restitution_combine_rule: InheritableVariable<CoefficientCombineRule>,
 
+    #[visit(optional)]
+    #[reflect(setter = "set_contact_force_event_threshold")]
+    pub(crate) contact_force_event_threshold: InheritableVariable<Option<f32>>,
+
     #[visit(skip)]
     #[reflect(hidden)]
     pub(crate) native: Cell<ColliderHandle>,
@@ -275,6 +279,7 @@ impl Default for Collider {
             solver_groups: Default::default(),
             friction_combine_rule: Default::default(),
             restitution_combine_rule: Default::default(),
+            contact_force_event_threshold: InheritableVariable::new(None),
             native: Cell::new(ColliderHandle::invalid()),
         }
     }
@@ -307,6 +312,7 @@ impl Clone for Collider {
             solver_groups: self.solver_groups.clone(),
             friction_combine_rule: self.friction_combine_rule.clone(),
             restitution_combine_rule: self.restitution_combine_rule.clone(),
+            contact_force_event_threshold: self.contact_force_event_threshold.clone(),
             // Do not copy. The copy will have its own native representation.
             native: Cell::new(ColliderHandle::invalid()),
         }
@@ -499,6 +505,20 @@ impl Collider {
         *self.restitution_combine_rule
     }
 
+    /// Sets the new contact force event threshold. When set to `Some(threshold)`, the physics world
+    /// will produce a [`crate::scene::graph::physics::ContactForceEvent`] each time the total force
+    /// magnitude of a contact of the collider exceeds the threshold. `None` disables contact force
+    /// events for the collider.
+    pub fn set_contact_force_event_threshold(&mut self, threshold: Option<f32>) -> Option<f32> {
+        self.contact_force_event_threshold
+            .set_value_and_mark_modified(threshold)
+    }
+
+    /// Returns current contact force event threshold of the collider.
+    pub fn contact_force_event_threshold(&self) -> Option<f32> {
+        *self.contact_force_event_threshold
+    }
+
     /// Returns an iterator that yields contact information for the collider.
     /// Contacts checks between two regular colliders
     pub fn contacts<'a>(
@@ -527,6 +547,7 @@ impl Collider {
             || self.solver_groups.need_sync()
             || self.friction_combine_rule.need_sync()
             || self.restitution_combine_rule.need_sync()
+            || self.contact_force_event_threshold.need_sync()
     }
 }
 
@@ -591,6 +612,7 @@ pub struct ColliderBuilder {
     solver_groups: InteractionGroups,
     friction_combine_rule: CoefficientCombineRule,
     restitution_combine_rule: CoefficientCombineRule,
+    contact_force_event_threshold: Option<f32>,
 }
 
 impl ColliderBuilder {
@@ -607,6 +629,7 @@ impl ColliderBuilder {
             solver_groups: Default::default(),
             friction_combine_rule: Default::default(),
             restitution_combine_rule: Default::default(),
+            contact_force_event_threshold: None,
         }
     }
 
@@ -664,6 +687,13 @@ impl ColliderBuilder {
         self
     }
 
+    /// Sets desired contact force event threshold. See [`Collider::set_contact_force_event_threshold`]
+    /// for more info.
+    pub fn with_contact_force_event_threshold(mut self, threshold: Option<f32>) -> Self {
+        self.contact_force_event_threshold = threshold;
+        self
+    }
+
     /// Creates collider node, but does not add it to a graph.
     pub fn build_collider(self) -> Collider {
         Collider {
@@ -677,6 +707,7 @@ impl ColliderBuilder {
             solver_groups: self.solver_groups.into(),
             friction_combine_rule: self.friction_combine_rule.into(),
             restitution_combine_rule: self.restitution_combine_rule.into(),
+            contact_force_event_threshold: self.contact_force_event_threshold.into(),
             native: Cell::new(ColliderHandle::invalid()),
         }
     }
@@ -721,6 +752,7 @@ mod test {
             .with_friction_combine_rule(CoefficientCombineRule::Max)
             .with_collision_groups(InteractionGroups::new(BitMask(1), BitMask(2)))
             .with_solver_groups(InteractionGroups::new(BitMask(1), BitMask(2)))
+            .with_contact_force_event_threshold(Some(10.0))
             .build_node();
 
         let mut child = ColliderBuilder::new(BaseBuilder::new()).build_collider();
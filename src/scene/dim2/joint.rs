@@ -14,6 +14,7 @@ use crate::{
         base::{Base, BaseBuilder},
         dim2::rigidbody::RigidBody,
         graph::Graph,
+        joint::{JointMotor, RopeJoint, SpringJoint},
         node::{Node, NodeTrait, SyncContext, TypeUuidProvider},
         Scene,
     },
@@ -40,6 +41,11 @@ pub struct BallJoint {
     #[reflect(description = "Allowed angles range for the joint (in radians).")]
     #[visit(optional)] // Backward compatibility
     pub limits_angles: Range<f32>,
+
+    /// A motor that drives relative rotation of the bodies.
+    #[reflect(description = "A motor that drives relative rotation of the bodies.")]
+    #[visit(optional)] // Backward compatibility
+    pub motor: JointMotor,
 }
 
 impl Default for BallJoint {
@@ -47,6 +53,7 @@ impl Default for BallJoint {
         Self {
             limits_enabled: false,
             limits_angles: -std::f32::consts::PI..std::f32::consts::PI,
+            motor: Default::default(),
         }
     }
 }
@@ -71,6 +78,13 @@ pub struct PrismaticJoint {
     #[reflect(description = "Allowed linear distance range along local X axis of the joint.")]
     #[visit(optional)] // Backward compatibility
     pub limits: Range<f32>,
+
+    /// A motor that drives relative translation along local X axis of the joint.
+    #[reflect(
+        description = "A motor that drives relative translation along local X axis of the joint."
+    )]
+    #[visit(optional)] // Backward compatibility
+    pub motor: JointMotor,
 }
 
 impl Default for PrismaticJoint {
@@ -78,6 +92,7 @@ impl Default for PrismaticJoint {
         Self {
             limits_enabled: false,
             limits: -std::f32::consts::PI..std::f32::consts::PI,
+            motor: Default::default(),
         }
     }
 }
@@ -91,6 +106,10 @@ pub enum JointParams {
     FixedJoint(FixedJoint),
     /// See [`PrismaticJoint`] for more info.
     PrismaticJoint(PrismaticJoint),
+    /// See [`RopeJoint`] for more info.
+    RopeJoint(RopeJoint),
+    /// See [`SpringJoint`] for more info.
+    SpringJoint(SpringJoint),
 }
 
 impl Default for JointParams {
@@ -22,25 +22,35 @@ use crate::{
         debug::SceneDrawingContext,
         dim2::{self, collider::ColliderShape, joint::JointParams, rigidbody::ApplyAction},
         graph::{
-            physics::{FeatureId, IntegrationParameters, PhysicsPerformanceStatistics},
+            physics::{
+                CollisionEvent, ContactForceEvent, FeatureId, IntegrationParameters,
+                PhysicsPerformanceStatistics, QueryFilter, ShapeCastStatus,
+            },
             NodePool,
         },
+        joint::{JointMotor, SpringJoint},
         node::{Node, NodeTrait},
     },
     utils::log::{Log, MessageKind},
 };
+use fxhash::FxHashMap;
 use rapier2d::{
+    control::{CharacterAutostep, CharacterLength, KinematicCharacterController},
     dynamics::{
-        CCDSolver, GenericJoint, GenericJointBuilder, ImpulseJointHandle, ImpulseJointSet,
-        IslandManager, JointAxesMask, JointAxis, MultibodyJointHandle, MultibodyJointSet,
-        RigidBody, RigidBodyActivation, RigidBodyBuilder, RigidBodyHandle, RigidBodySet,
-        RigidBodyType,
+        CCDSolver, GenericJoint, GenericJointBuilder, ImpulseJoint, ImpulseJointHandle,
+        ImpulseJointSet, IslandManager, JointAxesMask, JointAxis, MultibodyJointHandle,
+        MultibodyJointSet, RigidBody, RigidBodyActivation, RigidBodyBuilder, RigidBodyHandle,
+        RigidBodySet, RigidBodyType,
     },
     geometry::{
-        BroadPhase, Collider, ColliderBuilder, ColliderHandle, ColliderSet, Cuboid,
-        InteractionGroups, NarrowPhase, Ray, SharedShape,
+        ActiveCollisionTypes, BroadPhase, Collider, ColliderBuilder, ColliderHandle, ColliderSet,
+        CollisionEvent as NativeCollisionEvent, ContactForceEvent as NativeContactForceEvent,
+        ContactPair as NativeContactPair, Cuboid, InteractionGroups, NarrowPhase, Ray, SharedShape,
+    },
+    pipeline::{
+        ActiveEvents, DebugRenderPipeline, EventHandler, PhysicsPipeline,
+        QueryFilter as NativeQueryFilter, QueryPipeline,
     },
-    pipeline::{DebugRenderPipeline, EventHandler, PhysicsPipeline, QueryFilter, QueryPipeline},
 };
 use std::{
     cell::RefCell,
@@ -145,6 +155,130 @@ pub struct RayCastOptions {
     pub sort_results: bool,
 }
 
+/// A set of options for the shape cast.
+pub struct ShapeCastOptions {
+    /// A shape to cast. Trimeshes and height fields are not supported.
+    pub shape: ColliderShape,
+
+    /// Initial position of the shape in world coordinates.
+    pub shape_position: Vector2<f32>,
+
+    /// Rotation angle (in radians) of the shape in world coordinates.
+    pub shape_rotation: f32,
+
+    /// A direction of the cast. Can be non-normalized.
+    pub direction: Vector2<f32>,
+
+    /// Maximum distance of cast.
+    pub max_len: f32,
+
+    /// If true, the shape cast will stop immediately if the shape is penetrating another collider
+    /// at its initial position. Otherwise such colliders will be ignored if the shape moves away
+    /// from them.
+    pub stop_at_penetration: bool,
+
+    /// Filtering options.
+    pub filter: QueryFilter,
+}
+
+/// A shape cast result.
+#[derive(Debug, Clone)]
+pub struct ShapeCastResult {
+    /// A handle of the first collider hit by the shape.
+    pub collider: Handle<Node>,
+
+    /// Distance traveled by the shape before the hit.
+    pub toi: f32,
+
+    /// A point on the collider at the time of impact, in world coordinates.
+    pub witness1: Point2<f32>,
+
+    /// A point on the casted shape at the time of impact, in world coordinates.
+    pub witness2: Point2<f32>,
+
+    /// A normal of the collider at `witness1`, in world coordinates.
+    pub normal1: Vector2<f32>,
+
+    /// A normal of the casted shape at `witness2`, in world coordinates.
+    pub normal2: Vector2<f32>,
+
+    /// Status of the cast.
+    pub status: ShapeCastStatus,
+}
+
+/// A set of options for the shape intersection test.
+pub struct ShapeIntersectionOptions {
+    /// A shape to test. Trimeshes and height fields are not supported.
+    pub shape: ColliderShape,
+
+    /// Position of the shape in world coordinates.
+    pub shape_position: Vector2<f32>,
+
+    /// Rotation angle (in radians) of the shape in world coordinates.
+    pub shape_rotation: f32,
+
+    /// Filtering options.
+    pub filter: QueryFilter,
+}
+
+/// A point projection result.
+#[derive(Debug, Clone)]
+pub struct PointProjection {
+    /// A handle of the collider on which the point was projected.
+    pub collider: Handle<Node>,
+
+    /// Projected point in world coordinates.
+    pub point: Point2<f32>,
+
+    /// Whether the point was inside the collider or not.
+    pub is_inside: bool,
+}
+
+// Collects native physics events during a simulation step. Rapier calls the handler from the
+// solver, so the storage must be thread-safe.
+#[derive(Default)]
+struct EventCollector {
+    collision_events: Mutex<Vec<NativeCollisionEvent>>,
+    contact_force_events: Mutex<Vec<NativeContactForceEvent>>,
+}
+
+impl EventHandler for EventCollector {
+    fn handle_collision_event(
+        &self,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        event: NativeCollisionEvent,
+        _contact_pair: Option<&NativeContactPair>,
+    ) {
+        self.collision_events.lock().push(event);
+    }
+
+    fn handle_contact_force_event(
+        &self,
+        dt: f32,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        contact_pair: &NativeContactPair,
+        total_force_magnitude: f32,
+    ) {
+        self.contact_force_events
+            .lock()
+            .push(NativeContactForceEvent::from_contact_pair(
+                dt,
+                contact_pair,
+                total_force_magnitude,
+            ));
+    }
+}
+
+fn active_events(contact_force_event_threshold: Option<f32>) -> ActiveEvents {
+    if contact_force_event_threshold.is_some() {
+        ActiveEvents::COLLISION_EVENTS | ActiveEvents::CONTACT_FORCE_EVENTS
+    } else {
+        ActiveEvents::COLLISION_EVENTS
+    }
+}
+
 /// Data of the contact.
 pub struct ContactData {
     /// The contact point in the local-space of the first shape.
@@ -208,6 +342,19 @@ where
     map: BiDirHashMap<A, Handle<Node>>,
 }
 
+fn apply_joint_motor(joint: &mut GenericJoint, axis: JointAxis, motor: &JointMotor) {
+    if motor.enabled {
+        joint.set_motor(
+            axis,
+            motor.target_position,
+            motor.target_velocity,
+            motor.stiffness,
+            motor.damping,
+        );
+        joint.set_motor_max_force(axis, motor.max_force);
+    }
+}
+
 fn convert_joint_params(
     params: scene::dim2::joint::JointParams,
     local_frame1: Isometry2<f32>,
@@ -217,6 +364,7 @@ fn convert_joint_params(
         JointParams::BallJoint(_) => JointAxesMask::LOCKED_REVOLUTE_AXES,
         JointParams::FixedJoint(_) => JointAxesMask::LOCKED_FIXED_AXES,
         JointParams::PrismaticJoint(_) => JointAxesMask::LOCKED_PRISMATIC_AXES,
+        JointParams::RopeJoint(_) | JointParams::SpringJoint(_) => JointAxesMask::empty(),
     };
 
     let mut joint = GenericJointBuilder::new(locked_axis)
@@ -232,18 +380,79 @@ fn convert_joint_params(
                     [v.limits_angles.start, v.limits_angles.end],
                 );
             }
+            apply_joint_motor(&mut joint, JointAxis::AngX, &v.motor);
         }
         scene::dim2::joint::JointParams::FixedJoint(_) => {}
         scene::dim2::joint::JointParams::PrismaticJoint(v) => {
             if v.limits_enabled {
                 joint.set_limits(JointAxis::X, [v.limits.start, v.limits.end]);
             }
+            apply_joint_motor(&mut joint, JointAxis::X, &v.motor);
+        }
+        scene::dim2::joint::JointParams::RopeJoint(v) => {
+            // Coupled linear axes make the solver treat the limit as a limit of the distance
+            // between the anchors.
+            joint.coupled_axes = JointAxesMask::X | JointAxesMask::Y;
+            joint.set_limits(JointAxis::X, [0.0, v.max_distance]);
+        }
+        scene::dim2::joint::JointParams::SpringJoint(_) => {
+            // Rapier does not support coupled linear motors, so the joint does not restrict the
+            // motion of the bodies and the spring is simulated by `apply_spring_impulses`.
         }
     }
 
     joint
 }
 
+fn sync_spring(
+    springs: &mut FxHashMap<ImpulseJointHandle, SpringJoint>,
+    handle: ImpulseJointHandle,
+    params: &JointParams,
+) {
+    if let JointParams::SpringJoint(spring) = params {
+        springs.insert(handle, spring.clone());
+    } else {
+        springs.remove(&handle);
+    }
+}
+
+// Applies damped spring force between anchors of the bodies of the given joint for one simulation
+// step.
+fn apply_spring_impulses(
+    joint: &ImpulseJoint,
+    spring: &SpringJoint,
+    bodies: &mut RigidBodySet,
+    dt: f32,
+) {
+    let (body1, body2) = match (bodies.get(joint.body1), bodies.get(joint.body2)) {
+        (Some(body1), Some(body2)) => (body1, body2),
+        _ => return,
+    };
+
+    let anchor1 = body1.position() * Point2::from(joint.data.local_frame1.translation.vector);
+    let anchor2 = body2.position() * Point2::from(joint.data.local_frame2.translation.vector);
+    let delta = anchor2 - anchor1;
+    let distance = delta.norm();
+    if distance <= f32::EPSILON {
+        return;
+    }
+    let direction = delta.scale(1.0 / distance);
+
+    let relative_velocity =
+        (body2.velocity_at_point(&anchor2) - body1.velocity_at_point(&anchor1)).dot(&direction);
+    // Positive force pulls the bodies towards each other.
+    let force =
+        spring.stiffness * (distance - spring.rest_length) + spring.damping * relative_velocity;
+    let impulse = direction.scale(force * dt);
+
+    if let Some(body1) = bodies.get_mut(joint.body1) {
+        body1.apply_impulse_at_point(impulse, anchor1, true);
+    }
+    if let Some(body2) = bodies.get_mut(joint.body2) {
+        body2.apply_impulse_at_point(-impulse, anchor2, true);
+    }
+}
+
 // Converts descriptor in a shared shape.
 fn collider_shape_into_native_shape(shape: &ColliderShape) -> Option<SharedShape> {
     match shape {
@@ -340,10 +549,14 @@ pub struct PhysicsWorld {
     #[visit(skip)]
     #[reflect(hidden)]
     multibody_joints: Container<MultibodyJointSet, MultibodyJointHandle>,
+    // Parameters of impulse joints that are simulated as springs.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    springs: FxHashMap<ImpulseJointHandle, SpringJoint>,
     // Event handler collects info about contacts and proximity events.
     #[visit(skip)]
     #[reflect(hidden)]
-    event_handler: Box<dyn EventHandler>,
+    event_handler: EventCollector,
     #[visit(skip)]
     #[reflect(hidden)]
     query: RefCell<QueryPipeline>,
@@ -408,7 +621,8 @@ impl PhysicsWorld {
                 set: MultibodyJointSet::new(),
                 map: Default::default(),
             },
-            event_handler: Box::new(()),
+            springs: Default::default(),
+            event_handler: Default::default(),
             query: RefCell::new(Default::default()),
             performance_statistics: Default::default(),
             debug_render_pipeline: Default::default(),
@@ -446,6 +660,17 @@ impl PhysicsWorld {
                 max_ccd_substeps: self.integration_parameters.max_ccd_substeps as usize,
             };
 
+            for (handle, spring) in self.springs.iter() {
+                if let Some(joint) = self.joints.set.get(*handle) {
+                    apply_spring_impulses(
+                        joint,
+                        spring,
+                        &mut self.bodies.set,
+                        integration_parameters.dt,
+                    );
+                }
+            }
+
             self.pipeline.step(
                 &self.gravity,
                 &integration_parameters,
@@ -461,13 +686,55 @@ impl PhysicsWorld {
                 // so we keep updating it manually.
                 None,
                 &(),
-                &*self.event_handler,
+                &self.event_handler,
             );
         }
 
         self.performance_statistics.step_time += instant::Instant::now() - time;
     }
 
+    // Moves events collected during the last simulation step to the given storages. Native handles
+    // of colliders are converted to handles of respective scene nodes.
+    pub(crate) fn take_events(
+        &mut self,
+        collision_events: &mut Vec<CollisionEvent>,
+        contact_force_events: &mut Vec<ContactForceEvent>,
+    ) {
+        let colliders = &self.colliders.map;
+        let node_of =
+            |native: ColliderHandle| colliders.value_of(&native).cloned().unwrap_or_default();
+
+        collision_events.extend(self.event_handler.collision_events.get_mut().drain(..).map(
+            |event| {
+                CollisionEvent::new(
+                    node_of(event.collider1()),
+                    node_of(event.collider2()),
+                    event.started(),
+                    event.sensor(),
+                )
+            },
+        ));
+
+        contact_force_events.extend(
+            self.event_handler
+                .contact_force_events
+                .get_mut()
+                .drain(..)
+                .map(|event| ContactForceEvent {
+                    collider1: node_of(event.collider1),
+                    collider2: node_of(event.collider2),
+                    total_force: Vector3::new(event.total_force.x, event.total_force.y, 0.0),
+                    total_force_magnitude: event.total_force_magnitude,
+                    max_force_direction: Vector3::new(
+                        event.max_force_direction.x,
+                        event.max_force_direction.y,
+                        0.0,
+                    ),
+                    max_force_magnitude: event.max_force_magnitude,
+                }),
+        );
+    }
+
     pub(crate) fn add_body(&mut self, owner: Handle<Node>, body: RigidBody) -> RigidBodyHandle {
         let handle = self.bodies.set.insert(body);
         self.bodies.map.insert(handle, owner);
@@ -533,6 +800,7 @@ impl PhysicsWorld {
     }
 
     pub(crate) fn remove_joint(&mut self, handle: ImpulseJointHandle) {
+        self.springs.remove(&handle);
         if self.joints.set.remove(handle, false).is_some() {
             assert!(self.joints.map.remove_by_key(&handle).is_some());
         }
@@ -577,7 +845,7 @@ impl PhysicsWorld {
             &ray,
             opts.max_len,
             true,
-            QueryFilter::new().groups(InteractionGroups::new(
+            NativeQueryFilter::new().groups(InteractionGroups::new(
                 u32_to_group(opts.groups.memberships.0),
                 u32_to_group(opts.groups.filter.0),
             )),
@@ -609,6 +877,168 @@ impl PhysicsWorld {
         );
     }
 
+    // Prepares query pipeline and native query filter and passes them to the given closure.
+    fn with_query<R, F>(&self, filter: &QueryFilter, func: F) -> R
+    where
+        F: FnOnce(&QueryPipeline, NativeQueryFilter) -> R,
+    {
+        let mut query = self.query.borrow_mut();
+
+        // See `cast_ray` for more info.
+        query.update(&self.bodies.set, &self.colliders.set);
+
+        let predicate = |handle: ColliderHandle, collider: &Collider| {
+            if let Some(node) = self.colliders.map.value_of(&handle) {
+                if filter.exclude_colliders.contains(node) {
+                    return false;
+                }
+            }
+            if let Some(body) = collider
+                .parent()
+                .and_then(|body| self.bodies.map.value_of(&body))
+            {
+                if filter.exclude_rigid_bodies.contains(body) {
+                    return false;
+                }
+            }
+            true
+        };
+
+        let mut native_filter = NativeQueryFilter::new().groups(InteractionGroups::new(
+            u32_to_group(filter.groups.memberships.0),
+            u32_to_group(filter.groups.filter.0),
+        ));
+        if filter.exclude_sensors {
+            native_filter = native_filter.exclude_sensors();
+        }
+        if !filter.exclude_colliders.is_empty() || !filter.exclude_rigid_bodies.is_empty() {
+            native_filter = native_filter.predicate(&predicate);
+        }
+
+        func(&query, native_filter)
+    }
+
+    /// Sweeps a shape along the given direction and returns the first collider hit by the shape
+    /// (if any). Could be used to check whether a character can move in some direction or not,
+    /// to implement "thick" ray casts and so on.
+    pub fn cast_shape(&self, opts: ShapeCastOptions) -> Option<ShapeCastResult> {
+        let shape = collider_shape_into_native_shape(&opts.shape)?;
+        let shape_position = Isometry2 {
+            rotation: UnitComplex::new(opts.shape_rotation),
+            translation: Translation2 {
+                vector: opts.shape_position,
+            },
+        };
+        let direction = opts
+            .direction
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_default();
+
+        let (handle, toi) = self.with_query(&opts.filter, |query, filter| {
+            query.cast_shape(
+                &self.bodies.set,
+                &self.colliders.set,
+                &shape_position,
+                &direction,
+                &*shape,
+                opts.max_len,
+                opts.stop_at_penetration,
+                filter,
+            )
+        })?;
+
+        let shape_position_at_toi = Translation2::from(direction.scale(toi.toi)) * shape_position;
+
+        Some(ShapeCastResult {
+            collider: self.colliders.map.value_of(&handle).cloned()?,
+            toi: toi.toi,
+            witness1: toi.witness1,
+            witness2: shape_position_at_toi * toi.witness2,
+            normal1: *toi.normal1,
+            normal2: shape_position.rotation * *toi.normal2,
+            status: toi.status.into(),
+        })
+    }
+
+    /// Projects a point on the closest collider. If `solid` is true, then a point inside a collider
+    /// will be projected on itself, otherwise it will be projected on the boundary of the collider.
+    pub fn project_point(
+        &self,
+        point: Point2<f32>,
+        solid: bool,
+        filter: &QueryFilter,
+    ) -> Option<PointProjection> {
+        let (handle, projection) = self.with_query(filter, |query, filter| {
+            query.project_point(&self.bodies.set, &self.colliders.set, &point, solid, filter)
+        })?;
+
+        Some(PointProjection {
+            collider: self.colliders.map.value_of(&handle).cloned()?,
+            point: projection.point,
+            is_inside: projection.is_inside,
+        })
+    }
+
+    /// Searches for every collider that intersects with the given shape and passes its handle to
+    /// the given callback. The search stops if the callback returns `false`.
+    pub fn intersections_with_shape<C>(&self, opts: ShapeIntersectionOptions, mut callback: C)
+    where
+        C: FnMut(Handle<Node>) -> bool,
+    {
+        let shape = match collider_shape_into_native_shape(&opts.shape) {
+            Some(shape) => shape,
+            None => return,
+        };
+        let shape_position = Isometry2 {
+            rotation: UnitComplex::new(opts.shape_rotation),
+            translation: Translation2 {
+                vector: opts.shape_position,
+            },
+        };
+
+        self.with_query(&opts.filter, |query, filter| {
+            query.intersections_with_shape(
+                &self.bodies.set,
+                &self.colliders.set,
+                &shape_position,
+                &*shape,
+                filter,
+                |handle| {
+                    self.colliders
+                        .map
+                        .value_of(&handle)
+                        .map_or(true, |collider| callback(*collider))
+                },
+            )
+        })
+    }
+
+    /// Searches for every collider that contains the given point and passes its handle to the
+    /// given callback. The search stops if the callback returns `false`.
+    pub fn intersections_with_point<C>(
+        &self,
+        point: Point2<f32>,
+        filter: &QueryFilter,
+        mut callback: C,
+    ) where
+        C: FnMut(Handle<Node>) -> bool,
+    {
+        self.with_query(filter, |query, filter| {
+            query.intersections_with_point(
+                &self.bodies.set,
+                &self.colliders.set,
+                &point,
+                filter,
+                |handle| {
+                    self.colliders
+                        .map
+                        .value_of(&handle)
+                        .map_or(true, |collider| callback(*collider))
+                },
+            )
+        })
+    }
+
     pub(crate) fn set_rigid_body_position(
         &mut self,
         rigid_body: &scene::dim2::rigidbody::RigidBody,
@@ -624,6 +1054,223 @@ impl PhysicsWorld {
         }
     }
 
+    pub(crate) fn set_character_controller_position(
+        &mut self,
+        controller: &dim2::character_controller::CharacterController,
+        new_global_transform: &Matrix4<f32>,
+    ) {
+        if let Some(native) = self.bodies.set.get_mut(controller.native_body.get()) {
+            let new_position = isometry_from_global_transform(new_global_transform);
+            // The transform could be changed by the controller itself (see `move_character_controller`),
+            // in this case the body is already moving to the new position and teleporting it there
+            // would reset its velocity.
+            if (new_position.translation.vector - native.next_position().translation.vector).norm()
+                > 1.0e-5
+            {
+                native.set_position(new_position, false);
+            }
+        }
+    }
+
+    pub(crate) fn sync_to_character_controller_node(
+        &mut self,
+        handle: Handle<Node>,
+        controller: &dim2::character_controller::CharacterController,
+    ) {
+        if !controller.is_globally_enabled() {
+            self.remove_body(controller.native_body.get());
+            controller.native_body.set(Default::default());
+            controller.native_collider.set(Default::default());
+            return;
+        }
+
+        if controller.native_body.get() != RigidBodyHandle::invalid() {
+            if controller.need_sync_shape() || controller.collision_groups.need_sync() {
+                if let Some(native) = self.colliders.set.get_mut(controller.native_collider.get()) {
+                    if controller.need_sync_shape() {
+                        controller.radius.try_sync_model(|_| ());
+                        controller.half_height.try_sync_model(|_| ());
+                        native.set_shape(SharedShape::capsule_y(
+                            controller.half_height(),
+                            controller.radius(),
+                        ));
+                    }
+                    controller.collision_groups.try_sync_model(|v| {
+                        native.set_collision_groups(InteractionGroups::new(
+                            u32_to_group(v.memberships.0),
+                            u32_to_group(v.filter.0),
+                        ))
+                    });
+                }
+            }
+        } else {
+            let body = RigidBodyBuilder::kinematic_position_based()
+                .position(isometry_from_global_transform(
+                    &controller.global_transform(),
+                ))
+                .build();
+            let body_handle = self.add_body(handle, body);
+
+            let collider = ColliderBuilder::new(SharedShape::capsule_y(
+                controller.half_height(),
+                controller.radius(),
+            ))
+            .collision_groups(InteractionGroups::new(
+                u32_to_group(controller.collision_groups().memberships.0),
+                u32_to_group(controller.collision_groups().filter.0),
+            ))
+            // Kinematic bodies do not interact with fixed and other kinematic bodies by default,
+            // enable this explicitly so the controller will be able to produce collision events
+            // for static sensors (triggers) and level geometry.
+            .active_collision_types(ActiveCollisionTypes::all())
+            .active_events(ActiveEvents::COLLISION_EVENTS)
+            .build();
+            let collider_handle = self.add_collider(handle, body_handle, collider);
+
+            controller.native_body.set(body_handle);
+            controller.native_collider.set(collider_handle);
+
+            Log::writeln(
+                MessageKind::Information,
+                format!(
+                    "Native character controller 2D was created for node {}",
+                    controller.name()
+                ),
+            );
+        }
+    }
+
+    pub(crate) fn move_character_controller(
+        &mut self,
+        controller: &mut dim2::character_controller::CharacterController,
+        dt: f32,
+        parent_transform: Matrix4<f32>,
+    ) {
+        if !self.enabled {
+            return;
+        }
+
+        let body_handle = controller.native_body.get();
+        let (position, shape) = match (
+            self.bodies.set.get(body_handle),
+            self.colliders.set.get(controller.native_collider.get()),
+        ) {
+            (Some(body), Some(collider)) => (*body.position(), collider.shared_shape().clone()),
+            _ => return,
+        };
+
+        let native_controller = KinematicCharacterController {
+            up: Vector2::y_axis(),
+            offset: CharacterLength::Absolute(controller.offset()),
+            slide: controller.is_slide(),
+            autostep: if controller.max_step_height() > 0.0 {
+                Some(CharacterAutostep {
+                    max_height: CharacterLength::Absolute(controller.max_step_height()),
+                    min_width: CharacterLength::Absolute(controller.min_step_width()),
+                    include_dynamic_bodies: controller.is_step_on_dynamic_bodies(),
+                })
+            } else {
+                None
+            },
+            max_slope_climb_angle: controller.max_slope_climb_angle(),
+            min_slope_slide_angle: controller.min_slope_slide_angle(),
+            snap_to_ground: if controller.snap_to_ground() > 0.0 {
+                Some(CharacterLength::Absolute(controller.snap_to_ground()))
+            } else {
+                None
+            },
+        };
+
+        let groups = controller.collision_groups();
+        let filter = NativeQueryFilter::new()
+            .exclude_rigid_body(body_handle)
+            .groups(InteractionGroups::new(
+                u32_to_group(groups.memberships.0),
+                u32_to_group(groups.filter.0),
+            ));
+
+        let mut query = self.query.borrow_mut();
+        query.update(&self.bodies.set, &self.colliders.set);
+
+        let mut collisions = Vec::new();
+        let movement = native_controller.move_shape(
+            dt,
+            &self.bodies.set,
+            &self.colliders.set,
+            &query,
+            &*shape,
+            &position,
+            controller.desired_velocity.scale(dt),
+            filter,
+            |collision| collisions.push(collision),
+        );
+
+        if controller.is_push_dynamic_bodies() {
+            for collision in collisions.iter() {
+                native_controller.solve_character_collision_impulses(
+                    dt,
+                    &mut self.bodies.set,
+                    &self.colliders.set,
+                    &query,
+                    &*shape,
+                    controller.mass(),
+                    collision,
+                    filter,
+                );
+            }
+        }
+
+        drop(query);
+
+        // Classify collisions by their normals.
+        let max_slope_cos = controller.max_slope_climb_angle().cos();
+        let mut state = dim2::character_controller::CharacterControllerState {
+            grounded: movement.grounded,
+            translation: movement.translation,
+            ..Default::default()
+        };
+        for collision in collisions.iter() {
+            let motion = collision.translation_applied + collision.translation_remaining;
+            // Make sure that the normal is pointing towards the character.
+            let mut normal = *collision.toi.normal1;
+            if normal.dot(&motion) > 0.0 {
+                normal = -normal;
+            }
+            let cos = normal.dot(&Vector2::y());
+            if cos >= max_slope_cos {
+                state.grounded = true;
+            } else if cos <= -max_slope_cos {
+                state.touching_ceiling = true;
+            } else {
+                state.touching_wall = true;
+            }
+            if let Some(collider) = self.colliders.map.value_of(&collision.handle) {
+                if !state.collisions.contains(collider) {
+                    state.collisions.push(*collider);
+                }
+            }
+        }
+        controller.state = state;
+
+        let new_position = Translation2::from(movement.translation) * position;
+        if let Some(native) = self.bodies.set.get_mut(body_handle) {
+            // Kinematic bodies must be moved using their next position, this way the physics engine
+            // will calculate their velocity and will correctly resolve collisions with dynamic bodies.
+            native.set_next_kinematic_position(new_position);
+        }
+
+        let local_transform: Matrix4<f32> = parent_transform
+            .try_inverse()
+            .unwrap_or_else(Matrix4::identity)
+            * isometry2_to_mat4(&new_position);
+        let z = controller.local_transform().position().z;
+        controller.local_transform_mut().set_position(Vector3::new(
+            local_transform[12],
+            local_transform[13],
+            z,
+        ));
+    }
+
     pub(crate) fn sync_rigid_body_node(
         &mut self,
         rigid_body: &mut scene::dim2::rigidbody::RigidBody,
@@ -861,6 +1508,12 @@ impl PhysicsWorld {
                     collider_node
                         .restitution_combine_rule
                         .try_sync_model(|v| native.set_restitution_combine_rule(v.into()));
+                    collider_node
+                        .contact_force_event_threshold
+                        .try_sync_model(|v| {
+                            native.set_active_events(active_events(v));
+                            native.set_contact_force_event_threshold(v.unwrap_or_default());
+                        });
                 }
             }
         } else if let Some(parent_body) = nodes
@@ -891,7 +1544,13 @@ impl PhysicsWorld {
                             u32_to_group(collider_node.solver_groups().memberships.0),
                             u32_to_group(collider_node.solver_groups().filter.0),
                         ))
-                        .sensor(collider_node.is_sensor());
+                        .sensor(collider_node.is_sensor())
+                        .active_events(active_events(collider_node.contact_force_event_threshold()))
+                        .contact_force_event_threshold(
+                            collider_node
+                                .contact_force_event_threshold()
+                                .unwrap_or_default(),
+                        );
 
                     if let Some(density) = collider_node.density() {
                         builder = builder.density(density);
@@ -944,6 +1603,7 @@ impl PhysicsWorld {
                 }
             });
             joint.params.try_sync_model(|v| {
+                sync_spring(&mut self.springs, joint.native.get(), &v);
                 native.data =
                     // Preserve local frames.
                     convert_joint_params(v, native.data.local_frame1, native.data.local_frame2)
@@ -989,10 +1649,12 @@ impl PhysicsWorld {
                 assert!(self.bodies.set.get(native_body1).is_some());
                 assert!(self.bodies.set.get(native_body2).is_some());
 
-                let mut native_joint = convert_joint_params(params, local_frame1, local_frame2);
+                let mut native_joint =
+                    convert_joint_params(params.clone(), local_frame1, local_frame2);
                 native_joint.contacts_enabled = joint.is_contacts_enabled();
                 let native_handle =
                     self.add_joint(handle, native_body1, native_body2, native_joint);
+                sync_spring(&mut self.springs, native_handle, &params);
 
                 joint.native.set(native_handle);
                 joint.need_rebind.set(false);
@@ -1095,3 +1757,474 @@ impl Debug for PhysicsWorld {
         write!(f, "PhysicsWorld")
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Point2, Vector2, Vector3},
+            pool::Handle,
+        },
+        scene::{
+            base::BaseBuilder,
+            dim2::{
+                collider::{ColliderBuilder, ColliderShape},
+                joint::{BallJoint, JointBuilder, JointParams},
+                physics::{
+                    convert_joint_params, PhysicsWorld, QueryFilter, ShapeCastOptions,
+                    ShapeCastStatus, ShapeIntersectionOptions,
+                },
+                rigidbody::RigidBodyBuilder,
+            },
+            graph::{physics::CollisionEvent, Graph},
+            joint::{JointMotor, RopeJoint, SpringJoint},
+            node::Node,
+            rigidbody::RigidBodyType,
+            transform::TransformBuilder,
+        },
+    };
+    use rapier2d::{
+        dynamics::{JointAxesMask, JointAxis},
+        geometry::{ColliderHandle, CollisionEvent as NativeCollisionEvent, CollisionEventFlags},
+    };
+
+    // Creates a rigid body with a square collider, returns a handle of the collider.
+    fn make_square(
+        graph: &mut Graph,
+        position: Vector3<f32>,
+        body_type: RigidBodyType,
+        sensor: bool,
+    ) -> Handle<Node> {
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(0.5, 0.5))
+            .with_sensor(sensor)
+            .build(graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .build(),
+                )
+                .with_children(&[collider]),
+        )
+        .with_body_type(body_type)
+        .build(graph);
+        collider
+    }
+
+    #[test]
+    fn test_collision_events_2d() {
+        let mut graph = Graph::new();
+        graph.physics2d.gravity = Vector2::default();
+
+        let sensor = make_square(&mut graph, Vector3::default(), RigidBodyType::Static, true);
+        let other = make_square(
+            &mut graph,
+            Vector3::new(0.5, 0.0, 0.0),
+            RigidBodyType::Dynamic,
+            false,
+        );
+
+        let mut events = Vec::new();
+        for _ in 0..2 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+            events.extend_from_slice(graph.collision_events());
+        }
+
+        assert_eq!(events.len(), 1);
+        let event = events[0];
+        assert!(matches!(event, CollisionEvent::IntersectionStarted { .. }));
+        let colliders = event.colliders();
+        assert!(colliders == (sensor, other) || colliders == (other, sensor));
+    }
+
+    #[test]
+    fn test_collision_events_of_unknown_colliders_2d() {
+        let mut physics = PhysicsWorld::new();
+
+        physics
+            .event_handler
+            .collision_events
+            .get_mut()
+            .push(NativeCollisionEvent::Started(
+                ColliderHandle::from_raw_parts(123, 0),
+                ColliderHandle::from_raw_parts(321, 0),
+                CollisionEventFlags::empty(),
+            ));
+
+        let mut collision_events = Vec::new();
+        physics.take_events(&mut collision_events, &mut Vec::new());
+        assert_eq!(
+            collision_events,
+            vec![CollisionEvent::ContactStarted {
+                collider1: Handle::NONE,
+                collider2: Handle::NONE,
+            }]
+        );
+        assert!(physics.event_handler.collision_events.get_mut().is_empty());
+    }
+
+    fn make_cast_options(direction: Vector2<f32>, filter: QueryFilter) -> ShapeCastOptions {
+        ShapeCastOptions {
+            shape: ColliderShape::ball(0.5),
+            shape_position: Vector2::default(),
+            shape_rotation: 0.0,
+            direction,
+            max_len: 10.0,
+            stop_at_penetration: true,
+            filter,
+        }
+    }
+
+    #[test]
+    fn test_cast_shape_2d() {
+        let mut graph = Graph::new();
+
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(0.5, 0.5))
+            .build(&mut graph);
+        let body = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(5.0, 0.0, 0.0))
+                        .build(),
+                )
+                .with_children(&[collider]),
+        )
+        .with_body_type(RigidBodyType::Static)
+        .build(&mut graph);
+
+        // Bodies and colliders are created on first update, scene queries will "see" them only
+        // after the next update.
+        graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+        graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+
+        // Hit.
+        let result = graph
+            .physics2d
+            .cast_shape(make_cast_options(
+                Vector2::new(2.0, 0.0),
+                Default::default(),
+            ))
+            .unwrap();
+        assert_eq!(result.collider, collider);
+        assert!((result.toi - 4.0).abs() < 1.0e-4);
+        assert!((result.normal1 - Vector2::new(-1.0, 0.0)).norm() < 1.0e-4);
+        assert!((result.normal2 - Vector2::new(1.0, 0.0)).norm() < 1.0e-4);
+        assert!((result.witness1.x - 4.5).abs() < 1.0e-4);
+        assert!((result.witness2.x - 4.5).abs() < 1.0e-4);
+        assert_eq!(result.status, ShapeCastStatus::Converged);
+
+        // Miss - wrong direction.
+        assert!(graph
+            .physics2d
+            .cast_shape(make_cast_options(
+                Vector2::new(-1.0, 0.0),
+                Default::default()
+            ))
+            .is_none());
+
+        // Miss - too short.
+        let mut opts = make_cast_options(Vector2::new(1.0, 0.0), Default::default());
+        opts.max_len = 3.0;
+        assert!(graph.physics2d.cast_shape(opts).is_none());
+
+        // Miss - excluded collider.
+        assert!(graph
+            .physics2d
+            .cast_shape(make_cast_options(
+                Vector2::new(1.0, 0.0),
+                QueryFilter {
+                    exclude_colliders: vec![collider],
+                    ..Default::default()
+                }
+            ))
+            .is_none());
+
+        // Miss - excluded rigid body.
+        assert!(graph
+            .physics2d
+            .cast_shape(make_cast_options(
+                Vector2::new(1.0, 0.0),
+                QueryFilter {
+                    exclude_rigid_bodies: vec![body],
+                    ..Default::default()
+                }
+            ))
+            .is_none());
+    }
+
+    // Creates a static body with a cube collider at (5, 0), updates the graph to make the collider
+    // visible to scene queries and returns handles of the collider and the body.
+    fn make_query_scene(graph: &mut Graph) -> (Handle<Node>, Handle<Node>) {
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(0.5, 0.5))
+            .build(graph);
+        let body = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(5.0, 0.0, 0.0))
+                        .build(),
+                )
+                .with_children(&[collider]),
+        )
+        .with_body_type(RigidBodyType::Static)
+        .build(graph);
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+        graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+
+        (collider, body)
+    }
+
+    fn exclusion_filters(collider: Handle<Node>, body: Handle<Node>) -> [QueryFilter; 2] {
+        [
+            QueryFilter {
+                exclude_colliders: vec![collider],
+                ..Default::default()
+            },
+            QueryFilter {
+                exclude_rigid_bodies: vec![body],
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_project_point_2d() {
+        let mut graph = Graph::new();
+        let (collider, body) = make_query_scene(&mut graph);
+
+        // Point outside of the collider.
+        let result = graph
+            .physics2d
+            .project_point(Point2::new(0.0, 0.0), true, &Default::default())
+            .unwrap();
+        assert_eq!(result.collider, collider);
+        assert!((result.point - Point2::new(4.5, 0.0)).norm() < 1.0e-4);
+        assert!(!result.is_inside);
+
+        // Point inside of the solid collider is projected on itself.
+        let result = graph
+            .physics2d
+            .project_point(Point2::new(4.8, 0.0), true, &Default::default())
+            .unwrap();
+        assert_eq!(result.collider, collider);
+        assert!((result.point - Point2::new(4.8, 0.0)).norm() < 1.0e-4);
+        assert!(result.is_inside);
+
+        // Point inside of the hollow collider is projected on its boundary.
+        let result = graph
+            .physics2d
+            .project_point(Point2::new(4.8, 0.0), false, &Default::default())
+            .unwrap();
+        assert_eq!(result.collider, collider);
+        assert!((result.point - Point2::new(4.5, 0.0)).norm() < 1.0e-4);
+        assert!(result.is_inside);
+
+        // Miss - every collider is excluded.
+        for filter in exclusion_filters(collider, body).iter() {
+            assert!(graph
+                .physics2d
+                .project_point(Point2::new(0.0, 0.0), true, filter)
+                .is_none());
+        }
+    }
+
+    fn intersections_with_shape_2d(
+        graph: &Graph,
+        position: Vector2<f32>,
+        filter: QueryFilter,
+    ) -> Vec<Handle<Node>> {
+        let mut colliders = Vec::new();
+        graph.physics2d.intersections_with_shape(
+            ShapeIntersectionOptions {
+                shape: ColliderShape::ball(0.5),
+                shape_position: position,
+                shape_rotation: 0.0,
+                filter,
+            },
+            |collider| {
+                colliders.push(collider);
+                true
+            },
+        );
+        colliders
+    }
+
+    #[test]
+    fn test_intersections_with_shape_2d() {
+        let mut graph = Graph::new();
+        let (collider, body) = make_query_scene(&mut graph);
+
+        // Hit.
+        assert_eq!(
+            intersections_with_shape_2d(&graph, Vector2::new(4.2, 0.0), Default::default()),
+            vec![collider]
+        );
+
+        // Miss - the ball is too far.
+        assert!(
+            intersections_with_shape_2d(&graph, Vector2::new(3.8, 0.0), Default::default())
+                .is_empty()
+        );
+
+        // Miss - excluded collider and rigid body.
+        for filter in exclusion_filters(collider, body) {
+            assert!(intersections_with_shape_2d(&graph, Vector2::new(4.2, 0.0), filter).is_empty());
+        }
+    }
+
+    fn intersections_with_point_2d(
+        graph: &Graph,
+        point: Point2<f32>,
+        filter: &QueryFilter,
+    ) -> Vec<Handle<Node>> {
+        let mut colliders = Vec::new();
+        graph
+            .physics2d
+            .intersections_with_point(point, filter, |collider| {
+                colliders.push(collider);
+                true
+            });
+        colliders
+    }
+
+    #[test]
+    fn test_intersections_with_point_2d() {
+        let mut graph = Graph::new();
+        let (collider, body) = make_query_scene(&mut graph);
+
+        // Hit.
+        assert_eq!(
+            intersections_with_point_2d(&graph, Point2::new(5.2, 0.0), &Default::default()),
+            vec![collider]
+        );
+
+        // Miss - the point is outside of the collider.
+        assert!(
+            intersections_with_point_2d(&graph, Point2::new(4.2, 0.0), &Default::default())
+                .is_empty()
+        );
+
+        // Miss - excluded collider and rigid body.
+        for filter in exclusion_filters(collider, body).iter() {
+            assert!(intersections_with_point_2d(&graph, Point2::new(5.2, 0.0), filter).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_convert_joint_motor_2d() {
+        let joint = convert_joint_params(
+            JointParams::BallJoint(BallJoint {
+                limits_enabled: true,
+                limits_angles: -1.0..1.0,
+                motor: JointMotor {
+                    max_force: 100.0,
+                    ..JointMotor::velocity(0.5, 3.0)
+                },
+            }),
+            Default::default(),
+            Default::default(),
+        );
+
+        assert_eq!(joint.locked_axes, JointAxesMask::LOCKED_REVOLUTE_AXES);
+        let limits = joint.limits(JointAxis::AngX).unwrap();
+        assert_eq!((limits.min, limits.max), (-1.0, 1.0));
+        let motor = joint.motor(JointAxis::AngX).unwrap();
+        assert_eq!(motor.target_pos, 0.0);
+        assert_eq!(motor.target_vel, 0.5);
+        assert_eq!(motor.stiffness, 0.0);
+        assert_eq!(motor.damping, 3.0);
+        assert_eq!(motor.max_force, 100.0);
+
+        // Disabled motor must not be applied.
+        let joint = convert_joint_params(
+            JointParams::BallJoint(Default::default()),
+            Default::default(),
+            Default::default(),
+        );
+        assert!(joint.motor(JointAxis::AngX).is_none());
+        assert!(joint.limits(JointAxis::AngX).is_none());
+    }
+
+    #[test]
+    fn test_convert_rope_joint_2d() {
+        let joint = convert_joint_params(
+            JointParams::RopeJoint(RopeJoint { max_distance: 2.5 }),
+            Default::default(),
+            Default::default(),
+        );
+
+        assert_eq!(joint.locked_axes, JointAxesMask::empty());
+        assert_eq!(joint.coupled_axes, JointAxesMask::X | JointAxesMask::Y);
+        let limits = joint.limits(JointAxis::X).unwrap();
+        assert_eq!((limits.min, limits.max), (0.0, 2.5));
+        assert!(joint.motor(JointAxis::X).is_none());
+    }
+
+    #[test]
+    fn test_convert_spring_joint_2d() {
+        let joint = convert_joint_params(
+            JointParams::SpringJoint(SpringJoint {
+                rest_length: 1.5,
+                stiffness: 20.0,
+                damping: 0.5,
+            }),
+            Default::default(),
+            Default::default(),
+        );
+
+        // The spring is simulated separately, the joint itself must not restrict the bodies.
+        assert_eq!(joint.locked_axes, JointAxesMask::empty());
+        assert!(joint.motor(JointAxis::X).is_none());
+        assert!(joint.limits(JointAxis::X).is_none());
+    }
+
+    #[test]
+    fn test_spring_joint_simulation_2d() {
+        let mut graph = Graph::new();
+        graph.physics2d.gravity = Vector2::default();
+        graph.physics2d.integration_parameters.dt = Some(1.0 / 60.0);
+
+        let body1 = RigidBodyBuilder::new(BaseBuilder::new())
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::ball(0.5))
+            .build(&mut graph);
+        let body2 = RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider]))
+            .with_body_type(RigidBodyType::Dynamic)
+            .build(&mut graph);
+        // Anchors of the joint are at its position, which is the origin.
+        JointBuilder::new(BaseBuilder::new())
+            .with_body1(body1)
+            .with_body2(body2)
+            .with_contacts_enabled(false)
+            .with_params(JointParams::SpringJoint(SpringJoint {
+                rest_length: 1.0,
+                stiffness: 50.0,
+                damping: 5.0,
+            }))
+            .build(&mut graph);
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        // Stretch the spring.
+        graph[body2]
+            .local_transform_mut()
+            .set_position(Vector3::new(3.0, 0.0, 0.0));
+
+        for _ in 0..600 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        // Oscillations must be damped and the body must come to the rest length.
+        let position = graph[body2].global_position();
+        assert!((position.norm() - 1.0).abs() < 0.01, "{:?}", position);
+        assert!(position.x > 0.0);
+    }
+}
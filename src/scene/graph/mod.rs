@@ -43,11 +43,14 @@ use crate::{
         graph::{
             event::{GraphEvent, GraphEventBroadcaster},
             map::NodeHandleMap,
-            physics::{PhysicsPerformanceStatistics, PhysicsWorld},
+            physics::{
+                CollisionEvent, ContactForceEvent, PhysicsPerformanceStatistics, PhysicsWorld,
+            },
         },
         mesh::Mesh,
         node::{container::NodeContainer, Node, SyncContext, UpdateContext},
         pivot::Pivot,
+        ragdoll::Ragdoll,
         sound::context::SoundContext,
         transform::TransformBuilder,
     },
@@ -133,6 +136,12 @@ pub struct Graph {
     #[reflect(hidden)]
     pub event_broadcaster: GraphEventBroadcaster,
 
+    #[reflect(hidden)]
+    collision_events: Vec<CollisionEvent>,
+
+    #[reflect(hidden)]
+    contact_force_events: Vec<ContactForceEvent>,
+
     #[reflect(hidden)]
     pub(crate) script_message_sender: Sender<NodeScriptMessage>,
     #[reflect(hidden)]
@@ -152,6 +161,8 @@ impl Default for Graph {
             sound_context: Default::default(),
             performance_statistics: Default::default(),
             event_broadcaster: Default::default(),
+            collision_events: Default::default(),
+            contact_force_events: Default::default(),
             script_message_receiver: rx,
             script_message_sender: tx,
         }
@@ -258,6 +269,8 @@ impl Graph {
             sound_context: SoundContext::new(),
             performance_statistics: Default::default(),
             event_broadcaster: Default::default(),
+            collision_events: Default::default(),
+            contact_force_events: Default::default(),
             script_message_receiver: rx,
             script_message_sender: tx,
         }
@@ -1109,35 +1122,89 @@ impl Graph {
         self.sync_native(&switches);
         self.performance_statistics.sync_time = instant::Instant::now() - last_time;
 
+        // Events are kept only for a single frame.
+        self.collision_events.clear();
+        self.contact_force_events.clear();
+
         if switches.physics {
             self.physics.performance_statistics.reset();
             self.physics.update(dt);
+            self.physics
+                .take_events(&mut self.collision_events, &mut self.contact_force_events);
             self.performance_statistics.physics = self.physics.performance_statistics.clone();
         }
 
         if switches.physics2d {
             self.physics2d.performance_statistics.reset();
             self.physics2d.update(dt);
+            self.physics2d
+                .take_events(&mut self.collision_events, &mut self.contact_force_events);
             self.performance_statistics.physics2d = self.physics2d.performance_statistics.clone();
         }
 
         self.performance_statistics.sound_update_time =
             self.sound_context.state().full_render_duration();
 
+        // Ragdolls modify local transforms of bones, that are usually driven by animation, so they
+        // must be updated after every other node.
+        let mut ragdolls = Vec::new();
+
         if let Some(overrides) = switches.node_overrides.as_ref() {
             for handle in overrides {
-                self.update_node(*handle, frame_size, dt, switches.delete_dead_nodes);
+                if self.is_ragdoll(*handle) {
+                    ragdolls.push(*handle);
+                } else {
+                    self.update_node(*handle, frame_size, dt, switches.delete_dead_nodes);
+                }
             }
         } else {
             for i in 0..self.pool.get_capacity() {
-                self.update_node(
-                    self.pool.handle_from_index(i),
-                    frame_size,
-                    dt,
-                    switches.delete_dead_nodes,
-                );
+                let handle = self.pool.handle_from_index(i);
+                if self.is_ragdoll(handle) {
+                    ragdolls.push(handle);
+                } else {
+                    self.update_node(handle, frame_size, dt, switches.delete_dead_nodes);
+                }
             }
         }
+
+        for handle in ragdolls {
+            self.update_node(handle, frame_size, dt, switches.delete_dead_nodes);
+        }
+    }
+
+    fn is_ragdoll(&self, handle: Handle<Node>) -> bool {
+        self.pool
+            .try_borrow(handle)
+            .map_or(false, |node| node.cast::<Ragdoll>().is_some())
+    }
+
+    /// Returns a list of collision events (of both 3D and 2D physics) produced during the last
+    /// [`Graph::update`] call. The list is cleared on each update, so it contains events only for a
+    /// single frame. Scripts of the colliders receive the same events via script messages, so
+    /// there is no need to use this method in scripts.
+    pub fn collision_events(&self) -> &[CollisionEvent] {
+        &self.collision_events
+    }
+
+    /// Takes collision events produced during the last [`Graph::update`] call, leaving the list
+    /// empty. Script messages for the events are queued before plugins are updated, so draining
+    /// the events in a plugin does not affect scripts.
+    pub fn drain_collision_events(&mut self) -> std::vec::Drain<'_, CollisionEvent> {
+        self.collision_events.drain(..)
+    }
+
+    /// Returns a list of contact force events (of both 3D and 2D physics) produced during the last
+    /// [`Graph::update`] call. See [`crate::scene::collider::Collider::set_contact_force_event_threshold`]
+    /// for more info.
+    pub fn contact_force_events(&self) -> &[ContactForceEvent] {
+        &self.contact_force_events
+    }
+
+    /// Takes contact force events produced during the last [`Graph::update`] call, leaving the
+    /// list empty.
+    pub fn drain_contact_force_events(&mut self) -> std::vec::Drain<'_, ContactForceEvent> {
+        self.contact_force_events.drain(..)
     }
 
     /// Returns capacity of internal pool. Can be used to iterate over all **potentially**
@@ -26,6 +26,86 @@ use std::{
 };
 use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
 
+/// Joint motor drives a joint axis (linear or angular, depending on the joint) to a target position
+/// and/or with a target velocity. Motors could be used to create powered doors, vehicles, active
+/// ragdolls and so on. The motor acts like a spring-damper, the force that it applies is calculated
+/// like so: `stiffness * (target_position - position) + damping * (target_velocity - velocity)`
+/// and then clamped to `max_force`.
+#[derive(Clone, Debug, Visit, PartialEq, Reflect)]
+pub struct JointMotor {
+    /// Whether the motor is enabled or not. Default is `false`.
+    #[reflect(description = "Whether the motor is enabled or not.")]
+    pub enabled: bool,
+
+    /// Target position of the motor (in radians for angular axes).
+    #[reflect(description = "Target position of the motor (in radians for angular axes).")]
+    pub target_position: f32,
+
+    /// Target velocity of the motor (in radians per second for angular axes).
+    #[reflect(
+        description = "Target velocity of the motor (in radians per second for angular axes)."
+    )]
+    pub target_velocity: f32,
+
+    /// Stiffness of the motor, defines how fast the motor will reach the target position. Zero
+    /// stiffness means that the motor will only try to reach the target velocity.
+    #[reflect(
+        min_value = 0.0,
+        description = "Stiffness of the motor, defines how fast the motor will reach the target position."
+    )]
+    pub stiffness: f32,
+
+    /// Damping of the motor, defines how fast the motor will reach the target velocity.
+    #[reflect(
+        min_value = 0.0,
+        description = "Damping of the motor, defines how fast the motor will reach the target velocity."
+    )]
+    pub damping: f32,
+
+    /// Maximum force (or torque for angular axes) the motor can apply.
+    #[reflect(
+        min_value = 0.0,
+        description = "Maximum force (or torque for angular axes) the motor can apply."
+    )]
+    pub max_force: f32,
+}
+
+impl Default for JointMotor {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_position: 0.0,
+            target_velocity: 0.0,
+            stiffness: 0.0,
+            damping: 0.0,
+            max_force: f32::MAX,
+        }
+    }
+}
+
+impl JointMotor {
+    /// Creates new motor that drives the axis to the given target position.
+    pub fn position(target_position: f32, stiffness: f32, damping: f32) -> Self {
+        Self {
+            enabled: true,
+            target_position,
+            stiffness,
+            damping,
+            ..Default::default()
+        }
+    }
+
+    /// Creates new motor that drives the axis with the given target velocity.
+    pub fn velocity(target_velocity: f32, damping: f32) -> Self {
+        Self {
+            enabled: true,
+            target_velocity,
+            damping,
+            ..Default::default()
+        }
+    }
+}
+
 /// Ball joint locks any translational moves between two objects on the axis between objects, but
 /// allows rigid bodies to perform relative rotations. The real world example is a human shoulder,
 /// pendulum, etc.
@@ -60,6 +140,21 @@ pub struct BallJoint {
     #[reflect(description = "Allowed angle range around local Z axis of the joint (in radians).")]
     #[visit(optional)] // Backward compatibility
     pub z_limits_angles: Range<f32>,
+
+    /// A motor that drives rotation around local X axis of the joint.
+    #[reflect(description = "A motor that drives rotation around local X axis of the joint.")]
+    #[visit(optional)] // Backward compatibility
+    pub x_motor: JointMotor,
+
+    /// A motor that drives rotation around local Y axis of the joint.
+    #[reflect(description = "A motor that drives rotation around local Y axis of the joint.")]
+    #[visit(optional)] // Backward compatibility
+    pub y_motor: JointMotor,
+
+    /// A motor that drives rotation around local Z axis of the joint.
+    #[reflect(description = "A motor that drives rotation around local Z axis of the joint.")]
+    #[visit(optional)] // Backward compatibility
+    pub z_motor: JointMotor,
 }
 
 impl Default for BallJoint {
@@ -71,6 +166,9 @@ impl Default for BallJoint {
             y_limits_angles: -std::f32::consts::PI..std::f32::consts::PI,
             z_limits_enabled: false,
             z_limits_angles: -std::f32::consts::PI..std::f32::consts::PI,
+            x_motor: Default::default(),
+            y_motor: Default::default(),
+            z_motor: Default::default(),
         }
     }
 }
@@ -95,6 +193,13 @@ pub struct PrismaticJoint {
     )]
     #[visit(optional)] // Backward compatibility
     pub limits: Range<f32>,
+
+    /// A motor that drives relative translation along local X axis of the joint.
+    #[reflect(
+        description = "A motor that drives relative translation along local X axis of the joint."
+    )]
+    #[visit(optional)] // Backward compatibility
+    pub motor: JointMotor,
 }
 
 impl Default for PrismaticJoint {
@@ -102,6 +207,7 @@ impl Default for PrismaticJoint {
         Self {
             limits_enabled: false,
             limits: -std::f32::consts::PI..std::f32::consts::PI,
+            motor: Default::default(),
         }
     }
 }
@@ -122,6 +228,11 @@ pub struct RevoluteJoint {
     #[reflect(description = "Allowed angle range around local X axis of the joint (in radians).")]
     #[visit(optional)] // Backward compatibility
     pub limits: Range<f32>,
+
+    /// A motor that drives rotation around local X axis of the joint.
+    #[reflect(description = "A motor that drives rotation around local X axis of the joint.")]
+    #[visit(optional)] // Backward compatibility
+    pub motor: JointMotor,
 }
 
 impl Default for RevoluteJoint {
@@ -129,6 +240,61 @@ impl Default for RevoluteJoint {
         Self {
             limits_enabled: false,
             limits: -std::f32::consts::PI..std::f32::consts::PI,
+            motor: Default::default(),
+        }
+    }
+}
+
+/// Rope joint limits the maximum distance between anchors of two rigid bodies, but allows them to
+/// move closer to each other and to rotate freely. The real world example is a rope or a chain.
+#[derive(Clone, Debug, Visit, PartialEq, Reflect)]
+pub struct RopeJoint {
+    /// Maximum distance between anchors of the bodies.
+    #[reflect(
+        min_value = 0.0,
+        description = "Maximum distance between anchors of the bodies."
+    )]
+    pub max_distance: f32,
+}
+
+impl Default for RopeJoint {
+    fn default() -> Self {
+        Self { max_distance: 1.0 }
+    }
+}
+
+/// Spring joint pulls (or pushes) anchors of two rigid bodies to keep them at the rest length
+/// distance, the bodies can rotate freely. The real world example is a spring or a bungee cord.
+#[derive(Clone, Debug, Visit, PartialEq, Reflect)]
+pub struct SpringJoint {
+    /// Distance between anchors of the bodies at which the spring applies no force.
+    #[reflect(
+        min_value = 0.0,
+        description = "Distance between anchors of the bodies at which the spring applies no force."
+    )]
+    pub rest_length: f32,
+
+    /// Stiffness of the spring, the higher the value the stronger the spring pulls the bodies.
+    #[reflect(
+        min_value = 0.0,
+        description = "Stiffness of the spring, the higher the value the stronger the spring pulls the bodies."
+    )]
+    pub stiffness: f32,
+
+    /// Damping of the spring, it reduces oscillations of the bodies.
+    #[reflect(
+        min_value = 0.0,
+        description = "Damping of the spring, it reduces oscillations of the bodies."
+    )]
+    pub damping: f32,
+}
+
+impl Default for SpringJoint {
+    fn default() -> Self {
+        Self {
+            rest_length: 1.0,
+            stiffness: 10.0,
+            damping: 1.0,
         }
     }
 }
@@ -144,6 +310,10 @@ pub enum JointParams {
     PrismaticJoint(PrismaticJoint),
     /// See [`RevoluteJoint`] for more info.
     RevoluteJoint(RevoluteJoint),
+    /// See [`RopeJoint`] for more info.
+    RopeJoint(RopeJoint),
+    /// See [`SpringJoint`] for more info.
+    SpringJoint(SpringJoint),
 }
 
 impl Default for JointParams {
@@ -82,6 +82,30 @@ impl RenderPath {
     }
 }
 
+/// Blend shape is a named weight of a blend shape target (see [`surface::BlendShapeTarget`]). A mesh stores
+/// a set of blend shapes, which are applied to every surface that has a blend shape target with the same name.
+/// Weights could be animated using property animation tracks (for example, `blend_shapes[0].weight` property
+/// path), which is typically used for facial animation.
+#[derive(Debug, Clone, Default, PartialEq, Visit, Reflect)]
+pub struct BlendShape {
+    /// Name of the blend shape, it must match the name of a blend shape target of surfaces.
+    pub name: String,
+    /// Weight of the blend shape. Usually it is in `[0; 1]` range, but it could be out of this range for
+    /// exaggerated deformations.
+    #[reflect(step = 0.01)]
+    pub weight: f32,
+}
+
+impl BlendShape {
+    /// Creates a new blend shape with the given name and weight.
+    pub fn new<S: AsRef<str>>(name: S, weight: f32) -> Self {
+        Self {
+            name: name.as_ref().to_owned(),
+            weight,
+        }
+    }
+}
+
 /// See module docs.
 #[derive(Debug, Reflect, Clone, Visit)]
 pub struct Mesh {
@@ -97,6 +121,14 @@ pub struct Mesh {
     #[reflect(setter = "set_decal_layer_index")]
     decal_layer_index: InheritableVariable<u8>,
 
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_blend_shapes",
+        description = "A set of blend shape weights of the mesh. Blend shapes are matched with the blend \
+        shape targets of the surfaces by their names."
+    )]
+    blend_shapes: InheritableVariable<Vec<BlendShape>>,
+
     #[reflect(hidden)]
     #[visit(skip)]
     local_bounding_box: Cell<AxisAlignedBoundingBox>,
@@ -120,6 +152,7 @@ impl Default for Mesh {
             local_bounding_box_dirty: Cell::new(true),
             render_path: InheritableVariable::new(RenderPath::Deferred),
             decal_layer_index: InheritableVariable::new(0),
+            blend_shapes: Default::default(),
         }
     }
 }
@@ -258,6 +291,40 @@ impl Mesh {
     pub fn decal_layer_index(&self) -> u8 {
         *self.decal_layer_index
     }
+
+    /// Sets new set of blend shapes of the mesh. See [`BlendShape`] docs for more info.
+    pub fn set_blend_shapes(&mut self, blend_shapes: Vec<BlendShape>) -> Vec<BlendShape> {
+        self.blend_shapes.set_value_and_mark_modified(blend_shapes)
+    }
+
+    /// Returns a reference to the set of blend shapes of the mesh.
+    pub fn blend_shapes(&self) -> &[BlendShape] {
+        &self.blend_shapes
+    }
+
+    /// Sets a weight of a blend shape with the given name. Returns `false` if there's no such blend shape,
+    /// `true` - otherwise.
+    pub fn set_blend_shape_weight<S: AsRef<str>>(&mut self, name: S, weight: f32) -> bool {
+        if let Some(blend_shape) = self
+            .blend_shapes
+            .get_value_mut_and_mark_modified()
+            .iter_mut()
+            .find(|blend_shape| blend_shape.name == name.as_ref())
+        {
+            blend_shape.weight = weight;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns a weight of a blend shape with the given name (if any).
+    pub fn blend_shape_weight<S: AsRef<str>>(&self, name: S) -> Option<f32> {
+        self.blend_shapes
+            .iter()
+            .find(|blend_shape| blend_shape.name == name.as_ref())
+            .map(|blend_shape| blend_shape.weight)
+    }
 }
 
 impl NodeTrait for Mesh {
@@ -293,6 +360,11 @@ impl NodeTrait for Mesh {
     }
 
     fn update(&mut self, context: &mut UpdateContext) {
+        for surface in self.surfaces.get_value_mut_silent().iter_mut() {
+            surface.sync_material_resource();
+            surface.apply_blend_shapes(&self.blend_shapes);
+        }
+
         if self.surfaces.iter().any(|s| !s.bones.is_empty()) {
             let mut world_aabb = self
                 .local_bounding_box()
@@ -323,6 +395,7 @@ pub struct MeshBuilder {
     surfaces: Vec<Surface>,
     render_path: RenderPath,
     decal_layer_index: u8,
+    blend_shapes: Vec<BlendShape>,
 }
 
 impl MeshBuilder {
@@ -333,6 +406,7 @@ impl MeshBuilder {
             surfaces: Default::default(),
             render_path: RenderPath::Deferred,
             decal_layer_index: 0,
+            blend_shapes: Default::default(),
         }
     }
 
@@ -355,6 +429,12 @@ impl MeshBuilder {
         self
     }
 
+    /// Sets desired set of blend shapes.
+    pub fn with_blend_shapes(mut self, blend_shapes: Vec<BlendShape>) -> Self {
+        self.blend_shapes = blend_shapes;
+        self
+    }
+
     /// Creates new mesh.
     pub fn build_node(self) -> Node {
         Node::new(Mesh {
@@ -364,6 +444,7 @@ impl MeshBuilder {
             local_bounding_box_dirty: Cell::new(true),
             render_path: self.render_path.into(),
             decal_layer_index: self.decal_layer_index.into(),
+            blend_shapes: self.blend_shapes.into(),
             world_bounding_box: Default::default(),
         })
     }
@@ -5,6 +5,7 @@
 //! being able to re-use data when you need to draw the same mesh in many places.
 
 use crate::{
+    asset::ResourceState,
     core::{
         algebra::{Matrix4, Point3, Vector2, Vector3, Vector4},
         hash_combine,
@@ -13,10 +14,10 @@ use crate::{
         pool::{ErasedHandle, Handle},
         reflect::prelude::*,
         sparse::AtomicIndex,
-        variable::InheritableVariable,
+        variable::{InheritableVariable, VariableFlags},
         visitor::{Visit, VisitResult, Visitor},
     },
-    material::{Material, SharedMaterial},
+    material::{Material, MaterialResource, SharedMaterial},
     scene::{
         mesh::{
             buffer::{
@@ -24,14 +25,35 @@ use crate::{
                 VertexFetchError, VertexReadTrait, VertexWriteTrait,
             },
             vertex::StaticVertex,
+            BlendShape,
         },
         node::Node,
     },
-    utils::raw_mesh::{RawMesh, RawMeshBuilder},
+    utils::{
+        log::Log,
+        raw_mesh::{RawMesh, RawMeshBuilder},
+    },
 };
 use fxhash::FxHasher;
 use std::{hash::Hasher, sync::Arc};
 
+/// Blend shape target (morph target) is a set of per-vertex offsets of a surface. Blend shapes are used to deform
+/// surfaces without bones, typical example is facial animation. Every array of the target must either be empty
+/// (no offsets) or have exactly the same amount of elements as the vertex buffer of the surface. Influence of each
+/// target is defined by a weight of a respective [`super::BlendShape`] of a mesh, the targets and the blend shapes
+/// are matched by their names.
+#[derive(Debug, Clone, Default, PartialEq, Visit)]
+pub struct BlendShapeTarget {
+    /// Name of the target.
+    pub name: String,
+    /// Position offsets.
+    pub positions: Vec<Vector3<f32>>,
+    /// Normal offsets.
+    pub normals: Vec<Vector3<f32>>,
+    /// Tangent offsets.
+    pub tangents: Vec<Vector3<f32>>,
+}
+
 /// Data source of a surface. Each surface can share same data source, this is used
 /// in instancing technique to render multiple instances of same model at different
 /// places.
@@ -41,6 +63,8 @@ pub struct SurfaceData {
     pub vertex_buffer: VertexBuffer,
     /// Current geometry buffer.
     pub geometry_buffer: TriangleBuffer,
+    /// A set of blend shape targets of the surface. See [`BlendShapeTarget`] docs for more info.
+    pub blend_shape_targets: Vec<BlendShapeTarget>,
     // If true - indicates that surface was generated and does not have reference
     // resource. Procedural data will be serialized.
     is_procedural: bool,
@@ -57,6 +81,7 @@ impl SurfaceData {
         Self {
             vertex_buffer,
             geometry_buffer: triangles,
+            blend_shape_targets: Default::default(),
             is_procedural,
             cache_entry: AtomicIndex::unassigned(),
         }
@@ -101,6 +126,7 @@ impl SurfaceData {
         Self {
             vertex_buffer: VertexBuffer::new(raw.vertices.len(), layout, raw.vertices).unwrap(),
             geometry_buffer: TriangleBuffer::new(raw.triangles),
+            blend_shape_targets: Default::default(),
             is_procedural,
             cache_entry: AtomicIndex::unassigned(),
         }
@@ -854,6 +880,127 @@ impl SurfaceData {
     pub fn is_procedural(&self) -> bool {
         self.is_procedural
     }
+
+    /// Writes vertices of the `base` data with applied blend shape targets to the vertex buffer of the current data.
+    /// Weights are specified per blend shape target of the base data, missing weights are treated as zero. The vertex
+    /// buffer of the current data must have the same layout and the same amount of vertices as the base data.
+    pub fn apply_blend_shapes(
+        &mut self,
+        base: &SurfaceData,
+        weights: &[f32],
+    ) -> Result<(), VertexFetchError> {
+        let mut vertex_buffer_mut = self.vertex_buffer.modify();
+        for (index, (mut view, base_view)) in vertex_buffer_mut
+            .iter_mut()
+            .zip(base.vertex_buffer.iter())
+            .enumerate()
+        {
+            let mut position = base_view.read_3_f32(VertexAttributeUsage::Position)?;
+            let mut normal = base_view.read_3_f32(VertexAttributeUsage::Normal).ok();
+            let mut tangent = base_view.read_4_f32(VertexAttributeUsage::Tangent).ok();
+
+            for (target, weight) in base.blend_shape_targets.iter().zip(weights) {
+                if *weight == 0.0 {
+                    continue;
+                }
+
+                if let Some(offset) = target.positions.get(index) {
+                    position += offset.scale(*weight);
+                }
+                if let (Some(normal), Some(offset)) = (normal.as_mut(), target.normals.get(index)) {
+                    *normal += offset.scale(*weight);
+                }
+                if let (Some(tangent), Some(offset)) =
+                    (tangent.as_mut(), target.tangents.get(index))
+                {
+                    // Keep sign (W).
+                    *tangent += offset.scale(*weight).push(0.0);
+                }
+            }
+
+            view.write_3_f32(VertexAttributeUsage::Position, position)?;
+            if let Some(normal) = normal {
+                view.write_3_f32(
+                    VertexAttributeUsage::Normal,
+                    normal.try_normalize(f32::EPSILON).unwrap_or(normal),
+                )?;
+            }
+            if let Some(tangent) = tangent {
+                let xyz = tangent.xyz();
+                let xyz = xyz.try_normalize(f32::EPSILON).unwrap_or(xyz);
+                view.write_4_f32(
+                    VertexAttributeUsage::Tangent,
+                    Vector4::new(xyz.x, xyz.y, xyz.z, tangent.w),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Calculates tangent offsets of every blend shape target of the surface. Tangents depend on positions and
+    /// normals of vertices, so every target that moves vertices or changes their normals changes tangents too.
+    /// The offsets are calculated as the difference between tangents of the fully applied target and tangents of
+    /// the base mesh, both are calculated using [`Self::calculate_tangents`].
+    pub fn calculate_blend_shape_tangents(&mut self) -> Result<(), VertexFetchError> {
+        if self.blend_shape_targets.is_empty() {
+            return Ok(());
+        }
+
+        let mut base = SurfaceData::new(
+            self.vertex_buffer.clone(),
+            self.geometry_buffer.clone(),
+            true,
+        );
+        base.calculate_tangents()?;
+
+        let mut morphed = SurfaceData::new(
+            self.vertex_buffer.clone(),
+            self.geometry_buffer.clone(),
+            true,
+        );
+
+        for target in self.blend_shape_targets.iter_mut() {
+            target.tangents.clear();
+
+            if target.positions.is_empty() && target.normals.is_empty() {
+                continue;
+            }
+
+            {
+                let mut vertex_buffer_mut = morphed.vertex_buffer.modify();
+                for (index, (mut view, base_view)) in vertex_buffer_mut
+                    .iter_mut()
+                    .zip(base.vertex_buffer.iter())
+                    .enumerate()
+                {
+                    let mut position = base_view.read_3_f32(VertexAttributeUsage::Position)?;
+                    let mut normal = base_view.read_3_f32(VertexAttributeUsage::Normal)?;
+                    if let Some(offset) = target.positions.get(index) {
+                        position += offset;
+                    }
+                    if let Some(offset) = target.normals.get(index) {
+                        normal += offset;
+                    }
+                    view.write_3_f32(VertexAttributeUsage::Position, position)?;
+                    view.write_3_f32(
+                        VertexAttributeUsage::Normal,
+                        normal.try_normalize(f32::EPSILON).unwrap_or(normal),
+                    )?;
+                }
+            }
+
+            morphed.calculate_tangents()?;
+
+            for (view, base_view) in morphed.vertex_buffer.iter().zip(base.vertex_buffer.iter()) {
+                let tangent = view.read_4_f32(VertexAttributeUsage::Tangent)?;
+                let base_tangent = base_view.read_4_f32(VertexAttributeUsage::Tangent)?;
+                target.tangents.push(tangent.xyz() - base_tangent.xyz());
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Visit for SurfaceData {
@@ -864,7 +1011,10 @@ impl Visit for SurfaceData {
 
         if self.is_procedural {
             self.vertex_buffer.visit("VertexBuffer", &mut region)?;
-            self.geometry_buffer.visit("GeometryBuffer", &mut region)?
+            self.geometry_buffer.visit("GeometryBuffer", &mut region)?;
+            let _ = self
+                .blend_shape_targets
+                .visit("BlendShapeTargets", &mut region); // Backward compatibility.
         }
 
         Ok(())
@@ -1002,6 +1152,12 @@ pub struct Surface {
 
     material: InheritableVariable<SharedMaterial>,
 
+    #[reflect(
+        description = "Optional material resource. If set, the surface will use the material from the resource, \
+        unless the material of the surface is explicitly overridden."
+    )]
+    material_resource: InheritableVariable<Option<MaterialResource>>,
+
     /// Array of handles to scene nodes which are used as bones.
     pub bones: InheritableVariable<Vec<Handle<Node>>>,
 
@@ -1020,6 +1176,18 @@ pub struct Surface {
     // associated with vertex in `bones` array and store it as bone index in vertex.
     #[reflect(hidden)]
     pub(crate) vertex_weights: Vec<VertexWeightSet>,
+
+    // A copy of the data with applied blend shapes. It is created on demand only if the data has blend
+    // shape targets and at least one of them has non-zero weight, so meshes without blend shapes does not
+    // waste any memory.
+    #[reflect(hidden)]
+    blended_data: Option<SurfaceSharedData>,
+
+    #[reflect(hidden)]
+    blended_data_source: u64,
+
+    #[reflect(hidden)]
+    blend_shape_weights: Vec<f32>,
 }
 
 impl Clone for Surface {
@@ -1033,9 +1201,14 @@ impl Clone for Surface {
                 // Share the material.
                 self.material.clone()
             },
+            material_resource: self.material_resource.clone(),
             bones: self.bones.clone(),
             unique_material: self.unique_material.clone(),
             vertex_weights: self.vertex_weights.clone(),
+            // Blended data is unique per instance, it will be re-created on next update.
+            blended_data: None,
+            blended_data_source: 0,
+            blend_shape_weights: Default::default(),
         }
     }
 }
@@ -1044,6 +1217,10 @@ impl Visit for Surface {
     fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
         let mut region = visitor.enter_region(name)?;
 
+        let _ = self
+            .material_resource
+            .visit("MaterialResource", &mut region); // Backward compatibility.
+
         if region.is_reading() {
             // TODO: Remove in 0.30+.
             if self.data.visit("Data", &mut region).is_err() {
@@ -1054,8 +1231,15 @@ impl Visit for Surface {
 
             if self.material.visit("Material", &mut region).is_err() {
                 let mut old_material: SharedMaterial = Default::default();
-                old_material.visit("Material", &mut region)?;
-                self.material.set_value_silent(old_material);
+                match old_material.visit("Material", &mut region) {
+                    Ok(_) => {
+                        self.material.set_value_silent(old_material);
+                    }
+                    // The material is not stored if it comes from a material resource, it will be
+                    // taken from the resource as soon as the resource is loaded.
+                    Err(_) if self.material_resource.is_some() => (),
+                    Err(e) => return Err(e),
+                }
             }
 
             if self.bones.visit("Bones", &mut region).is_err() {
@@ -1065,7 +1249,11 @@ impl Visit for Surface {
             }
         } else {
             self.data.visit("Data", &mut region)?;
-            self.material.visit("Material", &mut region)?;
+            // Materials of material resources are stored in their own files, there's no need to
+            // embed them.
+            if !self.is_material_from_resource() {
+                self.material.visit("Material", &mut region)?;
+            }
             self.bones.visit("Bones", &mut region)?;
         }
 
@@ -1080,9 +1268,13 @@ impl Default for Surface {
         Self {
             data: SurfaceSharedData::new(SurfaceData::make_cube(Matrix4::identity())).into(),
             material: SharedMaterial::new(Material::standard()).into(),
+            material_resource: Default::default(),
             vertex_weights: Default::default(),
             bones: Default::default(),
             unique_material: Default::default(),
+            blended_data: None,
+            blended_data_source: 0,
+            blend_shape_weights: Default::default(),
         }
     }
 }
@@ -1106,7 +1298,7 @@ impl Surface {
     pub fn batch_id(&self) -> u64 {
         let mut hasher = FxHasher::default();
         hasher.write_u64(self.material_id());
-        hasher.write_u64(self.data.key());
+        hasher.write_u64(self.render_data().key());
         hasher.finish()
     }
 
@@ -1121,11 +1313,121 @@ impl Surface {
         &self.material
     }
 
-    /// Sets new material for the surface.
+    /// Sets new material for the surface. If the surface has a material resource, the material
+    /// will override the material from the resource.
     pub fn set_material(&mut self, material: SharedMaterial) {
         self.material.set_value_and_mark_modified(material);
     }
 
+    /// Returns current material resource of the surface.
+    pub fn material_resource(&self) -> Option<&MaterialResource> {
+        self.material_resource.as_ref()
+    }
+
+    /// Sets new material resource for the surface. Any previous override of the material (see
+    /// [`Self::set_material`]) is discarded, and the material from the resource will be used as
+    /// soon as the resource is loaded. The material is re-applied automatically when the resource
+    /// is reloaded (for example, when its file was changed).
+    pub fn set_material_resource(&mut self, resource: Option<MaterialResource>) {
+        self.material_resource.set_value_and_mark_modified(resource);
+        let material = self.material.clone_inner();
+        self.material
+            .set_value_with_flags(material, VariableFlags::NONE);
+        self.sync_material_resource();
+    }
+
+    /// Returns `true` if the material of the surface overrides the material from the material
+    /// resource.
+    pub fn is_material_overridden(&self) -> bool {
+        self.material_resource.is_some() && self.material.is_modified()
+    }
+
+    /// Returns `true` if the surface uses the material from its material resource (the resource is
+    /// set and the material is not overridden).
+    pub fn is_material_from_resource(&self) -> bool {
+        self.material_resource.is_some() && !self.material.is_modified()
+    }
+
+    /// Applies the material from the material resource (if any), unless the material of the
+    /// surface is overridden.
+    pub(crate) fn sync_material_resource(&mut self) {
+        if self.material.is_modified() {
+            return;
+        }
+
+        if let Some(resource) = self.material_resource.as_ref() {
+            if let ResourceState::Ok(state) = &*resource.state() {
+                if state.material != *self.material {
+                    self.material.set_value_silent(state.material.clone());
+                }
+            }
+        }
+    }
+
+    /// Returns data that should be used for rendering. It is the same as [`Self::data`], unless the data has blend
+    /// shape targets with non-zero weights - in this case it returns a unique (per surface) copy of the data with
+    /// applied blend shapes.
+    #[inline]
+    pub fn render_data(&self) -> SurfaceSharedData {
+        self.blended_data
+            .clone()
+            .unwrap_or_else(|| (*self.data).clone())
+    }
+
+    /// Applies blend shapes to the surface data, blend shapes are matched with blend shape targets of the data by
+    /// names. The result of blending will be available via [`Self::render_data`]. Blending is performed only if
+    /// the weights were changed since last call.
+    pub(crate) fn apply_blend_shapes(&mut self, blend_shapes: &[BlendShape]) {
+        let data = self.data.lock();
+
+        if data.blend_shape_targets.is_empty() {
+            self.blended_data = None;
+            self.blend_shape_weights.clear();
+            return;
+        }
+
+        let weights = data
+            .blend_shape_targets
+            .iter()
+            .map(|target| {
+                blend_shapes
+                    .iter()
+                    .find(|blend_shape| blend_shape.name == target.name)
+                    .map_or(0.0, |blend_shape| blend_shape.weight)
+            })
+            .collect::<Vec<_>>();
+
+        if weights.iter().all(|weight| *weight == 0.0) {
+            self.blended_data = None;
+            self.blend_shape_weights = weights;
+            return;
+        }
+
+        let source = self.data.key();
+        if self.blended_data_source != source
+            || self.blended_data.as_ref().map_or(true, |blended_data| {
+                blended_data.lock().vertex_buffer.vertex_count()
+                    != data.vertex_buffer.vertex_count()
+            })
+        {
+            let mut blended_data = data.clone();
+            blended_data.blend_shape_targets.clear();
+            blended_data.cache_entry = AtomicIndex::unassigned();
+            self.blended_data = Some(SurfaceSharedData::new(blended_data));
+            self.blended_data_source = source;
+        } else if self.blend_shape_weights == weights {
+            return;
+        }
+
+        if let Some(blended_data) = self.blended_data.as_ref() {
+            if let Err(err) = blended_data.lock().apply_blend_shapes(&data, &weights) {
+                Log::err(format!("Unable to apply blend shapes. Reason: {}", err));
+            }
+        }
+
+        self.blend_shape_weights = weights;
+    }
+
     /// Returns list of bones that affects the surface.
     #[inline]
     pub fn bones(&self) -> &[Handle<Node>] {
@@ -1147,6 +1449,7 @@ impl Surface {
 pub struct SurfaceBuilder {
     data: SurfaceSharedData,
     material: Option<SharedMaterial>,
+    material_resource: Option<MaterialResource>,
     bones: Vec<Handle<Node>>,
     unique_material: bool,
 }
@@ -1157,6 +1460,7 @@ impl SurfaceBuilder {
         Self {
             data,
             material: None,
+            material_resource: None,
             bones: Default::default(),
             unique_material: false,
         }
@@ -1168,6 +1472,13 @@ impl SurfaceBuilder {
         self
     }
 
+    /// Sets desired material resource. The material from the resource will be used as soon as the
+    /// resource is loaded.
+    pub fn with_material_resource(mut self, resource: MaterialResource) -> Self {
+        self.material_resource = Some(resource);
+        self
+    }
+
     /// Sets desired bones array. Make sure your vertices has valid indices of bones!
     pub fn with_bones(mut self, bones: Vec<Handle<Node>>) -> Self {
         self.bones = bones;
@@ -1188,9 +1499,130 @@ impl SurfaceBuilder {
                 .material
                 .unwrap_or_else(|| SharedMaterial::new(Material::standard()))
                 .into(),
+            material_resource: self.material_resource.into(),
             vertex_weights: Default::default(),
             bones: self.bones.into(),
             unique_material: self.unique_material.into(),
+            blended_data: None,
+            blended_data_source: 0,
+            blend_shape_weights: Default::default(),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector3, Vector4},
+        scene::mesh::{
+            buffer::{VertexAttributeUsage, VertexReadTrait},
+            surface::{BlendShapeTarget, Surface, SurfaceData, SurfaceSharedData},
+            BlendShape,
+        },
+    };
+
+    fn read_positions(data: &SurfaceData) -> Vec<Vector3<f32>> {
+        data.vertex_buffer
+            .iter()
+            .map(|v| v.read_3_f32(VertexAttributeUsage::Position).unwrap())
+            .collect()
+    }
+
+    fn read_tangents(data: &SurfaceData) -> Vec<Vector4<f32>> {
+        data.vertex_buffer
+            .iter()
+            .map(|v| v.read_4_f32(VertexAttributeUsage::Tangent).unwrap())
+            .collect()
+    }
+
+    // Mirrors the unit quad along X axis, which flips its tangents.
+    fn make_mirrored_quad() -> SurfaceData {
+        let mut data = SurfaceData::make_unit_xy_quad();
+        data.calculate_tangents().unwrap();
+        let positions = read_positions(&data)
+            .into_iter()
+            .map(|p| Vector3::new(-2.0 * p.x, 0.0, 0.0))
+            .collect();
+        data.blend_shape_targets.push(BlendShapeTarget {
+            name: "Mirror".to_string(),
+            positions,
+            normals: Default::default(),
+            tangents: Default::default(),
+        });
+        data
+    }
+
+    #[test]
+    fn test_apply_blend_shapes() {
+        let base = make_mirrored_quad();
+        let base_positions = read_positions(&base);
+
+        let mut blended = base.clone();
+        blended.apply_blend_shapes(&base, &[0.5]).unwrap();
+        for (blended, base) in read_positions(&blended).iter().zip(base_positions.iter()) {
+            assert_eq!(*blended, Vector3::new(0.0, base.y, base.z));
+        }
+
+        blended.apply_blend_shapes(&base, &[0.0]).unwrap();
+        assert_eq!(read_positions(&blended), base_positions);
+
+        // Missing weights are treated as zero.
+        blended.apply_blend_shapes(&base, &[]).unwrap();
+        assert_eq!(read_positions(&blended), base_positions);
+    }
+
+    #[test]
+    fn test_blend_shape_tangents() {
+        let mut base = make_mirrored_quad();
+        base.calculate_blend_shape_tangents().unwrap();
+
+        let target = &base.blend_shape_targets[0];
+        assert_eq!(target.tangents.len(), 4);
+        for tangent in target.tangents.iter() {
+            assert!((tangent - Vector3::new(-2.0, 0.0, 0.0)).norm() < 1.0e-5);
+        }
+
+        let mut blended = base.clone();
+        blended.apply_blend_shapes(&base, &[1.0]).unwrap();
+        for (blended, base) in read_tangents(&blended)
+            .iter()
+            .zip(read_tangents(&base).iter())
+        {
+            assert!((blended.xyz() + base.xyz()).norm() < 1.0e-5);
+            // Handedness must be preserved.
+            assert_eq!(blended.w, base.w);
+        }
+    }
+
+    #[test]
+    fn test_surface_blend_shapes() {
+        let mut data = make_mirrored_quad();
+        data.calculate_blend_shape_tangents().unwrap();
+        let base_positions = read_positions(&data);
+        let mut surface = Surface::new(SurfaceSharedData::new(data));
+
+        // No weights - original data is used for rendering.
+        surface.apply_blend_shapes(&[]);
+        assert!(surface.blended_data.is_none());
+        assert_eq!(surface.render_data().key(), surface.data().key());
+
+        // Blend shapes with unknown names are ignored.
+        surface.apply_blend_shapes(&[BlendShape::new("Foo", 1.0)]);
+        assert!(surface.blended_data.is_none());
+
+        surface.apply_blend_shapes(&[BlendShape::new("Mirror", 1.0)]);
+        let render_data = surface.render_data();
+        assert_ne!(render_data.key(), surface.data().key());
+        for (blended, base) in read_positions(&render_data.lock())
+            .iter()
+            .zip(base_positions.iter())
+        {
+            assert_eq!(*blended, Vector3::new(-base.x, base.y, base.z));
+        }
+        // Source data must be untouched.
+        assert_eq!(read_positions(&surface.data().lock()), base_positions);
+
+        surface.apply_blend_shapes(&[BlendShape::new("Mirror", 0.0)]);
+        assert!(surface.blended_data.is_none());
+    }
+}
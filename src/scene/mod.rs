@@ -8,6 +8,7 @@ pub mod accel;
 pub mod animation;
 pub mod base;
 pub mod camera;
+pub mod character_controller;
 pub mod collider;
 pub mod debug;
 pub mod decal;
@@ -17,9 +18,11 @@ pub mod joint;
 pub mod light;
 pub mod loader;
 pub mod mesh;
+pub mod navmesh;
 pub mod node;
 pub mod particle_system;
 pub mod pivot;
+pub mod ragdoll;
 pub mod rigidbody;
 pub mod sound;
 pub mod sprite;
@@ -37,10 +40,13 @@ use crate::{
         sstorage::ImmutableString,
         visitor::{Visit, VisitError, VisitResult, Visitor},
     },
-    engine::{resource_manager::ResourceManager, SerializationContext},
+    engine::{
+        resource_manager::{loader::BoxedLoaderFuture, ResourceManager},
+        SerializationContext,
+    },
     material::{
         shader::{SamplerFallback, Shader, STANDARD_SHADER_NAMES},
-        PropertyValue,
+        MaterialResource, PropertyValue,
     },
     resource::{curve::CurveResource, model::Model, texture::Texture},
     scene::{
@@ -166,7 +172,8 @@ pub struct Scene {
     #[reflect(hidden)]
     pub drawing_context: SceneDrawingContext,
 
-    /// A container for navigational meshes.
+    /// A container for navigational meshes. See also [`navmesh::NavigationalMesh`] scene node, that
+    /// could be a part of a prefab and could be moved with its transform.
     #[reflect(hidden)]
     pub navmeshes: NavMeshContainer,
 
@@ -253,6 +260,8 @@ struct UsedResourcesSet {
     shaders: FxHashSet<Shader>,
     textures: FxHashSet<Texture>,
     sound_buffers: FxHashSet<SoundBufferResource>,
+    materials: FxHashSet<MaterialResource>,
+    custom: Vec<BoxedLoaderFuture>,
 }
 
 impl UsedResourcesSet {
@@ -263,6 +272,8 @@ impl UsedResourcesSet {
         join_all(self.textures).await;
         join_all(self.sound_buffers).await;
         join_all(self.models).await;
+        join_all(self.materials).await;
+        join_all(self.custom).await;
     }
 }
 
@@ -424,6 +435,33 @@ fn restore_resources(
         });
     }
 
+    if !mapped {
+        entity.downcast_mut::<MaterialResource>(&mut |result| {
+            if let Some(material) = result {
+                resource_manager
+                    .state()
+                    .containers_mut()
+                    .materials
+                    .try_restore_resource(material);
+
+                used_resources.materials.insert(material.clone());
+
+                mapped = true;
+            }
+        });
+    }
+
+    if !mapped {
+        // Resources of user-defined types.
+        let mut state = resource_manager.state();
+        for container in state.containers_mut().custom_containers_mut() {
+            if let Some(future) = container.try_restore_reflect(entity) {
+                used_resources.custom.push(future);
+                break;
+            }
+        }
+    }
+
     entity.fields_mut(&mut |fields| {
         for field in fields {
             // Continue resolving.
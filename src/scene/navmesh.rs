@@ -0,0 +1,470 @@
+//! Navigational mesh (navmesh for short) is a scene node that holds a surface used for path finding.
+//!
+//! For more info see [`NavigationalMesh`].
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Point3, Vector3},
+        math::{aabb::AxisAlignedBoundingBox, ray::Ray, TriangleDefinition},
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        graph::Graph,
+        node::{Node, NodeTrait, TypeUuidProvider},
+    },
+    utils::{
+        astar::{PathError, PathKind},
+        navmesh::{
+            crowd::{DynamicObstacle, ObstacleShape},
+            Navmesh, NavmeshCarve,
+        },
+    },
+};
+use std::ops::{Deref, DerefMut};
+
+/// Navigational mesh is a scene node that owns a [`Navmesh`]. Unlike navmeshes stored in
+/// [`crate::scene::Scene::navmeshes`], the node could be a part of a prefab, it could be
+/// instantiated from a model and it follows its own transform (for example a navmesh on a deck of
+/// a moving ship).
+///
+/// # Coordinate spaces
+///
+/// The navmesh is stored in local coordinates of the node and it is never rebuilt when the node
+/// moves. Instead, world-space queries are transformed into local space of the node, see
+/// [`Self::query_closest`], [`Self::build_path`] and [`Self::ray_cast`]. Agents that walk on the
+/// navmesh should work in local coordinates as well - transform their positions and targets with
+/// [`Self::world_to_local`] before updating them with [`Self::navmesh_mut`] and transform the
+/// results back with [`Self::local_to_world`]. This way agents stay on the navmesh even if the
+/// node is moving.
+///
+/// # Carving
+///
+/// Holes could be cut in the navmesh at runtime, for example to block a closed door or a crate
+/// that was pushed by the player. See [`Self::carve`] and [`Self::restore`]. Carves are runtime-only,
+/// they're not saved with the scene and they're discarded when the navmesh is replaced.
+///
+/// ```rust
+/// use fyrox::{
+///     core::{algebra::Vector3, pool::Handle},
+///     scene::{graph::Graph, navmesh::NavigationalMesh, node::Node},
+///     utils::navmesh::crowd::{DynamicObstacle, ObstacleShape},
+/// };
+///
+/// fn close_door(graph: &mut Graph, navmesh: Handle<Node>, door_position: Vector3<f32>) {
+///     if let Some(navmesh) = graph[navmesh].cast_mut::<NavigationalMesh>() {
+///         navmesh.carve(DynamicObstacle::new(
+///             door_position,
+///             ObstacleShape::Box {
+///                 half_extents: Vector3::new(1.0, 1.5, 0.2),
+///             },
+///         ));
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Visit, Reflect, Default)]
+pub struct NavigationalMesh {
+    base: Base,
+
+    #[reflect(setter = "set_navmesh")]
+    navmesh: InheritableVariable<Navmesh>,
+}
+
+impl Deref for NavigationalMesh {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for NavigationalMesh {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for NavigationalMesh {
+    fn type_uuid() -> Uuid {
+        uuid!("d0ce963c-b50a-4707-bd21-af6dc0d1c668")
+    }
+}
+
+impl NavigationalMesh {
+    /// Sets new navmesh (in local coordinates of the node) and returns the previous one. Carves of
+    /// the previous navmesh are discarded.
+    pub fn set_navmesh(&mut self, navmesh: Navmesh) -> Navmesh {
+        self.navmesh.set_value_and_mark_modified(navmesh)
+    }
+
+    /// Returns a reference to the navmesh (in local coordinates of the node).
+    pub fn navmesh(&self) -> &Navmesh {
+        &self.navmesh
+    }
+
+    /// Returns a reference to the navmesh (in local coordinates of the node). It should be used
+    /// for path finding with agents, see [`crate::utils::navmesh::NavmeshAgent::update`]. Changes
+    /// made using this method are not saved, if the node is inherited from a prefab. Use
+    /// [`Self::set_navmesh`] to make a persistent change.
+    pub fn navmesh_mut(&mut self) -> &mut Navmesh {
+        self.navmesh.get_value_mut_silent()
+    }
+
+    /// Transforms the given point from world coordinates to local coordinates of the navmesh.
+    pub fn world_to_local(&self, point: Vector3<f32>) -> Vector3<f32> {
+        self.inv_global_transform()
+            .transform_point(&Point3::from(point))
+            .coords
+    }
+
+    /// Transforms the given point from local coordinates of the navmesh to world coordinates.
+    pub fn local_to_world(&self, point: Vector3<f32>) -> Vector3<f32> {
+        self.global_transform()
+            .transform_point(&Point3::from(point))
+            .coords
+    }
+
+    /// Searches the closest vertex of the navmesh to the given point (in world coordinates). See
+    /// [`Navmesh::query_closest`] for more info.
+    pub fn query_closest(&mut self, point: Vector3<f32>) -> Option<usize> {
+        let point = self.world_to_local(point);
+        self.navmesh.get_value_mut_silent().query_closest(point)
+    }
+
+    /// Tries to build a path between the vertices with the given indices. Points of the path are
+    /// in world coordinates. See [`Navmesh::build_path`] for more info.
+    pub fn build_path(
+        &mut self,
+        from: usize,
+        to: usize,
+        path: &mut Vec<Vector3<f32>>,
+    ) -> Result<PathKind, PathError> {
+        let result = self
+            .navmesh
+            .get_value_mut_silent()
+            .build_path(from, to, path);
+        let transform = self.global_transform();
+        for point in path.iter_mut() {
+            *point = transform.transform_point(&Point3::from(*point)).coords;
+        }
+        result
+    }
+
+    /// Tries to pick a triangle by the given ray (in world coordinates). The intersection point is
+    /// in world coordinates as well. See [`Navmesh::ray_cast`] for more info.
+    pub fn ray_cast(&self, ray: Ray) -> Option<(Vector3<f32>, usize, TriangleDefinition)> {
+        self.navmesh
+            .ray_cast(ray.transform(self.inv_global_transform()))
+            .map(|(point, index, triangle)| (self.local_to_world(point), index, triangle))
+    }
+
+    /// Cuts a hole in the navmesh using the given obstacle (in world coordinates). The obstacle is
+    /// converted to local coordinates of the node, rotated boxes are replaced with their bounding
+    /// boxes. See [`Navmesh::carve`] for more info.
+    pub fn carve(&mut self, obstacle: DynamicObstacle) -> Handle<NavmeshCarve> {
+        let obstacle = self.obstacle_to_local(obstacle);
+        self.navmesh.get_value_mut_silent().carve(obstacle)
+    }
+
+    /// Restores triangles that were cut out by the given carve and returns the obstacle of the
+    /// carve (in local coordinates of the node). Returns `None` if the handle is invalid. See
+    /// [`Navmesh::restore`] for more info.
+    pub fn restore(&mut self, carve: Handle<NavmeshCarve>) -> Option<DynamicObstacle> {
+        self.navmesh.get_value_mut_silent().restore(carve)
+    }
+
+    fn inv_global_transform(&self) -> Matrix4<f32> {
+        self.global_transform()
+            .try_inverse()
+            .unwrap_or_else(Matrix4::identity)
+    }
+
+    fn obstacle_to_local(&self, obstacle: DynamicObstacle) -> DynamicObstacle {
+        let inv_transform = self.inv_global_transform();
+        match obstacle.shape {
+            ObstacleShape::Cylinder {
+                radius,
+                half_height,
+            } => {
+                let scale = |axis: Vector3<f32>| inv_transform.transform_vector(&axis).norm();
+                DynamicObstacle::new(
+                    self.world_to_local(obstacle.position),
+                    ObstacleShape::Cylinder {
+                        radius: radius * scale(Vector3::x()).max(scale(Vector3::z())),
+                        half_height: half_height * scale(Vector3::y()),
+                    },
+                )
+            }
+            ObstacleShape::Box { .. } => {
+                let bounds = obstacle.bounding_box().transform(&inv_transform);
+                DynamicObstacle::new(
+                    bounds.center(),
+                    ObstacleShape::Box {
+                        half_extents: bounds.half_extents(),
+                    },
+                )
+            }
+        }
+    }
+}
+
+impl NodeTrait for NavigationalMesh {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        let mut bounding_box = AxisAlignedBoundingBox::default();
+        for vertex in self.navmesh.vertices() {
+            bounding_box.add_point(vertex.position);
+        }
+        bounding_box
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+}
+
+/// Allows you to create navigational mesh in declarative manner.
+pub struct NavigationalMeshBuilder {
+    base_builder: BaseBuilder,
+    navmesh: Navmesh,
+}
+
+impl NavigationalMeshBuilder {
+    /// Creates new navigational mesh builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            navmesh: Navmesh::new(&[], &[]),
+        }
+    }
+
+    /// Sets new navmesh (in local coordinates of the node).
+    pub fn with_navmesh(mut self, navmesh: Navmesh) -> Self {
+        self.navmesh = navmesh;
+        self
+    }
+
+    /// Creates new [`NavigationalMesh`] instance.
+    pub fn build_navigational_mesh(self) -> NavigationalMesh {
+        NavigationalMesh {
+            base: self.base_builder.build_base(),
+            navmesh: self.navmesh.into(),
+        }
+    }
+
+    /// Creates new navigational mesh node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_navigational_mesh())
+    }
+
+    /// Creates new navigational mesh node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{UnitQuaternion, Vector2, Vector3},
+            math::{ray::Ray, TriangleDefinition},
+        },
+        scene::{
+            base::{
+                test::{check_inheritable_properties_equality, inherit_node_properties},
+                BaseBuilder,
+            },
+            graph::Graph,
+            navmesh::{NavigationalMesh, NavigationalMeshBuilder},
+            transform::TransformBuilder,
+        },
+        utils::{
+            astar::PathKind,
+            navmesh::{
+                crowd::{DynamicObstacle, ObstacleShape},
+                Navmesh, NavmeshAgentBuilder,
+            },
+        },
+    };
+
+    // A strip of 4 quads along X axis.
+    fn make_navmesh() -> Navmesh {
+        let mut vertices = Vec::new();
+        for x in 0..5 {
+            vertices.push(Vector3::new(x as f32, 0.0, 0.0));
+            vertices.push(Vector3::new(x as f32, 0.0, 1.0));
+        }
+        let mut triangles = Vec::new();
+        for x in 0..4 {
+            let i = x * 2;
+            triangles.push(TriangleDefinition([i, i + 1, i + 2]));
+            triangles.push(TriangleDefinition([i + 2, i + 1, i + 3]));
+        }
+        Navmesh::new(&triangles, &vertices)
+    }
+
+    #[test]
+    fn test_navigational_mesh_inheritance() {
+        let parent = NavigationalMeshBuilder::new(BaseBuilder::new())
+            .with_navmesh(make_navmesh())
+            .build_node();
+
+        let mut child = NavigationalMeshBuilder::new(BaseBuilder::new()).build_navigational_mesh();
+
+        inherit_node_properties(&mut child, &parent);
+
+        let parent = parent.cast::<NavigationalMesh>().unwrap();
+
+        check_inheritable_properties_equality(&child, parent);
+        assert_eq!(child.navmesh().triangles().len(), 8);
+    }
+
+    #[test]
+    fn test_navigational_mesh_transform_and_carving() {
+        let mut graph = Graph::new();
+        let handle = NavigationalMeshBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(10.0, 0.0, 0.0))
+                    .build(),
+            ),
+        )
+        .with_navmesh(make_navmesh())
+        .build(&mut graph);
+
+        graph.update(Vector2::new(1.0, 1.0), 1.0 / 60.0, Default::default());
+
+        let navmesh = graph[handle].cast_mut::<NavigationalMesh>().unwrap();
+
+        // The navmesh itself stays in local coordinates.
+        assert_eq!(
+            navmesh.navmesh().vertices()[0].position,
+            Vector3::new(0.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            navmesh.world_to_local(Vector3::new(10.0, 0.0, 0.0)),
+            Vector3::default()
+        );
+
+        let (point, _, _) = navmesh
+            .ray_cast(Ray::new(
+                Vector3::new(12.5, 1.0, 0.5),
+                Vector3::new(0.0, -2.0, 0.0),
+            ))
+            .unwrap();
+        assert!(point.metric_distance(&Vector3::new(12.5, 0.0, 0.5)) < 1.0e-5);
+
+        let from = navmesh.query_closest(Vector3::new(10.0, 0.0, 0.0)).unwrap();
+        let to = navmesh.query_closest(Vector3::new(14.0, 0.0, 1.0)).unwrap();
+        let mut path = Vec::new();
+        assert!(matches!(
+            navmesh.build_path(from, to, &mut path),
+            Ok(PathKind::Full)
+        ));
+        assert!(path.iter().all(|p| p.x >= 9.99 && p.x <= 14.01));
+
+        let from = navmesh.world_to_local(Vector3::new(10.2, 0.0, 0.5));
+        let to = navmesh.world_to_local(Vector3::new(13.8, 0.0, 0.5));
+        let mut agent = NavmeshAgentBuilder::new().build();
+        assert!(matches!(
+            agent.calculate_path(navmesh.navmesh_mut(), from, to),
+            Ok(PathKind::Full)
+        ));
+
+        // Block the middle of the strip, the obstacle is in world coordinates.
+        let carve = navmesh.carve(DynamicObstacle::new(
+            Vector3::new(12.0, 0.0, 0.5),
+            ObstacleShape::Cylinder {
+                radius: 0.5,
+                half_height: 1.0,
+            },
+        ));
+        let triangle_count = navmesh.navmesh().triangles().len();
+        assert!((0..triangle_count).any(|i| !navmesh.navmesh().is_triangle_enabled(i)));
+        assert!(matches!(
+            agent.calculate_path(navmesh.navmesh_mut(), from, to),
+            Ok(PathKind::Partial)
+        ));
+
+        // Restore it back.
+        assert!(navmesh.restore(carve).is_some());
+        assert!((0..triangle_count).all(|i| navmesh.navmesh().is_triangle_enabled(i)));
+        assert!(matches!(
+            agent.calculate_path(navmesh.navmesh_mut(), from, to),
+            Ok(PathKind::Full)
+        ));
+    }
+
+    #[test]
+    fn test_navigational_mesh_moving() {
+        let mut graph = Graph::new();
+        let handle = NavigationalMeshBuilder::new(BaseBuilder::new())
+            .with_navmesh(make_navmesh())
+            .build(&mut graph);
+
+        graph.update(Vector2::new(1.0, 1.0), 1.0 / 60.0, Default::default());
+
+        let carve =
+            graph[handle]
+                .cast_mut::<NavigationalMesh>()
+                .unwrap()
+                .carve(DynamicObstacle::new(
+                    Vector3::new(2.0, 0.0, 0.5),
+                    ObstacleShape::Cylinder {
+                        radius: 0.5,
+                        half_height: 1.0,
+                    },
+                ));
+
+        // Rotate the navmesh around Y by 90 degrees and move it, the strip goes along -Z now.
+        graph[handle]
+            .local_transform_mut()
+            .set_position(Vector3::new(0.0, 5.0, 0.0))
+            .set_rotation(UnitQuaternion::from_axis_angle(
+                &Vector3::y_axis(),
+                90.0f32.to_radians(),
+            ));
+        graph.update(Vector2::new(1.0, 1.0), 1.0 / 60.0, Default::default());
+
+        let navmesh = graph[handle].cast_mut::<NavigationalMesh>().unwrap();
+
+        // The carve moves with the node.
+        let carved = navmesh
+            .navmesh()
+            .carve_ref(carve)
+            .unwrap()
+            .triangles()
+            .to_vec();
+        assert!(!carved.is_empty());
+        assert!(navmesh
+            .ray_cast(Ray::new(
+                Vector3::new(0.5, 6.0, -2.0),
+                Vector3::new(0.0, -2.0, 0.0),
+            ))
+            .is_none());
+
+        let (point, index, _) = navmesh
+            .ray_cast(Ray::new(
+                Vector3::new(0.5, 6.0, -3.5),
+                Vector3::new(0.0, -2.0, 0.0),
+            ))
+            .unwrap();
+        assert!(point.metric_distance(&Vector3::new(0.5, 5.0, -3.5)) < 1.0e-5);
+        assert!(!carved.contains(&(index as u32)));
+
+        // The same point in local coordinates.
+        let local = navmesh.world_to_local(point);
+        assert!(local.metric_distance(&Vector3::new(3.5, 0.0, 0.5)) < 1.0e-5);
+    }
+}
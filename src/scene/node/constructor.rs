@@ -10,6 +10,7 @@ use crate::{
         dim2::{self, rectangle::Rectangle},
         light::{directional::DirectionalLight, point::PointLight, spot::SpotLight},
         mesh::Mesh,
+        navmesh::NavigationalMesh,
         node::{Node, NodeTrait, TypeUuidProvider},
         particle_system::ParticleSystem,
         pivot::Pivot,
@@ -34,6 +35,7 @@ impl NodeConstructorContainer {
     pub fn new() -> Self {
         let container = NodeConstructorContainer::default();
 
+        container.add::<dim2::character_controller::CharacterController>();
         container.add::<dim2::collider::Collider>();
         container.add::<dim2::joint::Joint>();
         container.add::<Rectangle>();
@@ -42,15 +44,18 @@ impl NodeConstructorContainer {
         container.add::<PointLight>();
         container.add::<SpotLight>();
         container.add::<Mesh>();
+        container.add::<NavigationalMesh>();
         container.add::<ParticleSystem>();
         container.add::<Sound>();
         container.add::<Listener>();
         container.add::<Camera>();
+        container.add::<scene::character_controller::CharacterController>();
         container.add::<scene::collider::Collider>();
         container.add::<Decal>();
         container.add::<scene::joint::Joint>();
         container.add::<Pivot>();
         container.add::<scene::rigidbody::RigidBody>();
+        container.add::<scene::ragdoll::Ragdoll>();
         container.add::<Sprite>();
         container.add::<Terrain>();
         container.add::<AnimationPlayer>();
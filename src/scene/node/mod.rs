@@ -432,11 +432,15 @@ impl Node {
     define_is_as!(scene::rigidbody::RigidBody  => fn is_rigid_body, fn as_rigid_body, fn as_rigid_body_mut);
     define_is_as!(scene::collider::Collider => fn is_collider, fn as_collider, fn as_collider_mut);
     define_is_as!(scene::joint::Joint  => fn is_joint, fn as_joint, fn as_joint_mut);
+    define_is_as!(scene::character_controller::CharacterController => fn is_character_controller, fn as_character_controller, fn as_character_controller_mut);
+    define_is_as!(scene::ragdoll::Ragdoll => fn is_ragdoll, fn as_ragdoll, fn as_ragdoll_mut);
     define_is_as!(dim2::rigidbody::RigidBody => fn is_rigid_body2d, fn as_rigid_body2d, fn as_rigid_body2d_mut);
     define_is_as!(dim2::collider::Collider => fn is_collider2d, fn as_collider2d, fn as_collider2d_mut);
     define_is_as!(dim2::joint::Joint => fn is_joint2d, fn as_joint2d, fn as_joint2d_mut);
+    define_is_as!(dim2::character_controller::CharacterController => fn is_character_controller2d, fn as_character_controller2d, fn as_character_controller2d_mut);
     define_is_as!(Sound => fn is_sound, fn as_sound, fn as_sound_mut);
     define_is_as!(Listener => fn is_listener, fn as_listener, fn as_listener_mut);
+    define_is_as!(scene::navmesh::NavigationalMesh => fn is_navigational_mesh, fn as_navigational_mesh, fn as_navigational_mesh_mut);
 }
 
 impl Visit for Node {
@@ -0,0 +1,1129 @@
+//! Ragdoll is a set of rigid bodies, colliders and joints that approximates a character's skeleton
+//! and allows it to be driven by physics. See [`Ragdoll`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, UnitQuaternion, Vector3},
+        math::{aabb::AxisAlignedBoundingBox, Matrix4Ext},
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        collider::{ColliderBuilder, ColliderShape, InteractionGroups},
+        graph::Graph,
+        joint::{BallJoint, JointBuilder, JointParams},
+        node::{Node, NodeTrait, TypeUuidProvider, UpdateContext},
+        rigidbody::{RigidBody, RigidBodyBuilder, RigidBodyType},
+        transform::TransformBuilder,
+    },
+};
+use std::ops::{Deref, DerefMut};
+
+/// Limb is a part of a ragdoll, it binds a bone of a skeleton with a rigid body (physical bone) that
+/// drives the bone when the ragdoll is active. Limbs form a tree that repeats the hierarchy of the
+/// bones.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct Limb {
+    /// A handle of a bone of a skeleton.
+    pub bone: Handle<Node>,
+
+    /// A handle of a rigid body that drives the bone.
+    pub physical_bone: Handle<Node>,
+
+    /// Child limbs of the limb.
+    pub children: Vec<Limb>,
+
+    // Last known pose of the physical bone in world coordinates. It is used to blend the pose of
+    // the bone back to animation after the ragdoll was deactivated.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    physical_pose: Option<(Vector3<f32>, UnitQuaternion<f32>)>,
+
+    // Last known local pose of the bone defined by animation.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    animated_pose: Option<(Vector3<f32>, UnitQuaternion<f32>)>,
+
+    // Local pose of the bone that was written by the ragdoll during the last update (if any).
+    #[visit(skip)]
+    #[reflect(hidden)]
+    written_pose: Option<(Vector3<f32>, UnitQuaternion<f32>)>,
+}
+
+impl Default for Limb {
+    fn default() -> Self {
+        Self {
+            bone: Default::default(),
+            physical_bone: Default::default(),
+            children: Default::default(),
+            physical_pose: None,
+            animated_pose: None,
+            written_pose: None,
+        }
+    }
+}
+
+impl Limb {
+    /// Creates new limb that binds the given bone with the given rigid body.
+    pub fn new(bone: Handle<Node>, physical_bone: Handle<Node>, children: Vec<Limb>) -> Self {
+        Self {
+            bone,
+            physical_bone,
+            children,
+            physical_pose: None,
+            animated_pose: None,
+            written_pose: None,
+        }
+    }
+
+    /// Calls the given function for the limb and for each of its descendants.
+    pub fn iterate_recursive<F>(&self, func: &mut F)
+    where
+        F: FnMut(&Self),
+    {
+        func(self);
+
+        for child in self.children.iter() {
+            child.iterate_recursive(func)
+        }
+    }
+}
+
+/// Mapping of a humanoid skeleton. It is used to generate a ragdoll for a character, see
+/// [`RagdollBuilder::with_bone_mapping`]. The mapping is generic over the type of its items, so it
+/// could be used to store names of the bones too (see [`HumanoidBoneMapping::MIXAMO`]). Any bone
+/// could be missing (could be set to [`Handle::NONE`]), in this case a limb for the bone won't be
+/// generated.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HumanoidBoneMapping<T = Handle<Node>> {
+    /// Hips (pelvis) bone, it is the root of the ragdoll.
+    pub hips: T,
+    /// Lower part of the spine.
+    pub spine: T,
+    /// Upper part of the spine.
+    pub chest: T,
+    /// Neck bone.
+    pub neck: T,
+    /// Head bone.
+    pub head: T,
+    /// Left upper arm bone.
+    pub left_upper_arm: T,
+    /// Left forearm bone.
+    pub left_forearm: T,
+    /// Left hand bone.
+    pub left_hand: T,
+    /// Right upper arm bone.
+    pub right_upper_arm: T,
+    /// Right forearm bone.
+    pub right_forearm: T,
+    /// Right hand bone.
+    pub right_hand: T,
+    /// Left thigh (upper leg) bone.
+    pub left_thigh: T,
+    /// Left shin (lower leg) bone.
+    pub left_shin: T,
+    /// Left foot bone.
+    pub left_foot: T,
+    /// Right thigh (upper leg) bone.
+    pub right_thigh: T,
+    /// Right shin (lower leg) bone.
+    pub right_shin: T,
+    /// Right foot bone.
+    pub right_foot: T,
+}
+
+impl HumanoidBoneMapping<&'static str> {
+    /// Names of the bones of skeletons produced by Mixamo.
+    pub const MIXAMO: Self = Self {
+        hips: "mixamorig:Hips",
+        spine: "mixamorig:Spine",
+        chest: "mixamorig:Spine2",
+        neck: "mixamorig:Neck",
+        head: "mixamorig:Head",
+        left_upper_arm: "mixamorig:LeftArm",
+        left_forearm: "mixamorig:LeftForeArm",
+        left_hand: "mixamorig:LeftHand",
+        right_upper_arm: "mixamorig:RightArm",
+        right_forearm: "mixamorig:RightForeArm",
+        right_hand: "mixamorig:RightHand",
+        left_thigh: "mixamorig:LeftUpLeg",
+        left_shin: "mixamorig:LeftLeg",
+        left_foot: "mixamorig:LeftFoot",
+        right_thigh: "mixamorig:RightUpLeg",
+        right_shin: "mixamorig:RightLeg",
+        right_foot: "mixamorig:RightFoot",
+    };
+}
+
+impl<T> HumanoidBoneMapping<T> {
+    /// Creates new mapping by applying the given function to every item of the mapping.
+    pub fn map<U, F>(&self, mut func: F) -> HumanoidBoneMapping<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        HumanoidBoneMapping {
+            hips: func(&self.hips),
+            spine: func(&self.spine),
+            chest: func(&self.chest),
+            neck: func(&self.neck),
+            head: func(&self.head),
+            left_upper_arm: func(&self.left_upper_arm),
+            left_forearm: func(&self.left_forearm),
+            left_hand: func(&self.left_hand),
+            right_upper_arm: func(&self.right_upper_arm),
+            right_forearm: func(&self.right_forearm),
+            right_hand: func(&self.right_hand),
+            left_thigh: func(&self.left_thigh),
+            left_shin: func(&self.left_shin),
+            left_foot: func(&self.left_foot),
+            right_thigh: func(&self.right_thigh),
+            right_shin: func(&self.right_shin),
+            right_foot: func(&self.right_foot),
+        }
+    }
+
+    // The order must match `HUMANOID_TOPOLOGY`.
+    fn items(&self) -> [&T; 17] {
+        [
+            &self.hips,
+            &self.spine,
+            &self.chest,
+            &self.neck,
+            &self.head,
+            &self.left_upper_arm,
+            &self.left_forearm,
+            &self.left_hand,
+            &self.right_upper_arm,
+            &self.right_forearm,
+            &self.right_hand,
+            &self.left_thigh,
+            &self.left_shin,
+            &self.left_foot,
+            &self.right_thigh,
+            &self.right_shin,
+            &self.right_foot,
+        ]
+    }
+}
+
+impl HumanoidBoneMapping {
+    /// Searches for the bones with the given names in a hierarchy starting from the given skeleton
+    /// root. Bones that weren't found will be set to [`Handle::NONE`].
+    pub fn find_by_names(
+        graph: &Graph,
+        skeleton_root: Handle<Node>,
+        names: &HumanoidBoneMapping<&str>,
+    ) -> Self {
+        names.map(|name| {
+            graph
+                .find_by_name(skeleton_root, name)
+                .map(|(handle, _)| handle)
+                .unwrap_or_default()
+        })
+    }
+}
+
+// Defines a bone in a humanoid skeleton: an index of its parent bone, an index of a bone at which
+// the bone "ends" and a radius of the bone relative to its length. Bones without an end bone are
+// approximated by spheres with a radius relative to the length of the parent bone.
+struct HumanoidBone {
+    parent: Option<usize>,
+    end: Option<usize>,
+    relative_radius: f32,
+}
+
+const fn bone(parent: Option<usize>, end: Option<usize>, relative_radius: f32) -> HumanoidBone {
+    HumanoidBone {
+        parent,
+        end,
+        relative_radius,
+    }
+}
+
+const HUMANOID_TOPOLOGY: [HumanoidBone; 17] = [
+    // Hips
+    bone(None, Some(1), 0.6),
+    // Spine
+    bone(Some(0), Some(2), 0.6),
+    // Chest
+    bone(Some(1), Some(3), 0.6),
+    // Neck
+    bone(Some(2), Some(4), 0.3),
+    // Head
+    bone(Some(3), None, 1.0),
+    // Left arm
+    bone(Some(2), Some(6), 0.2),
+    bone(Some(5), Some(7), 0.2),
+    bone(Some(6), None, 0.3),
+    // Right arm
+    bone(Some(2), Some(9), 0.2),
+    bone(Some(8), Some(10), 0.2),
+    bone(Some(9), None, 0.3),
+    // Left leg
+    bone(Some(0), Some(12), 0.2),
+    bone(Some(11), Some(13), 0.15),
+    bone(Some(12), None, 0.2),
+    // Right leg
+    bone(Some(0), Some(15), 0.2),
+    bone(Some(14), Some(16), 0.15),
+    bone(Some(15), None, 0.2),
+];
+
+// Extracts rotation part of a transform matrix, discarding scale.
+fn rotation_of(transform: &Matrix4<f32>) -> UnitQuaternion<f32> {
+    let mut basis = transform.basis();
+    for mut column in basis.column_iter_mut() {
+        let length = column.norm();
+        if length > f32::EPSILON {
+            column /= length;
+        }
+    }
+    UnitQuaternion::from_matrix_eps(&basis, f32::EPSILON, 16, UnitQuaternion::identity())
+}
+
+fn position_of(transform: &Matrix4<f32>) -> Vector3<f32> {
+    Vector3::new(transform[12], transform[13], transform[14])
+}
+
+/// Ragdoll is a set of rigid bodies connected with joints that approximates a character's skeleton.
+/// Each rigid body (physical bone) is bound to a bone of the skeleton using [`Limb`]s.
+///
+/// # Activation and blending
+///
+/// When the ragdoll is inactive, its rigid bodies are kinematic and they follow the bones, which in
+/// their turn are driven by animation. When the ragdoll is active, the rigid bodies are dynamic and
+/// the bones follow them. The blend weight defines how much the pose of the physical bones affects
+/// the pose of the bones: `0.0` - the bones are driven by animation only, `1.0` - the bones are
+/// driven by physics only.
+///
+/// When the ragdoll is deactivated, the last pose of the physical bones is remembered and the bones
+/// stay in this pose until the blend weight is decreased. This allows you to smoothly blend a fallen
+/// character back to animation (for example to "get up" animation) by decreasing the blend weight
+/// over time.
+///
+/// The ragdoll modifies local transforms of the bones, so it must be updated after animation. The
+/// graph guarantees this by updating ragdolls after every other node.
+///
+/// # Generation
+///
+/// Physical bones, colliders and joints could be generated automatically for a humanoid skeleton,
+/// see [`RagdollBuilder::with_bone_mapping`].
+///
+/// ```rust
+/// use fyrox::{
+///     core::pool::Handle,
+///     scene::{
+///         base::BaseBuilder,
+///         graph::Graph,
+///         node::Node,
+///         ragdoll::{HumanoidBoneMapping, RagdollBuilder},
+///     },
+/// };
+///
+/// fn create_ragdoll(graph: &mut Graph, character_model: Handle<Node>) -> Handle<Node> {
+///     let mapping =
+///         HumanoidBoneMapping::find_by_names(graph, character_model, &HumanoidBoneMapping::MIXAMO);
+///
+///     RagdollBuilder::new(BaseBuilder::new().with_name("Ragdoll"))
+///         .with_bone_mapping(mapping)
+///         .build(graph)
+/// }
+/// ```
+#[derive(Clone, Debug, Visit, Reflect)]
+pub struct Ragdoll {
+    base: Base,
+
+    #[reflect(
+        description = "Whether the ragdoll is active (driven by physics) or not (driven by animation)."
+    )]
+    #[reflect(setter = "set_active")]
+    pub(crate) is_active: InheritableVariable<bool>,
+
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        step = 0.01,
+        description = "Defines how much the physical bones affect the bones."
+    )]
+    #[reflect(setter = "set_blend_weight")]
+    pub(crate) blend_weight: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_root_limb")]
+    pub(crate) root_limb: InheritableVariable<Limb>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    prev_active: Option<bool>,
+}
+
+impl Default for Ragdoll {
+    fn default() -> Self {
+        RagdollBuilder::new(BaseBuilder::new()).build_ragdoll()
+    }
+}
+
+impl Deref for Ragdoll {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for Ragdoll {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for Ragdoll {
+    fn type_uuid() -> Uuid {
+        uuid!("f4441683-dcef-472d-9d7d-4adca4579107")
+    }
+}
+
+impl Ragdoll {
+    /// Activates or deactivates the ragdoll. Active ragdoll is driven by physics, inactive - by
+    /// animation. See [`Ragdoll`] docs for more info.
+    pub fn set_active(&mut self, active: bool) -> bool {
+        self.is_active.set_value_and_mark_modified(active)
+    }
+
+    /// Returns `true` if the ragdoll is active (driven by physics), `false` - otherwise.
+    pub fn is_active(&self) -> bool {
+        *self.is_active
+    }
+
+    /// Sets the blend weight between animation (`0.0`) and physics (`1.0`). See [`Ragdoll`] docs
+    /// for more info.
+    pub fn set_blend_weight(&mut self, weight: f32) -> f32 {
+        self.blend_weight
+            .set_value_and_mark_modified(weight.clamp(0.0, 1.0))
+    }
+
+    /// Returns current blend weight between animation and physics.
+    pub fn blend_weight(&self) -> f32 {
+        *self.blend_weight
+    }
+
+    /// Sets new root limb of the ragdoll.
+    pub fn set_root_limb(&mut self, root_limb: Limb) -> Limb {
+        self.root_limb.set_value_and_mark_modified(root_limb)
+    }
+
+    /// Returns a reference to the root limb of the ragdoll.
+    pub fn root_limb(&self) -> &Limb {
+        &self.root_limb
+    }
+
+    fn set_body_types(limb: &Limb, context: &mut UpdateContext, active: bool) {
+        if let Some(body) = context
+            .nodes
+            .try_borrow_mut(limb.physical_bone)
+            .and_then(|n| n.cast_mut::<RigidBody>())
+        {
+            if active {
+                body.set_body_type(RigidBodyType::Dynamic);
+                body.wake_up();
+            } else {
+                body.set_body_type(RigidBodyType::KinematicPositionBased);
+            }
+        }
+
+        for child in limb.children.iter() {
+            Self::set_body_types(child, context, active);
+        }
+    }
+
+    fn update_limb(
+        &self,
+        limb: &mut Limb,
+        parent_animated_transform: Matrix4<f32>,
+        parent_transform: Matrix4<f32>,
+        context: &mut UpdateContext,
+    ) {
+        let active = *self.is_active;
+        let weight = *self.blend_weight;
+
+        let bone_local_transform = match context.nodes.try_borrow(limb.bone) {
+            Some(bone) => {
+                let local_transform = bone.local_transform();
+                let pose = (**local_transform.position(), **local_transform.rotation());
+                // If the bone still has the pose written by the ragdoll, then the animation did not
+                // change it since the last update and the last known animation pose must be used.
+                // Otherwise the ragdoll would blend its own output over and over again.
+                if limb.animated_pose.is_none() || limb.written_pose != Some(pose) {
+                    limb.animated_pose = Some(pose);
+                }
+                let (position, rotation) = limb.animated_pose.unwrap_or(pose);
+                let mut animated_local_transform = local_transform.clone();
+                animated_local_transform
+                    .set_position(position)
+                    .set_rotation(rotation);
+                animated_local_transform.matrix()
+            }
+            None => return,
+        };
+
+        // Global transform of the bone defined by animation.
+        let animated_transform = parent_animated_transform * bone_local_transform;
+        let animated_position = position_of(&animated_transform);
+        let animated_rotation = rotation_of(&animated_transform);
+
+        if let Some(body) = context
+            .nodes
+            .try_borrow(limb.physical_bone)
+            .and_then(|n| n.cast::<RigidBody>())
+        {
+            if active {
+                if let Some(isometry) = context.physics.rigid_body_position(body) {
+                    limb.physical_pose = Some((isometry.translation.vector, isometry.rotation));
+                }
+            } else {
+                // Make the kinematic physical bone follow the animated bone.
+                let body_parent = body.parent();
+                let body_parent_transform = if body_parent == self.self_handle {
+                    self.global_transform()
+                } else {
+                    context
+                        .nodes
+                        .try_borrow(body_parent)
+                        .map(|p| p.global_transform())
+                        .unwrap_or_else(Matrix4::identity)
+                };
+                let inv_body_parent_transform = body_parent_transform
+                    .try_inverse()
+                    .unwrap_or_else(Matrix4::identity);
+                let local_position = inv_body_parent_transform
+                    .transform_point(&animated_position.into())
+                    .coords;
+                let local_rotation =
+                    rotation_of(&body_parent_transform).inverse() * animated_rotation;
+
+                if let Some(body) = context
+                    .nodes
+                    .try_borrow_mut(limb.physical_bone)
+                    .and_then(|n| n.cast_mut::<RigidBody>())
+                {
+                    body.local_transform_mut()
+                        .set_position(local_position)
+                        .set_rotation(local_rotation);
+                }
+            }
+        }
+
+        let transform = match limb.physical_pose {
+            Some((physical_position, physical_rotation)) if weight > 0.0 => {
+                let position = animated_position.lerp(&physical_position, weight);
+                let rotation = animated_rotation
+                    .try_slerp(&physical_rotation, weight, f32::EPSILON)
+                    .unwrap_or(physical_rotation);
+
+                let local_position = parent_transform
+                    .try_inverse()
+                    .unwrap_or_else(Matrix4::identity)
+                    .transform_point(&position.into())
+                    .coords;
+                let local_rotation = rotation_of(&parent_transform).inverse() * rotation;
+
+                match context.nodes.try_borrow_mut(limb.bone) {
+                    Some(bone) => {
+                        bone.local_transform_mut()
+                            .set_position(local_position)
+                            .set_rotation(local_rotation);
+                        limb.written_pose = Some((local_position, local_rotation));
+                        parent_transform * bone.local_transform().matrix()
+                    }
+                    None => return,
+                }
+            }
+            _ => {
+                // Give the bone back to animation.
+                if limb.written_pose.take().is_some() {
+                    if let (Some(bone), Some((position, rotation))) =
+                        (context.nodes.try_borrow_mut(limb.bone), limb.animated_pose)
+                    {
+                        bone.local_transform_mut()
+                            .set_position(position)
+                            .set_rotation(rotation);
+                    }
+                }
+                animated_transform
+            }
+        };
+
+        for child in limb.children.iter_mut() {
+            self.update_limb(child, animated_transform, transform, context);
+        }
+    }
+}
+
+impl NodeTrait for Ragdoll {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.local_bounding_box()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.world_bounding_box()
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        let active = *self.is_active;
+        if self.prev_active != Some(active) {
+            Self::set_body_types(&self.root_limb, context, active);
+            self.prev_active = Some(active);
+        }
+
+        // Root limb could be a "virtual" limb without a bone, in this case its children are the
+        // actual roots.
+        let mut root_limb = std::mem::take(self.root_limb.get_value_mut_silent());
+        let roots = if root_limb.bone.is_some() {
+            std::slice::from_mut(&mut root_limb)
+        } else {
+            root_limb.children.as_mut_slice()
+        };
+        for limb in roots {
+            let parent_transform = context
+                .nodes
+                .try_borrow(limb.bone)
+                .and_then(|bone| context.nodes.try_borrow(bone.parent()))
+                .map(|parent| parent.global_transform())
+                .unwrap_or_else(Matrix4::identity);
+            self.update_limb(limb, parent_transform, parent_transform, context);
+        }
+        *self.root_limb.get_value_mut_silent() = root_limb;
+    }
+}
+
+/// Allows you to create a ragdoll in declarative manner.
+pub struct RagdollBuilder {
+    base_builder: BaseBuilder,
+    is_active: bool,
+    blend_weight: f32,
+    root_limb: Limb,
+    bone_mapping: Option<HumanoidBoneMapping>,
+    radius_scale: f32,
+    density: f32,
+    joint_limit: f32,
+    collision_groups: InteractionGroups,
+}
+
+impl RagdollBuilder {
+    /// Creates new ragdoll builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            is_active: false,
+            blend_weight: 1.0,
+            root_limb: Default::default(),
+            bone_mapping: None,
+            radius_scale: 1.0,
+            density: 1000.0,
+            joint_limit: 45.0f32.to_radians(),
+            collision_groups: Default::default(),
+        }
+    }
+
+    /// Sets whether the ragdoll is active or not.
+    pub fn with_active(mut self, active: bool) -> Self {
+        self.is_active = active;
+        self
+    }
+
+    /// Sets desired blend weight between animation and physics.
+    pub fn with_blend_weight(mut self, weight: f32) -> Self {
+        self.blend_weight = weight;
+        self
+    }
+
+    /// Sets desired root limb. Use this method if you want to create physical bones manually,
+    /// otherwise use [`Self::with_bone_mapping`].
+    pub fn with_root_limb(mut self, root_limb: Limb) -> Self {
+        self.root_limb = root_limb;
+        self
+    }
+
+    /// Sets a bone mapping that will be used to generate rigid bodies, colliders and joints when
+    /// the ragdoll is built using [`Self::build`]. Generated limbs replace the root limb (if any).
+    pub fn with_bone_mapping(mut self, bone_mapping: HumanoidBoneMapping) -> Self {
+        self.bone_mapping = Some(bone_mapping);
+        self
+    }
+
+    /// Sets a scale for radii of generated colliders.
+    pub fn with_radius_scale(mut self, scale: f32) -> Self {
+        self.radius_scale = scale;
+        self
+    }
+
+    /// Sets density of generated colliders. Default is `1000.0`, which is approximately the density
+    /// of a human body.
+    pub fn with_density(mut self, density: f32) -> Self {
+        self.density = density;
+        self
+    }
+
+    /// Sets angular limits (in radians) of generated ball joints. The same limit is used for every
+    /// axis of a joint.
+    pub fn with_joint_limit(mut self, limit: f32) -> Self {
+        self.joint_limit = limit;
+        self
+    }
+
+    /// Sets collision groups of generated colliders.
+    pub fn with_collision_groups(mut self, groups: InteractionGroups) -> Self {
+        self.collision_groups = groups;
+        self
+    }
+
+    /// Creates Ragdoll node but does not add it to the graph. Physical bones are **not** generated.
+    pub fn build_ragdoll(self) -> Ragdoll {
+        Ragdoll {
+            base: self.base_builder.build_base(),
+            is_active: self.is_active.into(),
+            blend_weight: self.blend_weight.into(),
+            root_limb: self.root_limb.into(),
+            prev_active: None,
+        }
+    }
+
+    /// Creates Ragdoll node but does not add it to the graph. Physical bones are **not** generated.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_ragdoll())
+    }
+
+    /// Creates Ragdoll node and adds it to the graph. If a bone mapping was set, it also generates
+    /// rigid bodies, colliders and joints for the bones and adds them as children of the ragdoll.
+    pub fn build(mut self, graph: &mut Graph) -> Handle<Node> {
+        let bone_mapping = self.bone_mapping.take();
+        let is_active = self.is_active;
+        let radius_scale = self.radius_scale;
+        let density = self.density;
+        let joint_limit = self.joint_limit;
+        let collision_groups = self.collision_groups;
+
+        let ragdoll = graph.add_node(self.build_node());
+
+        if let Some(bone_mapping) = bone_mapping {
+            // Make sure global transforms of the bones are up to date.
+            graph.update_hierarchical_data();
+
+            let inv_ragdoll_transform = graph[ragdoll]
+                .global_transform()
+                .try_inverse()
+                .unwrap_or_else(Matrix4::identity);
+            let ragdoll_rotation = rotation_of(&graph[ragdoll].global_transform());
+
+            let bones = bone_mapping.items().map(|handle| {
+                graph
+                    .try_get(*handle)
+                    .map(|bone| (*handle, bone.global_transform()))
+            });
+
+            // Find closest existing parent bone for every bone.
+            let parents = HUMANOID_TOPOLOGY
+                .iter()
+                .map(|desc| {
+                    let mut parent = desc.parent;
+                    while let Some(index) = parent {
+                        if bones[index].is_some() {
+                            break;
+                        }
+                        parent = HUMANOID_TOPOLOGY[index].parent;
+                    }
+                    parent
+                })
+                .collect::<Vec<_>>();
+
+            let mut lengths = [0.0f32; 17];
+            let mut physical_bones = [Handle::<Node>::NONE; 17];
+            for (index, desc) in HUMANOID_TOPOLOGY.iter().enumerate() {
+                let (bone_handle, bone_transform) = match bones[index] {
+                    Some(bone) => bone,
+                    None => continue,
+                };
+
+                let position = position_of(&bone_transform);
+                let rotation = rotation_of(&bone_transform);
+
+                let end = desc
+                    .end
+                    .and_then(|end| bones[end].map(|(_, t)| position_of(&t)));
+
+                let shape = match end {
+                    Some(end) => {
+                        let length = end.metric_distance(&position);
+                        lengths[index] = length;
+                        ColliderShape::capsule(
+                            Vector3::default(),
+                            rotation.inverse() * (end - position),
+                            (length * desc.relative_radius * radius_scale).max(0.01),
+                        )
+                    }
+                    None => {
+                        let parent_length = parents[index].map_or(0.1, |p| lengths[p]);
+                        ColliderShape::ball(
+                            (parent_length * desc.relative_radius * radius_scale).max(0.01),
+                        )
+                    }
+                };
+
+                let collider = ColliderBuilder::new(
+                    BaseBuilder::new().with_name(format!("{}_Collider", graph[bone_handle].name())),
+                )
+                .with_shape(shape)
+                .with_density(Some(density))
+                .with_collision_groups(collision_groups)
+                .build(graph);
+
+                let physical_bone = RigidBodyBuilder::new(
+                    BaseBuilder::new()
+                        .with_name(format!("{}_Body", graph[bone_handle].name()))
+                        .with_local_transform(
+                            TransformBuilder::new()
+                                .with_local_position(
+                                    inv_ragdoll_transform
+                                        .transform_point(&position.into())
+                                        .coords,
+                                )
+                                .with_local_rotation(ragdoll_rotation.inverse() * rotation)
+                                .build(),
+                        )
+                        .with_children(&[collider]),
+                )
+                .with_body_type(if is_active {
+                    RigidBodyType::Dynamic
+                } else {
+                    RigidBodyType::KinematicPositionBased
+                })
+                .build(graph);
+                graph.link_nodes(physical_bone, ragdoll);
+                physical_bones[index] = physical_bone;
+
+                if let Some(parent) = parents[index] {
+                    let joint = JointBuilder::new(
+                        BaseBuilder::new()
+                            .with_name(format!("{}_Joint", graph[bone_handle].name()))
+                            .with_local_transform(
+                                TransformBuilder::new()
+                                    .with_local_position(
+                                        inv_ragdoll_transform
+                                            .transform_point(&position.into())
+                                            .coords,
+                                    )
+                                    .with_local_rotation(ragdoll_rotation.inverse() * rotation)
+                                    .build(),
+                            ),
+                    )
+                    .with_params(JointParams::BallJoint(BallJoint {
+                        x_limits_enabled: true,
+                        x_limits_angles: -joint_limit..joint_limit,
+                        y_limits_enabled: true,
+                        y_limits_angles: -joint_limit..joint_limit,
+                        z_limits_enabled: true,
+                        z_limits_angles: -joint_limit..joint_limit,
+                        ..Default::default()
+                    }))
+                    .with_body1(physical_bones[parent])
+                    .with_body2(physical_bone)
+                    .with_contacts_enabled(false)
+                    .build(graph);
+                    graph.link_nodes(joint, ragdoll);
+                }
+            }
+
+            fn make_limb(
+                index: usize,
+                bones: &[Option<(Handle<Node>, Matrix4<f32>)>],
+                parents: &[Option<usize>],
+                physical_bones: &[Handle<Node>],
+            ) -> Limb {
+                Limb::new(
+                    bones[index].map(|(handle, _)| handle).unwrap_or_default(),
+                    physical_bones[index],
+                    (0..bones.len())
+                        .filter(|child| bones[*child].is_some() && parents[*child] == Some(index))
+                        .map(|child| make_limb(child, bones, parents, physical_bones))
+                        .collect(),
+                )
+            }
+
+            // Bones without parents are roots, there could be multiple roots if hips bone is
+            // missing. In this case a "virtual" root limb without a bone is used.
+            let mut roots = (0..bones.len())
+                .filter(|index| bones[*index].is_some() && parents[*index].is_none())
+                .map(|index| make_limb(index, &bones, &parents, &physical_bones))
+                .collect::<Vec<_>>();
+            let root_limb = if roots.len() == 1 {
+                roots.pop().unwrap()
+            } else {
+                Limb::new(Handle::NONE, Handle::NONE, roots)
+            };
+
+            if let Some(ragdoll) = graph[ragdoll].cast_mut::<Ragdoll>() {
+                ragdoll.set_root_limb(root_limb);
+            }
+        }
+
+        ragdoll
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{UnitQuaternion, Vector2, Vector3},
+            pool::Handle,
+        },
+        scene::{
+            base::{
+                test::{check_inheritable_properties_equality, inherit_node_properties},
+                BaseBuilder,
+            },
+            graph::Graph,
+            joint::Joint,
+            node::{Node, NodeTrait},
+            pivot::PivotBuilder,
+            ragdoll::{HumanoidBoneMapping, Limb, Ragdoll, RagdollBuilder, HUMANOID_TOPOLOGY},
+            rigidbody::RigidBody,
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_ragdoll_inheritance() {
+        let parent = RagdollBuilder::new(BaseBuilder::new())
+            .with_active(true)
+            .with_blend_weight(0.5)
+            .with_root_limb(Limb::default())
+            .build_node();
+
+        let mut child = RagdollBuilder::new(BaseBuilder::new()).build_ragdoll();
+
+        inherit_node_properties(&mut child, &parent);
+
+        let parent = parent.cast::<Ragdoll>().unwrap();
+
+        check_inheritable_properties_equality(&child.base, &parent.base);
+        check_inheritable_properties_equality(&child, parent);
+    }
+
+    // Creates a skeleton that repeats humanoid topology, every bone is shifted up from its parent.
+    fn make_skeleton(graph: &mut Graph) -> HumanoidBoneMapping {
+        let mut bones = Vec::new();
+        for (index, desc) in HUMANOID_TOPOLOGY.iter().enumerate() {
+            let bone = PivotBuilder::new(
+                BaseBuilder::new()
+                    .with_name(format!("Bone{}", index))
+                    .with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(Vector3::new(0.0, 0.5, 0.0))
+                            .build(),
+                    ),
+            )
+            .build(graph);
+            if let Some(parent) = desc.parent {
+                graph.link_nodes(bone, bones[parent]);
+            }
+            bones.push(bone);
+        }
+        HumanoidBoneMapping {
+            hips: bones[0],
+            spine: bones[1],
+            chest: bones[2],
+            neck: bones[3],
+            head: bones[4],
+            left_upper_arm: bones[5],
+            left_forearm: bones[6],
+            left_hand: bones[7],
+            right_upper_arm: bones[8],
+            right_forearm: bones[9],
+            right_hand: bones[10],
+            left_thigh: bones[11],
+            left_shin: bones[12],
+            left_foot: bones[13],
+            right_thigh: bones[14],
+            right_shin: bones[15],
+            right_foot: bones[16],
+        }
+    }
+
+    fn child_bones(limb: &Limb) -> Vec<Handle<Node>> {
+        limb.children.iter().map(|c| c.bone).collect()
+    }
+
+    fn count_nodes<T: NodeTrait>(graph: &Graph, ragdoll: Handle<Node>) -> usize {
+        graph[ragdoll]
+            .children()
+            .iter()
+            .filter(|c| graph[**c].cast::<T>().is_some())
+            .count()
+    }
+
+    fn build_ragdoll(graph: &mut Graph, mapping: HumanoidBoneMapping) -> Handle<Node> {
+        RagdollBuilder::new(BaseBuilder::new())
+            .with_bone_mapping(mapping)
+            .build(graph)
+    }
+
+    #[test]
+    fn test_ragdoll_generation() {
+        let mut graph = Graph::new();
+        let m = make_skeleton(&mut graph);
+        let ragdoll = build_ragdoll(&mut graph, m.clone());
+
+        let root = graph[ragdoll]
+            .cast::<Ragdoll>()
+            .unwrap()
+            .root_limb()
+            .clone();
+
+        let mut limbs = 0;
+        root.iterate_recursive(&mut |limb| {
+            limbs += 1;
+            assert!(graph[limb.physical_bone].cast::<RigidBody>().is_some());
+            assert_eq!(graph[limb.physical_bone].parent(), ragdoll);
+        });
+        assert_eq!(limbs, 17);
+        assert_eq!(count_nodes::<RigidBody>(&graph, ragdoll), 17);
+        assert_eq!(count_nodes::<Joint>(&graph, ragdoll), 16);
+
+        assert_eq!(root.bone, m.hips);
+        assert_eq!(
+            child_bones(&root),
+            vec![m.spine, m.left_thigh, m.right_thigh]
+        );
+        let spine = &root.children[0];
+        assert_eq!(child_bones(spine), vec![m.chest]);
+        let chest = &spine.children[0];
+        assert_eq!(
+            child_bones(chest),
+            vec![m.neck, m.left_upper_arm, m.right_upper_arm]
+        );
+        assert_eq!(child_bones(&chest.children[0]), vec![m.head]);
+        assert_eq!(child_bones(&chest.children[1]), vec![m.left_forearm]);
+        assert_eq!(
+            child_bones(&chest.children[1].children[0]),
+            vec![m.left_hand]
+        );
+        assert_eq!(child_bones(&root.children[2]), vec![m.right_shin]);
+        assert_eq!(
+            child_bones(&root.children[2].children[0]),
+            vec![m.right_foot]
+        );
+    }
+
+    #[test]
+    fn test_ragdoll_generation_with_missing_bones() {
+        let mut graph = Graph::new();
+        let m = make_skeleton(&mut graph);
+        let ragdoll = build_ragdoll(
+            &mut graph,
+            HumanoidBoneMapping {
+                spine: Handle::NONE,
+                left_forearm: Handle::NONE,
+                ..m.clone()
+            },
+        );
+
+        let root = graph[ragdoll]
+            .cast::<Ragdoll>()
+            .unwrap()
+            .root_limb()
+            .clone();
+
+        // Bones with missing parents must be attached to the closest existing parent.
+        assert_eq!(root.bone, m.hips);
+        assert_eq!(
+            child_bones(&root),
+            vec![m.chest, m.left_thigh, m.right_thigh]
+        );
+        let chest = &root.children[0];
+        assert_eq!(
+            child_bones(chest),
+            vec![m.neck, m.left_upper_arm, m.right_upper_arm]
+        );
+        assert_eq!(child_bones(&chest.children[1]), vec![m.left_hand]);
+
+        assert_eq!(count_nodes::<RigidBody>(&graph, ragdoll), 15);
+        assert_eq!(count_nodes::<Joint>(&graph, ragdoll), 14);
+    }
+
+    #[test]
+    fn test_ragdoll_generation_virtual_root() {
+        let mut graph = Graph::new();
+        let m = make_skeleton(&mut graph);
+        let ragdoll = build_ragdoll(
+            &mut graph,
+            HumanoidBoneMapping {
+                hips: Handle::NONE,
+                ..m.clone()
+            },
+        );
+
+        let root = graph[ragdoll]
+            .cast::<Ragdoll>()
+            .unwrap()
+            .root_limb()
+            .clone();
+
+        // Without hips there are three roots, so they must be attached to a virtual root limb.
+        assert_eq!(root.bone, Handle::NONE);
+        assert_eq!(root.physical_bone, Handle::NONE);
+        assert_eq!(
+            child_bones(&root),
+            vec![m.spine, m.left_thigh, m.right_thigh]
+        );
+
+        assert_eq!(count_nodes::<RigidBody>(&graph, ragdoll), 16);
+        assert_eq!(count_nodes::<Joint>(&graph, ragdoll), 13);
+    }
+
+    #[test]
+    fn test_ragdoll_does_not_blend_its_own_output() {
+        let mut graph = Graph::new();
+        let bone = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+
+        let mut limb = Limb::new(bone, Handle::NONE, vec![]);
+        limb.physical_pose = Some((Vector3::new(2.0, 0.0, 0.0), UnitQuaternion::identity()));
+        let ragdoll = RagdollBuilder::new(BaseBuilder::new())
+            .with_blend_weight(0.5)
+            .with_root_limb(limb)
+            .build(&mut graph);
+
+        let bone_position = |graph: &Graph| **graph[bone].local_transform().position();
+
+        for _ in 0..3 {
+            graph.update(Vector2::new(1.0, 1.0), 1.0 / 60.0, Default::default());
+            assert_eq!(bone_position(&graph), Vector3::new(1.0, 0.0, 0.0));
+        }
+
+        // Animation changes the pose.
+        graph[bone]
+            .local_transform_mut()
+            .set_position(Vector3::new(0.0, 1.0, 0.0));
+        for _ in 0..3 {
+            graph.update(Vector2::new(1.0, 1.0), 1.0 / 60.0, Default::default());
+            assert_eq!(bone_position(&graph), Vector3::new(1.0, 0.5, 0.0));
+        }
+
+        // Zero weight gives the bone back to animation.
+        graph[ragdoll]
+            .cast_mut::<Ragdoll>()
+            .unwrap()
+            .set_blend_weight(0.0);
+        graph.update(Vector2::new(1.0, 1.0), 1.0 / 60.0, Default::default());
+        assert_eq!(bone_position(&graph), Vector3::new(0.0, 1.0, 0.0));
+    }
+}
@@ -1,14 +1,19 @@
 //! Sound context.
 
 use crate::{
-    core::{pool::Handle, visitor::prelude::*},
-    scene::{node::Node, sound::Sound},
+    core::{algebra::Vector3, pool::Handle, visitor::prelude::*},
+    engine::SerializationContext,
+    scene::{
+        node::Node,
+        sound::{occlusion::Occlusion, Sound},
+    },
     utils::log::{Log, MessageKind},
 };
 use fxhash::FxHashSet;
 use fyrox_sound::{
     bus::AudioBusGraph,
     context::DistanceModel,
+    effects::Effect,
     renderer::Renderer,
     source::{SoundSource, SoundSourceBuilder, Status},
 };
@@ -16,12 +21,55 @@ use std::sync::MutexGuard;
 use std::time::Duration;
 
 /// Sound context.
-#[derive(Debug, Visit)]
+#[derive(Debug)]
 pub struct SoundContext {
-    #[visit(optional)]
     pub(crate) native: fyrox_sound::context::SoundContext,
 }
 
+impl Visit for SoundContext {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut region = visitor.enter_region(name)?;
+
+        // Native sound context knows nothing about serialization context, but it needs custom effect
+        // constructors to restore custom effects, so the environment is replaced for a while.
+        let environment = region.environment.clone();
+        if let Some(serialization_context) = environment
+            .as_ref()
+            .and_then(|e| e.downcast_ref::<SerializationContext>())
+        {
+            region.environment = Some(serialization_context.effect_constructors.clone());
+        }
+
+        match self.native.visit("Native", &mut region) {
+            Ok(_) => (),
+            // Scenes that were saved before the native context became serializable.
+            Err(VisitError::RegionDoesNotExist(region_name)) if region_name == "Native" => (),
+            Err(err) => Log::err(format!("Unable to load sound context. Reason: {:?}", err)),
+        }
+
+        region.environment = environment;
+
+        if region.is_reading() {
+            for bus in self.native.state().bus_graph_ref().buses_iter() {
+                for effect in bus.effects() {
+                    if let Effect::Custom(custom) = &effect.0 {
+                        if let Some(type_uuid) = custom.unknown_type() {
+                            Log::warn(format!(
+                                "There is no constructor for custom effect of {} type on {} audio \
+                                bus, the effect will pass the signal through.",
+                                type_uuid,
+                                bus.name()
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Proxy for guarded access to the sound context.
 pub struct SoundContextGuard<'a> {
     guard: MutexGuard<'a, fyrox_sound::context::State>,
@@ -58,6 +106,28 @@ impl<'a> SoundContextGuard<'a> {
         self.guard.distance_model()
     }
 
+    /// Sets new speed of sound (in units per second) which is used to calculate Doppler effect.
+    /// Default value is 343.3, which is the speed of sound in the air in meters per second.
+    pub fn set_speed_of_sound(&mut self, speed_of_sound: f32) {
+        self.guard.set_speed_of_sound(speed_of_sound);
+    }
+
+    /// Returns current speed of sound.
+    pub fn speed_of_sound(&self) -> f32 {
+        self.guard.speed_of_sound()
+    }
+
+    /// Sets global Doppler factor, it is multiplied with Doppler factor of each sound. 0.0 -
+    /// disables Doppler effect for every sound, 1.0 - physically correct effect (default).
+    pub fn set_doppler_factor(&mut self, doppler_factor: f32) {
+        self.guard.set_doppler_factor(doppler_factor);
+    }
+
+    /// Returns global Doppler factor.
+    pub fn doppler_factor(&self) -> f32 {
+        self.guard.doppler_factor()
+    }
+
     /// Normalizes given frequency using context's sampling rate. Normalized frequency then can be used
     /// to create filters.
     pub fn normalize_frequency(&self, f: f32) -> f32 {
@@ -137,14 +207,31 @@ impl SoundContext {
     pub(crate) fn set_sound_position(&mut self, sound: &Sound) {
         if let Some(source) = self.native.state().try_get_source_mut(sound.native.get()) {
             source.set_position(sound.global_position());
+            source.set_direction(sound.look_vector());
         }
     }
 
-    pub(crate) fn sync_with_sound(&self, sound: &mut Sound) {
+    pub(crate) fn listener_position(&self) -> Vector3<f32> {
+        self.native.state().listener().position()
+    }
+
+    // Syncs the state of the native source back to the sound and passes the properties, that are
+    // calculated every frame, to the native source. Everything is done under a single lock.
+    pub(crate) fn sync_with_sound(
+        &self,
+        sound: &mut Sound,
+        velocity: Vector3<f32>,
+        occlusion: Option<Occlusion>,
+    ) {
         if let Some(source) = self.native.state().try_get_source_mut(sound.native.get()) {
             // Sync back.
             sound.status.set_value_silent(source.status());
             sound.playback_time.set_value_silent(source.playback_time());
+
+            source.set_velocity(velocity);
+            if let Some(occlusion) = occlusion {
+                source.set_occlusion(occlusion.gain, occlusion.low_pass());
+            }
         }
     }
 
@@ -209,6 +296,12 @@ impl SoundContext {
             sound.audio_bus.try_sync_model(|audio_bus| {
                 source.set_bus(audio_bus);
             });
+            sound.doppler_factor.try_sync_model(|v| {
+                source.set_doppler_factor(v);
+            });
+            sound.cone.try_sync_model(|v| {
+                source.set_cone(v);
+            });
         } else {
             match SoundSourceBuilder::new()
                 .with_gain(sound.gain())
@@ -223,6 +316,9 @@ impl SoundContext {
                 .with_max_distance(sound.max_distance())
                 .with_bus(sound.audio_bus())
                 .with_rolloff_factor(sound.rolloff_factor())
+                .with_doppler_factor(sound.doppler_factor())
+                .with_direction(sound.look_vector())
+                .with_cone(sound.cone())
                 .build()
             {
                 Ok(source) => {
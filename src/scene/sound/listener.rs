@@ -5,16 +5,20 @@
 
 use crate::{
     core::{
+        algebra::Vector3,
         math::aabb::AxisAlignedBoundingBox,
         pool::Handle,
         reflect::prelude::*,
         uuid::{uuid, Uuid},
+        variable::InheritableVariable,
         visitor::prelude::*,
     },
+    define_with,
     scene::{
         base::{Base, BaseBuilder},
         graph::Graph,
-        node::{Node, NodeTrait, SyncContext, TypeUuidProvider},
+        node::{Node, NodeTrait, SyncContext, TypeUuidProvider, UpdateContext},
+        sound::calculate_velocity,
     },
 };
 use std::ops::{Deref, DerefMut};
@@ -33,9 +37,24 @@ use std::ops::{Deref, DerefMut};
 ///
 /// 2D sound sources (with spatial blend == 0.0) are not influenced by listener's position and
 /// orientation.
+///
+/// Velocity of the listener is used to calculate Doppler effect, by default it is calculated
+/// automatically from frame-to-frame position changes. See [`Listener::set_velocity`] for more info.
 #[derive(Visit, Reflect, Default, Clone, Debug)]
 pub struct Listener {
     base: Base,
+
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_velocity",
+        description = "Velocity of the listener, that is used to calculate Doppler effect. \
+        If it is not set, the velocity will be calculated from position changes."
+    )]
+    velocity: InheritableVariable<Option<Vector3<f32>>>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    prev_position: Option<Vector3<f32>>,
 }
 
 impl Deref for Listener {
@@ -58,6 +77,20 @@ impl TypeUuidProvider for Listener {
     }
 }
 
+impl Listener {
+    /// Sets velocity of the listener, it is used only to calculate Doppler effect. `None` (default)
+    /// means that the velocity will be calculated automatically from frame-to-frame position
+    /// changes of the listener.
+    pub fn set_velocity(&mut self, velocity: Option<Vector3<f32>>) -> Option<Vector3<f32>> {
+        self.velocity.set_value_and_mark_modified(velocity)
+    }
+
+    /// Returns velocity of the listener, `None` means that the velocity is calculated automatically.
+    pub fn velocity(&self) -> Option<Vector3<f32>> {
+        *self.velocity
+    }
+}
+
 impl NodeTrait for Listener {
     crate::impl_query_component!();
 
@@ -88,23 +121,52 @@ impl NodeTrait for Listener {
         native.set_position(self.global_position());
         native.set_orientation_lh(self.look_vector(), self.up_vector());
     }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        if !self.is_globally_enabled() {
+            return;
+        }
+
+        let position = self.global_position();
+        let velocity = self
+            .velocity
+            .unwrap_or_else(|| calculate_velocity(self.prev_position, position, context.dt));
+        self.prev_position = Some(position);
+        context
+            .sound_context
+            .native
+            .state()
+            .listener_mut()
+            .set_velocity(velocity);
+    }
 }
 
 /// Allows you to create listener in declarative manner.
 pub struct ListenerBuilder {
     base_builder: BaseBuilder,
+    velocity: Option<Vector3<f32>>,
 }
 
 impl ListenerBuilder {
     /// Creates new listner builder.
     pub fn new(base_builder: BaseBuilder) -> Self {
-        Self { base_builder }
+        Self {
+            base_builder,
+            velocity: None,
+        }
     }
 
+    define_with!(
+        /// Sets desired velocity. See [`Listener::set_velocity`] for more info.
+        fn with_velocity(velocity: Option<Vector3<f32>>)
+    );
+
     /// Creates listener instance.
     pub fn build_listener(self) -> Listener {
         Listener {
             base: self.base_builder.build_base(),
+            velocity: self.velocity.into(),
+            prev_position: None,
         }
     }
 
@@ -121,6 +183,7 @@ impl ListenerBuilder {
 
 #[cfg(test)]
 mod test {
+    use crate::core::algebra::Vector3;
     use crate::scene::base::test::inherit_node_properties;
     use crate::scene::{
         base::{test::check_inheritable_properties_equality, BaseBuilder},
@@ -129,7 +192,9 @@ mod test {
 
     #[test]
     fn test_listener_inheritance() {
-        let parent = ListenerBuilder::new(BaseBuilder::new()).build_node();
+        let parent = ListenerBuilder::new(BaseBuilder::new())
+            .with_velocity(Some(Vector3::new(1.0, 2.0, 3.0)))
+            .build_node();
 
         let mut child = ListenerBuilder::new(BaseBuilder::new()).build_listener();
 
@@ -138,5 +203,6 @@ mod test {
         let parent = parent.cast::<Listener>().unwrap();
 
         check_inheritable_properties_equality(&child.base, &parent.base);
+        check_inheritable_properties_equality(&child, parent);
     }
 }
@@ -2,7 +2,7 @@
 
 use crate::{
     core::{
-        algebra::Matrix4,
+        algebra::{Matrix4, Vector3},
         math::{aabb::AxisAlignedBoundingBox, m4x4_approx_eq},
         pool::Handle,
         reflect::prelude::*,
@@ -29,10 +29,10 @@ pub use fyrox_sound::{
     error::SoundError,
     hrtf::HrirSphere,
     renderer::{hrtf::HrtfRenderer, Renderer},
-    source::Status,
+    source::{SoundCone, Status},
 };
 
-use crate::scene::Scene;
+use crate::scene::{sound::occlusion::Occlusion, Scene};
 use fyrox_resource::ResourceState;
 use fyrox_sound::source::SoundSource;
 use std::{
@@ -43,6 +43,7 @@ use std::{
 
 pub mod context;
 pub mod listener;
+pub mod occlusion;
 
 /// Sound source.
 #[derive(Visit, Reflect, Debug)]
@@ -97,9 +98,51 @@ pub struct Sound {
     )]
     audio_bus: InheritableVariable<String>,
 
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_velocity",
+        description = "Velocity of the sound, that is used to calculate Doppler effect. \
+        If it is not set, the velocity will be calculated from position changes."
+    )]
+    velocity: InheritableVariable<Option<Vector3<f32>>>,
+
+    #[visit(optional)]
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[reflect(setter = "set_doppler_factor")]
+    doppler_factor: InheritableVariable<f32>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_cone")]
+    cone: InheritableVariable<SoundCone>,
+
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_occlusion_enabled",
+        description = "Whether the sound should be occluded by colliders between the sound and the listener."
+    )]
+    occlusion_enabled: InheritableVariable<bool>,
+
+    #[visit(optional)]
+    #[reflect(min_value = 0.0, step = 0.01)]
+    #[reflect(setter = "set_occlusion_smoothing")]
+    occlusion_smoothing: InheritableVariable<f32>,
+
     #[reflect(hidden)]
     #[visit(skip)]
     pub(crate) native: Cell<Handle<SoundSource>>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    prev_position: Option<Vector3<f32>>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    occlusion: Occlusion,
+
+    // Native source that has received current occlusion.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    occluded_native: Handle<SoundSource>,
 }
 
 impl Deref for Sound {
@@ -133,7 +176,15 @@ impl Default for Sound {
             playback_time: Default::default(),
             spatial_blend: InheritableVariable::new(1.0),
             audio_bus: InheritableVariable::new(AudioBusGraph::PRIMARY_BUS.to_string()),
+            velocity: InheritableVariable::new(None),
+            doppler_factor: InheritableVariable::new(1.0),
+            cone: Default::default(),
+            occlusion_enabled: InheritableVariable::new(false),
+            occlusion_smoothing: InheritableVariable::new(0.1),
             native: Default::default(),
+            prev_position: None,
+            occlusion: Default::default(),
+            occluded_native: Default::default(),
         }
     }
 }
@@ -155,8 +206,16 @@ impl Clone for Sound {
             playback_time: self.playback_time.clone(),
             spatial_blend: self.spatial_blend.clone(),
             audio_bus: self.audio_bus.clone(),
+            velocity: self.velocity.clone(),
+            doppler_factor: self.doppler_factor.clone(),
+            cone: self.cone.clone(),
+            occlusion_enabled: self.occlusion_enabled.clone(),
+            occlusion_smoothing: self.occlusion_smoothing.clone(),
             // Do not copy. The copy will have its own native representation.
             native: Default::default(),
+            prev_position: None,
+            occlusion: Default::default(),
+            occluded_native: Default::default(),
         }
     }
 }
@@ -346,6 +405,117 @@ impl Sound {
     pub fn audio_bus(&self) -> &str {
         &self.audio_bus
     }
+
+    /// Sets velocity of the sound, it is used only to calculate Doppler effect. `None` (default)
+    /// means that the velocity will be calculated automatically from frame-to-frame position
+    /// changes of the sound. Automatic calculation could give velocity spikes if the sound is
+    /// teleported, set the velocity explicitly if this is an issue.
+    pub fn set_velocity(&mut self, velocity: Option<Vector3<f32>>) -> Option<Vector3<f32>> {
+        self.velocity.set_value_and_mark_modified(velocity)
+    }
+
+    /// Returns velocity of the sound, `None` means that the velocity is calculated automatically.
+    pub fn velocity(&self) -> Option<Vector3<f32>> {
+        *self.velocity
+    }
+
+    /// Sets Doppler factor of the sound. 0.0 - disables Doppler effect for the sound, 1.0 -
+    /// physically correct effect (default), values larger than 1.0 exaggerate the effect. See
+    /// also [`context::SoundContextGuard::set_doppler_factor`].
+    pub fn set_doppler_factor(&mut self, doppler_factor: f32) -> f32 {
+        self.doppler_factor
+            .set_value_and_mark_modified(doppler_factor.max(0.0))
+    }
+
+    /// Returns Doppler factor of the sound.
+    pub fn doppler_factor(&self) -> f32 {
+        *self.doppler_factor
+    }
+
+    /// Sets new sound cone of the sound. Sound cone defines directivity of the sound, the cone is
+    /// oriented along look vector (+Z axis) of the global transform of the sound. See [`SoundCone`]
+    /// docs for more info.
+    pub fn set_cone(&mut self, cone: SoundCone) -> SoundCone {
+        self.cone.set_value_and_mark_modified(cone)
+    }
+
+    /// Returns sound cone of the sound.
+    pub fn cone(&self) -> SoundCone {
+        *self.cone
+    }
+
+    /// Enables or disables geometry-based occlusion of the sound. When enabled, the engine casts
+    /// rays between the listener and the sound each frame, every collider on the way attenuates
+    /// and muffles the sound according to its [`occlusion::AcousticMaterial`]. Colliders, that
+    /// contain the sound or the listener, are ignored.
+    pub fn set_occlusion_enabled(&mut self, enabled: bool) -> bool {
+        self.occlusion_enabled.set_value_and_mark_modified(enabled)
+    }
+
+    /// Returns true if geometry-based occlusion is enabled for the sound.
+    pub fn is_occlusion_enabled(&self) -> bool {
+        *self.occlusion_enabled
+    }
+
+    /// Sets time (in seconds) that is needed for the occlusion to reach ~63% of its new value. It
+    /// is used to smoothly change the occlusion to prevent "zipper" noise when an obstacle appears
+    /// between the sound and the listener. Default value is 0.1 seconds.
+    pub fn set_occlusion_smoothing(&mut self, time: f32) -> f32 {
+        self.occlusion_smoothing
+            .set_value_and_mark_modified(time.max(0.0))
+    }
+
+    /// Returns current occlusion smoothing time.
+    pub fn occlusion_smoothing(&self) -> f32 {
+        *self.occlusion_smoothing
+    }
+
+    /// Returns current (smoothed) occlusion of the sound.
+    pub fn occlusion(&self) -> Occlusion {
+        self.occlusion
+    }
+
+    // Returns new occlusion of the sound, if it must be passed to the native source.
+    fn update_occlusion(
+        &mut self,
+        context: &mut UpdateContext,
+        position: Vector3<f32>,
+    ) -> Option<Occlusion> {
+        // Native sources are created with no occlusion, a new one must receive current occlusion.
+        let native = self.native.get();
+        let is_new_native = self.occluded_native != native;
+        self.occluded_native = native;
+
+        // Disabled occlusion, that has faded out completely, has nothing to update.
+        if !*self.occlusion_enabled && self.occlusion == Occlusion::default() {
+            return None;
+        }
+
+        let target = if *self.occlusion_enabled {
+            Occlusion::calculate(
+                context.physics,
+                context.nodes,
+                context.sound_context.listener_position(),
+                position,
+            )
+        } else {
+            Occlusion::default()
+        };
+
+        let k = if *self.occlusion_smoothing > 0.0 {
+            1.0 - (-context.dt / *self.occlusion_smoothing).exp()
+        } else {
+            1.0
+        };
+        let prev_occlusion = self.occlusion;
+        self.occlusion.follow(&target, k);
+
+        if is_new_native || self.occlusion != prev_occlusion {
+            Some(self.occlusion)
+        } else {
+            None
+        }
+    }
 }
 
 impl NodeTrait for Sound {
@@ -394,7 +564,17 @@ impl NodeTrait for Sound {
     }
 
     fn update(&mut self, context: &mut UpdateContext) {
-        context.sound_context.sync_with_sound(self);
+        let position = self.global_position();
+        let velocity = self
+            .velocity
+            .unwrap_or_else(|| calculate_velocity(self.prev_position, position, context.dt));
+        self.prev_position = Some(position);
+
+        let occlusion = self.update_occlusion(context, position);
+
+        context
+            .sound_context
+            .sync_with_sound(self, velocity, occlusion);
     }
 
     fn validate(&self, _scene: &Scene) -> Result<(), String> {
@@ -420,6 +600,17 @@ impl NodeTrait for Sound {
     }
 }
 
+pub(crate) fn calculate_velocity(
+    prev_position: Option<Vector3<f32>>,
+    position: Vector3<f32>,
+    dt: f32,
+) -> Vector3<f32> {
+    match prev_position {
+        Some(prev_position) if dt > 0.0 => (position - prev_position).scale(1.0 / dt),
+        _ => Default::default(),
+    }
+}
+
 /// Sound builder, allows you to create a new [`Sound`] instance.
 pub struct SoundBuilder {
     base_builder: BaseBuilder,
@@ -436,6 +627,11 @@ pub struct SoundBuilder {
     playback_time: Duration,
     spatial_blend: f32,
     audio_bus: String,
+    velocity: Option<Vector3<f32>>,
+    doppler_factor: f32,
+    cone: SoundCone,
+    occlusion_enabled: bool,
+    occlusion_smoothing: f32,
 }
 
 impl SoundBuilder {
@@ -456,6 +652,11 @@ impl SoundBuilder {
             spatial_blend: 1.0,
             playback_time: Default::default(),
             audio_bus: AudioBusGraph::PRIMARY_BUS.to_string(),
+            velocity: None,
+            doppler_factor: 1.0,
+            cone: Default::default(),
+            occlusion_enabled: false,
+            occlusion_smoothing: 0.1,
         }
     }
 
@@ -524,6 +725,31 @@ impl SoundBuilder {
         fn with_audio_bus(audio_bus: String)
     );
 
+    define_with!(
+        /// Sets desired velocity. See [`Sound::set_velocity`] for more info.
+        fn with_velocity(velocity: Option<Vector3<f32>>)
+    );
+
+    define_with!(
+        /// Sets desired Doppler factor. See [`Sound::set_doppler_factor`] for more info.
+        fn with_doppler_factor(doppler_factor: f32)
+    );
+
+    define_with!(
+        /// Sets desired sound cone. See [`Sound::set_cone`] for more info.
+        fn with_cone(cone: SoundCone)
+    );
+
+    define_with!(
+        /// Enables or disables occlusion. See [`Sound::set_occlusion_enabled`] for more info.
+        fn with_occlusion_enabled(occlusion_enabled: bool)
+    );
+
+    define_with!(
+        /// Sets desired occlusion smoothing time. See [`Sound::set_occlusion_smoothing`] for more info.
+        fn with_occlusion_smoothing(occlusion_smoothing: f32)
+    );
+
     /// Creates a new [`Sound`] node.
     #[must_use]
     pub fn build_sound(self) -> Sound {
@@ -542,7 +768,15 @@ impl SoundBuilder {
             playback_time: self.playback_time.into(),
             spatial_blend: self.spatial_blend.into(),
             audio_bus: self.audio_bus.into(),
+            velocity: self.velocity.into(),
+            doppler_factor: self.doppler_factor.into(),
+            cone: self.cone.into(),
+            occlusion_enabled: self.occlusion_enabled.into(),
+            occlusion_smoothing: self.occlusion_smoothing.into(),
             native: Default::default(),
+            prev_position: None,
+            occlusion: Default::default(),
+            occluded_native: Default::default(),
         }
     }
 
@@ -560,10 +794,11 @@ impl SoundBuilder {
 
 #[cfg(test)]
 mod test {
+    use crate::core::algebra::Vector3;
     use crate::scene::base::test::inherit_node_properties;
     use crate::scene::{
         base::{test::check_inheritable_properties_equality, BaseBuilder},
-        sound::{Sound, SoundBuilder},
+        sound::{Sound, SoundBuilder, SoundCone},
     };
     use fyrox_sound::source::Status;
     use std::time::Duration;
@@ -579,6 +814,11 @@ mod test {
             .with_looping(true)
             .with_play_once(true)
             .with_panning(0.1)
+            .with_velocity(Some(Vector3::new(1.0, 2.0, 3.0)))
+            .with_doppler_factor(2.0)
+            .with_cone(SoundCone::new(1.0, 2.0, 0.5))
+            .with_occlusion_enabled(true)
+            .with_occlusion_smoothing(0.5)
             .build_node();
 
         let mut child = SoundBuilder::new(BaseBuilder::new()).build_sound();
@@ -0,0 +1,422 @@
+//! Geometry-based sound occlusion. See [`AcousticMaterial`] and [`super::Sound::set_occlusion_enabled`]
+//! docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Point3, Vector3},
+        arrayvec::ArrayVec,
+        pool::Handle,
+        reflect::prelude::*,
+        visitor::prelude::*,
+    },
+    scene::{
+        collider::Collider,
+        graph::{
+            physics::{Intersection, PhysicsWorld, RayCastOptions},
+            NodePool,
+        },
+        node::Node,
+    },
+};
+
+/// Cutoff frequency (in Hz) of the occlusion low-pass filter that is considered as "no filtering".
+pub const OPEN_CUTOFF_FREQUENCY: f32 = 20000.0;
+
+/// Acoustic material defines how a sound passes through a collider. Every collider that is
+/// between a listener and a sound attenuates the sound and muffles it with a low-pass filter.
+/// Sensor colliders are ignored.
+#[derive(Copy, Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct AcousticMaterial {
+    /// Amount of the signal that passes through the collider, 0.0 - the collider blocks the sound
+    /// completely, 1.0 - the collider is acoustically transparent.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub transmission: f32,
+
+    /// Cutoff frequency (in Hz) of a low-pass filter that is applied to the signal that passes
+    /// through the collider.
+    #[reflect(min_value = 20.0, max_value = 20000.0, step = 10.0)]
+    pub low_pass_cutoff: f32,
+}
+
+impl Default for AcousticMaterial {
+    fn default() -> Self {
+        Self {
+            transmission: 0.3,
+            low_pass_cutoff: 1500.0,
+        }
+    }
+}
+
+impl AcousticMaterial {
+    /// Creates new acoustic material.
+    pub fn new(transmission: f32, low_pass_cutoff: f32) -> Self {
+        Self {
+            transmission: transmission.clamp(0.0, 1.0),
+            low_pass_cutoff: low_pass_cutoff.clamp(20.0, OPEN_CUTOFF_FREQUENCY),
+        }
+    }
+
+    /// Creates acoustically transparent material, colliders with such material does not occlude
+    /// sounds at all.
+    pub fn transparent() -> Self {
+        Self::new(1.0, OPEN_CUTOFF_FREQUENCY)
+    }
+}
+
+/// Occlusion of a sound by colliders between the sound and a listener.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Occlusion {
+    /// Amount of the signal that reaches the listener.
+    pub gain: f32,
+    /// Cutoff frequency of the low-pass filter (in Hz).
+    pub low_pass_cutoff: f32,
+}
+
+impl Default for Occlusion {
+    fn default() -> Self {
+        Self {
+            gain: 1.0,
+            low_pass_cutoff: OPEN_CUTOFF_FREQUENCY,
+        }
+    }
+}
+
+impl Occlusion {
+    /// Calculates occlusion for a sound at the given position by casting a ray from the listener
+    /// to the sound. Colliders, that contain the listener or the sound are ignored, so a sound that
+    /// is attached to a rigid body will not be occluded by the colliders of the body.
+    pub fn calculate(
+        physics: &PhysicsWorld,
+        nodes: &NodePool,
+        listener_position: Vector3<f32>,
+        sound_position: Vector3<f32>,
+    ) -> Self {
+        let mut occlusion = Self::default();
+
+        let direction = sound_position - listener_position;
+        let distance = direction.norm();
+        if distance <= f32::EPSILON {
+            return occlusion;
+        }
+
+        let mut forward = ArrayVec::<Intersection, 64>::new();
+        physics.cast_ray(
+            RayCastOptions {
+                ray_origin: Point3::from(listener_position),
+                ray_direction: direction,
+                max_len: distance,
+                groups: Default::default(),
+                sort_results: false,
+            },
+            &mut forward,
+        );
+
+        if forward.is_empty() {
+            return occlusion;
+        }
+
+        // Reverse ray is used to find colliders that contain the sound, ray cast gives zero time
+        // of impact for such colliders.
+        let mut backward = ArrayVec::<Intersection, 64>::new();
+        physics.cast_ray(
+            RayCastOptions {
+                ray_origin: Point3::from(sound_position),
+                ray_direction: -direction,
+                max_len: distance,
+                groups: Default::default(),
+                sort_results: false,
+            },
+            &mut backward,
+        );
+
+        let contains_end_point = |intersections: &[Intersection], collider: Handle<Node>| {
+            intersections
+                .iter()
+                .any(|i| i.collider == collider && i.toi <= f32::EPSILON)
+        };
+
+        // A ray could hit the same collider multiple times (for example a triangle mesh), each
+        // collider must be counted once.
+        let mut counted = ArrayVec::<Handle<Node>, 64>::new();
+        for intersection in forward.iter() {
+            let handle = intersection.collider;
+            if counted.contains(&handle)
+                || contains_end_point(&forward, handle)
+                || contains_end_point(&backward, handle)
+            {
+                continue;
+            }
+            let _ = counted.try_push(handle);
+
+            if let Some(collider) = nodes.try_borrow(handle).and_then(|n| n.cast::<Collider>()) {
+                if collider.is_sensor() {
+                    continue;
+                }
+
+                let material = collider.acoustic_material();
+                occlusion.gain *= material.transmission;
+                occlusion.low_pass_cutoff = occlusion.low_pass_cutoff.min(material.low_pass_cutoff);
+            }
+        }
+
+        occlusion
+    }
+
+    /// Moves the occlusion towards the target by the given fraction (in `[0.0; 1.0]` range). The
+    /// occlusion snaps to the target when it is close enough, so it stops changing eventually.
+    pub fn follow(&mut self, target: &Occlusion, k: f32) {
+        const GAIN_EPSILON: f32 = 1.0e-3;
+        const LOG_CUTOFF_EPSILON: f32 = 1.0e-3;
+
+        self.gain += (target.gain - self.gain) * k;
+        // Pitch is perceived logarithmically, so the cutoff frequency is interpolated in log space.
+        let cutoff = self.low_pass_cutoff.max(1.0).ln();
+        let target_cutoff = target.low_pass_cutoff.max(1.0).ln();
+        let new_cutoff = cutoff + (target_cutoff - cutoff) * k;
+        self.low_pass_cutoff = new_cutoff.exp();
+
+        if (target.gain - self.gain).abs() <= GAIN_EPSILON
+            && (target_cutoff - new_cutoff).abs() <= LOG_CUTOFF_EPSILON
+        {
+            *self = *target;
+        }
+    }
+
+    /// Returns cutoff frequency of the low-pass filter, `None` means that the filter is not needed.
+    pub fn low_pass(&self) -> Option<f32> {
+        if self.low_pass_cutoff >= OPEN_CUTOFF_FREQUENCY - 1.0 {
+            None
+        } else {
+            Some(self.low_pass_cutoff)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Vector2, Vector3},
+            pool::Handle,
+        },
+        scene::{
+            base::BaseBuilder,
+            collider::{ColliderBuilder, ColliderShape},
+            graph::Graph,
+            node::Node,
+            rigidbody::{RigidBodyBuilder, RigidBodyType},
+            sound::{
+                occlusion::{AcousticMaterial, Occlusion, OPEN_CUTOFF_FREQUENCY},
+                Sound, SoundBuilder,
+            },
+            transform::TransformBuilder,
+        },
+    };
+
+    fn make_wall(
+        graph: &mut Graph,
+        position: Vector3<f32>,
+        material: AcousticMaterial,
+        sensor: bool,
+    ) {
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(0.5, 2.0, 2.0))
+            .with_sensor(sensor)
+            .with_acoustic_material(material)
+            .build(graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .build(),
+                )
+                .with_children(&[collider]),
+        )
+        .with_body_type(RigidBodyType::Static)
+        .build(graph);
+    }
+
+    fn make_sound(graph: &mut Graph, position: Vector3<f32>) -> Handle<Node> {
+        SoundBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(position)
+                    .build(),
+            ),
+        )
+        .with_occlusion_enabled(true)
+        .with_occlusion_smoothing(0.0)
+        .build(graph)
+    }
+
+    fn update(graph: &mut Graph) {
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+    }
+
+    fn native_occlusion(graph: &Graph, sound: Handle<Node>) -> (f32, Option<f32>) {
+        let state = graph.sound_context.native.state();
+        let source = state.source(graph[sound].cast::<Sound>().unwrap().native.get());
+        (source.occlusion_gain(), source.occlusion_low_pass())
+    }
+
+    fn assert_occlusion_eq(a: Occlusion, b: Occlusion) {
+        assert!((a.gain - b.gain).abs() < 1.0e-4, "{a:?} != {b:?}");
+        assert!(
+            (a.low_pass_cutoff - b.low_pass_cutoff).abs() < 1.0e-1,
+            "{a:?} != {b:?}"
+        );
+    }
+
+    #[test]
+    fn test_occlusion_follow() {
+        let open = Occlusion::default();
+        let target = Occlusion {
+            gain: 0.5,
+            low_pass_cutoff: 200.0,
+        };
+
+        let mut occlusion = open;
+        occlusion.follow(&target, 0.0);
+        assert_occlusion_eq(occlusion, open);
+
+        occlusion.follow(&target, 1.0);
+        assert_occlusion_eq(occlusion, target);
+
+        // Cutoff frequency is interpolated in log space, so the middle is the geometric mean.
+        let mut occlusion = open;
+        occlusion.follow(&target, 0.5);
+        assert_occlusion_eq(
+            occlusion,
+            Occlusion {
+                gain: 0.75,
+                low_pass_cutoff: 2000.0,
+            },
+        );
+
+        // Occlusion must reach the target in finite amount of steps.
+        let mut occlusion = open;
+        let mut steps = 0;
+        while occlusion != target {
+            occlusion.follow(&target, 0.1);
+            steps += 1;
+            assert!(steps < 1000);
+        }
+    }
+
+    #[test]
+    fn test_occlusion_calculate() {
+        let mut graph = Graph::new();
+
+        // Listener is at the origin.
+        make_wall(
+            &mut graph,
+            Vector3::new(2.0, 0.0, 0.0),
+            AcousticMaterial::new(0.5, 1000.0),
+            false,
+        );
+        make_wall(
+            &mut graph,
+            Vector3::new(4.0, 0.0, 0.0),
+            AcousticMaterial::new(0.5, 3000.0),
+            false,
+        );
+        // Sensors do not occlude sounds.
+        make_wall(
+            &mut graph,
+            Vector3::new(6.0, 0.0, 0.0),
+            AcousticMaterial::new(0.0, 20.0),
+            true,
+        );
+        // Colliders that contain a sound do not occlude it.
+        make_wall(
+            &mut graph,
+            Vector3::new(0.0, 0.0, 5.0),
+            AcousticMaterial::new(0.0, 20.0),
+            false,
+        );
+
+        let behind_walls = make_sound(&mut graph, Vector3::new(8.0, 0.0, 0.0));
+        let inside_wall = make_sound(&mut graph, Vector3::new(0.0, 0.0, 5.0));
+        let free = make_sound(&mut graph, Vector3::new(-8.0, 0.0, 0.0));
+
+        // Colliders are created on first update, scene queries will "see" them only after the
+        // next update.
+        update(&mut graph);
+        update(&mut graph);
+
+        let occlusion = |graph: &Graph, handle: Handle<Node>| {
+            graph[handle].cast::<Sound>().unwrap().occlusion()
+        };
+
+        assert_occlusion_eq(
+            occlusion(&graph, behind_walls),
+            Occlusion {
+                gain: 0.25,
+                low_pass_cutoff: 1000.0,
+            },
+        );
+        assert_eq!(occlusion(&graph, inside_wall), Occlusion::default());
+        assert_eq!(occlusion(&graph, free), Occlusion::default());
+
+        let (gain, low_pass) = native_occlusion(&graph, behind_walls);
+        assert!((gain - 0.25).abs() < 1.0e-4);
+        assert!((low_pass.unwrap() - 1000.0).abs() < 1.0e-1);
+        assert_eq!(native_occlusion(&graph, free), (1.0, None));
+    }
+
+    #[test]
+    fn test_unchanged_occlusion_is_not_pushed() {
+        let mut graph = Graph::new();
+
+        make_wall(
+            &mut graph,
+            Vector3::new(2.0, 0.0, 0.0),
+            AcousticMaterial::new(0.5, 1000.0),
+            false,
+        );
+        let sound = make_sound(&mut graph, Vector3::new(4.0, 0.0, 0.0));
+
+        update(&mut graph);
+        update(&mut graph);
+        assert!((native_occlusion(&graph, sound).0 - 0.5).abs() < 1.0e-4);
+
+        // Unchanged occlusion must not be passed to the native source again.
+        let native = graph[sound].cast::<Sound>().unwrap().native.get();
+        graph
+            .sound_context
+            .native
+            .state()
+            .source_mut(native)
+            .set_occlusion(0.1, None);
+        update(&mut graph);
+        assert_eq!(native_occlusion(&graph, sound), (0.1, None));
+
+        // Disabling occlusion fades it out.
+        graph[sound]
+            .cast_mut::<Sound>()
+            .unwrap()
+            .set_occlusion_enabled(false);
+        update(&mut graph);
+        assert_eq!(native_occlusion(&graph, sound), (1.0, None));
+        assert_eq!(
+            graph[sound]
+                .cast::<Sound>()
+                .unwrap()
+                .occlusion()
+                .low_pass_cutoff,
+            OPEN_CUTOFF_FREQUENCY
+        );
+
+        // Disabled occlusion is not updated at all.
+        let native = graph[sound].cast::<Sound>().unwrap().native.get();
+        graph
+            .sound_context
+            .native
+            .state()
+            .source_mut(native)
+            .set_occlusion(0.1, None);
+        update(&mut graph);
+        assert_eq!(native_occlusion(&graph, sound), (0.1, None));
+    }
+}
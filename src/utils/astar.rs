@@ -79,7 +79,6 @@ impl PathVertex {
     }
 
     fn clear(&mut self) {
-        self.g_penalty = 1f32;
         self.g_score = f32::MAX;
         self.f_score = f32::MAX;
         self.state = PathVertexState::NonVisited;
@@ -169,6 +168,12 @@ impl PathFinder {
         self.vertices = vertices;
     }
 
+    /// Adds new vertex and returns its index.
+    pub fn add_vertex(&mut self, vertex: PathVertex) -> usize {
+        self.vertices.push(vertex);
+        self.vertices.len() - 1
+    }
+
     /// Tries to find a vertex closest to given point.
     ///
     /// # Notes
@@ -194,6 +199,28 @@ impl PathFinder {
         }
     }
 
+    /// Removes bidirectional link between two vertices.
+    pub fn unlink_bidirect(&mut self, a: usize, b: usize) {
+        self.unlink_unidirect(a, b);
+        self.unlink_unidirect(b, a);
+    }
+
+    /// Removes unidirectional link from vertex `a` to vertex `b`.
+    pub fn unlink_unidirect(&mut self, a: usize, b: usize) {
+        if let Some(vertex_a) = self.vertices.get_mut(a) {
+            vertex_a.neighbours.retain(|n| *n as usize != b);
+        }
+    }
+
+    /// Removes every vertex with index equal or greater than given length, links to removed vertices
+    /// are removed too.
+    pub fn truncate(&mut self, len: usize) {
+        self.vertices.truncate(len);
+        for vertex in self.vertices.iter_mut() {
+            vertex.neighbours.retain(|n| (*n as usize) < len);
+        }
+    }
+
     /// Returns shared reference to path vertex at given index.
     pub fn get_vertex(&self, index: usize) -> Option<&PathVertex> {
         self.vertices.get(index)
@@ -204,6 +231,11 @@ impl PathFinder {
         &self.vertices
     }
 
+    /// Returns mutable reference to array of vertices.
+    pub fn vertices_mut(&mut self) -> &mut [PathVertex] {
+        &mut self.vertices
+    }
+
     /// Tries to build path from begin point to end point. Returns path kind:
     /// - Full: there are direct path from begin to end.
     /// - Partial: there are not direct path from begin to end, but it is closest.
@@ -221,16 +253,42 @@ impl PathFinder {
         to: usize,
         path: &mut Vec<Vector3<f32>>,
     ) -> Result<PathKind, PathError> {
+        self.build_with_cost(from, to, path, 1.0, |_, _, cost| Some(cost))
+    }
+
+    /// Same as [`Self::build`], but allows to modify cost of moving between two vertices. The
+    /// closure receives indices of the vertices and default cost of the move, it should return new
+    /// cost or `None` if the move is forbidden.
+    ///
+    /// `min_cost_multiplier` is the smallest ratio between the cost returned by the closure and the
+    /// default cost. It is used to scale the heuristic, otherwise the heuristic could overestimate
+    /// the cost of a path through cheap vertices and the path will not be the shortest one.
+    pub fn build_with_cost<F>(
+        &mut self,
+        from: usize,
+        to: usize,
+        path: &mut Vec<Vector3<f32>>,
+        min_cost_multiplier: f32,
+        mut cost: F,
+    ) -> Result<PathKind, PathError>
+    where
+        F: FnMut(usize, usize, f32) -> Option<f32>,
+    {
         if self.vertices.is_empty() {
             return Ok(PathKind::Empty);
         }
 
         path.clear();
 
+        let mut min_penalty = 1.0f32;
         for vertex in self.vertices.iter_mut() {
             vertex.clear();
+            min_penalty = min_penalty.min(vertex.g_penalty);
         }
 
+        // Penalties less than 1.0 make moves cheaper too.
+        let heuristic_scale = (min_penalty * min_cost_multiplier.min(1.0)).max(0.0);
+
         let end_pos = self
             .vertices
             .get(to)
@@ -244,7 +302,7 @@ impl PathFinder {
             .ok_or(PathError::InvalidIndex(from))?;
         start.state = PathVertexState::Open;
         start.g_score = 0.0;
-        start.f_score = heuristic(start.position, end_pos);
+        start.f_score = heuristic(start.position, end_pos) * heuristic_scale;
 
         let mut open_set_size = 1;
         while open_set_size > 0 {
@@ -287,13 +345,17 @@ impl PathFinder {
                     .get_mut(*neighbour_index as usize)
                     .ok_or(PathError::InvalidIndex(*neighbour_index as usize))?;
 
-                let g_score = current_vertex.g_score
-                    + ((current_vertex.position - neighbour.position).norm_squared()
-                        * neighbour.g_penalty);
+                let default_cost = (current_vertex.position - neighbour.position).norm_squared()
+                    * neighbour.g_penalty;
+                let g_score = match cost(current_index, *neighbour_index as usize, default_cost) {
+                    Some(cost) => current_vertex.g_score + cost,
+                    None => continue,
+                };
                 if g_score < neighbour.g_score {
                     neighbour.parent = Some(current_index);
                     neighbour.g_score = g_score;
-                    neighbour.f_score = g_score + heuristic(neighbour.position, end_pos);
+                    neighbour.f_score =
+                        g_score + heuristic(neighbour.position, end_pos) * heuristic_scale;
 
                     if neighbour.state != PathVertexState::Open {
                         neighbour.state = PathVertexState::Open;
@@ -343,7 +405,7 @@ mod test {
     use crate::rand::Rng;
     use crate::{
         core::{algebra::Vector3, rand},
-        utils::astar::{PathFinder, PathVertex},
+        utils::astar::{PathFinder, PathKind, PathVertex},
     };
 
     #[test]
@@ -419,4 +481,75 @@ mod test {
 
         assert!(paths_count > 0);
     }
+
+    // Creates a square with two equal routes from the vertex 0 to the vertex 3: through vertex 1
+    // and through vertex 2.
+    fn make_square() -> PathFinder {
+        let mut pathfinder = PathFinder::new();
+        pathfinder.set_vertices(vec![
+            PathVertex::new(Vector3::new(0.0, 0.0, 0.0)),
+            PathVertex::new(Vector3::new(1.0, 0.0, 0.0)),
+            PathVertex::new(Vector3::new(0.0, 1.0, 0.0)),
+            PathVertex::new(Vector3::new(1.0, 1.0, 0.0)),
+        ]);
+        pathfinder.link_bidirect(0, 1);
+        pathfinder.link_bidirect(0, 2);
+        pathfinder.link_bidirect(1, 3);
+        pathfinder.link_bidirect(2, 3);
+        pathfinder
+    }
+
+    #[test]
+    fn test_penalty_is_kept_between_searches() {
+        let mut pathfinder = make_square();
+        pathfinder.vertices_mut()[1].set_penalty(10.0);
+
+        let mut path = Vec::new();
+        for _ in 0..2 {
+            assert!(matches!(
+                pathfinder.build(0, 3, &mut path),
+                Ok(PathKind::Full)
+            ));
+            assert_eq!(path[1], Vector3::new(0.0, 1.0, 0.0));
+        }
+    }
+
+    #[test]
+    fn test_cheap_moves() {
+        let mut pathfinder = PathFinder::new();
+        pathfinder.set_vertices(vec![
+            PathVertex::new(Vector3::new(0.0, 0.0, 0.0)),
+            PathVertex::new(Vector3::new(5.0, 0.0, 0.0)),
+            PathVertex::new(Vector3::new(5.0, 5.0, 0.0)),
+            PathVertex::new(Vector3::new(10.0, 0.0, 0.0)),
+        ]);
+        pathfinder.link_bidirect(0, 1);
+        pathfinder.link_bidirect(1, 3);
+        pathfinder.link_bidirect(0, 2);
+        pathfinder.link_bidirect(2, 3);
+
+        // Moves through the vertex 2 are much cheaper than the straight path.
+        let mut path = Vec::new();
+        assert!(matches!(
+            pathfinder.build_with_cost(0, 3, &mut path, 0.01, |a, b, cost| {
+                if a == 2 || b == 2 {
+                    Some(cost * 0.01)
+                } else {
+                    Some(cost)
+                }
+            }),
+            Ok(PathKind::Full)
+        ));
+        assert_eq!(path[1], Vector3::new(5.0, 5.0, 0.0));
+
+        // The same for vertex penalties.
+        pathfinder.vertices_mut()[2].set_penalty(0.01);
+        pathfinder.vertices_mut()[3].set_penalty(0.01);
+        pathfinder.vertices_mut()[1].set_penalty(1.0);
+        assert!(matches!(
+            pathfinder.build(0, 3, &mut path),
+            Ok(PathKind::Full)
+        ));
+        assert_eq!(path[1], Vector3::new(5.0, 5.0, 0.0));
+    }
 }
@@ -0,0 +1,122 @@
+//! Blackboard is a typed key-value storage that is shared by every node of a behavior tree. It
+//! allows nodes to exchange data (for example a leaf could find a target and store its position
+//! so other leaves could use it), and it is used by condition decorators to guard branches of
+//! the tree. Game code could also write to the blackboard to pass results of its own systems
+//! (perception, navigation, etc.) to the tree.
+
+use crate::{
+    core::{algebra::Vector3, pool::Handle, visitor::prelude::*},
+    scene::node::Node,
+};
+use fxhash::FxHashMap;
+
+/// A value that could be stored in a blackboard.
+#[derive(Debug, PartialEq, Visit, Clone)]
+pub enum BlackboardValue {
+    /// Boolean value.
+    Bool(bool),
+    /// Integer value.
+    Integer(i64),
+    /// Floating point value.
+    Float(f32),
+    /// 3D vector (position, direction, etc.).
+    Vector3(Vector3<f32>),
+    /// String value.
+    String(String),
+    /// Handle of a scene node.
+    Node(Handle<Node>),
+}
+
+impl Default for BlackboardValue {
+    fn default() -> Self {
+        Self::Bool(false)
+    }
+}
+
+/// A type that could be stored in a blackboard.
+pub trait BlackboardType: Sized {
+    /// Wraps the value.
+    fn into_value(self) -> BlackboardValue;
+
+    /// Tries to extract a value of the type, returns `None` if the value has different type.
+    fn from_value(value: &BlackboardValue) -> Option<Self>;
+}
+
+macro_rules! impl_blackboard_type {
+    ($ty:ty, $variant:ident) => {
+        impl BlackboardType for $ty {
+            fn into_value(self) -> BlackboardValue {
+                BlackboardValue::$variant(self)
+            }
+
+            fn from_value(value: &BlackboardValue) -> Option<Self> {
+                if let BlackboardValue::$variant(v) = value {
+                    Some(v.clone())
+                } else {
+                    None
+                }
+            }
+        }
+
+        impl From<$ty> for BlackboardValue {
+            fn from(v: $ty) -> Self {
+                BlackboardValue::$variant(v)
+            }
+        }
+    };
+}
+
+impl_blackboard_type!(bool, Bool);
+impl_blackboard_type!(i64, Integer);
+impl_blackboard_type!(f32, Float);
+impl_blackboard_type!(Vector3<f32>, Vector3);
+impl_blackboard_type!(String, String);
+impl_blackboard_type!(Handle<Node>, Node);
+
+/// See module docs.
+#[derive(Default, Debug, PartialEq, Visit, Clone)]
+pub struct Blackboard {
+    values: FxHashMap<String, BlackboardValue>,
+}
+
+impl Blackboard {
+    /// Sets a value for the given key, returns previous value (if any).
+    pub fn set<K: Into<String>, T: BlackboardType>(
+        &mut self,
+        key: K,
+        value: T,
+    ) -> Option<BlackboardValue> {
+        self.values.insert(key.into(), value.into_value())
+    }
+
+    /// Tries to get a value of the given type. Returns `None` if there is no such key, or the value
+    /// has different type.
+    pub fn get<T: BlackboardType>(&self, key: &str) -> Option<T> {
+        self.values.get(key).and_then(T::from_value)
+    }
+
+    /// Returns a reference to untyped value for the given key.
+    pub fn value(&self, key: &str) -> Option<&BlackboardValue> {
+        self.values.get(key)
+    }
+
+    /// Returns true if the blackboard has a value for the given key.
+    pub fn contains(&self, key: &str) -> bool {
+        self.values.contains_key(key)
+    }
+
+    /// Removes a value for the given key and returns it.
+    pub fn remove(&mut self, key: &str) -> Option<BlackboardValue> {
+        self.values.remove(key)
+    }
+
+    /// Removes every value from the blackboard.
+    pub fn clear(&mut self) {
+        self.values.clear()
+    }
+
+    /// Returns an iterator over every key-value pair of the blackboard.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &BlackboardValue)> {
+        self.values.iter()
+    }
+}
@@ -4,6 +4,9 @@
 //! implement AND logical function. `Selector` node will execute children until `Status::Success`
 //! is returned from any descendant node. In other worlds `Selector` implement OR logical
 //! function.
+//!
+//! Both nodes remember a child that has returned `Status::Running` and continue execution from
+//! this child on next tick, instead of re-evaluating the preceding children.
 
 use crate::{
     core::{pool::Handle, visitor::prelude::*},
@@ -39,6 +42,8 @@ where
     pub children: Vec<Handle<BehaviorNode<B>>>,
     /// Current kind of the node.
     pub kind: CompositeNodeKind,
+    #[visit(optional)]
+    pub(crate) running_child: Option<u32>,
 }
 
 impl<B> Default for CompositeNode<B>
@@ -49,6 +54,7 @@ where
         Self {
             children: Default::default(),
             kind: Default::default(),
+            running_child: None,
         }
     }
 }
@@ -59,7 +65,11 @@ where
 {
     /// Creates new composite node of given kind and set of children nodes.
     pub fn new(kind: CompositeNodeKind, children: Vec<Handle<BehaviorNode<B>>>) -> Self {
-        Self { children, kind }
+        Self {
+            children,
+            kind,
+            running_child: None,
+        }
     }
 
     /// Creates new sequence composite node with a set of children nodes.
@@ -67,6 +77,7 @@ where
         Self {
             children,
             kind: CompositeNodeKind::Sequence,
+            running_child: None,
         }
     }
 
@@ -75,6 +86,7 @@ where
         Self {
             children,
             kind: CompositeNodeKind::Selector,
+            running_child: None,
         }
     }
 
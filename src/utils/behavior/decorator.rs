@@ -0,0 +1,149 @@
+//! Decorator is a node with a single child, that modifies the result of its child or decides
+//! whether the child should be executed at all. See [`DecoratorKind`] for the list of available
+//! decorators.
+
+use crate::{
+    core::{pool::Handle, visitor::prelude::*},
+    utils::behavior::{blackboard::BlackboardValue, BehaviorNode, BehaviorTree},
+};
+
+/// Defines exact behavior of the decorator node.
+#[derive(Debug, PartialEq, Visit, Clone)]
+pub enum DecoratorKind {
+    /// Inverts the result of the child: `Success` becomes `Failure` and vice versa. `Running` is
+    /// returned as is.
+    Inverter,
+    /// Returns `Success` when the child is finished, no matter what the child has returned.
+    Succeeder,
+    /// Executes the child given amount of times, returns `Running` until the last run is finished.
+    /// Returns `Failure` immediately if the child fails.
+    Repeat {
+        /// Amount of repetitions.
+        count: u32,
+    },
+    /// Executes the child until it fails, then returns `Success`.
+    RepeatUntilFailure,
+    /// Prevents the child from being executed for the given amount of seconds after it was
+    /// finished. Returns `Failure` while cooling down.
+    Cooldown {
+        /// Cooldown duration in seconds.
+        duration: f32,
+    },
+    /// Aborts the child and returns `Failure` if the child is running longer than the given amount
+    /// of seconds.
+    Timeout {
+        /// Timeout in seconds.
+        duration: f32,
+    },
+    /// Executes the child only if the blackboard contains given value for the given key, otherwise
+    /// returns `Failure`. A running child is aborted as soon as the condition is not met anymore.
+    Condition {
+        /// A key of the blackboard value.
+        key: String,
+        /// Expected value.
+        value: BlackboardValue,
+    },
+}
+
+impl Default for DecoratorKind {
+    fn default() -> Self {
+        Self::Inverter
+    }
+}
+
+/// See module docs.
+#[derive(Debug, PartialEq, Visit, Clone)]
+pub struct DecoratorNode<B>
+where
+    B: Clone,
+{
+    /// A child node.
+    pub child: Handle<BehaviorNode<B>>,
+    /// Current kind of the node.
+    pub kind: DecoratorKind,
+    // Amount of finished repetitions for `Repeat`, tree time of the end of cooldown for `Cooldown`,
+    // tree time of the start of the child for `Timeout`.
+    #[visit(optional)]
+    pub(crate) counter: u32,
+    #[visit(optional)]
+    pub(crate) time: Option<f32>,
+}
+
+impl<B> Default for DecoratorNode<B>
+where
+    B: Clone,
+{
+    fn default() -> Self {
+        Self {
+            child: Default::default(),
+            kind: Default::default(),
+            counter: 0,
+            time: None,
+        }
+    }
+}
+
+impl<B> DecoratorNode<B>
+where
+    B: Clone + 'static,
+{
+    /// Creates new decorator node of given kind with the given child.
+    pub fn new(kind: DecoratorKind, child: Handle<BehaviorNode<B>>) -> Self {
+        Self {
+            child,
+            kind,
+            counter: 0,
+            time: None,
+        }
+    }
+
+    /// Creates new inverter. See [`DecoratorKind::Inverter`] for more info.
+    pub fn new_inverter(child: Handle<BehaviorNode<B>>) -> Self {
+        Self::new(DecoratorKind::Inverter, child)
+    }
+
+    /// Creates new succeeder. See [`DecoratorKind::Succeeder`] for more info.
+    pub fn new_succeeder(child: Handle<BehaviorNode<B>>) -> Self {
+        Self::new(DecoratorKind::Succeeder, child)
+    }
+
+    /// Creates new repeater. See [`DecoratorKind::Repeat`] for more info.
+    pub fn new_repeat(count: u32, child: Handle<BehaviorNode<B>>) -> Self {
+        Self::new(DecoratorKind::Repeat { count }, child)
+    }
+
+    /// Creates new repeater. See [`DecoratorKind::RepeatUntilFailure`] for more info.
+    pub fn new_repeat_until_failure(child: Handle<BehaviorNode<B>>) -> Self {
+        Self::new(DecoratorKind::RepeatUntilFailure, child)
+    }
+
+    /// Creates new cooldown. See [`DecoratorKind::Cooldown`] for more info.
+    pub fn new_cooldown(duration: f32, child: Handle<BehaviorNode<B>>) -> Self {
+        Self::new(DecoratorKind::Cooldown { duration }, child)
+    }
+
+    /// Creates new timeout. See [`DecoratorKind::Timeout`] for more info.
+    pub fn new_timeout(duration: f32, child: Handle<BehaviorNode<B>>) -> Self {
+        Self::new(DecoratorKind::Timeout { duration }, child)
+    }
+
+    /// Creates new condition guard. See [`DecoratorKind::Condition`] for more info.
+    pub fn new_condition<K: Into<String>, V: Into<BlackboardValue>>(
+        key: K,
+        value: V,
+        child: Handle<BehaviorNode<B>>,
+    ) -> Self {
+        Self::new(
+            DecoratorKind::Condition {
+                key: key.into(),
+                value: value.into(),
+            },
+            child,
+        )
+    }
+
+    /// Adds self to the tree and return handle to self.
+    pub fn add_to(self, tree: &mut BehaviorTree<B>) -> Handle<BehaviorNode<B>> {
+        tree.add_node(BehaviorNode::Decorator(self))
+    }
+}
@@ -6,8 +6,16 @@
 //! games. The main concept is in its name. Tree is a set of connected nodes, where each node could
 //! have single parent and zero or more children nodes. Execution path of the tree is defined by the
 //! actions of the nodes. Behavior tree has a set of hard coded nodes as well as leaf nodes with
-//! user-defined logic. Hard coded nodes are: Sequence, Selector, Leaf. Leaf is special - it has
-//! custom method `tick` that can contain any logic you want.
+//! user-defined logic. Hard coded nodes are: Sequence, Selector, Parallel, decorators (Inverter,
+//! Succeeder, Repeat, RepeatUntilFailure, Cooldown, Timeout, Condition) and Leaf. Leaf is special -
+//! it has custom method `tick` that can contain any logic you want.
+//!
+//! Every node of the tree has access to a [`Blackboard`] - a typed storage that is shared by the
+//! whole tree, it could be used to pass data between nodes.
+//!
+//! Composite nodes remember their running children, so a node that has returned
+//! [`Status::Running`] will be re-entered directly on next tick, instead of re-evaluating the
+//! whole tree.
 //!
 //! For more info see:
 //! - [Wikipedia article](https://en.wikipedia.org/wiki/Behavior_tree_(artificial_intelligence,_robotics_and_control))
@@ -20,16 +28,24 @@ use crate::{
     },
     utils::behavior::{
         composite::{CompositeNode, CompositeNodeKind},
+        decorator::{DecoratorKind, DecoratorNode},
         leaf::LeafNode,
+        parallel::ParallelNode,
     },
 };
 use std::fmt::Debug;
 use std::ops::{Index, IndexMut};
 
+pub mod blackboard;
 pub mod composite;
+pub mod decorator;
 pub mod leaf;
+pub mod parallel;
+
+pub use blackboard::{Blackboard, BlackboardType, BlackboardValue};
 
 /// Status of execution of behavior tree node.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Visit)]
 pub enum Status {
     /// Action was successful.
     Success,
@@ -39,6 +55,12 @@ pub enum Status {
     Running,
 }
 
+impl Default for Status {
+    fn default() -> Self {
+        Self::Success
+    }
+}
+
 /// A trait for user-defined actions for behavior tree.
 pub trait Behavior<'a>: Visit + Default + PartialEq + Debug + Clone {
     /// A context in which the behavior will be performed.
@@ -48,6 +70,23 @@ pub trait Behavior<'a>: Visit + Default + PartialEq + Debug + Clone {
     /// the current execution path of the behavior tree it belongs
     /// to.
     fn tick(&mut self, context: &mut Self::Context) -> Status;
+
+    /// The same as [`Self::tick`], but it also has access to the blackboard of the tree. Default
+    /// implementation just calls [`Self::tick`], override it if your action needs the blackboard.
+    fn tick_with_blackboard(
+        &mut self,
+        context: &mut Self::Context,
+        blackboard: &mut Blackboard,
+    ) -> Status {
+        let _ = blackboard;
+        self.tick(context)
+    }
+
+    /// A function that will be called when the node is aborted by its parent (for example by a
+    /// timeout or a failed condition) or when the whole tree is reset. It should drop any progress
+    /// that the behavior keeps between ticks, so next tick will start the action from scratch.
+    /// It could be called for a behavior that is not running. Default implementation does nothing.
+    fn reset(&mut self) {}
 }
 
 /// Root node of the tree.
@@ -71,7 +110,7 @@ where
 }
 
 /// Possible variations of behavior nodes.
-#[derive(Debug, PartialEq, Visit, Eq, Clone)]
+#[derive(Debug, PartialEq, Visit, Clone)]
 pub enum BehaviorNode<B>
 where
     B: Clone,
@@ -84,6 +123,10 @@ where
     Composite(CompositeNode<B>),
     /// A node with custom logic.
     Leaf(LeafNode<B>),
+    /// A node that modifies the result of its child.
+    Decorator(DecoratorNode<B>),
+    /// A node that executes its children simultaneously.
+    Parallel(ParallelNode<B>),
 }
 
 impl<B> Default for BehaviorNode<B>
@@ -103,6 +146,10 @@ where
 {
     nodes: Pool<BehaviorNode<B>>,
     root: Handle<BehaviorNode<B>>,
+    #[visit(optional)]
+    blackboard: Blackboard,
+    #[visit(optional)]
+    time: f32,
 }
 
 impl<B> Default for BehaviorTree<B>
@@ -113,6 +160,8 @@ where
         Self {
             nodes: Default::default(),
             root: Default::default(),
+            blackboard: Default::default(),
+            time: 0.0,
         }
     }
 }
@@ -127,7 +176,12 @@ where
         let root = nodes.spawn(BehaviorNode::Root(RootNode {
             child: Default::default(),
         }));
-        Self { nodes, root }
+        Self {
+            nodes,
+            root,
+            blackboard: Default::default(),
+            time: 0.0,
+        }
     }
 
     /// Adds a node to the tree, returns its handle.
@@ -144,59 +198,274 @@ where
         }
     }
 
-    fn tick_recursive<'a, Ctx>(&self, handle: Handle<BehaviorNode<B>>, context: &mut Ctx) -> Status
+    /// Returns a reference to the blackboard of the tree.
+    pub fn blackboard(&self) -> &Blackboard {
+        &self.blackboard
+    }
+
+    /// Returns a reference to the blackboard of the tree. It could be used to pass data from
+    /// game code to the tree.
+    pub fn blackboard_mut(&mut self) -> &mut Blackboard {
+        &mut self.blackboard
+    }
+
+    /// Returns total time (in seconds) that was passed to [`Self::tick_with_dt`].
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    fn composite_mut(&mut self, handle: Handle<BehaviorNode<B>>) -> &mut CompositeNode<B> {
+        if let BehaviorNode::Composite(composite) = &mut self.nodes[handle] {
+            composite
+        } else {
+            unreachable!("must be composite")
+        }
+    }
+
+    fn parallel_mut(&mut self, handle: Handle<BehaviorNode<B>>) -> &mut ParallelNode<B> {
+        if let BehaviorNode::Parallel(parallel) = &mut self.nodes[handle] {
+            parallel
+        } else {
+            unreachable!("must be parallel")
+        }
+    }
+
+    fn decorator_mut(&mut self, handle: Handle<BehaviorNode<B>>) -> &mut DecoratorNode<B> {
+        if let BehaviorNode::Decorator(decorator) = &mut self.nodes[handle] {
+            decorator
+        } else {
+            unreachable!("must be decorator")
+        }
+    }
+
+    fn tick_composite<'a, Ctx>(
+        &mut self,
+        handle: Handle<BehaviorNode<B>>,
+        context: &mut Ctx,
+    ) -> Status
     where
         B: Behavior<'a, Context = Ctx>,
     {
-        match self.nodes[handle] {
-            BehaviorNode::Root(ref root) => {
-                if root.child.is_some() {
-                    self.tick_recursive(root.child, context)
-                } else {
-                    Status::Success
+        let composite = self.composite_mut(handle);
+        let kind = composite.kind.clone();
+        let count = composite.children.len();
+        // Continue from the running child (if any).
+        let start = composite.running_child.take().unwrap_or(0) as usize;
+
+        for i in start..count {
+            let child = self.composite_mut(handle).children[i];
+            match (self.tick_recursive(child, context), &kind) {
+                (Status::Running, _) => {
+                    self.composite_mut(handle).running_child = Some(i as u32);
+                    return Status::Running;
                 }
+                (Status::Failure, CompositeNodeKind::Sequence) => return Status::Failure,
+                (Status::Success, CompositeNodeKind::Selector) => return Status::Success,
+                _ => (),
             }
-            BehaviorNode::Composite(ref composite) => match composite.kind {
-                CompositeNodeKind::Sequence => {
-                    let mut all_succeeded = true;
-                    for child in composite.children.iter() {
-                        match self.tick_recursive(*child, context) {
-                            Status::Failure => {
-                                all_succeeded = false;
-                                break;
-                            }
-                            Status::Running => {
-                                return Status::Running;
-                            }
-                            _ => (),
-                        }
-                    }
-                    if all_succeeded {
-                        Status::Success
-                    } else {
+        }
+
+        match kind {
+            CompositeNodeKind::Sequence => Status::Success,
+            CompositeNodeKind::Selector => Status::Failure,
+        }
+    }
+
+    fn tick_parallel<'a, Ctx>(
+        &mut self,
+        handle: Handle<BehaviorNode<B>>,
+        context: &mut Ctx,
+    ) -> Status
+    where
+        B: Behavior<'a, Context = Ctx>,
+    {
+        let parallel = self.parallel_mut(handle);
+        let count = parallel.children.len();
+        parallel.results.resize(count, None);
+
+        for i in 0..count {
+            let parallel = self.parallel_mut(handle);
+            if parallel.results[i].is_some() {
+                continue;
+            }
+            let child = parallel.children[i];
+            let status = self.tick_recursive(child, context);
+            if status != Status::Running {
+                self.parallel_mut(handle).results[i] = Some(status);
+            }
+        }
+
+        let parallel = self.parallel_mut(handle);
+        let status = if parallel.is_satisfied(parallel.failure_policy, Status::Failure) {
+            Status::Failure
+        } else if parallel.is_satisfied(parallel.success_policy, Status::Success) {
+            Status::Success
+        } else if parallel.results.iter().all(|r| r.is_some()) {
+            Status::Failure
+        } else {
+            return Status::Running;
+        };
+
+        // Abort children that are still running.
+        self.reset_recursive(handle);
+
+        status
+    }
+
+    fn tick_decorator<'a, Ctx>(
+        &mut self,
+        handle: Handle<BehaviorNode<B>>,
+        context: &mut Ctx,
+    ) -> Status
+    where
+        B: Behavior<'a, Context = Ctx>,
+    {
+        let time = self.time;
+        let decorator = self.decorator_mut(handle);
+        let child = decorator.child;
+
+        match decorator.kind.clone() {
+            DecoratorKind::Inverter => match self.tick_recursive(child, context) {
+                Status::Success => Status::Failure,
+                Status::Failure => Status::Success,
+                Status::Running => Status::Running,
+            },
+            DecoratorKind::Succeeder => match self.tick_recursive(child, context) {
+                Status::Running => Status::Running,
+                _ => Status::Success,
+            },
+            DecoratorKind::Repeat { count } => {
+                if count == 0 {
+                    return Status::Success;
+                }
+                match self.tick_recursive(child, context) {
+                    Status::Running => Status::Running,
+                    Status::Failure => {
+                        self.decorator_mut(handle).counter = 0;
                         Status::Failure
                     }
-                }
-                CompositeNodeKind::Selector => {
-                    for child in composite.children.iter() {
-                        match self.tick_recursive(*child, context) {
-                            Status::Success => return Status::Success,
-                            Status::Running => return Status::Running,
-                            _ => (),
+                    Status::Success => {
+                        let decorator = self.decorator_mut(handle);
+                        decorator.counter += 1;
+                        if decorator.counter >= count {
+                            decorator.counter = 0;
+                            Status::Success
+                        } else {
+                            Status::Running
                         }
                     }
-                    Status::Failure
                 }
+            }
+            DecoratorKind::RepeatUntilFailure => match self.tick_recursive(child, context) {
+                Status::Failure => Status::Success,
+                _ => Status::Running,
             },
-            BehaviorNode::Leaf(ref leaf) => {
-                leaf.behavior.as_ref().unwrap().borrow_mut().tick(context)
+            DecoratorKind::Cooldown { duration } => {
+                if let Some(end) = decorator.time {
+                    if time < end {
+                        return Status::Failure;
+                    }
+                    decorator.time = None;
+                }
+                let status = self.tick_recursive(child, context);
+                if status != Status::Running {
+                    self.decorator_mut(handle).time = Some(time + duration);
+                }
+                status
+            }
+            DecoratorKind::Timeout { duration } => {
+                let start = *decorator.time.get_or_insert(time);
+                if time - start >= duration {
+                    self.reset_recursive(handle);
+                    return Status::Failure;
+                }
+                let status = self.tick_recursive(child, context);
+                if status != Status::Running {
+                    self.decorator_mut(handle).time = None;
+                }
+                status
+            }
+            DecoratorKind::Condition { key, value } => {
+                if self.blackboard.value(&key) == Some(&value) {
+                    self.tick_recursive(child, context)
+                } else {
+                    self.reset_recursive(child);
+                    Status::Failure
+                }
+            }
+        }
+    }
+
+    fn tick_recursive<'a, Ctx>(
+        &mut self,
+        handle: Handle<BehaviorNode<B>>,
+        context: &mut Ctx,
+    ) -> Status
+    where
+        B: Behavior<'a, Context = Ctx>,
+    {
+        match self.nodes[handle] {
+            BehaviorNode::Root(ref root) => {
+                let child = root.child;
+                if child.is_some() {
+                    self.tick_recursive(child, context)
+                } else {
+                    Status::Success
+                }
             }
+            BehaviorNode::Composite(_) => self.tick_composite(handle, context),
+            BehaviorNode::Parallel(_) => self.tick_parallel(handle, context),
+            BehaviorNode::Decorator(_) => self.tick_decorator(handle, context),
+            BehaviorNode::Leaf(ref leaf) => leaf
+                .behavior
+                .as_ref()
+                .unwrap()
+                .borrow_mut()
+                .tick_with_blackboard(context, &mut self.blackboard),
             BehaviorNode::Unknown => {
                 unreachable!()
             }
         }
     }
 
+    fn reset_recursive<'a>(&mut self, handle: Handle<BehaviorNode<B>>)
+    where
+        B: Behavior<'a>,
+    {
+        let children = match &mut self.nodes[handle] {
+            BehaviorNode::Root(root) => vec![root.child],
+            BehaviorNode::Composite(composite) => {
+                composite.running_child = None;
+                composite.children.clone()
+            }
+            BehaviorNode::Parallel(parallel) => {
+                parallel.results.clear();
+                parallel.children.clone()
+            }
+            BehaviorNode::Decorator(decorator) => {
+                decorator.counter = 0;
+                // Cooldown must be kept, otherwise it could be bypassed by aborting the node.
+                if !matches!(decorator.kind, DecoratorKind::Cooldown { .. }) {
+                    decorator.time = None;
+                }
+                vec![decorator.child]
+            }
+            BehaviorNode::Leaf(leaf) => {
+                if let Some(behavior) = leaf.behavior.as_ref() {
+                    behavior.borrow_mut().reset();
+                }
+                Vec::new()
+            }
+            BehaviorNode::Unknown => Vec::new(),
+        };
+
+        for child in children {
+            if child.is_some() {
+                self.reset_recursive(child);
+            }
+        }
+    }
+
     /// Tries to get a shared reference to a node by given handle.
     pub fn node(&self, handle: Handle<BehaviorNode<B>>) -> Option<&BehaviorNode<B>> {
         self.nodes.try_borrow(handle)
@@ -207,13 +476,33 @@ where
         self.nodes.try_borrow_mut(handle)
     }
 
-    /// Performs a single update tick with given context.
-    pub fn tick<'a, Ctx>(&self, context: &mut Ctx) -> Status
+    /// Performs a single update tick with given context. Time of the tree is not advanced, use
+    /// [`Self::tick_with_dt`] if the tree has time-based decorators (cooldowns and timeouts).
+    pub fn tick<'a, Ctx>(&mut self, context: &mut Ctx) -> Status
     where
         B: Behavior<'a, Context = Ctx>,
     {
+        self.tick_with_dt(0.0, context)
+    }
+
+    /// Advances time of the tree by the given amount of seconds and performs a single update tick
+    /// with given context.
+    pub fn tick_with_dt<'a, Ctx>(&mut self, dt: f32, context: &mut Ctx) -> Status
+    where
+        B: Behavior<'a, Context = Ctx>,
+    {
+        self.time += dt;
         self.tick_recursive(self.root, context)
     }
+
+    /// Aborts every running node of the tree, so the next tick will start from the entry node.
+    /// See [`Behavior::reset`] for more info.
+    pub fn reset<'a>(&mut self)
+    where
+        B: Behavior<'a>,
+    {
+        self.reset_recursive(self.root)
+    }
 }
 
 impl<B: Clone + 'static> Index<Handle<BehaviorNode<B>>> for BehaviorTree<B> {
@@ -233,11 +522,13 @@ impl<B: Clone + 'static> IndexMut<Handle<BehaviorNode<B>>> for BehaviorTree<B> {
 #[cfg(test)]
 mod test {
     use crate::{
-        core::{futures::executor::block_on, visitor::prelude::*},
+        core::{futures::executor::block_on, pool::Handle, visitor::prelude::*},
         utils::behavior::{
             composite::{CompositeNode, CompositeNodeKind},
+            decorator::DecoratorNode,
             leaf::LeafNode,
-            Behavior, BehaviorTree, Status,
+            parallel::{ParallelNode, ParallelPolicy},
+            Behavior, BehaviorNode, BehaviorTree, Blackboard, Status,
         },
     };
     use std::{env, fs::File, io::Write, path::PathBuf};
@@ -372,7 +663,7 @@ mod test {
 
     #[test]
     fn test_behavior() {
-        let tree = create_tree();
+        let mut tree = create_tree();
 
         let mut ctx = Environment {
             distance_to_door: 3.0,
@@ -414,4 +705,201 @@ mod test {
 
         assert_eq!(saved_tree, loaded_tree);
     }
+
+    // Counts its ticks in the blackboard and finishes after given amount of ticks.
+    #[derive(Debug, PartialEq, Default, Visit, Clone)]
+    struct CountAction {
+        name: String,
+        duration: u32,
+        ticks: u32,
+    }
+
+    impl<'a> Behavior<'a> for CountAction {
+        type Context = ();
+
+        fn tick(&mut self, _context: &mut Self::Context) -> Status {
+            self.ticks += 1;
+            if self.ticks >= self.duration {
+                self.ticks = 0;
+                Status::Success
+            } else {
+                Status::Running
+            }
+        }
+
+        fn tick_with_blackboard(
+            &mut self,
+            context: &mut Self::Context,
+            blackboard: &mut Blackboard,
+        ) -> Status {
+            let count = blackboard.get::<i64>(&self.name).unwrap_or_default();
+            blackboard.set(self.name.clone(), count + 1);
+            self.tick(context)
+        }
+
+        fn reset(&mut self) {
+            self.ticks = 0;
+        }
+    }
+
+    fn count(
+        tree: &mut BehaviorTree<CountAction>,
+        name: &str,
+        duration: u32,
+    ) -> Handle<BehaviorNode<CountAction>> {
+        LeafNode::new(CountAction {
+            name: name.to_owned(),
+            duration,
+            ticks: 0,
+        })
+        .add_to(tree)
+    }
+
+    fn ticks(tree: &BehaviorTree<CountAction>, name: &str) -> i64 {
+        tree.blackboard().get::<i64>(name).unwrap_or_default()
+    }
+
+    // Returns progress of a running count action.
+    fn progress(
+        tree: &BehaviorTree<CountAction>,
+        handle: Handle<BehaviorNode<CountAction>>,
+    ) -> u32 {
+        match tree[handle] {
+            BehaviorNode::Leaf(ref leaf) => leaf.behavior.as_ref().unwrap().borrow().ticks,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_running_node_resumption() {
+        let mut tree = BehaviorTree::new();
+        let a = count(&mut tree, "a", 1);
+        let b = count(&mut tree, "b", 3);
+        let entry = CompositeNode::new_sequence(vec![a, b]).add_to(&mut tree);
+        tree.set_entry_node(entry);
+
+        assert_eq!(tree.tick(&mut ()), Status::Running);
+        assert_eq!(tree.tick(&mut ()), Status::Running);
+        assert_eq!(tree.tick(&mut ()), Status::Success);
+        assert_eq!(ticks(&tree, "a"), 1);
+        assert_eq!(ticks(&tree, "b"), 3);
+    }
+
+    #[test]
+    fn test_decorators() {
+        // Condition + inverter.
+        let mut tree = BehaviorTree::new();
+        let a = count(&mut tree, "a", 1);
+        let inverter = DecoratorNode::new_inverter(a).add_to(&mut tree);
+        let entry = DecoratorNode::new_condition("enabled", true, inverter).add_to(&mut tree);
+        tree.set_entry_node(entry);
+        assert_eq!(tree.tick(&mut ()), Status::Failure);
+        assert_eq!(ticks(&tree, "a"), 0);
+        tree.blackboard_mut().set("enabled", true);
+        assert_eq!(tree.tick(&mut ()), Status::Failure);
+        assert_eq!(ticks(&tree, "a"), 1);
+
+        // Repeat.
+        let mut tree = BehaviorTree::new();
+        let a = count(&mut tree, "a", 1);
+        let entry = DecoratorNode::new_repeat(3, a).add_to(&mut tree);
+        tree.set_entry_node(entry);
+        assert_eq!(tree.tick(&mut ()), Status::Running);
+        assert_eq!(tree.tick(&mut ()), Status::Running);
+        assert_eq!(tree.tick(&mut ()), Status::Success);
+        assert_eq!(ticks(&tree, "a"), 3);
+
+        // Cooldown.
+        let mut tree = BehaviorTree::new();
+        let a = count(&mut tree, "a", 1);
+        let entry = DecoratorNode::new_cooldown(1.0, a).add_to(&mut tree);
+        tree.set_entry_node(entry);
+        assert_eq!(tree.tick_with_dt(0.1, &mut ()), Status::Success);
+        assert_eq!(tree.tick_with_dt(0.5, &mut ()), Status::Failure);
+        assert_eq!(tree.tick_with_dt(0.6, &mut ()), Status::Success);
+        assert_eq!(ticks(&tree, "a"), 2);
+
+        // Timeout.
+        let mut tree = BehaviorTree::new();
+        let a = count(&mut tree, "a", 100);
+        let entry = DecoratorNode::new_timeout(1.0, a).add_to(&mut tree);
+        tree.set_entry_node(entry);
+        assert_eq!(tree.tick_with_dt(0.6, &mut ()), Status::Running);
+        assert_eq!(tree.tick_with_dt(0.6, &mut ()), Status::Running);
+        assert_eq!(tree.tick_with_dt(0.6, &mut ()), Status::Failure);
+    }
+
+    #[test]
+    fn test_parallel() {
+        let mut tree = BehaviorTree::new();
+        let a = count(&mut tree, "a", 1);
+        let b = count(&mut tree, "b", 2);
+        let entry = ParallelNode::new(
+            ParallelPolicy::RequireAll,
+            ParallelPolicy::RequireOne,
+            vec![a, b],
+        )
+        .add_to(&mut tree);
+        tree.set_entry_node(entry);
+
+        assert_eq!(tree.tick(&mut ()), Status::Running);
+        assert_eq!(tree.tick(&mut ()), Status::Success);
+        // Finished children must not be executed again.
+        assert_eq!(ticks(&tree, "a"), 1);
+        assert_eq!(ticks(&tree, "b"), 2);
+    }
+
+    #[test]
+    fn test_aborted_leaf_reset() {
+        // Timeout.
+        let mut tree = BehaviorTree::new();
+        let a = count(&mut tree, "a", 3);
+        let entry = DecoratorNode::new_timeout(1.0, a).add_to(&mut tree);
+        tree.set_entry_node(entry);
+        assert_eq!(tree.tick_with_dt(0.6, &mut ()), Status::Running);
+        assert_eq!(tree.tick_with_dt(0.6, &mut ()), Status::Running);
+        assert_eq!(progress(&tree, a), 2);
+        assert_eq!(tree.tick_with_dt(0.6, &mut ()), Status::Failure);
+        assert_eq!(progress(&tree, a), 0);
+        // Aborted action must start from scratch.
+        assert_eq!(tree.tick_with_dt(0.6, &mut ()), Status::Running);
+        assert_eq!(progress(&tree, a), 1);
+
+        // Condition.
+        let mut tree = BehaviorTree::new();
+        let a = count(&mut tree, "a", 3);
+        let entry = DecoratorNode::new_condition("enabled", true, a).add_to(&mut tree);
+        tree.set_entry_node(entry);
+        tree.blackboard_mut().set("enabled", true);
+        assert_eq!(tree.tick(&mut ()), Status::Running);
+        assert_eq!(progress(&tree, a), 1);
+        tree.blackboard_mut().set("enabled", false);
+        assert_eq!(tree.tick(&mut ()), Status::Failure);
+        assert_eq!(progress(&tree, a), 0);
+
+        // Parallel node aborts its running children when it is finished.
+        let mut tree = BehaviorTree::new();
+        let a = count(&mut tree, "a", 1);
+        let b = count(&mut tree, "b", 3);
+        let entry = ParallelNode::new(
+            ParallelPolicy::RequireOne,
+            ParallelPolicy::RequireOne,
+            vec![a, b],
+        )
+        .add_to(&mut tree);
+        tree.set_entry_node(entry);
+        assert_eq!(tree.tick(&mut ()), Status::Success);
+        assert_eq!(progress(&tree, b), 0);
+
+        // Tree reset.
+        let mut tree = BehaviorTree::new();
+        let a = count(&mut tree, "a", 3);
+        let entry = CompositeNode::new_sequence(vec![a]).add_to(&mut tree);
+        tree.set_entry_node(entry);
+        assert_eq!(tree.tick(&mut ()), Status::Running);
+        assert_eq!(tree.tick(&mut ()), Status::Running);
+        tree.reset();
+        assert_eq!(progress(&tree, a), 0);
+        assert_eq!(tree.tick(&mut ()), Status::Running);
+    }
 }
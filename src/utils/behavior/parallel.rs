@@ -0,0 +1,88 @@
+//! Parallel node executes all its children on every tick, until the result is defined by its
+//! success and failure policies. Children, that are finished, are not executed again until the
+//! parallel node itself is finished. For example a parallel node could be used to make a bot shoot
+//! at a target while it is moving to cover.
+
+use crate::{
+    core::{pool::Handle, visitor::prelude::*},
+    utils::behavior::{BehaviorNode, BehaviorTree, Status},
+};
+
+/// Defines how many children must have the same result to finish the parallel node.
+#[derive(Debug, Copy, PartialEq, Visit, Eq, Clone)]
+pub enum ParallelPolicy {
+    /// A single child is enough.
+    RequireOne,
+    /// Every child must have the same result.
+    RequireAll,
+}
+
+impl Default for ParallelPolicy {
+    fn default() -> Self {
+        Self::RequireAll
+    }
+}
+
+/// See module docs.
+#[derive(Debug, PartialEq, Visit, Eq, Clone)]
+pub struct ParallelNode<B>
+where
+    B: Clone,
+{
+    /// A set of children.
+    pub children: Vec<Handle<BehaviorNode<B>>>,
+    /// Defines when the node returns `Success`.
+    pub success_policy: ParallelPolicy,
+    /// Defines when the node returns `Failure`. Failure policy is checked before success policy.
+    /// If every child is finished, but none of the policies is satisfied, the node returns
+    /// `Failure`.
+    pub failure_policy: ParallelPolicy,
+    #[visit(optional)]
+    pub(crate) results: Vec<Option<Status>>,
+}
+
+impl<B> Default for ParallelNode<B>
+where
+    B: Clone,
+{
+    fn default() -> Self {
+        Self {
+            children: Default::default(),
+            success_policy: ParallelPolicy::RequireAll,
+            failure_policy: ParallelPolicy::RequireOne,
+            results: Default::default(),
+        }
+    }
+}
+
+impl<B> ParallelNode<B>
+where
+    B: Clone + 'static,
+{
+    /// Creates new parallel node with given policies and set of children nodes.
+    pub fn new(
+        success_policy: ParallelPolicy,
+        failure_policy: ParallelPolicy,
+        children: Vec<Handle<BehaviorNode<B>>>,
+    ) -> Self {
+        Self {
+            children,
+            success_policy,
+            failure_policy,
+            results: Default::default(),
+        }
+    }
+
+    /// Adds self to the tree and return handle to self.
+    pub fn add_to(self, tree: &mut BehaviorTree<B>) -> Handle<BehaviorNode<B>> {
+        tree.add_node(BehaviorNode::Parallel(self))
+    }
+
+    pub(crate) fn is_satisfied(&self, policy: ParallelPolicy, status: Status) -> bool {
+        let mut count = self.results.iter().filter(|r| **r == Some(status));
+        match policy {
+            ParallelPolicy::RequireOne => count.next().is_some(),
+            ParallelPolicy::RequireAll => count.count() == self.children.len(),
+        }
+    }
+}
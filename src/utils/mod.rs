@@ -14,7 +14,9 @@ pub mod watcher;
 
 use crate::{
     core::algebra::Vector2,
-    event::{ElementState, ModifiersState, MouseScrollDelta, VirtualKeyCode, WindowEvent},
+    event::{
+        ElementState, ModifiersState, MouseScrollDelta, TouchPhase, VirtualKeyCode, WindowEvent,
+    },
     gui::{
         draw,
         message::{ButtonState, KeyCode, KeyboardModifiers, OsEvent},
@@ -251,6 +253,16 @@ pub fn translate_state(state: ElementState) -> ButtonState {
     }
 }
 
+/// Translates library touch phase into fyrox-ui touch phase.
+pub fn translate_touch_phase(phase: TouchPhase) -> crate::gui::message::TouchPhase {
+    match phase {
+        TouchPhase::Started => crate::gui::message::TouchPhase::Started,
+        TouchPhase::Moved => crate::gui::message::TouchPhase::Moved,
+        TouchPhase::Ended => crate::gui::message::TouchPhase::Ended,
+        TouchPhase::Cancelled => crate::gui::message::TouchPhase::Cancelled,
+    }
+}
+
 /// Translates window event to fyrox-ui event.
 pub fn translate_event(event: &WindowEvent) -> Option<OsEvent> {
     match event {
@@ -277,6 +289,11 @@ pub fn translate_event(event: &WindowEvent) -> Option<OsEvent> {
         &WindowEvent::ModifiersChanged(modifiers) => Some(OsEvent::KeyboardModifiers(
             translate_keyboard_modifiers(modifiers),
         )),
+        WindowEvent::Touch(touch) => Some(OsEvent::Touch {
+            phase: translate_touch_phase(touch.phase),
+            position: Vector2::new(touch.location.x as f32, touch.location.y as f32),
+            id: touch.id,
+        }),
         _ => None,
     }
 }
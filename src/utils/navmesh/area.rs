@@ -0,0 +1,111 @@
+//! Area types of navigation mesh triangles and per-agent query filters.
+//!
+//! Every triangle of a navigation mesh has an area type ([`NavmeshArea`]), for example walkable
+//! ground, water, road or door. Area types do not mean anything by themselves, instead every agent
+//! has a [`NavmeshQueryFilter`] that defines a cost multiplier for every area type and a set of
+//! areas that the agent cannot walk on at all. For example, a car could prefer roads and never
+//! enter water, while a soldier could avoid water, but still swim if there is no other way.
+
+use crate::core::visitor::prelude::*;
+
+/// Area type of a navigation mesh triangle. There are few predefined area types, but any index
+/// less than [`NavmeshArea::MAX_COUNT`] could be used for custom area types.
+#[derive(Visit, Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct NavmeshArea(pub u8);
+
+impl NavmeshArea {
+    /// Maximum amount of area types.
+    pub const MAX_COUNT: usize = 32;
+
+    /// Default area type of every triangle.
+    pub const WALKABLE: Self = Self(0);
+
+    /// Water area type.
+    pub const WATER: Self = Self(1);
+
+    /// Road area type.
+    pub const ROAD: Self = Self(2);
+
+    /// Door area type.
+    pub const DOOR: Self = Self(3);
+}
+
+/// Query filter defines how an agent treats areas of a navigation mesh. By default, every area
+/// has cost multiplier of 1.0 and no area is excluded.
+#[derive(Visit, Clone, Debug, PartialEq, Default)]
+pub struct NavmeshQueryFilter {
+    costs: Vec<f32>,
+    excluded: u32,
+}
+
+impl NavmeshQueryFilter {
+    /// Creates new filter with default costs and no excluded areas.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets cost multiplier for the given area. Multipliers larger than 1.0 make agents avoid the
+    /// area, multipliers less than 1.0 make agents prefer it.
+    pub fn set_area_cost(&mut self, area: NavmeshArea, cost: f32) {
+        let index = area.0 as usize;
+        if index < NavmeshArea::MAX_COUNT {
+            if self.costs.len() <= index {
+                self.costs.resize(index + 1, 1.0);
+            }
+            self.costs[index] = cost.max(0.0);
+        }
+    }
+
+    /// Sets cost multiplier for the given area. See [`Self::set_area_cost`] for more info.
+    pub fn with_area_cost(mut self, area: NavmeshArea, cost: f32) -> Self {
+        self.set_area_cost(area, cost);
+        self
+    }
+
+    /// Returns cost multiplier of the given area.
+    pub fn area_cost(&self, area: NavmeshArea) -> f32 {
+        self.costs.get(area.0 as usize).cloned().unwrap_or(1.0)
+    }
+
+    /// Excludes (or includes back) the given area. Paths never go through excluded areas.
+    pub fn set_area_excluded(&mut self, area: NavmeshArea, excluded: bool) {
+        if let Some(mask) = 1u32.checked_shl(area.0 as u32) {
+            if excluded {
+                self.excluded |= mask;
+            } else {
+                self.excluded &= !mask;
+            }
+        }
+    }
+
+    /// Excludes the given area. See [`Self::set_area_excluded`] for more info.
+    pub fn with_excluded_area(mut self, area: NavmeshArea) -> Self {
+        self.set_area_excluded(area, true);
+        self
+    }
+
+    /// Returns true if the given area is excluded.
+    pub fn is_area_excluded(&self, area: NavmeshArea) -> bool {
+        1u32.checked_shl(area.0 as u32)
+            .map_or(false, |mask| self.excluded & mask != 0)
+    }
+
+    /// Returns the smallest cost multiplier among the areas that are not excluded. Areas with
+    /// default cost are taken into account as well, so the result is never larger than 1.0.
+    pub fn min_area_cost(&self) -> f32 {
+        self.costs
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !self.is_area_excluded(NavmeshArea(*index as u8)))
+            .fold(1.0, |min, (_, cost)| min.min(*cost))
+    }
+
+    /// Returns cost multiplier of the given area, or `None` if the area is excluded.
+    pub fn cost(&self, area: NavmeshArea) -> Option<f32> {
+        if self.is_area_excluded(area) {
+            None
+        } else {
+            Some(self.area_cost(area))
+        }
+    }
+}
@@ -0,0 +1,1154 @@
+//! Automatic navigation mesh generation (baking) from scene geometry.
+//!
+//! # Overview
+//!
+//! The baker works in the same manner as [Recast](https://github.com/recastnavigation/recastnavigation):
+//!
+//! 1) Static geometry of the scene (meshes, terrains and colliders) is collected in world space.
+//! 2) The geometry is voxelized into a heightfield - a grid of columns, where each column contains
+//! a set of solid spans. A span is walkable if the slope of the geometry that produced it is less
+//! than maximum slope of an agent.
+//! 3) Walkable spans are filtered: an agent can step on low obstacles (less than maximum step
+//! height) and it needs enough free space above a span to stand on it.
+//! 4) Walkable area is eroded by agent radius, so the agent will not clip through walls.
+//! 5) Remaining cells with the same height are merged into rectangles, each rectangle is converted
+//! into a convex polygon and triangulated. Cells that are reachable from each other share vertices,
+//! which gives the connectivity of the navigation mesh.
+//!
+//! Resolution of the resulting navigation mesh is defined by cell size, smaller cells give more
+//! precise navigation mesh, but it takes more time to bake it and it will have more triangles.
+//!
+//! # Static geometry
+//!
+//! A node is considered static if neither the node itself nor any of its ancestors is a non-static
+//! rigid body. Sensor colliders are ignored. Trimesh, heightfield and convex polyhedron colliders
+//! are ignored too, because they are built from meshes and terrains which are already included.
+//! Balls, cylinders, cones and capsules are approximated by their bounding boxes.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use fyrox::{
+//!     scene::Scene,
+//!     utils::navmesh::{
+//!         bake::{NavmeshBakingError, NavmeshBakingSettings},
+//!         Navmesh,
+//!     },
+//! };
+//!
+//! fn bake_navmesh(scene: &mut Scene) -> Result<(), NavmeshBakingError> {
+//!     let navmesh = Navmesh::bake(
+//!         scene,
+//!         &NavmeshBakingSettings::default(),
+//!         |_, _| true,
+//!         Default::default(),
+//!         Default::default(),
+//!     )?;
+//!     scene.navmeshes.add(navmesh);
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Point3, Vector3},
+        math::TriangleDefinition,
+        pool::Handle,
+        reflect::prelude::*,
+    },
+    scene::{
+        collider::{Collider, ColliderShape},
+        graph::Graph,
+        mesh::{
+            buffer::{VertexAttributeUsage, VertexFetchError, VertexReadTrait},
+            surface::SurfaceData,
+            Mesh,
+        },
+        node::Node,
+        rigidbody::{RigidBody, RigidBodyType},
+        terrain::Terrain,
+        Scene,
+    },
+    utils::{lightmap::CancellationToken, navmesh::Navmesh},
+};
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    fmt::{Display, Formatter},
+    ops::Deref,
+    sync::{
+        atomic::{self, AtomicU32},
+        Arc,
+    },
+};
+
+/// A set of parameters for navigation mesh baking.
+#[derive(Clone, Debug, PartialEq, Reflect)]
+pub struct NavmeshBakingSettings {
+    /// Size of a heightfield cell on XZ plane (in meters).
+    #[reflect(min_value = 0.01, step = 0.05)]
+    pub cell_size: f32,
+    /// Size of a heightfield cell along Y axis (in meters).
+    #[reflect(min_value = 0.01, step = 0.05)]
+    pub cell_height: f32,
+    /// Radius of an agent (in meters). Walkable area is shrunk by this value.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub agent_radius: f32,
+    /// Height of an agent (in meters). An agent can stand on a surface only if there is enough
+    /// free space above it.
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub agent_height: f32,
+    /// Maximum slope (in degrees) of a surface on which an agent can walk.
+    #[reflect(min_value = 0.0, max_value = 90.0, step = 1.0)]
+    pub agent_max_slope: f32,
+    /// Maximum height (in meters) of an obstacle an agent can step on.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub agent_max_step_height: f32,
+}
+
+impl Default for NavmeshBakingSettings {
+    fn default() -> Self {
+        Self {
+            cell_size: 0.2,
+            cell_height: 0.1,
+            agent_radius: 0.4,
+            agent_height: 2.0,
+            agent_max_slope: 45.0,
+            agent_max_step_height: 0.4,
+        }
+    }
+}
+
+/// Navigation mesh baking stage.
+#[derive(Copy, Clone, PartialOrd, PartialEq, Ord, Eq, Debug)]
+#[repr(u32)]
+pub enum ProgressStage {
+    /// Collecting static geometry of the scene.
+    GeometryCaching = 0,
+    /// Converting triangles into a heightfield.
+    Voxelization = 1,
+    /// Filtering walkable spans, eroding walkable area.
+    Filtering = 2,
+    /// Building the navigation mesh from walkable cells.
+    MeshBuilding = 3,
+}
+
+/// Progress internals.
+#[derive(Default)]
+pub struct ProgressData {
+    stage: AtomicU32,
+    // Range is [0; max_iterations]
+    progress: AtomicU32,
+    max_iterations: AtomicU32,
+}
+
+impl ProgressData {
+    /// Returns progress percentage in [0; 100] range.
+    pub fn progress_percent(&self) -> u32 {
+        let iterations = self.max_iterations.load(atomic::Ordering::SeqCst);
+        if iterations > 0 {
+            self.progress.load(atomic::Ordering::SeqCst) * 100 / iterations
+        } else {
+            0
+        }
+    }
+
+    /// Returns current stage.
+    pub fn stage(&self) -> ProgressStage {
+        match self.stage.load(atomic::Ordering::SeqCst) {
+            0 => ProgressStage::GeometryCaching,
+            1 => ProgressStage::Voxelization,
+            2 => ProgressStage::Filtering,
+            3 => ProgressStage::MeshBuilding,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Sets new stage with max iterations per stage.
+    fn set_stage(&self, stage: ProgressStage, max_iterations: u32) {
+        self.max_iterations
+            .store(max_iterations, atomic::Ordering::SeqCst);
+        self.progress.store(0, atomic::Ordering::SeqCst);
+        self.stage.store(stage as u32, atomic::Ordering::SeqCst);
+    }
+
+    /// Advances progress.
+    fn advance_progress(&self) {
+        self.progress.fetch_add(1, atomic::Ordering::SeqCst);
+    }
+}
+
+/// Small helper that allows you to track progress of navigation mesh baking.
+#[derive(Clone, Default)]
+pub struct ProgressIndicator(pub Arc<ProgressData>);
+
+impl ProgressIndicator {
+    /// Creates new progress indicator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Deref for ProgressIndicator {
+    type Target = ProgressData;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// An error that may occur during navigation mesh baking.
+#[derive(Debug)]
+pub enum NavmeshBakingError {
+    /// Baking was cancelled by user.
+    Cancelled,
+    /// Vertex buffer of a mesh lacks required data.
+    InvalidData(VertexFetchError),
+}
+
+impl Display for NavmeshBakingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NavmeshBakingError::Cancelled => {
+                write!(f, "Navmesh baking was cancelled by the user.")
+            }
+            NavmeshBakingError::InvalidData(v) => {
+                write!(f, "Vertex buffer of a mesh lacks required data {v}.")
+            }
+        }
+    }
+}
+
+impl From<VertexFetchError> for NavmeshBakingError {
+    fn from(e: VertexFetchError) -> Self {
+        Self::InvalidData(e)
+    }
+}
+
+fn check_cancelled(cancellation_token: &CancellationToken) -> Result<(), NavmeshBakingError> {
+    if cancellation_token.is_cancelled() {
+        Err(NavmeshBakingError::Cancelled)
+    } else {
+        Ok(())
+    }
+}
+
+fn is_static(graph: &Graph, mut handle: Handle<Node>) -> bool {
+    while let Some(node) = graph.try_get(handle) {
+        if let Some(body) = node.cast::<RigidBody>() {
+            if body.body_type() != RigidBodyType::Static {
+                return false;
+            }
+        }
+        handle = node.parent();
+    }
+    true
+}
+
+fn transform(transform: &Matrix4<f32>, point: Vector3<f32>) -> Vector3<f32> {
+    transform.transform_point(&Point3::from(point)).coords
+}
+
+fn collect_surface(
+    data: &SurfaceData,
+    global_transform: &Matrix4<f32>,
+    triangles: &mut Vec<[Vector3<f32>; 3]>,
+) -> Result<(), VertexFetchError> {
+    let vertex_buffer = &data.vertex_buffer;
+    for triangle in data.geometry_buffer.iter() {
+        let mut world_triangle = [Vector3::default(); 3];
+        for (world_vertex, index) in world_triangle.iter_mut().zip(triangle.0.iter()) {
+            let position = vertex_buffer
+                .get(*index as usize)
+                .ok_or(VertexFetchError::NoSuchAttribute(
+                    VertexAttributeUsage::Position,
+                ))?
+                .read_3_f32(VertexAttributeUsage::Position)?;
+            *world_vertex = transform(global_transform, position);
+        }
+        triangles.push(world_triangle);
+    }
+    Ok(())
+}
+
+fn collect_box(
+    min: Vector3<f32>,
+    max: Vector3<f32>,
+    global_transform: &Matrix4<f32>,
+    triangles: &mut Vec<[Vector3<f32>; 3]>,
+) {
+    // Bit 0 - x, bit 1 - y, bit 2 - z.
+    let corner = |i: usize| {
+        transform(
+            global_transform,
+            Vector3::new(
+                if i & 1 != 0 { max.x } else { min.x },
+                if i & 2 != 0 { max.y } else { min.y },
+                if i & 4 != 0 { max.z } else { min.z },
+            ),
+        )
+    };
+
+    // Faces are counter-clockwise when looking from outside of the box.
+    for [a, b, c, d] in [
+        [2, 6, 7, 3], // +Y
+        [0, 1, 5, 4], // -Y
+        [1, 3, 7, 5], // +X
+        [0, 4, 6, 2], // -X
+        [4, 5, 7, 6], // +Z
+        [0, 2, 3, 1], // -Z
+    ] {
+        triangles.push([corner(a), corner(b), corner(c)]);
+        triangles.push([corner(a), corner(c), corner(d)]);
+    }
+}
+
+fn collect_collider(collider: &Collider, triangles: &mut Vec<[Vector3<f32>; 3]>) {
+    let global_transform = collider.global_transform();
+    let (min, max) = match collider.shape() {
+        ColliderShape::Cuboid(cuboid) => (-cuboid.half_extents, cuboid.half_extents),
+        ColliderShape::Ball(ball) => (Vector3::repeat(-ball.radius), Vector3::repeat(ball.radius)),
+        ColliderShape::Cylinder(cylinder) => (
+            Vector3::new(-cylinder.radius, -cylinder.half_height, -cylinder.radius),
+            Vector3::new(cylinder.radius, cylinder.half_height, cylinder.radius),
+        ),
+        ColliderShape::Cone(cone) => (
+            Vector3::new(-cone.radius, -cone.half_height, -cone.radius),
+            Vector3::new(cone.radius, cone.half_height, cone.radius),
+        ),
+        ColliderShape::Capsule(capsule) => (
+            capsule.begin.inf(&capsule.end) - Vector3::repeat(capsule.radius),
+            capsule.begin.sup(&capsule.end) + Vector3::repeat(capsule.radius),
+        ),
+        ColliderShape::Triangle(triangle) => {
+            triangles.push([
+                transform(&global_transform, triangle.a),
+                transform(&global_transform, triangle.b),
+                transform(&global_transform, triangle.c),
+            ]);
+            return;
+        }
+        ColliderShape::Segment(_)
+        | ColliderShape::Trimesh(_)
+        | ColliderShape::Heightfield(_)
+        | ColliderShape::Polyhedron(_) => return,
+    };
+    collect_box(min, max, &global_transform, triangles);
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Span {
+    min: i32,
+    max: i32,
+    walkable: bool,
+}
+
+/// Adds a span to a column (sorted by `min`), merges overlapping spans.
+fn add_span(column: &mut Vec<Span>, mut span: Span, merge_threshold: i32) {
+    let mut i = 0;
+    while i < column.len() {
+        let other = column[i];
+        if other.min > span.max {
+            break;
+        }
+        if other.max < span.min {
+            i += 1;
+            continue;
+        }
+
+        // Walkable flag is defined by the top-most surface.
+        if (other.max - span.max).abs() <= merge_threshold {
+            span.walkable |= other.walkable;
+        } else if other.max > span.max {
+            span.walkable = other.walkable;
+        }
+        span.min = span.min.min(other.min);
+        span.max = span.max.max(other.max);
+        column.remove(i);
+    }
+    column.insert(i, span);
+}
+
+/// Clips a polygon by an axis-aligned plane, keeps the part that is on the "greater" side of the
+/// plane if `keep_greater` is set, or the opposite part otherwise.
+fn clip_polygon(
+    input: &[Vector3<f32>],
+    output: &mut Vec<Vector3<f32>>,
+    axis: usize,
+    value: f32,
+    keep_greater: bool,
+) {
+    output.clear();
+    let distance = |p: &Vector3<f32>| {
+        if keep_greater {
+            p[axis] - value
+        } else {
+            value - p[axis]
+        }
+    };
+    for (i, a) in input.iter().enumerate() {
+        let b = &input[(i + 1) % input.len()];
+        let da = distance(a);
+        let db = distance(b);
+        if da >= 0.0 {
+            output.push(*a);
+        }
+        if (da >= 0.0) != (db >= 0.0) {
+            let t = da / (da - db);
+            output.push(a.lerp(b, t));
+        }
+    }
+}
+
+struct Heightfield {
+    origin: Vector3<f32>,
+    width: usize,
+    depth: usize,
+    columns: Vec<Vec<Span>>,
+}
+
+#[derive(Clone, Debug)]
+struct Cell {
+    x: usize,
+    z: usize,
+    floor: i32,
+    ceiling: i32,
+    // -X, +Z, +X, -Z
+    neighbors: [Option<usize>; 4],
+}
+
+const DIRECTIONS: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+fn voxelize(
+    triangles: &[[Vector3<f32>; 3]],
+    settings: &NavmeshBakingSettings,
+    cancellation_token: &CancellationToken,
+    progress_indicator: &ProgressIndicator,
+) -> Result<Heightfield, NavmeshBakingError> {
+    let mut min = Vector3::repeat(f32::MAX);
+    let mut max = Vector3::repeat(-f32::MAX);
+    for vertex in triangles.iter().flatten() {
+        min = min.inf(vertex);
+        max = max.sup(vertex);
+    }
+
+    let cs = settings.cell_size;
+    let ch = settings.cell_height;
+    let width = ((max.x - min.x) / cs).ceil().max(1.0) as usize;
+    let depth = ((max.z - min.z) / cs).ceil().max(1.0) as usize;
+    let mut heightfield = Heightfield {
+        origin: min,
+        width,
+        depth,
+        columns: vec![Vec::new(); width * depth],
+    };
+
+    let min_normal_y = settings.agent_max_slope.to_radians().cos();
+    let merge_threshold = (settings.agent_max_step_height / ch).floor() as i32;
+    let cell_index = |v: f32, size: usize| (v.floor().max(0.0) as usize).min(size - 1);
+
+    progress_indicator.set_stage(ProgressStage::Voxelization, triangles.len() as u32);
+
+    let mut row = Vec::new();
+    let mut cell = Vec::new();
+    let mut temp = Vec::new();
+    for triangle in triangles {
+        check_cancelled(cancellation_token)?;
+
+        let normal = (triangle[1] - triangle[0]).cross(&(triangle[2] - triangle[0]));
+        let walkable = normal
+            .try_normalize(f32::EPSILON)
+            .map_or(false, |n| n.y >= min_normal_y);
+
+        let tmin = triangle[0].inf(&triangle[1]).inf(&triangle[2]);
+        let tmax = triangle[0].sup(&triangle[1]).sup(&triangle[2]);
+        let x0 = cell_index((tmin.x - min.x) / cs, width);
+        let x1 = cell_index((tmax.x - min.x) / cs, width);
+        let z0 = cell_index((tmin.z - min.z) / cs, depth);
+        let z1 = cell_index((tmax.z - min.z) / cs, depth);
+
+        for z in z0..=z1 {
+            let cz0 = min.z + z as f32 * cs;
+            clip_polygon(triangle, &mut temp, 2, cz0, true);
+            clip_polygon(&temp, &mut row, 2, cz0 + cs, false);
+            if row.is_empty() {
+                continue;
+            }
+
+            for x in x0..=x1 {
+                let cx0 = min.x + x as f32 * cs;
+                clip_polygon(&row, &mut temp, 0, cx0, true);
+                clip_polygon(&temp, &mut cell, 0, cx0 + cs, false);
+                if cell.is_empty() {
+                    continue;
+                }
+
+                let (ymin, ymax) = cell
+                    .iter()
+                    .fold((f32::MAX, -f32::MAX), |(a, b), v| (a.min(v.y), b.max(v.y)));
+
+                add_span(
+                    &mut heightfield.columns[z * width + x],
+                    Span {
+                        min: ((ymin - min.y) / ch).floor() as i32,
+                        max: ((ymax - min.y) / ch).ceil() as i32,
+                        walkable,
+                    },
+                    merge_threshold,
+                );
+            }
+        }
+
+        progress_indicator.advance_progress();
+    }
+
+    Ok(heightfield)
+}
+
+fn build_cells(
+    heightfield: &mut Heightfield,
+    settings: &NavmeshBakingSettings,
+    cancellation_token: &CancellationToken,
+    progress_indicator: &ProgressIndicator,
+) -> Result<Vec<Cell>, NavmeshBakingError> {
+    let step_cells = (settings.agent_max_step_height / settings.cell_height).floor() as i32;
+    let height_cells = (settings.agent_height / settings.cell_height).ceil() as i32;
+    let radius_cells = (settings.agent_radius / settings.cell_size).ceil() as u32;
+
+    progress_indicator.set_stage(
+        ProgressStage::Filtering,
+        heightfield.columns.len() as u32 * 2,
+    );
+
+    // Open cells on top of walkable spans with enough free space above them.
+    let mut cells = Vec::new();
+    let mut columns = Vec::with_capacity(heightfield.columns.len());
+    for (index, column) in heightfield.columns.iter_mut().enumerate() {
+        check_cancelled(cancellation_token)?;
+
+        // An agent can step on low obstacles.
+        let mut previous: Option<Span> = None;
+        for span in column.iter_mut() {
+            let original = *span;
+            if let Some(previous) = previous {
+                if !span.walkable && previous.walkable && span.max - previous.max <= step_cells {
+                    span.walkable = true;
+                }
+            }
+            previous = Some(original);
+        }
+
+        let start = cells.len();
+        for (i, span) in column.iter().enumerate() {
+            let ceiling = column.get(i + 1).map_or(i32::MAX, |next| next.min);
+            if span.walkable && ceiling.saturating_sub(span.max) >= height_cells {
+                cells.push(Cell {
+                    x: index % heightfield.width,
+                    z: index / heightfield.width,
+                    floor: span.max,
+                    ceiling,
+                    neighbors: [None; 4],
+                });
+            }
+        }
+        columns.push(start..cells.len());
+
+        progress_indicator.advance_progress();
+    }
+
+    // Find connections between neighbour cells.
+    for i in 0..cells.len() {
+        if i % heightfield.width == 0 {
+            check_cancelled(cancellation_token)?;
+        }
+
+        let Cell {
+            x,
+            z,
+            floor,
+            ceiling,
+            ..
+        } = cells[i];
+        for (direction, (dx, dz)) in DIRECTIONS.iter().enumerate() {
+            let nx = x as isize + dx;
+            let nz = z as isize + dz;
+            if nx < 0
+                || nz < 0
+                || nx >= heightfield.width as isize
+                || nz >= heightfield.depth as isize
+            {
+                continue;
+            }
+
+            let column = columns[nz as usize * heightfield.width + nx as usize].clone();
+            let neighbor = column.into_iter().find(|&n| {
+                let neighbor = &cells[n];
+                (neighbor.floor - floor).abs() <= step_cells
+                    && ceiling.min(neighbor.ceiling) - floor.max(neighbor.floor) >= height_cells
+            });
+            cells[i].neighbors[direction] = neighbor;
+        }
+    }
+
+    for _ in 0..heightfield.columns.len() {
+        progress_indicator.advance_progress();
+    }
+
+    if radius_cells == 0 {
+        return Ok(cells);
+    }
+
+    // Erode walkable area by agent radius. Distance to the border is calculated using
+    // Dijkstra's algorithm, where orthogonal steps cost 2 and diagonal steps cost 3.
+    let mut distances = vec![u32::MAX; cells.len()];
+    let mut queue = BinaryHeap::new();
+    for (i, cell) in cells.iter().enumerate() {
+        if cell.neighbors.iter().any(|n| n.is_none()) {
+            distances[i] = 0;
+            queue.push(Reverse((0, i)));
+        }
+    }
+    while let Some(Reverse((distance, i))) = queue.pop() {
+        if distance > distances[i] {
+            continue;
+        }
+        for direction in 0..4 {
+            if let Some(n) = cells[i].neighbors[direction] {
+                let mut relax = |n: usize, cost: u32| {
+                    if distance + cost < distances[n] {
+                        distances[n] = distance + cost;
+                        queue.push(Reverse((distance + cost, n)));
+                    }
+                };
+                relax(n, 2);
+                if let Some(d) = cells[n].neighbors[(direction + 1) % 4] {
+                    relax(d, 3);
+                }
+            }
+        }
+    }
+
+    let mut remap = vec![None; cells.len()];
+    let mut eroded = Vec::with_capacity(cells.len());
+    for (i, cell) in cells.iter().enumerate() {
+        if distances[i] >= radius_cells * 2 {
+            remap[i] = Some(eroded.len());
+            eroded.push(cell.clone());
+        }
+    }
+    for cell in eroded.iter_mut() {
+        for neighbor in cell.neighbors.iter_mut() {
+            *neighbor = neighbor.and_then(|n| remap[n]);
+        }
+    }
+
+    Ok(eroded)
+}
+
+fn find_root(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+// Rectangle of connected cells with the same floor height.
+struct CellRect {
+    width: usize,
+    depth: usize,
+    // Row-major, rows go along +Z.
+    cells: Vec<usize>,
+}
+
+impl CellRect {
+    fn cell(&self, x: usize, z: usize) -> usize {
+        self.cells[z * self.width + x]
+    }
+}
+
+// See `DIRECTIONS`.
+const POSITIVE_X: usize = 2;
+const POSITIVE_Z: usize = 1;
+
+/// Greedily merges connected cells with the same floor height into rectangles.
+fn merge_cells(cells: &[Cell]) -> Vec<CellRect> {
+    let mut merged = vec![false; cells.len()];
+    let mut rects = Vec::new();
+    for origin in 0..cells.len() {
+        if merged[origin] {
+            continue;
+        }
+
+        let floor = cells[origin].floor;
+        let mergeable = |merged: &[bool], neighbor: Option<usize>| {
+            neighbor.filter(|&n| !merged[n] && cells[n].floor == floor)
+        };
+
+        let mut row = vec![origin];
+        while let Some(n) = mergeable(&merged, cells[*row.last().unwrap()].neighbors[POSITIVE_X]) {
+            row.push(n);
+        }
+        for &cell in row.iter() {
+            merged[cell] = true;
+        }
+
+        // Extend the rectangle along +Z while next row is fully connected.
+        let mut rect = CellRect {
+            width: row.len(),
+            depth: 1,
+            cells: row.clone(),
+        };
+        'rows: loop {
+            let mut next_row: Vec<usize> = Vec::with_capacity(row.len());
+            for (i, &cell) in row.iter().enumerate() {
+                match mergeable(&merged, cells[cell].neighbors[POSITIVE_Z]) {
+                    Some(n)
+                        if i == 0 || cells[next_row[i - 1]].neighbors[POSITIVE_X] == Some(n) =>
+                    {
+                        next_row.push(n)
+                    }
+                    _ => break 'rows,
+                }
+            }
+            for &cell in next_row.iter() {
+                merged[cell] = true;
+            }
+            rect.cells.extend_from_slice(&next_row);
+            rect.depth += 1;
+            row = next_row;
+        }
+
+        rects.push(rect);
+    }
+    rects
+}
+
+fn build_navmesh(
+    heightfield: &Heightfield,
+    cells: &[Cell],
+    settings: &NavmeshBakingSettings,
+    cancellation_token: &CancellationToken,
+    progress_indicator: &ProgressIndicator,
+) -> Result<Navmesh, NavmeshBakingError> {
+    if cells.is_empty() {
+        return Ok(Navmesh::default());
+    }
+
+    progress_indicator.set_stage(ProgressStage::MeshBuilding, cells.len() as u32);
+
+    // Each cell has four corners: 0 - (x, z), 1 - (x + 1, z), 2 - (x + 1, z + 1), 3 - (x, z + 1).
+    // Corners of connected cells are merged.
+    let mut parents = (0..cells.len() * 4).collect::<Vec<_>>();
+    // Shared corners of a cell and its neighbour for each direction.
+    const SHARED_CORNERS: [[(usize, usize); 2]; 4] = [
+        [(0, 1), (3, 2)],
+        [(3, 0), (2, 1)],
+        [(1, 0), (2, 3)],
+        [(0, 3), (1, 2)],
+    ];
+    for (i, cell) in cells.iter().enumerate() {
+        for (direction, neighbor) in cell.neighbors.iter().enumerate() {
+            if let Some(n) = neighbor {
+                for (a, b) in SHARED_CORNERS[direction] {
+                    let a = find_root(&mut parents, i * 4 + a);
+                    let b = find_root(&mut parents, n * 4 + b);
+                    parents[a] = b;
+                }
+            }
+        }
+    }
+
+    // Height of a merged corner is the average height of the cells that share it.
+    let cs = settings.cell_size;
+    let origin = heightfield.origin;
+    let floor_height = |cell: &Cell| origin.y + cell.floor as f32 * settings.cell_height;
+    let mut heights = vec![(0.0, 0); parents.len()];
+    for (i, cell) in cells.iter().enumerate() {
+        for corner in 0..4 {
+            let height = &mut heights[find_root(&mut parents, i * 4 + corner)];
+            height.0 += floor_height(cell);
+            height.1 += 1;
+        }
+    }
+
+    let rects = merge_cells(cells);
+
+    // Corners of the rectangles, other points on the borders of a rectangle are needed only if
+    // they are corners of some other rectangle, otherwise neighbour rectangles will not share
+    // edges.
+    let mut is_rect_corner = vec![false; parents.len()];
+    for rect in rects.iter() {
+        let (w, d) = (rect.width - 1, rect.depth - 1);
+        for (x, z, corner) in [(0, 0, 0), (w, 0, 1), (w, d, 2), (0, d, 3)] {
+            is_rect_corner[find_root(&mut parents, rect.cell(x, z) * 4 + corner)] = true;
+        }
+    }
+
+    let mut vertex_indices = vec![u32::MAX; parents.len()];
+    let mut vertices = Vec::new();
+    let mut triangles = Vec::new();
+    let mut polygon = Vec::new();
+    for rect in rects.iter() {
+        check_cancelled(cancellation_token)?;
+
+        // Walk around the rectangle in the same order as the corners of a cell are triangulated.
+        polygon.clear();
+        let (w, d) = (rect.width, rect.depth);
+        let border = (0..d)
+            .map(|z| (0, z, 0))
+            .chain((0..w).map(|x| (x, d - 1, 3)))
+            .chain((0..d).rev().map(|z| (w - 1, z, 2)))
+            .chain((0..w).rev().map(|x| (x, 0, 1)));
+        for (x, z, corner) in border {
+            let root = find_root(&mut parents, rect.cell(x, z) * 4 + corner);
+            if !is_rect_corner[root] {
+                continue;
+            }
+            if vertex_indices[root] == u32::MAX {
+                vertex_indices[root] = vertices.len() as u32;
+                let cell = &cells[rect.cell(x, z)];
+                let (dx, dz) = [(0, 0), (1, 0), (1, 1), (0, 1)][corner];
+                let (sum, count) = heights[root];
+                vertices.push(Vector3::new(
+                    origin.x + (cell.x + dx) as f32 * cs,
+                    sum / count as f32,
+                    origin.z + (cell.z + dz) as f32 * cs,
+                ));
+            }
+            polygon.push(vertex_indices[root]);
+        }
+
+        if let [a, b, c, d] = polygon[..] {
+            triangles.push(TriangleDefinition([a, b, c]));
+            triangles.push(TriangleDefinition([c, d, a]));
+        } else {
+            // Polygon has some extra points on its edges, it is still convex so it could be
+            // triangulated using a fan around its center.
+            let first = &cells[rect.cell(0, 0)];
+            let center = vertices.len() as u32;
+            vertices.push(Vector3::new(
+                origin.x + (first.x as f32 + w as f32 * 0.5) * cs,
+                floor_height(first),
+                origin.z + (first.z as f32 + d as f32 * 0.5) * cs,
+            ));
+            for (i, &a) in polygon.iter().enumerate() {
+                let b = polygon[(i + 1) % polygon.len()];
+                triangles.push(TriangleDefinition([a, b, center]));
+            }
+        }
+
+        for _ in 0..rect.cells.len() {
+            progress_indicator.advance_progress();
+        }
+    }
+
+    Ok(Navmesh::new(&triangles, &vertices))
+}
+
+/// Static geometry of a scene (in world space) that is used to bake a navigation mesh. Collecting
+/// the geometry needs an access to the scene, while the baking itself does not, so the geometry
+/// could be collected on the main thread and then the navigation mesh could be baked on a
+/// separate thread.
+#[derive(Clone, Default, Debug)]
+pub struct NavmeshGeometry {
+    triangles: Vec<[Vector3<f32>; 3]>,
+}
+
+impl NavmeshGeometry {
+    /// Collects static geometry of the given scene. See [module docs](self) for more info.
+    ///
+    /// `filter` allows you to exclude nodes from baking.
+    /// `progress_indicator` allows you to get info about current progress.
+    /// `cancellation_token` allows you to stop collecting in any time.
+    pub fn from_scene<F>(
+        scene: &mut Scene,
+        mut filter: F,
+        cancellation_token: CancellationToken,
+        progress_indicator: ProgressIndicator,
+    ) -> Result<Self, NavmeshBakingError>
+    where
+        F: FnMut(Handle<Node>, &Node) -> bool,
+    {
+        scene.graph.update_hierarchical_data();
+
+        let graph = &scene.graph;
+
+        progress_indicator.set_stage(ProgressStage::GeometryCaching, graph.node_count());
+
+        let mut triangles = Vec::new();
+        for (handle, node) in graph.pair_iter() {
+            check_cancelled(&cancellation_token)?;
+
+            if filter(handle, node) && is_static(graph, handle) {
+                if let Some(mesh) = node.cast::<Mesh>() {
+                    for surface in mesh.surfaces() {
+                        let data = surface.data();
+                        let data = data.lock();
+                        collect_surface(&data, &mesh.global_transform(), &mut triangles)?;
+                    }
+                } else if let Some(terrain) = node.cast::<Terrain>() {
+                    for chunk in terrain.chunks_ref() {
+                        let data = chunk.data();
+                        let data = data.lock();
+                        collect_surface(&data, &terrain.global_transform(), &mut triangles)?;
+                    }
+                } else if let Some(collider) = node.cast::<Collider>() {
+                    if !collider.is_sensor() {
+                        collect_collider(collider, &mut triangles);
+                    }
+                }
+            }
+
+            progress_indicator.advance_progress();
+        }
+
+        Ok(Self { triangles })
+    }
+
+    /// Bakes navigation mesh from the geometry. This method is blocking, run it on a separate
+    /// thread if you need to track the progress.
+    ///
+    /// `progress_indicator` allows you to get info about current progress.
+    /// `cancellation_token` allows you to stop baking in any time.
+    pub fn bake(
+        &self,
+        settings: &NavmeshBakingSettings,
+        cancellation_token: CancellationToken,
+        progress_indicator: ProgressIndicator,
+    ) -> Result<Navmesh, NavmeshBakingError> {
+        if self.triangles.is_empty() {
+            return Ok(Navmesh::default());
+        }
+
+        let mut heightfield = voxelize(
+            &self.triangles,
+            settings,
+            &cancellation_token,
+            &progress_indicator,
+        )?;
+
+        let cells = build_cells(
+            &mut heightfield,
+            settings,
+            &cancellation_token,
+            &progress_indicator,
+        )?;
+
+        build_navmesh(
+            &heightfield,
+            &cells,
+            settings,
+            &cancellation_token,
+            &progress_indicator,
+        )
+    }
+}
+
+impl Navmesh {
+    /// Bakes navigation mesh from static geometry of the given scene. See [module docs](self) for
+    /// more info. This method is blocking, use [`NavmeshGeometry`] if you need to bake navigation
+    /// mesh on a separate thread.
+    ///
+    /// `filter` allows you to exclude nodes from baking.
+    /// `progress_indicator` allows you to get info about current progress.
+    /// `cancellation_token` allows you to stop baking in any time.
+    pub fn bake<F>(
+        scene: &mut Scene,
+        settings: &NavmeshBakingSettings,
+        filter: F,
+        cancellation_token: CancellationToken,
+        progress_indicator: ProgressIndicator,
+    ) -> Result<Self, NavmeshBakingError>
+    where
+        F: FnMut(Handle<Node>, &Node) -> bool,
+    {
+        NavmeshGeometry::from_scene(
+            scene,
+            filter,
+            cancellation_token.clone(),
+            progress_indicator.clone(),
+        )?
+        .bake(settings, cancellation_token, progress_indicator)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector3,
+        scene::{
+            base::BaseBuilder,
+            collider::{ColliderBuilder, ColliderShape},
+            transform::TransformBuilder,
+            Scene,
+        },
+        utils::{
+            astar::PathKind,
+            lightmap::CancellationToken,
+            navmesh::{
+                bake::{NavmeshBakingError, NavmeshBakingSettings},
+                Navmesh,
+            },
+        },
+    };
+    use fxhash::FxHashMap;
+
+    fn add_box(scene: &mut Scene, position: Vector3<f32>, half_extents: Vector3<f32>) {
+        ColliderBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(position)
+                    .build(),
+            ),
+        )
+        .with_shape(ColliderShape::cuboid(
+            half_extents.x,
+            half_extents.y,
+            half_extents.z,
+        ))
+        .build(&mut scene.graph);
+    }
+
+    #[test]
+    fn test_navmesh_baking() {
+        let mut scene = Scene::new();
+        // Ground.
+        add_box(
+            &mut scene,
+            Vector3::new(0.0, -0.5, 0.0),
+            Vector3::new(5.0, 0.5, 5.0),
+        );
+        // Tall obstacle in the center, it is too narrow to stand on it.
+        add_box(
+            &mut scene,
+            Vector3::new(0.0, 2.0, 0.0),
+            Vector3::new(0.3, 2.0, 0.3),
+        );
+
+        let settings = NavmeshBakingSettings::default();
+        let navmesh = Navmesh::bake(
+            &mut scene,
+            &settings,
+            |_, _| true,
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
+
+        assert!(!navmesh.triangles().is_empty());
+
+        for vertex in navmesh.vertices() {
+            let p = vertex.position;
+            // Every vertex is on the ground.
+            assert!(p.y.abs() <= settings.cell_height * 2.0);
+            // Walkable area is eroded by agent radius around the obstacle and near the borders.
+            assert!(p.x.abs().max(p.z.abs()) >= 0.3 + settings.agent_radius - settings.cell_size);
+            assert!(p.x.abs().max(p.z.abs()) <= 5.0 - settings.agent_radius + settings.cell_size);
+        }
+    }
+
+    #[test]
+    fn test_navmesh_baking_merges_coplanar_cells() {
+        let mut scene = Scene::new();
+        add_box(
+            &mut scene,
+            Vector3::new(0.0, -0.5, 0.0),
+            Vector3::new(5.0, 0.5, 5.0),
+        );
+
+        let navmesh = Navmesh::bake(
+            &mut scene,
+            &NavmeshBakingSettings::default(),
+            |_, _| true,
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
+
+        // Flat ground is a single rectangle.
+        assert_eq!(navmesh.triangles().len(), 2);
+        assert_eq!(navmesh.vertices().len(), 4);
+    }
+
+    #[test]
+    fn test_navmesh_baking_connectivity() {
+        let mut scene = Scene::new();
+        add_box(
+            &mut scene,
+            Vector3::new(0.0, -0.5, 0.0),
+            Vector3::new(5.0, 0.5, 5.0),
+        );
+        // A step that is low enough to walk on it splits the ground into rectangles of different
+        // sizes.
+        add_box(
+            &mut scene,
+            Vector3::new(1.0, 0.1, 0.5),
+            Vector3::new(1.5, 0.1, 2.0),
+        );
+        add_box(
+            &mut scene,
+            Vector3::new(-2.0, 2.0, -2.0),
+            Vector3::new(0.5, 2.0, 0.5),
+        );
+
+        let mut navmesh = Navmesh::bake(
+            &mut scene,
+            &NavmeshBakingSettings::default(),
+            |_, _| true,
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
+
+        // Every edge is shared by at most two triangles, otherwise there are overlapping
+        // triangles.
+        let mut edges = FxHashMap::<(u32, u32), usize>::default();
+        for triangle in navmesh.triangles() {
+            for (a, b) in [(0, 1), (1, 2), (2, 0)] {
+                let edge = (triangle[a].min(triangle[b]), triangle[a].max(triangle[b]));
+                *edges.entry(edge).or_default() += 1;
+            }
+        }
+        assert!(edges.values().all(|count| *count <= 2));
+
+        // Every point of the walkable area is reachable from any other.
+        let points = [
+            Vector3::new(-4.0, 0.0, -4.0),
+            Vector3::new(4.0, 0.0, 4.0),
+            Vector3::new(1.0, 0.2, 0.5),
+            Vector3::new(-4.0, 0.0, 4.0),
+        ];
+        let mut path = Vec::new();
+        for begin in points {
+            for end in points {
+                let from = navmesh.query_closest(begin).unwrap();
+                let to = navmesh.query_closest(end).unwrap();
+                assert!(matches!(
+                    navmesh.build_path(from, to, &mut path),
+                    Ok(PathKind::Full)
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_navmesh_baking_cancellation() {
+        let mut scene = Scene::new();
+        add_box(&mut scene, Vector3::default(), Vector3::new(5.0, 0.5, 5.0));
+
+        let token = CancellationToken::new();
+        token.cancel();
+
+        assert!(matches!(
+            Navmesh::bake(
+                &mut scene,
+                &NavmeshBakingSettings::default(),
+                |_, _| true,
+                token,
+                Default::default(),
+            ),
+            Err(NavmeshBakingError::Cancelled)
+        ));
+    }
+}
@@ -0,0 +1,951 @@
+//! Crowd simulation with local avoidance.
+//!
+//! # Overview
+//!
+//! [`Crowd`] owns a set of agents that move on the same navigation mesh. Each agent follows its own
+//! path (see [`NavmeshAgent`]), but its velocity is adjusted every frame to avoid collisions with
+//! other agents and dynamic obstacles. Avoidance is based on
+//! [ORCA](https://gamma.cs.unc.edu/ORCA/) (optimal reciprocal collision avoidance) - every neighbour
+//! agent forms a half-plane of permitted velocities, each agent of a pair takes half of the
+//! responsibility to avoid a collision. Dynamic obstacles do not move by themselves, so agents take
+//! full responsibility to avoid them. Resulting movement is constrained to the triangles of the
+//! navigation mesh, agents slide along the borders of the navigation mesh. Off-mesh links are
+//! traversed without avoidance.
+//!
+//! Avoidance is performed on XZ plane, agents and obstacles, that do not overlap vertically, do not
+//! affect each other.
+//!
+//! # Usage
+//!
+//! ```
+//! use fyrox::{
+//!     core::algebra::Vector3,
+//!     utils::navmesh::{
+//!         crowd::{Crowd, CrowdAgent, DynamicObstacle, ObstacleShape},
+//!         Navmesh, NavmeshAgentBuilder,
+//!     },
+//! };
+//!
+//! fn update_crowd(crowd: &mut Crowd, navmesh: &mut Navmesh, dt: f32) {
+//!     crowd.update(dt, navmesh);
+//!
+//!     for agent in crowd.agents() {
+//!         // Sync positions of bots with their agents here.
+//!         let _ = agent.position();
+//!     }
+//! }
+//!
+//! fn make_crowd() -> Crowd {
+//!     let mut crowd = Crowd::new();
+//!     crowd.add_agent(CrowdAgent::new(
+//!         NavmeshAgentBuilder::new()
+//!             .with_position(Vector3::new(-5.0, 0.0, 0.0))
+//!             .with_target(Vector3::new(5.0, 0.0, 0.0))
+//!             .build(),
+//!         0.4,
+//!     ));
+//!     crowd.add_obstacle(DynamicObstacle::new(
+//!         Vector3::default(),
+//!         ObstacleShape::Cylinder {
+//!             radius: 1.0,
+//!             half_height: 1.0,
+//!         },
+//!     ));
+//!     crowd
+//! }
+//! ```
+
+use crate::{
+    core::{
+        algebra::{Vector2, Vector3},
+        math::{aabb::AxisAlignedBoundingBox, ray::Ray},
+        pool::{Handle, Pool},
+        visitor::prelude::*,
+    },
+    utils::navmesh::{Navmesh, NavmeshAgent},
+};
+
+/// Shape of a dynamic obstacle.
+#[derive(Visit, Clone, Debug, PartialEq)]
+pub enum ObstacleShape {
+    /// Vertical cylinder.
+    Cylinder {
+        /// Radius of the cylinder.
+        radius: f32,
+        /// Half of the height of the cylinder.
+        half_height: f32,
+    },
+    /// Axis-aligned box.
+    Box {
+        /// Half extents of the box.
+        half_extents: Vector3<f32>,
+    },
+}
+
+impl Default for ObstacleShape {
+    fn default() -> Self {
+        Self::Cylinder {
+            radius: 0.5,
+            half_height: 1.0,
+        }
+    }
+}
+
+/// Dynamic obstacle is an object (a box, a barrel, a car, etc.) that agents should steer around.
+/// Obstacles could be added, moved and removed at any time.
+#[derive(Visit, Clone, Debug, PartialEq, Default)]
+pub struct DynamicObstacle {
+    /// Position of the center of the obstacle.
+    pub position: Vector3<f32>,
+    /// Shape of the obstacle.
+    pub shape: ObstacleShape,
+}
+
+impl DynamicObstacle {
+    /// Creates new dynamic obstacle.
+    pub fn new(position: Vector3<f32>, shape: ObstacleShape) -> Self {
+        Self { position, shape }
+    }
+
+    /// Returns axis-aligned bounding box of the obstacle.
+    pub fn bounding_box(&self) -> AxisAlignedBoundingBox {
+        let half_extents = match self.shape {
+            ObstacleShape::Cylinder {
+                radius,
+                half_height,
+            } => Vector3::new(radius, half_height, radius),
+            ObstacleShape::Box { half_extents } => half_extents,
+        };
+        AxisAlignedBoundingBox::from_min_max(
+            self.position - half_extents,
+            self.position + half_extents,
+        )
+    }
+
+    fn vertical_range(&self) -> (f32, f32) {
+        let half_height = match self.shape {
+            ObstacleShape::Cylinder { half_height, .. } => half_height,
+            ObstacleShape::Box { half_extents } => half_extents.y,
+        };
+        (self.position.y - half_height, self.position.y + half_height)
+    }
+
+    /// Returns true if the obstacle overlaps the given triangle. It is used to carve navigation
+    /// meshes, see [`Navmesh::carve`].
+    pub fn intersects_triangle(&self, triangle: &[Vector3<f32>; 3]) -> bool {
+        let (min, max) = self.vertical_range();
+        let triangle_min = triangle[0].y.min(triangle[1].y).min(triangle[2].y);
+        let triangle_max = triangle[0].y.max(triangle[1].y).max(triangle[2].y);
+        if triangle_max < min || triangle_min > max {
+            return false;
+        }
+
+        let points = triangle.map(xz);
+        let center = xz(self.position);
+        match self.shape {
+            ObstacleShape::Cylinder { radius, .. } => {
+                distance_to_triangle(center, &points) < radius
+            }
+            ObstacleShape::Box { half_extents } => {
+                let half_extents = xz(half_extents);
+                // Separating axis test, axes of the box and normals of the edges of the triangle.
+                let edge_normal =
+                    |a: Vector2<f32>, b: Vector2<f32>| Vector2::new(a.y - b.y, b.x - a.x);
+                let axes = [
+                    Vector2::x(),
+                    Vector2::y(),
+                    edge_normal(points[0], points[1]),
+                    edge_normal(points[1], points[2]),
+                    edge_normal(points[2], points[0]),
+                ];
+                axes.iter().all(|axis| {
+                    let box_center = center.dot(axis);
+                    let box_extent = half_extents.x * axis.x.abs() + half_extents.y * axis.y.abs();
+                    let (triangle_min, triangle_max) =
+                        points.iter().fold((f32::MAX, f32::MIN), |(min, max), p| {
+                            (min.min(p.dot(axis)), max.max(p.dot(axis)))
+                        });
+                    box_center + box_extent > triangle_min && box_center - box_extent < triangle_max
+                })
+            }
+        }
+    }
+
+    /// Returns center and radius of a circle (on XZ plane) that should be avoided by an agent at
+    /// the given position.
+    fn avoidance_circle(&self, agent_position: Vector2<f32>) -> (Vector2<f32>, f32) {
+        let center = xz(self.position);
+        match self.shape {
+            ObstacleShape::Cylinder { radius, .. } => (center, radius),
+            ObstacleShape::Box { half_extents } => {
+                let half_extents = xz(half_extents);
+                let closest = agent_position
+                    .sup(&(center - half_extents))
+                    .inf(&(center + half_extents));
+                if closest == agent_position {
+                    // The agent is inside the box.
+                    (center, half_extents.norm())
+                } else {
+                    (closest, 0.0)
+                }
+            }
+        }
+    }
+}
+
+/// An agent of a crowd. See module docs for more info.
+#[derive(Visit, Clone, Debug)]
+pub struct CrowdAgent {
+    agent: NavmeshAgent,
+    velocity: Vector3<f32>,
+    radius: f32,
+    height: f32,
+    #[visit(skip)]
+    new_velocity: Vector2<f32>,
+}
+
+impl Default for CrowdAgent {
+    fn default() -> Self {
+        Self::new(Default::default(), 0.4)
+    }
+}
+
+impl CrowdAgent {
+    /// Creates new crowd agent of the given radius. The agent will follow the path of the given
+    /// navmesh agent, maximum speed of the crowd agent is equal to the speed of the navmesh agent.
+    pub fn new(agent: NavmeshAgent, radius: f32) -> Self {
+        Self {
+            agent,
+            velocity: Default::default(),
+            radius,
+            height: 2.0,
+            new_velocity: Default::default(),
+        }
+    }
+
+    /// Returns a reference to the inner navmesh agent.
+    pub fn agent(&self) -> &NavmeshAgent {
+        &self.agent
+    }
+
+    /// Returns a reference to the inner navmesh agent. It could be used to change target, speed or
+    /// to teleport the agent.
+    pub fn agent_mut(&mut self) -> &mut NavmeshAgent {
+        &mut self.agent
+    }
+
+    /// Returns current position of the agent.
+    pub fn position(&self) -> Vector3<f32> {
+        self.agent.position
+    }
+
+    /// Returns current velocity of the agent.
+    pub fn velocity(&self) -> Vector3<f32> {
+        self.velocity
+    }
+
+    /// Sets new radius of the agent.
+    pub fn set_radius(&mut self, radius: f32) {
+        self.radius = radius.max(0.0);
+    }
+
+    /// Returns current radius of the agent.
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    /// Sets new height of the agent. Height is used to ignore agents and obstacles, that are above
+    /// or below the agent (for example on another floor). Position of the agent is at its feet.
+    pub fn set_height(&mut self, height: f32) {
+        self.height = height.max(0.0);
+    }
+
+    /// Returns current height of the agent.
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
+    fn overlaps_vertically(&self, min: f32, max: f32) -> bool {
+        let y = self.agent.position.y;
+        y <= max && y + self.height >= min
+    }
+
+    /// Calculates velocity that moves the agent along its path.
+    fn preferred_velocity(&mut self, dt: f32) -> Vector2<f32> {
+        let agent = &mut self.agent;
+        let position = xz(agent.position);
+
+        // Skip path points that were already reached. Off-mesh links are never skipped, they're
+        // traversed by the agent itself.
+        let arrival_distance = self.radius.max(0.1);
+        while agent.current_link().is_none()
+            && (agent.current as usize + 2) < agent.path.len()
+            && (xz(agent.path[agent.current as usize + 1]) - position).norm() <= arrival_distance
+        {
+            agent.current += 1;
+        }
+
+        if let Some(target) = agent.steering_target() {
+            let delta = xz(target) - position;
+            let distance = delta.norm();
+            if distance > f32::EPSILON {
+                // Slow down at the end of the path to not overshoot it.
+                return delta.scale(agent.speed.min(distance / dt.max(f32::EPSILON)) / distance);
+            }
+        }
+
+        Vector2::default()
+    }
+}
+
+fn xz(v: Vector3<f32>) -> Vector2<f32> {
+    Vector2::new(v.x, v.z)
+}
+
+fn det(a: Vector2<f32>, b: Vector2<f32>) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+fn distance_to_segment(point: Vector2<f32>, a: Vector2<f32>, b: Vector2<f32>) -> f32 {
+    let edge = b - a;
+    let t = (point - a).dot(&edge) / edge.norm_squared().max(f32::EPSILON);
+    (a + edge.scale(t.clamp(0.0, 1.0)) - point).norm()
+}
+
+fn distance_to_triangle(point: Vector2<f32>, triangle: &[Vector2<f32>; 3]) -> f32 {
+    let d0 = det(triangle[1] - triangle[0], point - triangle[0]);
+    let d1 = det(triangle[2] - triangle[1], point - triangle[1]);
+    let d2 = det(triangle[0] - triangle[2], point - triangle[2]);
+    if (d0 >= 0.0 && d1 >= 0.0 && d2 >= 0.0) || (d0 <= 0.0 && d1 <= 0.0 && d2 <= 0.0) {
+        // The point is inside the triangle.
+        0.0
+    } else {
+        distance_to_segment(point, triangle[0], triangle[1])
+            .min(distance_to_segment(point, triangle[1], triangle[2]))
+            .min(distance_to_segment(point, triangle[2], triangle[0]))
+    }
+}
+
+/// Boundary of a half-plane of permitted velocities, permitted velocities are on the left side of
+/// the line.
+#[derive(Copy, Clone, Debug)]
+struct Line {
+    point: Vector2<f32>,
+    direction: Vector2<f32>,
+}
+
+const RVO_EPSILON: f32 = 0.00001;
+
+/// Creates ORCA half-plane for a pair of objects. `responsibility` defines how much of the
+/// avoidance is performed by the agent (0.5 for agents, 1.0 for obstacles).
+#[allow(clippy::too_many_arguments)]
+fn orca_line(
+    position: Vector2<f32>,
+    velocity: Vector2<f32>,
+    other_position: Vector2<f32>,
+    other_velocity: Vector2<f32>,
+    combined_radius: f32,
+    time_horizon: f32,
+    dt: f32,
+    responsibility: f32,
+) -> Line {
+    let relative_position = other_position - position;
+    let relative_velocity = velocity - other_velocity;
+    let distance_sq = relative_position.norm_squared();
+    let combined_radius_sq = combined_radius * combined_radius;
+
+    let direction;
+    let u;
+
+    if distance_sq > combined_radius_sq {
+        // No collision.
+        let inv_time_horizon = 1.0 / time_horizon;
+        // Vector from cutoff center to relative velocity.
+        let w = relative_velocity - relative_position.scale(inv_time_horizon);
+        let w_length_sq = w.norm_squared();
+        let dot = w.dot(&relative_position);
+
+        if dot < 0.0 && dot * dot > combined_radius_sq * w_length_sq {
+            // Project on cut-off circle.
+            let w_length = w_length_sq.sqrt();
+            let unit_w = w.scale(1.0 / w_length);
+            direction = Vector2::new(unit_w.y, -unit_w.x);
+            u = unit_w.scale(combined_radius * inv_time_horizon - w_length);
+        } else {
+            // Project on legs.
+            let leg = (distance_sq - combined_radius_sq).sqrt();
+            direction = if det(relative_position, w) > 0.0 {
+                // Left leg.
+                Vector2::new(
+                    relative_position.x * leg - relative_position.y * combined_radius,
+                    relative_position.x * combined_radius + relative_position.y * leg,
+                )
+                .scale(1.0 / distance_sq)
+            } else {
+                // Right leg.
+                -Vector2::new(
+                    relative_position.x * leg + relative_position.y * combined_radius,
+                    -relative_position.x * combined_radius + relative_position.y * leg,
+                )
+                .scale(1.0 / distance_sq)
+            };
+            u = direction.scale(relative_velocity.dot(&direction)) - relative_velocity;
+        }
+    } else {
+        // Collision. Project on cut-off circle of time step.
+        let inv_time_step = 1.0 / dt;
+        let w = relative_velocity - relative_position.scale(inv_time_step);
+        let w_length = w.norm();
+        let unit_w = if w_length > RVO_EPSILON {
+            w.scale(1.0 / w_length)
+        } else {
+            Vector2::x()
+        };
+        direction = Vector2::new(unit_w.y, -unit_w.x);
+        u = unit_w.scale(combined_radius * inv_time_step - w_length);
+    }
+
+    Line {
+        point: velocity + u.scale(responsibility),
+        direction,
+    }
+}
+
+/// Solves one-dimensional linear program on a specified line subject to linear constraints
+/// defined by lines and a circular constraint.
+fn linear_program1(
+    lines: &[Line],
+    line_no: usize,
+    radius: f32,
+    opt_velocity: Vector2<f32>,
+    direction_opt: bool,
+    result: &mut Vector2<f32>,
+) -> bool {
+    let line = lines[line_no];
+    let dot = line.point.dot(&line.direction);
+    let discriminant = dot * dot + radius * radius - line.point.norm_squared();
+
+    if discriminant < 0.0 {
+        // Max speed circle fully invalidates the line.
+        return false;
+    }
+
+    let sqrt_discriminant = discriminant.sqrt();
+    let mut t_left = -dot - sqrt_discriminant;
+    let mut t_right = -dot + sqrt_discriminant;
+
+    for other in &lines[..line_no] {
+        let denominator = det(line.direction, other.direction);
+        let numerator = det(other.direction, line.point - other.point);
+
+        if denominator.abs() <= RVO_EPSILON {
+            // Lines are (almost) parallel.
+            if numerator < 0.0 {
+                return false;
+            }
+            continue;
+        }
+
+        let t = numerator / denominator;
+        if denominator >= 0.0 {
+            // Line bounds the line on the right.
+            t_right = t_right.min(t);
+        } else {
+            // Line bounds the line on the left.
+            t_left = t_left.max(t);
+        }
+
+        if t_left > t_right {
+            return false;
+        }
+    }
+
+    let t = if direction_opt {
+        // Optimize direction.
+        if opt_velocity.dot(&line.direction) > 0.0 {
+            t_right
+        } else {
+            t_left
+        }
+    } else {
+        // Optimize closest point.
+        line.direction
+            .dot(&(opt_velocity - line.point))
+            .clamp(t_left, t_right)
+    };
+
+    *result = line.point + line.direction.scale(t);
+
+    true
+}
+
+/// Solves two-dimensional linear program subject to linear constraints defined by lines and a
+/// circular constraint. Returns the number of the line it fails on, or the number of lines if
+/// successful.
+fn linear_program2(
+    lines: &[Line],
+    radius: f32,
+    opt_velocity: Vector2<f32>,
+    direction_opt: bool,
+    result: &mut Vector2<f32>,
+) -> usize {
+    *result = if direction_opt {
+        // Optimize direction, the velocity is a unit vector in this case.
+        opt_velocity.scale(radius)
+    } else if opt_velocity.norm_squared() > radius * radius {
+        // Optimize closest point and outside circle.
+        opt_velocity.normalize().scale(radius)
+    } else {
+        // Optimize closest point and inside circle.
+        opt_velocity
+    };
+
+    for (i, line) in lines.iter().enumerate() {
+        if det(line.direction, line.point - *result) > 0.0 {
+            // Result does not satisfy constraint i. Compute new optimal result.
+            let previous = *result;
+            if !linear_program1(lines, i, radius, opt_velocity, direction_opt, result) {
+                *result = previous;
+                return i;
+            }
+        }
+    }
+
+    lines.len()
+}
+
+/// Solves three-dimensional linear program subject to linear constraints defined by lines and a
+/// circular constraint. It is used when the two-dimensional program is infeasible, first
+/// `obstacle_lines` lines are never relaxed.
+fn linear_program3(
+    lines: &[Line],
+    obstacle_lines: usize,
+    begin_line: usize,
+    radius: f32,
+    result: &mut Vector2<f32>,
+) {
+    let mut distance = 0.0;
+
+    for (i, line) in lines.iter().enumerate().skip(begin_line) {
+        if det(line.direction, line.point - *result) > distance {
+            // Result does not satisfy constraint of line i.
+            let mut projected_lines = lines[..obstacle_lines].to_vec();
+
+            for other in &lines[obstacle_lines..i] {
+                let determinant = det(line.direction, other.direction);
+
+                let point = if determinant.abs() <= RVO_EPSILON {
+                    if line.direction.dot(&other.direction) > 0.0 {
+                        // Lines are in the same direction.
+                        continue;
+                    }
+                    // Lines are in opposite direction.
+                    (line.point + other.point).scale(0.5)
+                } else {
+                    line.point
+                        + line
+                            .direction
+                            .scale(det(other.direction, line.point - other.point) / determinant)
+                };
+
+                projected_lines.push(Line {
+                    point,
+                    direction: (other.direction - line.direction).normalize(),
+                });
+            }
+
+            let previous = *result;
+            if linear_program2(
+                &projected_lines,
+                radius,
+                Vector2::new(-line.direction.y, line.direction.x),
+                true,
+                result,
+            ) < projected_lines.len()
+            {
+                // This should in principle not happen. The result is by definition already in the
+                // feasible region of this linear program. If it fails, it is due to small
+                // floating point error, and the current result is kept.
+                *result = previous;
+            }
+
+            distance = det(line.direction, line.point - *result);
+        }
+    }
+}
+
+fn project_on_navmesh(navmesh: &Navmesh, position: Vector3<f32>) -> Option<Vector3<f32>> {
+    navmesh
+        .ray_cast(Ray::new(
+            position + Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, -10.0, 0.0),
+        ))
+        .map(|(point, _, _)| point)
+}
+
+/// See module docs.
+#[derive(Visit, Clone, Debug)]
+pub struct Crowd {
+    agents: Pool<CrowdAgent>,
+    obstacles: Pool<DynamicObstacle>,
+    time_horizon: f32,
+    obstacle_time_horizon: f32,
+    neighbor_distance: f32,
+    max_neighbors: u32,
+}
+
+impl Default for Crowd {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Crowd {
+    /// Creates new empty crowd.
+    pub fn new() -> Self {
+        Self {
+            agents: Default::default(),
+            obstacles: Default::default(),
+            time_horizon: 2.0,
+            obstacle_time_horizon: 1.0,
+            neighbor_distance: 5.0,
+            max_neighbors: 10,
+        }
+    }
+
+    /// Adds new agent to the crowd and returns its handle.
+    pub fn add_agent(&mut self, agent: CrowdAgent) -> Handle<CrowdAgent> {
+        self.agents.spawn(agent)
+    }
+
+    /// Removes an agent from the crowd and returns it.
+    pub fn remove_agent(&mut self, handle: Handle<CrowdAgent>) -> CrowdAgent {
+        self.agents.free(handle)
+    }
+
+    /// Tries to borrow an agent by its handle.
+    pub fn agent(&self, handle: Handle<CrowdAgent>) -> Option<&CrowdAgent> {
+        self.agents.try_borrow(handle)
+    }
+
+    /// Tries to borrow an agent by its handle.
+    pub fn agent_mut(&mut self, handle: Handle<CrowdAgent>) -> Option<&mut CrowdAgent> {
+        self.agents.try_borrow_mut(handle)
+    }
+
+    /// Returns an iterator over every agent of the crowd.
+    pub fn agents(&self) -> impl Iterator<Item = &CrowdAgent> {
+        self.agents.iter()
+    }
+
+    /// Returns an iterator over every agent of the crowd.
+    pub fn agents_mut(&mut self) -> impl Iterator<Item = &mut CrowdAgent> {
+        self.agents.iter_mut()
+    }
+
+    /// Adds new dynamic obstacle and returns its handle.
+    pub fn add_obstacle(&mut self, obstacle: DynamicObstacle) -> Handle<DynamicObstacle> {
+        self.obstacles.spawn(obstacle)
+    }
+
+    /// Removes a dynamic obstacle and returns it.
+    pub fn remove_obstacle(&mut self, handle: Handle<DynamicObstacle>) -> DynamicObstacle {
+        self.obstacles.free(handle)
+    }
+
+    /// Tries to borrow a dynamic obstacle by its handle. Obstacles could be moved freely.
+    pub fn obstacle(&self, handle: Handle<DynamicObstacle>) -> Option<&DynamicObstacle> {
+        self.obstacles.try_borrow(handle)
+    }
+
+    /// Tries to borrow a dynamic obstacle by its handle. Obstacles could be moved freely.
+    pub fn obstacle_mut(
+        &mut self,
+        handle: Handle<DynamicObstacle>,
+    ) -> Option<&mut DynamicObstacle> {
+        self.obstacles.try_borrow_mut(handle)
+    }
+
+    /// Returns an iterator over every dynamic obstacle.
+    pub fn obstacles(&self) -> impl Iterator<Item = &DynamicObstacle> {
+        self.obstacles.iter()
+    }
+
+    /// Sets time (in seconds) for which agents must be safe from collisions with other agents. The
+    /// larger the value, the sooner agents will respond to other agents, but the less freedom they
+    /// have in choosing their velocities. Default value is 2.0 seconds.
+    pub fn set_time_horizon(&mut self, time_horizon: f32) {
+        self.time_horizon = time_horizon.max(f32::EPSILON);
+    }
+
+    /// Returns current time horizon of agent-agent avoidance.
+    pub fn time_horizon(&self) -> f32 {
+        self.time_horizon
+    }
+
+    /// Sets time (in seconds) for which agents must be safe from collisions with dynamic
+    /// obstacles. Default value is 1.0 second.
+    pub fn set_obstacle_time_horizon(&mut self, time_horizon: f32) {
+        self.obstacle_time_horizon = time_horizon.max(f32::EPSILON);
+    }
+
+    /// Returns current time horizon of agent-obstacle avoidance.
+    pub fn obstacle_time_horizon(&self) -> f32 {
+        self.obstacle_time_horizon
+    }
+
+    /// Sets maximum distance (in meters) at which an agent takes other agents into account.
+    /// Default value is 5.0 meters.
+    pub fn set_neighbor_distance(&mut self, distance: f32) {
+        self.neighbor_distance = distance.max(0.0);
+    }
+
+    /// Returns current neighbor distance.
+    pub fn neighbor_distance(&self) -> f32 {
+        self.neighbor_distance
+    }
+
+    /// Sets maximum amount of the closest agents that an agent takes into account. Default value
+    /// is 10.
+    pub fn set_max_neighbors(&mut self, max_neighbors: u32) {
+        self.max_neighbors = max_neighbors;
+    }
+
+    /// Returns maximum amount of neighbors.
+    pub fn max_neighbors(&self) -> u32 {
+        self.max_neighbors
+    }
+
+    /// Performs single update tick that moves every agent of the crowd to its target along its
+    /// path, while avoiding other agents and dynamic obstacles.
+    pub fn update(&mut self, dt: f32, navmesh: &mut Navmesh) {
+        if dt <= 0.0 {
+            return;
+        }
+
+        for agent in self.agents.iter_mut() {
+            if agent.agent.path_dirty {
+                // Agent stays in place if there is no path.
+                let _ =
+                    agent
+                        .agent
+                        .calculate_path(navmesh, agent.agent.position, agent.agent.target);
+                agent.agent.path_dirty = false;
+            }
+        }
+
+        // Compute new velocities using the current state of the crowd.
+        let handles = self.agents.pair_iter().map(|(h, _)| h).collect::<Vec<_>>();
+        let mut lines = Vec::new();
+        let mut neighbors = Vec::new();
+        for &handle in handles.iter() {
+            let agent = &self.agents[handle];
+            if agent.agent.current_link().is_some() {
+                continue;
+            }
+            let position = xz(agent.agent.position);
+            let velocity = xz(agent.velocity);
+
+            lines.clear();
+
+            // Obstacle lines go first, they must not be relaxed.
+            for obstacle in self.obstacles.iter() {
+                let (min, max) = obstacle.vertical_range();
+                if !agent.overlaps_vertically(min, max) {
+                    continue;
+                }
+
+                let (center, radius) = obstacle.avoidance_circle(position);
+                let combined_radius = agent.radius + radius;
+                let reach = combined_radius + agent.agent.speed * self.obstacle_time_horizon;
+                if (center - position).norm_squared() <= reach * reach {
+                    lines.push(orca_line(
+                        position,
+                        velocity,
+                        center,
+                        Vector2::default(),
+                        combined_radius,
+                        self.obstacle_time_horizon,
+                        dt,
+                        1.0,
+                    ));
+                }
+            }
+            let obstacle_lines = lines.len();
+
+            neighbors.clear();
+            for (other_handle, other) in self.agents.pair_iter() {
+                if other_handle == handle
+                    || !agent.overlaps_vertically(
+                        other.agent.position.y,
+                        other.agent.position.y + other.height,
+                    )
+                {
+                    continue;
+                }
+                let distance_sq = (xz(other.agent.position) - position).norm_squared();
+                if distance_sq <= self.neighbor_distance * self.neighbor_distance {
+                    neighbors.push((distance_sq, other_handle));
+                }
+            }
+            neighbors.sort_by(|a, b| a.0.total_cmp(&b.0));
+            neighbors.truncate(self.max_neighbors as usize);
+
+            for (_, other_handle) in neighbors.iter() {
+                let other = &self.agents[*other_handle];
+                lines.push(orca_line(
+                    position,
+                    velocity,
+                    xz(other.agent.position),
+                    xz(other.velocity),
+                    agent.radius + other.radius,
+                    self.time_horizon,
+                    dt,
+                    0.5,
+                ));
+            }
+
+            let agent = &mut self.agents[handle];
+            let preferred_velocity = agent.preferred_velocity(dt);
+            let max_speed = agent.agent.speed;
+            let mut new_velocity = Vector2::default();
+            let failed_line = linear_program2(
+                &lines,
+                max_speed,
+                preferred_velocity,
+                false,
+                &mut new_velocity,
+            );
+            if failed_line < lines.len() {
+                linear_program3(
+                    &lines,
+                    obstacle_lines,
+                    failed_line,
+                    max_speed,
+                    &mut new_velocity,
+                );
+            }
+            agent.new_velocity = new_velocity;
+        }
+
+        // Move agents, keep them on the navmesh.
+        for agent in self.agents.iter_mut() {
+            let position = agent.agent.position;
+
+            if agent.agent.current_link().is_some() {
+                // Agents on off-mesh links ignore avoidance and the navmesh.
+                agent.agent.follow_path(dt);
+                agent.velocity = (agent.agent.position - position).scale(1.0 / dt);
+                continue;
+            }
+
+            let offset = agent.new_velocity.scale(dt);
+            let new_position = [
+                Vector3::new(offset.x, 0.0, offset.y),
+                // Slide along the border of the navmesh.
+                Vector3::new(offset.x, 0.0, 0.0),
+                Vector3::new(0.0, 0.0, offset.y),
+            ]
+            .iter()
+            .find_map(|offset| project_on_navmesh(navmesh, position + offset));
+
+            match new_position {
+                Some(new_position) => {
+                    agent.velocity = (new_position - position).scale(1.0 / dt);
+                    agent.agent.position = new_position;
+                }
+                None => agent.velocity = Vector3::default(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector3, math::TriangleDefinition},
+        utils::navmesh::{
+            crowd::{Crowd, CrowdAgent, DynamicObstacle, ObstacleShape},
+            Navmesh, NavmeshAgentBuilder,
+        },
+    };
+
+    fn make_navmesh() -> Navmesh {
+        Navmesh::new(
+            &[TriangleDefinition([0, 1, 2])],
+            &[
+                Vector3::new(-100.0, 0.0, -100.0),
+                Vector3::new(100.0, 0.0, -100.0),
+                Vector3::new(0.0, 0.0, 100.0),
+            ],
+        )
+    }
+
+    fn make_agent(from: Vector3<f32>, to: Vector3<f32>) -> CrowdAgent {
+        CrowdAgent::new(
+            NavmeshAgentBuilder::new()
+                .with_position(from)
+                .with_target(to)
+                .build(),
+            0.5,
+        )
+    }
+
+    #[test]
+    fn test_agents_avoidance() {
+        let mut navmesh = make_navmesh();
+        let mut crowd = Crowd::new();
+        let a = crowd.add_agent(make_agent(
+            Vector3::new(-5.0, 0.0, 0.0),
+            Vector3::new(5.0, 0.0, 0.0),
+        ));
+        let b = crowd.add_agent(make_agent(
+            Vector3::new(5.0, 0.0, 0.1),
+            Vector3::new(-5.0, 0.0, 0.1),
+        ));
+
+        for _ in 0..1200 {
+            crowd.update(1.0 / 60.0, &mut navmesh);
+            let distance = crowd
+                .agent(a)
+                .unwrap()
+                .position()
+                .metric_distance(&crowd.agent(b).unwrap().position());
+            assert!(distance >= 0.95);
+        }
+
+        for agent in crowd.agents() {
+            assert!(agent.position().metric_distance(&agent.agent().target()) < 0.1);
+        }
+    }
+
+    #[test]
+    fn test_obstacle_avoidance() {
+        let mut navmesh = make_navmesh();
+        let mut crowd = Crowd::new();
+        let a = crowd.add_agent(make_agent(
+            Vector3::new(-5.0, 0.0, 0.0),
+            Vector3::new(5.0, 0.0, 0.0),
+        ));
+        let obstacle = crowd.add_obstacle(DynamicObstacle::new(
+            Vector3::new(0.0, 0.0, 0.1),
+            ObstacleShape::Cylinder {
+                radius: 1.0,
+                half_height: 1.0,
+            },
+        ));
+
+        for _ in 0..1200 {
+            crowd.update(1.0 / 60.0, &mut navmesh);
+            let distance = crowd
+                .agent(a)
+                .unwrap()
+                .position()
+                .metric_distance(&crowd.obstacle(obstacle).unwrap().position);
+            assert!(distance >= 1.45);
+        }
+
+        let agent = crowd.agent(a).unwrap();
+        assert!(agent.position().metric_distance(&agent.agent().target()) < 0.1);
+    }
+}
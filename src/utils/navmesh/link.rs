@@ -0,0 +1,101 @@
+//! Off-mesh links connect two distant points of a navigation mesh, that cannot be connected by
+//! triangles - jumps over gaps, ladders, teleports, etc. Links are added to a navigation mesh
+//! using [`super::Navmesh::add_link`] and then path finding treats them as any other edge of the
+//! navigation mesh. Agents report links they are traversing (see
+//! [`super::NavmeshAgent::current_link`]), so animation (or any other game logic) can react to it.
+
+use crate::{
+    core::{algebra::Vector3, pool::Handle, visitor::prelude::*},
+    utils::navmesh::area::NavmeshArea,
+};
+
+/// Kind of an off-mesh link. Kind does not affect path finding, it is used to tell the game what
+/// kind of movement should be performed. The only exception is [`OffMeshLinkKind::Teleport`] -
+/// agents move to the end of such links instantly.
+#[derive(Visit, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OffMeshLinkKind {
+    /// A jump over a gap or down from a ledge.
+    Jump,
+    /// A ladder.
+    Ladder,
+    /// A teleport.
+    Teleport,
+    /// User-defined kind.
+    Custom(u32),
+}
+
+impl Default for OffMeshLinkKind {
+    fn default() -> Self {
+        Self::Jump
+    }
+}
+
+/// See module docs.
+#[derive(Visit, Clone, Debug, PartialEq)]
+pub struct OffMeshLink {
+    /// Start point of the link.
+    pub start: Vector3<f32>,
+    /// End point of the link.
+    pub end: Vector3<f32>,
+    /// If true, the link could be traversed from end to start as well.
+    pub bidirectional: bool,
+    /// Kind of the link.
+    pub kind: OffMeshLinkKind,
+    /// Area type of the link, it is used by query filters the same way as areas of triangles.
+    pub area: NavmeshArea,
+    /// Additional cost multiplier of the link.
+    pub cost: f32,
+}
+
+impl Default for OffMeshLink {
+    fn default() -> Self {
+        Self::new(Default::default(), Default::default(), Default::default())
+    }
+}
+
+impl OffMeshLink {
+    /// Creates new bidirectional link of the given kind between two points.
+    pub fn new(start: Vector3<f32>, end: Vector3<f32>, kind: OffMeshLinkKind) -> Self {
+        Self {
+            start,
+            end,
+            bidirectional: true,
+            kind,
+            area: NavmeshArea::WALKABLE,
+            cost: 1.0,
+        }
+    }
+
+    /// Sets whether the link could be traversed from end to start or not.
+    pub fn with_bidirectional(mut self, bidirectional: bool) -> Self {
+        self.bidirectional = bidirectional;
+        self
+    }
+
+    /// Sets area type of the link.
+    pub fn with_area(mut self, area: NavmeshArea) -> Self {
+        self.area = area;
+        self
+    }
+
+    /// Sets additional cost multiplier of the link.
+    pub fn with_cost(mut self, cost: f32) -> Self {
+        self.cost = cost;
+        self
+    }
+}
+
+/// Off-mesh link that is a part of a path of an agent.
+#[derive(Visit, Clone, Debug, PartialEq, Default)]
+pub struct PathLink {
+    /// Index of the path point at which the link begins, the link ends at the next path point.
+    pub segment: u32,
+    /// Handle of the link in the navigation mesh.
+    pub link: Handle<OffMeshLink>,
+    /// Kind of the link.
+    pub kind: OffMeshLinkKind,
+    /// A point at which the agent enters the link.
+    pub start: Vector3<f32>,
+    /// A point at which the agent leaves the link.
+    pub end: Vector3<f32>,
+}
@@ -0,0 +1,1363 @@
+//! Contains all structures and methods to create and manage navigation meshes (navmesh).
+//!
+//! Navigation mesh is a set of convex polygons which is used for path finding in complex
+//! environment. Navigation mesh could be created from a set of pre-authored triangles or it could be
+//! baked automatically from scene geometry, see [`bake`] module docs for more info. Triangles could
+//! have different area types with per-agent traversal costs (see [`area`] module docs), distant
+//! parts of a navigation mesh could be connected by off-mesh links (see [`link`] module docs).
+
+#![warn(missing_docs)]
+
+use crate::{
+    core::{
+        algebra::{Point3, Vector3},
+        arrayvec::ArrayVec,
+        math::{self, aabb::AxisAlignedBoundingBox, ray::Ray, TriangleDefinition},
+        octree::{Octree, OctreeNode},
+        pool::{Handle, Pool},
+        reflect::{blank_reflect, prelude::*},
+        visitor::{Visit, VisitError, VisitResult, Visitor},
+    },
+    scene::mesh::{
+        buffer::{VertexAttributeUsage, VertexReadTrait},
+        Mesh,
+    },
+    utils::{
+        astar::{PathError, PathFinder, PathKind, PathVertex},
+        navmesh::{
+            area::{NavmeshArea, NavmeshQueryFilter},
+            crowd::DynamicObstacle,
+            link::{OffMeshLink, OffMeshLinkKind, PathLink},
+        },
+        raw_mesh::{RawMeshBuilder, RawVertex},
+    },
+};
+use fxhash::FxHashMap;
+use std::{
+    any::Any,
+    hash::{Hash, Hasher},
+};
+
+pub mod area;
+pub mod bake;
+pub mod crowd;
+pub mod link;
+
+/// See module docs.
+#[derive(Clone, Debug, Default)]
+pub struct Navmesh {
+    octree: Octree,
+    triangles: Vec<TriangleDefinition>,
+    pathfinder: PathFinder,
+    query_buffer: Vec<u32>,
+    areas: Vec<NavmeshArea>,
+    links: Pool<OffMeshLink>,
+    // Path finder contains vertices of the mesh, followed by a pair of vertices for each off-mesh
+    // link. Vertices of links are not serialized and created again on load.
+    mesh_vertex_count: usize,
+    edge_triangles: FxHashMap<Edge, Vec<u32>>,
+    link_vertices: Vec<LinkVertices>,
+    // Amount of carves that cover a triangle, triangles with non-zero count are excluded from the
+    // octree and the path finder graph.
+    carve_counts: Vec<u32>,
+    carves: Pool<NavmeshCarve>,
+}
+
+// Navmesh is a large chunk of data that is not meant to be edited via reflection.
+impl Reflect for Navmesh {
+    blank_reflect!();
+}
+
+// Only persistent data is compared, carves are runtime-only.
+impl PartialEq for Navmesh {
+    fn eq(&self, other: &Self) -> bool {
+        self.triangles == other.triangles
+            && self.areas == other.areas
+            && self.links == other.links
+            && self.vertices().len() == other.vertices().len()
+            && self
+                .vertices()
+                .iter()
+                .zip(other.vertices())
+                .all(|(a, b)| a.position == b.position)
+    }
+}
+
+/// A hole in a navigation mesh made by an obstacle, see [`Navmesh::carve`] for more info.
+#[derive(Clone, Debug, Default)]
+pub struct NavmeshCarve {
+    obstacle: DynamicObstacle,
+    triangles: Vec<u32>,
+}
+
+impl NavmeshCarve {
+    /// Returns the obstacle that made the hole.
+    pub fn obstacle(&self) -> &DynamicObstacle {
+        &self.obstacle
+    }
+
+    /// Returns indices of the triangles that were cut out by the obstacle.
+    pub fn triangles(&self) -> &[u32] {
+        &self.triangles
+    }
+}
+
+#[derive(Clone, Debug)]
+struct LinkVertices {
+    link: Handle<OffMeshLink>,
+    // Indices of triangles to which start and end of the link are attached.
+    triangles: [Option<usize>; 2],
+}
+
+// Visits a field that could be missing in older files, any other error is propagated.
+fn visit_optional<T: Visit>(value: &mut T, name: &str, visitor: &mut Visitor) -> VisitResult {
+    match value.visit(name, visitor) {
+        Err(VisitError::RegionDoesNotExist(region)) if visitor.is_reading() && region == name => {
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+impl Visit for Navmesh {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut region = visitor.enter_region(name)?;
+
+        if region.is_reading() {
+            self.pathfinder.visit("PathFinder", &mut region)?;
+        } else {
+            let mut pathfinder = self.pathfinder.clone();
+            pathfinder.truncate(self.mesh_vertex_count);
+            pathfinder.visit("PathFinder", &mut region)?;
+        }
+        self.triangles.visit("Triangles", &mut region)?;
+        visit_optional(&mut self.areas, "Areas", &mut region)?;
+        visit_optional(&mut self.links, "Links", &mut region)?;
+
+        drop(region);
+
+        // No need to save octree, we can restore it on load.
+        if visitor.is_reading() {
+            self.mesh_vertex_count = self.pathfinder.vertices().len();
+            self.areas
+                .resize(self.triangles.len(), NavmeshArea::WALKABLE);
+            self.edge_triangles = build_edge_triangles(&self.triangles);
+            self.carve_counts = vec![0; self.triangles.len()];
+
+            self.rebuild_octree();
+            self.rebuild_links();
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Edge {
+    a: u32,
+    b: u32,
+}
+
+impl PartialEq for Edge {
+    fn eq(&self, other: &Self) -> bool {
+        // Direction-agnostic compare.
+        (self.a == other.a && self.b == other.b) || (self.a == other.b && self.b == other.a)
+    }
+}
+
+impl Eq for Edge {}
+
+impl Hash for Edge {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Direction-agnostic hash.
+        (self.a as u64 + self.b as u64).hash(state)
+    }
+}
+
+fn build_edge_triangles(triangles: &[TriangleDefinition]) -> FxHashMap<Edge, Vec<u32>> {
+    let mut edge_triangles = FxHashMap::<Edge, Vec<u32>>::default();
+    for (index, triangle) in triangles.iter().enumerate() {
+        for (a, b) in [(0, 1), (1, 2), (2, 0)] {
+            edge_triangles
+                .entry(Edge {
+                    a: triangle[a],
+                    b: triangle[b],
+                })
+                .or_default()
+                .push(index as u32);
+        }
+    }
+    edge_triangles
+}
+
+impl Navmesh {
+    /// Creates new navigation mesh from given set of triangles and vertices. This is
+    /// low level method that allows to specify triangles and vertices directly. In
+    /// most cases you should use `from_mesh` method.
+    pub fn new(triangles: &[TriangleDefinition], vertices: &[Vector3<f32>]) -> Self {
+        // Build triangles for octree.
+        let raw_triangles = triangles
+            .iter()
+            .map(|t| {
+                [
+                    vertices[t[0] as usize],
+                    vertices[t[1] as usize],
+                    vertices[t[2] as usize],
+                ]
+            })
+            .collect::<Vec<[Vector3<f32>; 3]>>();
+
+        // Fill in pathfinder.
+        let mut pathfinder = PathFinder::new();
+        pathfinder.set_vertices(vertices.iter().map(|v| PathVertex::new(*v)).collect());
+
+        let edge_triangles = build_edge_triangles(triangles);
+        for edge in edge_triangles.keys() {
+            pathfinder.link_bidirect(edge.a as usize, edge.b as usize);
+        }
+
+        Self {
+            triangles: triangles.to_vec(),
+            octree: Octree::new(&raw_triangles, 32),
+            pathfinder,
+            query_buffer: Default::default(),
+            areas: vec![NavmeshArea::WALKABLE; triangles.len()],
+            links: Default::default(),
+            mesh_vertex_count: vertices.len(),
+            edge_triangles,
+            link_vertices: Default::default(),
+            carve_counts: vec![0; triangles.len()],
+            carves: Default::default(),
+        }
+    }
+
+    /// Creates new navigation mesh (navmesh) from given mesh. It is most simple way to create complex
+    /// navigation mesh, it should be used in pair with model loading functionality - you can
+    /// load model from file and turn it into navigation mesh, or even build navigation mesh
+    /// from a model in existing scene. This method "eats" any kind of meshes with any amount
+    /// of surfaces - it joins all surfaces into single mesh and creates navmesh from it.
+    ///
+    /// Example:
+    /// ```
+    /// use fyrox::scene::Scene;
+    /// use fyrox::utils::navmesh::Navmesh;
+    ///
+    /// fn make_navmesh(scene: &Scene, navmesh_name: &str) -> Navmesh {
+    ///     // Find mesh node in existing scene and create navigation mesh from it.
+    ///     let navmesh_node_handle = scene.graph.find_by_name_from_root(navmesh_name).unwrap().0;
+    ///     Navmesh::from_mesh(scene.graph[navmesh_node_handle].as_mesh())
+    /// }
+    /// ```
+    pub fn from_mesh(mesh: &Mesh) -> Self {
+        // Join surfaces into one simple mesh.
+        let mut builder = RawMeshBuilder::<RawVertex>::default();
+        let global_transform = mesh.global_transform();
+        for surface in mesh.surfaces() {
+            let shared_data = surface.data();
+            let shared_data = shared_data.lock();
+
+            let vertex_buffer = &shared_data.vertex_buffer;
+            for triangle in shared_data.geometry_buffer.iter() {
+                builder.insert(RawVertex::from(
+                    global_transform
+                        .transform_point(&Point3::from(
+                            vertex_buffer
+                                .get(triangle[0] as usize)
+                                .unwrap()
+                                .read_3_f32(VertexAttributeUsage::Position)
+                                .unwrap(),
+                        ))
+                        .coords,
+                ));
+                builder.insert(RawVertex::from(
+                    global_transform
+                        .transform_point(&Point3::from(
+                            vertex_buffer
+                                .get(triangle[1] as usize)
+                                .unwrap()
+                                .read_3_f32(VertexAttributeUsage::Position)
+                                .unwrap(),
+                        ))
+                        .coords,
+                ));
+                builder.insert(RawVertex::from(
+                    global_transform
+                        .transform_point(&Point3::from(
+                            vertex_buffer
+                                .get(triangle[2] as usize)
+                                .unwrap()
+                                .read_3_f32(VertexAttributeUsage::Position)
+                                .unwrap(),
+                        ))
+                        .coords,
+                ));
+            }
+        }
+
+        let mesh = builder.build();
+
+        Navmesh::new(
+            &mesh.triangles,
+            &mesh
+                .vertices
+                .into_iter()
+                .map(|v| Vector3::new(v.x, v.y, v.z))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Searches closest graph vertex to given point. Returns Some(index), or None
+    /// if navmesh was empty.
+    pub fn query_closest(&mut self, point: Vector3<f32>) -> Option<usize> {
+        self.octree.point_query(point, &mut self.query_buffer);
+        if self.query_buffer.is_empty() {
+            // TODO: This is not optimal. It is better to trace ray down from given point
+            //  and pick closest triangle.
+            math::get_closest_point(self.vertices(), point)
+        } else {
+            math::get_closest_point_triangles(
+                self.pathfinder.vertices(),
+                &self.triangles,
+                &self.query_buffer,
+                point,
+            )
+        }
+    }
+
+    /// Returns reference to array of triangles.
+    pub fn triangles(&self) -> &[TriangleDefinition] {
+        &self.triangles
+    }
+
+    /// Returns reference to array of vertices.
+    pub fn vertices(&self) -> &[PathVertex] {
+        &self.pathfinder.vertices()[..self.mesh_vertex_count]
+    }
+
+    /// Sets area type of a triangle with the given index. See [`area`] module docs for more info.
+    pub fn set_triangle_area(&mut self, triangle: usize, area: NavmeshArea) {
+        if let Some(triangle_area) = self.areas.get_mut(triangle) {
+            *triangle_area = area;
+        }
+    }
+
+    /// Returns area type of a triangle with the given index.
+    pub fn triangle_area(&self, triangle: usize) -> Option<NavmeshArea> {
+        self.areas.get(triangle).cloned()
+    }
+
+    /// Returns reference to array of area types of triangles.
+    pub fn areas(&self) -> &[NavmeshArea] {
+        &self.areas
+    }
+
+    /// Adds new off-mesh link and returns its handle. See [`link`] module docs for more info.
+    pub fn add_link(&mut self, link: OffMeshLink) -> Handle<OffMeshLink> {
+        let handle = self.links.spawn(link);
+        self.rebuild_links();
+        handle
+    }
+
+    /// Removes an off-mesh link and returns it.
+    pub fn remove_link(&mut self, handle: Handle<OffMeshLink>) -> OffMeshLink {
+        let link = self.links.free(handle);
+        self.rebuild_links();
+        link
+    }
+
+    /// Tries to borrow an off-mesh link by its handle.
+    pub fn link(&self, handle: Handle<OffMeshLink>) -> Option<&OffMeshLink> {
+        self.links.try_borrow(handle)
+    }
+
+    /// Returns an iterator over every off-mesh link with its handle.
+    pub fn links(&self) -> impl Iterator<Item = (Handle<OffMeshLink>, &OffMeshLink)> {
+        self.links.pair_iter()
+    }
+
+    /// Cuts a hole in the navigation mesh using the given obstacle (for example a closed door or a
+    /// crate). Every triangle that overlaps the obstacle is excluded from path finding and ray
+    /// casting until the hole is restored using [`Self::restore`]. Carving is performed per
+    /// triangle, so its precision is defined by the size of the triangles. Only the affected parts
+    /// of the octree and the path finder graph are updated, so carving is cheap enough to be done
+    /// at runtime. Paths of agents are not recalculated automatically.
+    ///
+    /// Carves are runtime-only, they're not serialized and they're not cloned with the navmesh
+    /// when the navmesh is inherited from a prefab. Carve the navmesh again after loading if needed.
+    pub fn carve(&mut self, obstacle: DynamicObstacle) -> Handle<NavmeshCarve> {
+        let triangles = self.carve_triangles(&obstacle);
+        self.carves.spawn(NavmeshCarve {
+            obstacle,
+            triangles,
+        })
+    }
+
+    /// Restores triangles that were cut out by the given carve and returns the obstacle of the
+    /// carve. Returns `None` if the handle is invalid (for example, if the carve was already
+    /// restored), the navmesh stays unchanged in this case.
+    pub fn restore(&mut self, handle: Handle<NavmeshCarve>) -> Option<DynamicObstacle> {
+        let carve = self.carves.try_free(handle)?;
+        self.restore_triangles(&carve.triangles);
+        Some(carve.obstacle)
+    }
+
+    /// Changes the obstacle of the given carve (for example if the obstacle has moved). Triangles
+    /// that are not covered by the obstacle anymore are restored.
+    pub fn set_carve_obstacle(&mut self, handle: Handle<NavmeshCarve>, obstacle: DynamicObstacle) {
+        if let Some(carve) = self.carves.try_borrow_mut(handle) {
+            // Carve first, so the triangles covered by both obstacles are not updated twice.
+            let old_triangles = std::mem::take(&mut carve.triangles);
+            let triangles = self.carve_triangles(&obstacle);
+            self.restore_triangles(&old_triangles);
+            self.carves[handle] = NavmeshCarve {
+                obstacle,
+                triangles,
+            };
+        }
+    }
+
+    /// Tries to borrow a carve by its handle.
+    pub fn carve_ref(&self, handle: Handle<NavmeshCarve>) -> Option<&NavmeshCarve> {
+        self.carves.try_borrow(handle)
+    }
+
+    /// Returns an iterator over every carve with its handle.
+    pub fn carves(&self) -> impl Iterator<Item = (Handle<NavmeshCarve>, &NavmeshCarve)> {
+        self.carves.pair_iter()
+    }
+
+    /// Returns `true` if the triangle with the given index is not cut out by any carve.
+    pub fn is_triangle_enabled(&self, triangle: usize) -> bool {
+        self.carve_counts
+            .get(triangle)
+            .map_or(false, |count| *count == 0)
+    }
+
+    fn carve_triangles(&mut self, obstacle: &DynamicObstacle) -> Vec<u32> {
+        if self.triangles.is_empty() {
+            return Default::default();
+        }
+
+        // Carved triangles are excluded from the octree, so the triangles of other carves must be
+        // checked as well.
+        let mut candidates = std::mem::take(&mut self.query_buffer);
+        self.octree
+            .aabb_query(&obstacle.bounding_box(), &mut candidates);
+        candidates.extend(
+            self.carves
+                .iter()
+                .flat_map(|carve| carve.triangles.iter().cloned()),
+        );
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        let triangles = candidates
+            .iter()
+            .cloned()
+            .filter(|index| obstacle.intersects_triangle(&self.triangle_points(*index as usize)))
+            .collect::<Vec<_>>();
+
+        self.query_buffer = candidates;
+
+        for &index in triangles.iter() {
+            let index = index as usize;
+            self.carve_counts[index] += 1;
+            if self.carve_counts[index] == 1 {
+                let bounds = AxisAlignedBoundingBox::from_points(&self.triangle_points(index));
+                self.octree.remove(index as u32, &bounds);
+                self.update_triangle_edges(index);
+            }
+        }
+
+        self.rebuild_links();
+
+        triangles
+    }
+
+    fn restore_triangles(&mut self, triangles: &[u32]) {
+        for &index in triangles {
+            let index = index as usize;
+            if let Some(count) = self.carve_counts.get_mut(index) {
+                if *count > 0 {
+                    *count -= 1;
+                    if *count == 0 {
+                        let bounds =
+                            AxisAlignedBoundingBox::from_points(&self.triangle_points(index));
+                        self.octree.insert(index as u32, &bounds);
+                        self.update_triangle_edges(index);
+                    }
+                }
+            }
+        }
+
+        self.rebuild_links();
+    }
+
+    // Edge of the mesh is a part of the path finder graph only if it belongs to at least one
+    // triangle that is not cut out.
+    fn update_triangle_edges(&mut self, index: usize) {
+        let triangle = self.triangles[index].clone();
+        for (a, b) in [(0, 1), (1, 2), (2, 0)] {
+            let (a, b) = (triangle[a], triangle[b]);
+            let enabled = self
+                .edge_triangles
+                .get(&Edge { a, b })
+                .map_or(false, |triangles| {
+                    triangles
+                        .iter()
+                        .any(|t| self.carve_counts[*t as usize] == 0)
+                });
+
+            self.pathfinder.unlink_bidirect(a as usize, b as usize);
+            if enabled {
+                self.pathfinder.link_bidirect(a as usize, b as usize);
+            }
+        }
+    }
+
+    fn triangle_points(&self, index: usize) -> [Vector3<f32>; 3] {
+        let triangle = &self.triangles[index];
+        let vertices = self.pathfinder.vertices();
+        [
+            vertices[triangle[0] as usize].position,
+            vertices[triangle[1] as usize].position,
+            vertices[triangle[2] as usize].position,
+        ]
+    }
+
+    fn rebuild_octree(&mut self) {
+        let raw_triangles = (0..self.triangles.len())
+            .map(|index| self.triangle_points(index))
+            .collect::<Vec<_>>();
+        self.octree = Octree::new(&raw_triangles, 32);
+    }
+
+    fn rebuild_links(&mut self) {
+        self.pathfinder.truncate(self.mesh_vertex_count);
+        self.link_vertices.clear();
+
+        for (handle, link) in self.links.pair_iter() {
+            let mut triangles = [None; 2];
+            let mut indices = [0; 2];
+            for (i, point) in [link.start, link.end].into_iter().enumerate() {
+                indices[i] = self.pathfinder.add_vertex(PathVertex::new(point));
+
+                // Attach the end point of the link to the vertices of the triangle below it.
+                if let Some((_, index, triangle)) = self.ray_cast(Ray::new(
+                    point + Vector3::new(0.0, 1.0, 0.0),
+                    Vector3::new(0.0, -10.0, 0.0),
+                )) {
+                    triangles[i] = Some(index);
+                    for vertex in triangle.0 {
+                        self.pathfinder.link_bidirect(indices[i], vertex as usize);
+                    }
+                } else if let Some(closest) = math::get_closest_point(self.vertices(), point) {
+                    self.pathfinder.link_bidirect(indices[i], closest);
+                }
+            }
+
+            self.pathfinder.link_unidirect(indices[0], indices[1]);
+            if link.bidirectional {
+                self.pathfinder.link_unidirect(indices[1], indices[0]);
+            }
+
+            self.link_vertices.push(LinkVertices {
+                link: handle,
+                triangles,
+            });
+        }
+    }
+
+    /// Returns shared reference to inner octree.
+    pub fn octree(&self) -> &Octree {
+        &self.octree
+    }
+
+    /// Tries to build path using indices of begin and end points.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// use fyrox::utils::navmesh::Navmesh;
+    /// use fyrox::core::algebra::Vector3;
+    /// use fyrox::utils::astar::{PathKind, PathError};
+    ///
+    /// fn find_path(navmesh: &mut Navmesh, begin: Vector3<f32>, end: Vector3<f32>, path: &mut Vec<Vector3<f32>>) -> Result<PathKind, PathError> {
+    ///     if let Some(begin_index) = navmesh.query_closest(begin) {
+    ///         if let Some(end_index) = navmesh.query_closest(end) {
+    ///             return navmesh.build_path(begin_index, end_index, path);
+    ///         }
+    ///     }
+    ///     Ok(PathKind::Empty)
+    /// }
+    /// ```
+    pub fn build_path(
+        &mut self,
+        from: usize,
+        to: usize,
+        path: &mut Vec<Vector3<f32>>,
+    ) -> Result<PathKind, PathError> {
+        self.build_path_with_filter(from, to, path, &Default::default())
+    }
+
+    /// Same as [`Self::build_path`], but uses the given filter to calculate traversal costs of
+    /// areas and to exclude some areas completely. Off-mesh links could be a part of the path.
+    pub fn build_path_with_filter(
+        &mut self,
+        from: usize,
+        to: usize,
+        path: &mut Vec<Vector3<f32>>,
+        filter: &NavmeshQueryFilter,
+    ) -> Result<PathKind, PathError> {
+        let mesh_vertex_count = self.mesh_vertex_count;
+        let edge_triangles = &self.edge_triangles;
+        let areas = &self.areas;
+        let links = &self.links;
+        let link_vertices = &self.link_vertices;
+
+        let carve_counts = &self.carve_counts;
+
+        let triangle_cost = |triangle: usize| {
+            if carve_counts.get(triangle).map_or(false, |count| *count > 0) {
+                None
+            } else {
+                areas.get(triangle).map_or(Some(1.0), |a| filter.cost(*a))
+            }
+        };
+
+        // Off-mesh links could be cheaper than any area, so they are taken into account as well.
+        let min_cost_multiplier = links
+            .iter()
+            .filter_map(|link| filter.cost(link.area).map(|cost| cost * link.cost))
+            .fold(filter.min_area_cost(), f32::min);
+
+        self.pathfinder
+            .build_with_cost(from, to, path, min_cost_multiplier, |a, b, cost| {
+                match (
+                    a.checked_sub(mesh_vertex_count),
+                    b.checked_sub(mesh_vertex_count),
+                ) {
+                    (None, None) => {
+                        // An edge of the mesh, the cheapest adjacent triangle defines the cost.
+                        match edge_triangles.get(&Edge {
+                            a: a as u32,
+                            b: b as u32,
+                        }) {
+                            Some(triangles) => triangles
+                                .iter()
+                                .filter_map(|t| triangle_cost(*t as usize))
+                                .min_by(|x, y| x.total_cmp(y))
+                                .map(|multiplier| cost * multiplier),
+                            None => Some(cost),
+                        }
+                    }
+                    (Some(a), Some(b)) if a / 2 == b / 2 => {
+                        // Off-mesh link itself.
+                        let link = &links[link_vertices[a / 2].link];
+                        filter
+                            .cost(link.area)
+                            .map(|multiplier| cost * multiplier * link.cost)
+                    }
+                    (Some(link), None) | (None, Some(link)) => {
+                        // An edge between the mesh and the end point of a link.
+                        match link_vertices[link / 2].triangles[link % 2] {
+                            Some(triangle) => {
+                                triangle_cost(triangle).map(|multiplier| cost * multiplier)
+                            }
+                            None => Some(cost),
+                        }
+                    }
+                    _ => None,
+                }
+            })
+    }
+
+    /// Tries to pick a triangle by given ray. Returns closest result.
+    pub fn ray_cast(&self, ray: Ray) -> Option<(Vector3<f32>, usize, TriangleDefinition)> {
+        let mut buffer = ArrayVec::<Handle<OctreeNode>, 128>::new();
+
+        self.octree.ray_query_static(&ray, &mut buffer);
+
+        let mut closest_distance = f32::MAX;
+        let mut result = None;
+        for node in buffer.into_iter() {
+            if let OctreeNode::Leaf { indices, .. } = self.octree.node(node) {
+                for &index in indices {
+                    let triangle = self.triangles[index as usize].clone();
+                    let a = self.pathfinder.vertices()[triangle[0] as usize].position;
+                    let b = self.pathfinder.vertices()[triangle[1] as usize].position;
+                    let c = self.pathfinder.vertices()[triangle[2] as usize].position;
+
+                    if let Some(intersection) = ray.triangle_intersection_point(&[a, b, c]) {
+                        let distance = intersection.metric_distance(&ray.origin);
+                        if distance < closest_distance {
+                            closest_distance = distance;
+                            result = Some((intersection, index as usize, triangle));
+                        }
+                    }
+                }
+            } else {
+                unreachable!()
+            }
+        }
+
+        result
+    }
+}
+
+/// Navmesh agent is a "pathfinding unit" that performs navigation on a mesh. It is designed to
+/// cover most of simple use cases when you need to build and follow some path from point A to point B.
+#[derive(Visit, Clone, Debug)]
+pub struct NavmeshAgent {
+    path: Vec<Vector3<f32>>,
+    current: u32,
+    position: Vector3<f32>,
+    last_warp_position: Vector3<f32>,
+    target: Vector3<f32>,
+    last_target_position: Vector3<f32>,
+    recalculation_threshold: f32,
+    speed: f32,
+    path_dirty: bool,
+    #[visit(optional)]
+    path_links: Vec<PathLink>,
+    #[visit(optional)]
+    query_filter: NavmeshQueryFilter,
+}
+
+impl Default for NavmeshAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NavmeshAgent {
+    /// Creates new navigation mesh agent.
+    pub fn new() -> Self {
+        Self {
+            path: vec![],
+            current: 0,
+            position: Default::default(),
+            last_warp_position: Default::default(),
+            target: Default::default(),
+            last_target_position: Default::default(),
+            recalculation_threshold: 0.25,
+            speed: 1.5,
+            path_dirty: true,
+            path_links: Default::default(),
+            query_filter: Default::default(),
+        }
+    }
+
+    /// Returns agent's position.
+    pub fn position(&self) -> Vector3<f32> {
+        self.position
+    }
+
+    /// Returns agent's path that will be followed.
+    pub fn path(&self) -> &[Vector3<f32>] {
+        &self.path
+    }
+
+    /// Sets new speed of agent's movement.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+
+    /// Returns current agent's movement speed.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Sets new query filter of the agent, the path will be recalculated on next update. See
+    /// [`area`] module docs for more info.
+    pub fn set_query_filter(&mut self, filter: NavmeshQueryFilter) {
+        self.query_filter = filter;
+        self.path_dirty = true;
+    }
+
+    /// Returns current query filter of the agent.
+    pub fn query_filter(&self) -> &NavmeshQueryFilter {
+        &self.query_filter
+    }
+
+    /// Returns every off-mesh link of the agent's path.
+    pub fn path_links(&self) -> &[PathLink] {
+        &self.path_links
+    }
+
+    /// Returns an off-mesh link that the agent is traversing at the moment (if any). It could be
+    /// used to play a jump or climbing animation, for example.
+    pub fn current_link(&self) -> Option<&PathLink> {
+        self.path_links
+            .iter()
+            .find(|link| link.segment == self.current)
+    }
+}
+
+fn closest_point_index_in_triangle_and_adjacent(
+    triangle: TriangleDefinition,
+    navmesh: &Navmesh,
+    to: Vector3<f32>,
+) -> Option<usize> {
+    let mut triangles = ArrayVec::<TriangleDefinition, 4>::new();
+    triangles.push(triangle);
+    math::get_closest_point_triangle_set(navmesh.pathfinder.vertices(), &triangles, to)
+}
+
+impl NavmeshAgent {
+    /// Calculates path from point A to point B. In most cases there is no need to use this method
+    /// directly, because `update` will call it anyway if target position has moved.
+    pub fn calculate_path(
+        &mut self,
+        navmesh: &mut Navmesh,
+        from: Vector3<f32>,
+        to: Vector3<f32>,
+    ) -> Result<PathKind, PathError> {
+        self.path.clear();
+        self.path_links.clear();
+
+        self.current = 0;
+
+        let (n_from, begin, from_triangle) = if let Some((point, index, triangle)) = navmesh
+            .ray_cast(Ray::new(
+                from + Vector3::new(0.0, 1.0, 0.0),
+                Vector3::new(0.0, -10.0, 0.0),
+            )) {
+            (
+                closest_point_index_in_triangle_and_adjacent(triangle, navmesh, to),
+                Some(point),
+                Some(index),
+            )
+        } else {
+            (navmesh.query_closest(from), None, None)
+        };
+
+        let (n_to, end, to_triangle) = if let Some((point, index, triangle)) =
+            navmesh.ray_cast(Ray::new(
+                to + Vector3::new(0.0, 1.0, 0.0),
+                Vector3::new(0.0, -10.0, 0.0),
+            )) {
+            (
+                closest_point_index_in_triangle_and_adjacent(triangle, navmesh, from),
+                Some(point),
+                Some(index),
+            )
+        } else {
+            (navmesh.query_closest(to), None, None)
+        };
+
+        if let (Some(from_triangle), Some(to_triangle)) = (from_triangle, to_triangle) {
+            if from_triangle == to_triangle {
+                self.path.push(from);
+                self.path.push(to);
+
+                return Ok(PathKind::Full);
+            }
+        }
+
+        if let (Some(n_from), Some(n_to)) = (n_from, n_to) {
+            let result =
+                navmesh.build_path_with_filter(n_from, n_to, &mut self.path, &self.query_filter);
+
+            if let Some(end) = end {
+                if self.path.is_empty() {
+                    self.path.push(end);
+                } else {
+                    self.path.insert(0, end)
+                }
+            }
+
+            if let Some(begin) = begin {
+                self.path.push(begin);
+            }
+
+            self.path.reverse();
+
+            self.find_path_links(navmesh);
+
+            // Perform few smoothing passes to straighten computed path.
+            for _ in 0..2 {
+                self.smooth_path(navmesh);
+            }
+
+            result
+        } else {
+            Err(PathError::Custom("Empty navmesh!".to_owned()))
+        }
+    }
+
+    fn find_path_links(&mut self, navmesh: &Navmesh) {
+        for (segment, points) in self.path.windows(2).enumerate() {
+            for (handle, link) in navmesh.links.pair_iter() {
+                if (points[0] == link.start && points[1] == link.end)
+                    || (link.bidirectional && points[0] == link.end && points[1] == link.start)
+                {
+                    self.path_links.push(PathLink {
+                        segment: segment as u32,
+                        link: handle,
+                        kind: link.kind,
+                        start: points[0],
+                        end: points[1],
+                    });
+                    break;
+                }
+            }
+        }
+    }
+
+    fn smooth_path(&mut self, navmesh: &Navmesh) {
+        let vertices = navmesh.vertices();
+
+        let mut i = 0;
+        while i < self.path.len().saturating_sub(2) {
+            // End points of off-mesh links must stay in place.
+            if self
+                .path_links
+                .iter()
+                .any(|link| link.segment as usize == i || link.segment as usize == i + 1)
+            {
+                i += 1;
+                continue;
+            }
+
+            let begin = self.path[i];
+            let end = self.path[i + 2];
+            let delta = end - begin;
+
+            let max_delta = (delta.x.max(delta.y).max(delta.z)).abs();
+
+            // Calculate center point between end points of each path edge.
+            //     i+1
+            //      ^
+            //     / \
+            //    /   \
+            //   /     \
+            //  /-  *  -\
+            // i    C   i+2
+            let center = (begin + end).scale(0.5);
+
+            // And check if center is lying on navmesh or not. If so - replace i+1 vertex
+            // with its projection on the triangle it belongs to.
+            for (index, triangle) in navmesh.triangles.iter().enumerate() {
+                // Path must not be straightened through excluded areas and holes.
+                if !navmesh.is_triangle_enabled(index)
+                    || self.query_filter.is_area_excluded(navmesh.areas[index])
+                {
+                    continue;
+                }
+
+                let a = vertices[triangle[0] as usize].position;
+                let b = vertices[triangle[1] as usize].position;
+                let c = vertices[triangle[2] as usize].position;
+
+                // Ignore degenerated triangles.
+                if let Some(normal) = (c - a).cross(&(b - a)).try_normalize(f32::EPSILON) {
+                    // Calculate signed distance between triangle and segment's center.
+                    let signed_distance = (center - a).dot(&normal);
+
+                    // And check "slope": If center is too far from triangle, check next triangle.
+                    if signed_distance.abs() <= max_delta {
+                        // Project center on the triangle.
+                        let center_projection = center - normal.scale(signed_distance);
+
+                        // And check if the projection lies inside the triangle.
+                        if math::is_point_inside_triangle(&center_projection, &[a, b, c]) {
+                            self.path[i + 1] = center_projection;
+                            break;
+                        }
+                    }
+                }
+            }
+
+            i += 1;
+        }
+    }
+
+    /// Performs single update tick that moves agent to the target along the path (which is automatically
+    /// recalculated if target's position has changed).
+    pub fn update(&mut self, dt: f32, navmesh: &mut Navmesh) -> Result<PathKind, PathError> {
+        if self.path_dirty {
+            self.calculate_path(navmesh, self.position, self.target)?;
+            self.path_dirty = false;
+        }
+
+        self.follow_path(dt);
+
+        Ok(PathKind::Full)
+    }
+
+    fn follow_path(&mut self, dt: f32) {
+        if let Some(end) = self
+            .current_link()
+            .filter(|link| link.kind == OffMeshLinkKind::Teleport)
+            .map(|link| link.end)
+        {
+            self.position = end;
+            self.current += 1;
+            return;
+        }
+
+        if let Some(source) = self.path.get(self.current as usize) {
+            if let Some(destination) = self.path.get((self.current + 1) as usize) {
+                let ray = Ray::from_two_points(*source, *destination);
+                let d = ray.dir.try_normalize(f32::EPSILON).unwrap_or_default();
+                self.position += d.scale(self.speed * dt);
+                if ray.project_point(&self.position) >= 1.0 {
+                    self.current += 1;
+                }
+            }
+        }
+    }
+
+    /// Returns current steering target which in most cases next path point from which
+    /// agent is close to.
+    pub fn steering_target(&self) -> Option<Vector3<f32>> {
+        self.path
+            .get(self.current as usize + 1)
+            .or_else(|| self.path.last())
+            .cloned()
+    }
+
+    /// Sets new target for the agent.
+    pub fn set_target(&mut self, new_target: Vector3<f32>) {
+        if new_target.metric_distance(&self.last_target_position) >= self.recalculation_threshold {
+            self.path_dirty = true;
+            self.last_target_position = new_target;
+        }
+
+        self.target = new_target;
+    }
+
+    /// Returns current target of the agent.
+    pub fn target(&self) -> Vector3<f32> {
+        self.target
+    }
+
+    /// Sets new position of the agent.
+    pub fn set_position(&mut self, new_position: Vector3<f32>) {
+        if new_position.metric_distance(&self.last_warp_position) >= self.recalculation_threshold {
+            self.path_dirty = true;
+            self.last_warp_position = new_position;
+        }
+
+        self.position = new_position;
+    }
+}
+
+/// Allows you to build agent in declarative manner.
+pub struct NavmeshAgentBuilder {
+    position: Vector3<f32>,
+    target: Vector3<f32>,
+    recalculation_threshold: f32,
+    speed: f32,
+    query_filter: NavmeshQueryFilter,
+}
+
+impl Default for NavmeshAgentBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NavmeshAgentBuilder {
+    /// Creates new builder instance.
+    pub fn new() -> Self {
+        Self {
+            position: Default::default(),
+            target: Default::default(),
+            recalculation_threshold: 0.25,
+            speed: 1.5,
+            query_filter: Default::default(),
+        }
+    }
+
+    /// Sets new desired position of the agent being built.
+    pub fn with_position(mut self, position: Vector3<f32>) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Sets new desired target of the agent being built.
+    pub fn with_target(mut self, position: Vector3<f32>) -> Self {
+        self.target = position;
+        self
+    }
+
+    /// Sets new desired recalculation threshold (in meters) of the agent being built.
+    pub fn with_recalculation_threshold(mut self, threshold: f32) -> Self {
+        self.recalculation_threshold = threshold;
+        self
+    }
+
+    /// Sets new desired movement speed of the agent being built.
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Sets new query filter of the agent being built.
+    pub fn with_query_filter(mut self, filter: NavmeshQueryFilter) -> Self {
+        self.query_filter = filter;
+        self
+    }
+
+    /// Build the agent.
+    pub fn build(self) -> NavmeshAgent {
+        NavmeshAgent {
+            position: self.position,
+            last_warp_position: self.position,
+            target: self.target,
+            last_target_position: self.target,
+            recalculation_threshold: self.recalculation_threshold,
+            speed: self.speed,
+            query_filter: self.query_filter,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::Vector3,
+            math::{ray::Ray, TriangleDefinition},
+            pool::Handle,
+            visitor::prelude::*,
+        },
+        utils::{
+            astar::PathKind,
+            navmesh::{
+                area::{NavmeshArea, NavmeshQueryFilter},
+                crowd::{DynamicObstacle, ObstacleShape},
+                link::{OffMeshLink, OffMeshLinkKind},
+                Navmesh, NavmeshAgentBuilder,
+            },
+        },
+    };
+
+    // Creates a grid of quads on XZ plane, every quad is 1x1 meter.
+    fn make_grid(size: u32, offset: Vector3<f32>) -> (Vec<TriangleDefinition>, Vec<Vector3<f32>>) {
+        let mut vertices = Vec::new();
+        for z in 0..=size {
+            for x in 0..=size {
+                vertices.push(offset + Vector3::new(x as f32, 0.0, z as f32));
+            }
+        }
+        let mut triangles = Vec::new();
+        for z in 0..size {
+            for x in 0..size {
+                let i = z * (size + 1) + x;
+                triangles.push(TriangleDefinition([i, i + size + 1, i + 1]));
+                triangles.push(TriangleDefinition([i + 1, i + size + 1, i + size + 2]));
+            }
+        }
+        (triangles, vertices)
+    }
+
+    #[test]
+    fn test_excluded_area() {
+        let (triangles, vertices) = make_grid(3, Vector3::default());
+        let mut navmesh = Navmesh::new(&triangles, &vertices);
+
+        // Middle column of the two lower rows is water.
+        for index in [2, 3, 8, 9] {
+            navmesh.set_triangle_area(index, NavmeshArea::WATER);
+        }
+
+        let mut agent = NavmeshAgentBuilder::new()
+            .with_query_filter(NavmeshQueryFilter::new().with_excluded_area(NavmeshArea::WATER))
+            .build();
+        let result = agent.calculate_path(
+            &mut navmesh,
+            Vector3::new(0.5, 0.0, 0.5),
+            Vector3::new(2.5, 0.0, 0.5),
+        );
+        assert!(matches!(result, Ok(PathKind::Full)));
+        assert!(agent
+            .path()
+            .iter()
+            .all(|p| p.x <= 1.01 || p.x >= 1.99 || p.z >= 1.99));
+        assert!(agent.path().iter().any(|p| p.z > 1.99));
+    }
+
+    #[test]
+    fn test_off_mesh_link() {
+        let (mut triangles, mut vertices) = make_grid(1, Vector3::default());
+        let (other_triangles, other_vertices) = make_grid(1, Vector3::new(3.0, 0.0, 0.0));
+        let offset = vertices.len() as u32;
+        triangles.extend(
+            other_triangles
+                .into_iter()
+                .map(|t| TriangleDefinition(t.0.map(|i| i + offset))),
+        );
+        vertices.extend(other_vertices);
+
+        let mut navmesh = Navmesh::new(&triangles, &vertices);
+        let link = navmesh.add_link(OffMeshLink::new(
+            Vector3::new(0.9, 0.0, 0.5),
+            Vector3::new(3.1, 0.0, 0.5),
+            OffMeshLinkKind::Jump,
+        ));
+
+        let target = Vector3::new(3.8, 0.0, 0.5);
+        let mut agent = NavmeshAgentBuilder::new()
+            .with_position(Vector3::new(0.2, 0.0, 0.5))
+            .with_target(target)
+            .build();
+
+        let mut link_traversed = false;
+        for _ in 0..100 {
+            assert!(agent.update(0.1, &mut navmesh).is_ok());
+            if let Some(path_link) = agent.current_link() {
+                assert_eq!(path_link.link, link);
+                assert_eq!(path_link.kind, OffMeshLinkKind::Jump);
+                link_traversed = true;
+            }
+        }
+
+        assert!(link_traversed);
+        assert_eq!(navmesh.vertices().len(), vertices.len());
+        assert!(agent.position().metric_distance(&target) < 0.2);
+    }
+
+    // Writes a navmesh in the format that was used before areas and links were added.
+    fn write_legacy_navmesh(navmesh: &Navmesh, write_areas: bool) -> Visitor {
+        let mut visitor = Visitor::new();
+        let mut region = visitor.enter_region("Navmesh").unwrap();
+        navmesh
+            .pathfinder
+            .clone()
+            .visit("PathFinder", &mut region)
+            .unwrap();
+        navmesh
+            .triangles
+            .clone()
+            .visit("Triangles", &mut region)
+            .unwrap();
+        if write_areas {
+            // Empty region instead of valid data.
+            drop(region.enter_region("Areas").unwrap());
+        }
+        drop(region);
+
+        Visitor::load_from_memory(visitor.save_binary_to_vec().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_navmesh_visit_missing_fields() {
+        let (triangles, vertices) = make_grid(2, Vector3::default());
+        let navmesh = Navmesh::new(&triangles, &vertices);
+
+        let mut visitor = write_legacy_navmesh(&navmesh, false);
+        let mut loaded = Navmesh::default();
+        loaded.visit("Navmesh", &mut visitor).unwrap();
+        assert_eq!(loaded.triangles(), navmesh.triangles());
+        assert_eq!(loaded.vertices().len(), navmesh.vertices().len());
+        assert_eq!(loaded.areas(), navmesh.areas());
+        assert_eq!(loaded.links().count(), 0);
+
+        // Corrupted data must not be ignored.
+        let mut visitor = write_legacy_navmesh(&navmesh, true);
+        let mut loaded = Navmesh::default();
+        assert!(loaded.visit("Navmesh", &mut visitor).is_err());
+    }
+
+    #[test]
+    fn test_cheap_area_path() {
+        let (triangles, vertices) = make_grid(4, Vector3::default());
+        let mut navmesh = Navmesh::new(&triangles, &vertices);
+
+        // Road goes around the grid along its borders.
+        for (index, triangle) in triangles.iter().enumerate() {
+            let center = (vertices[triangle[0] as usize]
+                + vertices[triangle[1] as usize]
+                + vertices[triangle[2] as usize])
+                .scale(1.0 / 3.0);
+            if center.x < 1.0 || center.x > 3.0 || center.z > 3.0 {
+                navmesh.set_triangle_area(index, NavmeshArea::ROAD);
+            }
+        }
+
+        let filter = NavmeshQueryFilter::new().with_area_cost(NavmeshArea::ROAD, 0.01);
+        let from = navmesh.query_closest(Vector3::new(0.0, 0.0, 0.0)).unwrap();
+        let to = navmesh.query_closest(Vector3::new(4.0, 0.0, 0.0)).unwrap();
+        let mut path = Vec::new();
+        assert!(matches!(
+            navmesh.build_path_with_filter(from, to, &mut path, &filter),
+            Ok(PathKind::Full)
+        ));
+
+        // Cheap road is much better than the straight path, the heuristic must not prevent the
+        // search from finding it. The path follows the inner border of the road.
+        assert!(path.iter().any(|p| p.z > 2.99));
+    }
+
+    #[test]
+    fn test_overlapping_carves() {
+        let (triangles, vertices) = make_grid(4, Vector3::default());
+        let mut navmesh = Navmesh::new(&triangles, &vertices);
+
+        let down = |x: f32, z: f32| Ray::new(Vector3::new(x, 1.0, z), Vector3::new(0.0, -2.0, 0.0));
+        let cylinder = |x: f32| {
+            DynamicObstacle::new(
+                Vector3::new(x, 0.0, 1.5),
+                ObstacleShape::Cylinder {
+                    radius: 0.3,
+                    half_height: 1.0,
+                },
+            )
+        };
+
+        let a = navmesh.carve(cylinder(1.5));
+        let b = navmesh.carve(cylinder(1.6));
+        let a_triangles = navmesh.carve_ref(a).unwrap().triangles().to_vec();
+        let b_triangles = navmesh.carve_ref(b).unwrap().triangles().to_vec();
+        assert!(!a_triangles.is_empty());
+        // Triangles that were cut out by the first carve must be found by the second one as well.
+        assert!(a_triangles.iter().all(|t| b_triangles.contains(t)));
+        assert!(navmesh.ray_cast(down(1.5, 1.5)).is_none());
+
+        assert!(navmesh.restore(a).is_some());
+        assert!(b_triangles
+            .iter()
+            .all(|t| !navmesh.is_triangle_enabled(*t as usize)));
+        assert!(navmesh.ray_cast(down(1.5, 1.5)).is_none());
+
+        assert!(navmesh.restore(b).is_some());
+        assert!((0..navmesh.triangles().len()).all(|t| navmesh.is_triangle_enabled(t)));
+        assert!(navmesh.ray_cast(down(1.5, 1.5)).is_some());
+    }
+
+    #[test]
+    fn test_restore_stale_carve() {
+        let (triangles, vertices) = make_grid(4, Vector3::default());
+        let mut navmesh = Navmesh::new(&triangles, &vertices);
+
+        let obstacle = DynamicObstacle::new(
+            Vector3::new(1.5, 0.0, 1.5),
+            ObstacleShape::Cylinder {
+                radius: 0.3,
+                half_height: 1.0,
+            },
+        );
+        let a = navmesh.carve(obstacle.clone());
+        assert_eq!(navmesh.restore(a), Some(obstacle.clone()));
+
+        // The slot of the first carve is reused by the second one, the stale handle must not
+        // restore it.
+        let b = navmesh.carve(obstacle.clone());
+        let b_triangles = navmesh.carve_ref(b).unwrap().triangles().to_vec();
+        assert!(!b_triangles.is_empty());
+        assert_eq!(navmesh.restore(a), None);
+        assert!(navmesh.carve_ref(b).is_some());
+        assert!(b_triangles
+            .iter()
+            .all(|t| !navmesh.is_triangle_enabled(*t as usize)));
+
+        assert_eq!(navmesh.restore(b), Some(obstacle));
+        assert_eq!(navmesh.restore(b), None);
+        assert!((0..navmesh.triangles().len()).all(|t| navmesh.is_triangle_enabled(t)));
+        assert_eq!(navmesh.restore(Handle::NONE), None);
+    }
+}